@@ -0,0 +1,442 @@
+//! Interactive approval of `ask` verdicts over a Unix socket.
+//!
+//! `railgun approve` listens on `policy.approve.socket` and prompts in its
+//! terminal for every `ask` the hook forwards. The hook waits up to
+//! `timeout_secs` for the answer, capped at [`MAX_TIMEOUT_SECS`] to stay
+//! well within the hook timeout; if nobody is listening or no answer
+//! arrives in time, the verdict stays `ask` and Claude Code prompts as
+//! usual.
+//!
+//! The socket is in the per-user runtime directory, not the project, and
+//! the hook only talks to a server running as its own user (see
+//! [`connect`]). That keeps other users on the machine, and files the agent
+//! writes into the project, from answering. It doesn't stop the agent
+//! itself: it runs as the same user, so it could bind the socket or answer
+//! on it. Use `railgun approve` to answer asks from another terminal, not
+//! as a boundary against the agent.
+//!
+//! The protocol is one JSON line each way: an [`ApprovalRequest`] from the
+//! hook, then an [`ApprovalResponse`] from `railgun approve`.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use eyre::{Context, Result};
+use rg_policy::Middleware;
+use rg_types::{Config, HookInput, Verdict};
+use serde::{Deserialize, Serialize};
+
+use crate::config_loader;
+
+/// Longest wait for an answer, in seconds, whatever `timeout_secs` says.
+/// The hook may wait this long twice, through the daemon and then
+/// in-process, and both must fit in [`crate::install::HOOK_TIMEOUT_SECS`].
+pub const MAX_TIMEOUT_SECS: u64 = 30;
+
+/// Longest tool input shown in the prompt, in characters.
+const MAX_INPUT_CHARS: usize = 2000;
+
+/// An `ask` forwarded by the hook.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    /// Tool name.
+    pub tool: String,
+    /// Why the policy asks.
+    pub reason: String,
+    /// Tool input as compact JSON, truncated.
+    pub input: String,
+    /// Working directory of the call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+}
+
+/// The human's answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalResponse {
+    /// Whether the call may proceed.
+    pub allow: bool,
+}
+
+impl ApprovalRequest {
+    /// Build a request for an `ask` verdict.
+    fn new(input: &HookInput, reason: &str) -> Self {
+        let json = input.tool_input.to_string();
+        let shown = match json.char_indices().nth(MAX_INPUT_CHARS) {
+            Some((end, _)) => format!("{}...", &json[..end]),
+            None => json,
+        };
+        Self {
+            tool: input.tool_name.clone(),
+            reason: reason.to_string(),
+            input: shown,
+            cwd: input.cwd.clone(),
+        }
+    }
+}
+
+/// Hook side of the channel: forwards `ask` verdicts and applies the answer.
+#[derive(Debug, Clone)]
+pub struct ApprovalChannel {
+    /// Socket `railgun approve` listens on.
+    socket: PathBuf,
+    /// How long to wait for an answer.
+    timeout: Duration,
+}
+
+impl ApprovalChannel {
+    /// Create a channel to `socket`.
+    pub fn new(socket: impl Into<PathBuf>, timeout: Duration) -> Self {
+        Self {
+            socket: socket.into(),
+            timeout,
+        }
+    }
+
+    /// Build the channel from configuration, if enabled.
+    pub fn from_config(config_path: &Path, config: &Config) -> Option<Self> {
+        let approve = &config.policy.approve;
+        if approve.enabled && approve.timeout_secs > MAX_TIMEOUT_SECS {
+            tracing::warn!(
+                "policy.approve.timeout_secs = {} is above the {MAX_TIMEOUT_SECS}s limit; using {MAX_TIMEOUT_SECS}",
+                approve.timeout_secs
+            );
+        }
+        approve.enabled.then(|| {
+            Self::new(
+                config_loader::approve_socket(config_path, config),
+                Duration::from_secs(approve.timeout_secs.min(MAX_TIMEOUT_SECS)),
+            )
+        })
+    }
+
+    /// How long [`Self::request`] waits for an answer.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Send a request and wait for the answer.
+    pub fn request(&self, request: &ApprovalRequest) -> io::Result<ApprovalResponse> {
+        let mut stream = connect(&self.socket, self.timeout)?;
+
+        writeln!(stream, "{}", serde_json::to_string(request)?)?;
+        let mut line = String::new();
+        let _ = BufReader::new(stream).read_line(&mut line)?;
+        Ok(serde_json::from_str(&line)?)
+    }
+}
+
+impl Middleware for ApprovalChannel {
+    fn process(&self, input: &HookInput, verdict: Verdict) -> Verdict {
+        let Verdict::Ask { reason, .. } = &verdict else {
+            return verdict;
+        };
+
+        match self.request(&ApprovalRequest::new(input, reason)) {
+            Ok(ApprovalResponse { allow: true }) => {
+                tracing::info!(tool = %input.tool_name, "approved via railgun approve");
+                Verdict::Allow
+            }
+            Ok(ApprovalResponse { allow: false }) => Verdict::deny_with_context(
+                format!("Rejected by the user: {reason}"),
+                "The user rejected this action in railgun approve. Do not retry it; ask the user how to proceed.",
+            ),
+            Err(e) => {
+                tracing::debug!(error = %e, "approval channel unavailable; asking in Claude Code");
+                verdict
+            }
+        }
+    }
+}
+
+/// Run `railgun approve`: answer forwarded `ask` verdicts from the terminal.
+pub fn run_approve(config_path: &str) -> Result<()> {
+    let config = config_loader::load_config(config_path)?;
+    let socket = config_loader::approve_socket(Path::new(config_path), &config);
+    if !config.policy.approve.enabled {
+        println!("Note: policy.approve.enabled is false, so the hook won't forward asks yet.");
+    }
+
+    let listener = bind(&socket, "railgun approve")?;
+    println!("Waiting for approval requests on {}", socket.display());
+    println!("Press Ctrl-C to stop.");
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        let (stream, _) = listener.accept().context("Failed to accept connection")?;
+        let served = serve_one(stream, |request| {
+            println!();
+            println!("Tool:   {}", request.tool);
+            if let Some(cwd) = &request.cwd {
+                println!("In:     {cwd}");
+            }
+            println!("Reason: {}", request.reason);
+            println!("Input:  {}", request.input);
+            print!("Allow? [y/N] ");
+            let _ = io::stdout().flush();
+            // End of input stops the server after denying this request
+            let answer = lines.next().and_then(io::Result::ok);
+            let allow = answer
+                .as_deref()
+                .is_some_and(|a| matches!(a.trim(), "y" | "Y" | "yes"));
+            println!("{}", if allow { "Allowed." } else { "Denied." });
+            (allow, answer.is_some())
+        });
+        match served {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => eprintln!("Warning: {e}"),
+        }
+    }
+
+    let _ = std::fs::remove_file(&socket);
+    Ok(())
+}
+
+/// Bind the socket, replacing a stale one left by a previous run of
+/// `command`. Its directory is created, or restricted, to this user.
+pub(crate) fn bind(socket: &Path, command: &str) -> Result<UnixListener> {
+    if let Some(dir) = socket.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        private_dir(dir).with_context(|| format!("Failed to secure {}", dir.display()))?;
+    }
+    if socket.exists() {
+        if UnixStream::connect(socket).is_ok() {
+            return Err(eyre::eyre!(
+                "{command} is already running on {}",
+                socket.display()
+            ));
+        }
+        std::fs::remove_file(socket)
+            .with_context(|| format!("Failed to remove stale socket {}", socket.display()))?;
+    }
+    UnixListener::bind(socket).with_context(|| format!("Failed to listen on {}", socket.display()))
+}
+
+/// Connect to a socket served by [`bind`], with `timeout` on reads and
+/// writes.
+///
+/// Whoever answers on the socket decides tool calls, so the connection is
+/// refused unless the socket's directory is private to this user and the
+/// server runs as this user.
+pub(crate) fn connect(socket: &Path, timeout: Duration) -> io::Result<UnixStream> {
+    if let Some(dir) = socket.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        check_private(dir)?;
+    }
+    let stream = UnixStream::connect(socket)?;
+    let uid = peer_uid(&stream, socket)?;
+    if uid != rustix::process::getuid().as_raw() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is served by another user (uid {uid})", socket.display()),
+        ));
+    }
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    Ok(stream)
+}
+
+/// Create `dir` with access for this user only, or restrict an existing one
+/// this user owns.
+fn private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)?;
+    if check_private(dir).is_err() {
+        let _ = owned_dir(dir)?;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    check_private(dir)
+}
+
+/// Check that `dir` is a directory owned by this user that no one else can
+/// access.
+fn check_private(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let mode = owned_dir(dir)?.mode();
+    if mode & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!(
+                "{} is accessible to other users (mode {:o})",
+                dir.display(),
+                mode & 0o777
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Metadata of `dir`, if it is a directory (not a symlink) owned by this
+/// user.
+fn owned_dir(dir: &Path) -> io::Result<std::fs::Metadata> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() || metadata.uid() != rustix::process::getuid().as_raw() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not a directory owned by this user", dir.display()),
+        ));
+    }
+    Ok(metadata)
+}
+
+/// User ID of the process serving `stream`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn peer_uid(stream: &UnixStream, _socket: &Path) -> io::Result<u32> {
+    Ok(rustix::net::sockopt::socket_peercred(stream)?.uid.as_raw())
+}
+
+/// User ID of the process serving `stream`: the owner of the socket file,
+/// where peer credentials aren't available.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn peer_uid(_stream: &UnixStream, socket: &Path) -> io::Result<u32> {
+    use std::os::unix::fs::MetadataExt;
+
+    Ok(std::fs::symlink_metadata(socket)?.uid())
+}
+
+/// Answer one request. `answer` returns the decision and whether to keep
+/// serving; the result is that flag.
+fn serve_one(
+    stream: UnixStream,
+    mut answer: impl FnMut(&ApprovalRequest) -> (bool, bool),
+) -> Result<bool> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    let _ = reader.read_line(&mut line)?;
+    let request: ApprovalRequest =
+        serde_json::from_str(&line).context("Invalid approval request")?;
+
+    let (allow, keep_serving) = answer(&request);
+    let mut stream = stream;
+    // The hook may have timed out and gone; that is not an error here
+    let _ = writeln!(
+        stream,
+        "{}",
+        serde_json::to_string(&ApprovalResponse { allow })?
+    );
+    Ok(keep_serving)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input() -> HookInput {
+        HookInput {
+            cwd: Some("/work/app".to_string()),
+            ..HookInput::new("Bash", serde_json::json!({ "command": "git clean -fd" }))
+        }
+    }
+
+    fn answer_once(
+        listener: UnixListener,
+        allow: bool,
+    ) -> std::thread::JoinHandle<ApprovalRequest> {
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut seen = None;
+            let _ = serve_one(stream, |request| {
+                seen = Some(request.clone());
+                (allow, true)
+            })
+            .unwrap();
+            seen.unwrap()
+        })
+    }
+
+    #[test]
+    fn test_allowed_by_user() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("approve.sock");
+        let server = answer_once(bind(&socket, "railgun approve").unwrap(), true);
+
+        let channel = ApprovalChannel::new(&socket, Duration::from_secs(5));
+        assert!(channel
+            .process(&input(), Verdict::ask("Destructive git operation"))
+            .is_allow());
+
+        let request = server.join().unwrap();
+        assert_eq!(request.tool, "Bash");
+        assert_eq!(request.reason, "Destructive git operation");
+        assert_eq!(request.input, r#"{"command":"git clean -fd"}"#);
+        assert_eq!(request.cwd.as_deref(), Some("/work/app"));
+    }
+
+    #[test]
+    fn test_denied_by_user() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("approve.sock");
+        let server = answer_once(bind(&socket, "railgun approve").unwrap(), false);
+
+        let channel = ApprovalChannel::new(&socket, Duration::from_secs(5));
+        let verdict = channel.process(&input(), Verdict::ask("Destructive git operation"));
+        assert!(verdict.is_deny());
+        assert!(verdict.reason().unwrap().contains("Rejected by the user"));
+        let _ = server.join().unwrap();
+    }
+
+    #[test]
+    fn test_falls_back_to_ask() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("approve.sock");
+
+        // Nobody listening
+        let channel = ApprovalChannel::new(&socket, Duration::from_millis(100));
+        assert!(channel.process(&input(), Verdict::ask("confirm")).is_ask());
+
+        // Listening but never answering
+        let _listener = bind(&socket, "railgun approve").unwrap();
+        assert!(channel.process(&input(), Verdict::ask("confirm")).is_ask());
+
+        // Nor to a socket other users could serve
+        std::fs::set_permissions(
+            dir.path(),
+            std::os::unix::fs::PermissionsExt::from_mode(0o777),
+        )
+        .unwrap();
+        let err = channel
+            .request(&ApprovalRequest::new(&input(), "confirm"))
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(channel.process(&input(), Verdict::ask("confirm")).is_ask());
+
+        // Other verdicts are never forwarded
+        assert!(channel
+            .process(&input(), Verdict::deny("blocked"))
+            .is_deny());
+    }
+
+    #[test]
+    fn test_timeout_is_capped() {
+        let mut config = Config::default();
+        config.policy.approve.enabled = true;
+        let timeout = |config: &Config| {
+            ApprovalChannel::from_config(Path::new("railgun.toml"), config)
+                .unwrap()
+                .timeout()
+        };
+        assert_eq!(timeout(&config), Duration::from_secs(30));
+
+        config.policy.approve.timeout_secs = 120;
+        assert_eq!(timeout(&config), Duration::from_secs(MAX_TIMEOUT_SECS));
+        config.policy.approve.timeout_secs = 5;
+        assert_eq!(timeout(&config), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_long_input_is_truncated() {
+        let mut long = input();
+        long.tool_input = serde_json::json!({ "content": "x".repeat(5000) });
+        let request = ApprovalRequest::new(&long, "large write");
+        assert_eq!(request.input.chars().count(), MAX_INPUT_CHARS + 3);
+        assert!(request.input.ends_with("..."));
+    }
+}
@@ -0,0 +1,127 @@
+//! Manage the secrets baseline of known false positives.
+
+use std::path::Path;
+
+use eyre::{Context, Result};
+use rg_policy::baseline::{fingerprint, Baseline, BaselineEntry};
+use rg_policy::SecretScanner;
+use rg_types::Config;
+
+use crate::config_loader;
+
+/// Baseline every secret detected in `text` (or the contents of `file`).
+pub fn run_add(
+    config_path: &str,
+    text: Option<&str>,
+    file: Option<&Path>,
+    note: Option<&str>,
+) -> Result<()> {
+    let mut config = config_loader::load_config(config_path)?;
+    let path = config_loader::baseline_path(Path::new(config_path), &config);
+
+    let content = match (text, file) {
+        (_, Some(file)) => std::fs::read_to_string(file)
+            .with_context(|| format!("Failed to read {}", file.display()))?,
+        (Some(text), None) => text.to_string(),
+        (None, None) => return Err(eyre::eyre!("Provide TEXT or --file to scan for secrets")),
+    };
+
+    // Scan without the existing baseline so already-baselined secrets are reported
+    config.policy.secrets.baseline.clear();
+    let scanner = SecretScanner::new(&config.policy.secrets);
+    let matches = scanner.scan(&content);
+
+    if matches.is_empty() {
+        println!("No secrets detected; nothing to baseline.");
+        return Ok(());
+    }
+
+    let mut baseline = Baseline::load(&path)?;
+    let mut added = 0;
+    for m in matches {
+        if m.secret_type == "private_key" {
+            println!("Skipping {}: private keys cannot be baselined", m.redacted);
+            continue;
+        }
+
+        // Base64 matches cover the encoded blob, so baseline exactly what was matched
+        let Some(secret) = content.get(m.position.clone()) else {
+            continue;
+        };
+        let entry = BaselineEntry {
+            fingerprint: fingerprint(secret),
+            secret_type: m.secret_type,
+            redacted: m.redacted,
+            note: note.map(str::to_string),
+        };
+
+        let (secret_type, redacted) = (entry.secret_type.clone(), entry.redacted.clone());
+        if baseline.insert(entry) {
+            added += 1;
+            println!("Baselined {secret_type}: {redacted}");
+        } else {
+            println!("Already baselined {secret_type}: {redacted}");
+        }
+    }
+
+    if added > 0 {
+        baseline.save(&path)?;
+        println!();
+        println!("Wrote {added} new entries to {}", path.display());
+        resign_note(&config, &path);
+    }
+
+    Ok(())
+}
+
+/// List baselined secrets.
+pub fn run_list(config_path: &str) -> Result<()> {
+    let config = config_loader::load_config(config_path)?;
+    let path = config_loader::baseline_path(Path::new(config_path), &config);
+    let baseline = Baseline::load(&path)?;
+
+    if baseline.entries.is_empty() {
+        println!("No baselined secrets in {}", path.display());
+        return Ok(());
+    }
+
+    for entry in &baseline.entries {
+        print!(
+            "{}  {}  {}",
+            entry.fingerprint, entry.secret_type, entry.redacted
+        );
+        if let Some(ref note) = entry.note {
+            print!("  # {note}");
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Remove a baselined secret by fingerprint.
+pub fn run_remove(config_path: &str, fingerprint: &str) -> Result<()> {
+    let config = config_loader::load_config(config_path)?;
+    let path = config_loader::baseline_path(Path::new(config_path), &config);
+    let mut baseline = Baseline::load(&path)?;
+
+    if !baseline.remove(fingerprint) {
+        eyre::bail!("No baseline entry with fingerprint {fingerprint}");
+    }
+
+    baseline.save(&path)?;
+    println!("Removed {fingerprint} from {}", path.display());
+    resign_note(&config, &path);
+
+    Ok(())
+}
+
+/// Remind to re-sign a changed baseline, which no longer loads otherwise.
+fn resign_note(config: &Config, path: &Path) {
+    if config.policy.require_signed {
+        println!(
+            "policy.require_signed is set: sign it with `railgun sign -k KEY {}`",
+            path.display()
+        );
+    }
+}
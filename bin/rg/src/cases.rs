@@ -0,0 +1,176 @@
+//! Policy regression cases: run a file of tool calls through the policy and
+//! compare each decision with the expected one.
+
+use std::path::Path;
+
+use eyre::{Context, Result};
+use rg_policy::{inspect, RuntimePolicy};
+use rg_types::{HookInput, PolicyCase, RuleAction};
+use serde_json::{json, Value};
+
+/// Longest tool input shown in a case label.
+const MAX_LABEL_INPUT: usize = 60;
+
+/// Outcome of one case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseResult {
+    /// The case's name, or its tool call.
+    pub label: String,
+    /// Expected decision.
+    pub expected: RuleAction,
+    /// Actual decision (`allow`, `ask`, or `deny`).
+    pub actual: &'static str,
+    /// Reason for the actual decision, if any.
+    pub reason: Option<String>,
+}
+
+impl CaseResult {
+    /// Whether the decision matched.
+    pub fn passed(&self) -> bool {
+        self.expected.to_string() == self.actual
+    }
+}
+
+/// Load cases from a YAML (or JSON) list of `{tool_name, tool_input,
+/// expect}` entries.
+pub fn load_cases(path: &Path) -> Result<Vec<PolicyCase>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_yaml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Run every case through the policy.
+pub fn run_cases(policy: &RuntimePolicy, cases: &[PolicyCase]) -> Vec<CaseResult> {
+    cases
+        .iter()
+        .map(|case| {
+            let input = HookInput::new(case.tool_name.clone(), case.tool_input.clone());
+            let (verdict, _) = inspect(&input, policy);
+            CaseResult {
+                label: label(case.name.as_deref(), &case.tool_name, &case.tool_input),
+                expected: case.expect,
+                actual: verdict.permission_decision(),
+                reason: verdict.reason().map(str::to_string),
+            }
+        })
+        .collect()
+}
+
+/// A case's name, or its tool call with a shortened input.
+pub(crate) fn label(name: Option<&str>, tool_name: &str, tool_input: &Value) -> String {
+    if let Some(name) = name {
+        return name.to_string();
+    }
+    let input = tool_input.to_string();
+    if input.chars().count() > MAX_LABEL_INPUT {
+        let short: String = input.chars().take(MAX_LABEL_INPUT).collect();
+        format!("{tool_name} {short}...")
+    } else {
+        format!("{tool_name} {input}")
+    }
+}
+
+/// Format results for the terminal: one line per case, then a summary.
+pub fn format_human(results: &[CaseResult]) -> String {
+    use std::fmt::Write;
+
+    let mut output = String::new();
+    for result in results {
+        if result.passed() {
+            let _ = writeln!(output, "PASS {}", result.label);
+        } else {
+            let _ = writeln!(
+                output,
+                "FAIL {}: expected {}, got {}",
+                result.label, result.expected, result.actual
+            );
+            if let Some(reason) = &result.reason {
+                let _ = writeln!(output, "     {reason}");
+            }
+        }
+    }
+
+    let failed = results.iter().filter(|r| !r.passed()).count();
+    let _ = writeln!(
+        output,
+        "\n{} passed, {failed} failed",
+        results.len() - failed
+    );
+    output
+}
+
+/// Results as JSON: every case, then the totals.
+pub fn to_json(results: &[CaseResult]) -> Value {
+    let failed = results.iter().filter(|r| !r.passed()).count();
+    json!({
+        "results": results
+            .iter()
+            .map(|r| json!({
+                "label": r.label,
+                "expected": r.expected,
+                "actual": r.actual,
+                "passed": r.passed(),
+                "reason": r.reason,
+            }))
+            .collect::<Vec<_>>(),
+        "passed": results.len() - failed,
+        "failed": failed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rg_types::Config;
+
+    const CASES: &str = r#"
+- tool_name: Bash
+  tool_input: { command: "ls -la" }
+  expect: allow
+- name: no root wipe
+  tool_name: Bash
+  tool_input:
+    command: rm -rf /
+  expect: deny
+- tool_name: Bash
+  tool_input: { command: "git push --force origin main" }
+  expect: allow
+"#;
+
+    #[test]
+    fn test_run_cases() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cases.yaml");
+        std::fs::write(&path, CASES).unwrap();
+
+        let cases = load_cases(&path).unwrap();
+        let policy = RuntimePolicy::new(&Config::default());
+        let results = run_cases(&policy, &cases);
+
+        assert_eq!(
+            results.iter().map(CaseResult::passed).collect::<Vec<_>>(),
+            [true, true, false]
+        );
+        assert_eq!(results[1].label, "no root wipe");
+        assert_eq!(results[2].actual, "ask");
+
+        let output = format_human(&results);
+        assert!(output.contains(
+            "FAIL Bash {\"command\":\"git push --force origin main\"}: expected allow, got ask"
+        ));
+        assert!(output.ends_with("2 passed, 1 failed\n"));
+
+        let json = to_json(&results);
+        assert_eq!(json["failed"], 1);
+        assert_eq!(json["results"][1]["expected"], "deny");
+        assert_eq!(json["results"][2]["passed"], false);
+    }
+
+    #[test]
+    fn test_invalid_cases() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cases.yaml");
+        std::fs::write(&path, "- tool_name: Bash\n  expect: maybe\n").unwrap();
+        assert!(load_cases(&path).is_err());
+    }
+}
@@ -1,6 +1,10 @@
 //! CLI argument parsing with clap.
 
-use clap::{Parser, Subcommand};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand, ValueEnum};
 
 /// Railgun - Claude Code LLM Protection Hook
 ///
@@ -16,6 +20,10 @@ pub struct Cli {
     /// Path to configuration file
     #[arg(short, long, default_value = "railgun.toml", global = true)]
     pub config: String,
+
+    /// Apply the config's `[profile.NAME]` section
+    #[arg(short, long, global = true, env = "RAILGUN_PROFILE")]
+    pub profile: Option<String>,
 }
 
 /// Available subcommands
@@ -26,29 +34,390 @@ pub enum Commands {
     /// Exit codes:
     ///   0 - Tool use allowed
     ///   2 - Tool use blocked (reason written to stderr as JSON)
-    Hook,
+    Hook {
+        /// Behavior when the configuration file cannot be loaded
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = ConfigErrorMode::SafeMode,
+            env = "RAILGUN_ON_CONFIG_ERROR"
+        )]
+        on_config_error: ConfigErrorMode,
+
+        /// Forward the payload to a running `railgun daemon`, inspecting
+        /// in-process if none answers
+        #[arg(long)]
+        client: bool,
+
+        /// Agent whose hook payload format to read and answer in
+        #[arg(long, value_enum, default_value_t = Agent::Claude)]
+        agent: Agent,
+    },
+
+    /// Write a commented starter configuration file
+    ///
+    /// Example:
+    ///   railgun init --preset paranoid
+    Init {
+        /// How strict the starting policy is
+        #[arg(long, value_enum, default_value_t = Preset::Balanced)]
+        preset: Preset,
+
+        /// Overwrite an existing configuration file
+        #[arg(long)]
+        force: bool,
+    },
 
     /// Install hook into ~/.claude/settings.json
-    Install,
+    Install {
+        /// Agent to install the hook into
+        #[arg(long, value_enum, default_value_t = Agent::Claude)]
+        agent: Agent,
+        /// Install into this repository's .claude/settings.json instead, to
+        /// commit the hook with the project
+        #[arg(long)]
+        project: bool,
+        /// With --project, use .claude/settings.local.json, which is not
+        /// committed
+        #[arg(long, requires = "project")]
+        local: bool,
+        /// Point existing Railgun hooks at this binary, e.g. after it moved
+        #[arg(long)]
+        update: bool,
+    },
 
     /// Uninstall hook from ~/.claude/settings.json
-    Uninstall,
+    Uninstall {
+        /// Agent to uninstall the hook from
+        #[arg(long, value_enum, default_value_t = Agent::Claude)]
+        agent: Agent,
+        /// Uninstall from this repository's .claude/settings.json instead
+        #[arg(long)]
+        project: bool,
+        /// With --project, use .claude/settings.local.json
+        #[arg(long, requires = "project")]
+        local: bool,
+        /// Remove the hook from user and project settings, and delete the
+        /// global config directory and Railgun's state and caches
+        #[arg(long, conflicts_with = "project")]
+        purge: bool,
+        /// With --purge, list what would be removed without removing it
+        #[arg(long, requires = "purge")]
+        dry_run: bool,
+    },
 
     /// Validate configuration file
-    Lint,
+    Lint {
+        /// Also run the config's `[[tests]]` policy assertions
+        #[arg(long)]
+        run_tests: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = LintFormat::Human)]
+        format: LintFormat,
+    },
+
+    /// Explain a rule: what it matches, why, examples, and how to override it
+    Explain {
+        /// Rule ID or code from a deny reason; lists all codes if omitted
+        rule_id: Option<String>,
+    },
 
-    /// Test policy with a specific tool input
+    /// Diagnose the installation: hook registration, hook binary,
+    /// configuration, and a sample inspection
+    Doctor,
+
+    /// Summarize the effective policy: which config file was loaded, the
+    /// mode, and each scanner's settings and rule counts
+    Status,
+
+    /// Query the audit log of hook decisions
     ///
-    /// Example:
+    /// Requires `policy.audit.enabled`. Examples:
+    ///   railgun log --deny-only --since 2h
+    ///   railgun log --tool Bash --rule aws --format json
+    Log {
+        /// Only show denied calls
+        #[arg(long)]
+        deny_only: bool,
+        /// Only show calls of this tool
+        #[arg(long)]
+        tool: Option<String>,
+        /// Only show calls from the last DURATION, e.g. 30m, 2h, 7d
+        #[arg(long, value_parser = parse_duration)]
+        since: Option<Duration>,
+        /// Only show calls with a rule ID containing RULE
+        #[arg(long)]
+        rule: Option<String>,
+        /// Show at most the last N matching calls
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = LogFormat::Table)]
+        format: LogFormat,
+    },
+
+    /// Re-decide the calls in the audit log and list those whose decision
+    /// would change
+    ///
+    /// Uses this config's audit log, inspected under this config or the one
+    /// given with --policy. Examples:
+    ///   railgun replay --since 7d
+    ///   railgun replay --policy railgun.strict.toml --tool Bash
+    Replay {
+        /// Config to decide the calls under, instead of --config
+        #[arg(long)]
+        policy: Option<PathBuf>,
+        /// Only replay denied calls
+        #[arg(long)]
+        deny_only: bool,
+        /// Only replay calls of this tool
+        #[arg(long)]
+        tool: Option<String>,
+        /// Only replay calls from the last DURATION, e.g. 30m, 2h, 7d
+        #[arg(long, value_parser = parse_duration)]
+        since: Option<Duration>,
+        /// Only replay calls with a rule ID containing RULE
+        #[arg(long)]
+        rule: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = LogFormat::Table)]
+        format: LogFormat,
+    },
+
+    /// Test policy with a specific tool input, or a file of cases
+    ///
+    /// Examples:
     ///   railgun test Bash '{"command":"rm -rf /"}'
+    ///   railgun test --cases cases.yaml
     Test {
         /// Tool name (e.g., "Bash", "Write", "Edit")
-        tool_name: String,
+        #[arg(required_unless_present = "cases")]
+        tool_name: Option<String>,
         /// Tool input as JSON
-        tool_input: String,
+        #[arg(required_unless_present = "cases")]
+        tool_input: Option<String>,
+        /// Run a YAML list of `{tool_name, tool_input, expect}` cases instead
+        #[arg(long, conflicts_with_all = ["tool_name", "tool_input"])]
+        cases: Option<PathBuf>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    /// Scan a Claude Code transcript's tool calls and outputs against the
+    /// policy, for review after the fact
+    ///
+    /// Tool calls are inspected as the hook would now; tool outputs are
+    /// scanned for secrets. Exits 2 if anything was found.
+    ///
+    /// Example:
+    ///   railgun scan-transcript ~/.claude/projects/my-app/SESSION.jsonl
+    ScanTranscript {
+        /// Transcript JSONL file (`transcript_path` in hook payloads)
+        path: PathBuf,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    /// Compare two configs: settings changed, list entries added and
+    /// removed, protections weakened, and corpus calls decided differently
+    ///
+    /// The corpus is the built-in benchmark calls plus both configs'
+    /// `[[tests]]` and any `--cases`. Exits 2 if the new config weakens a
+    /// protection.
+    ///
+    /// Example:
+    ///   railgun diff railgun.toml candidate.toml
+    Diff {
+        /// Config before the change
+        old: PathBuf,
+        /// Config after the change
+        new: PathBuf,
+        /// Add a YAML list of `{tool_name, tool_input, expect}` cases to the
+        /// corpus
+        #[arg(long)]
+        cases: Option<PathBuf>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+    },
+
+    /// Print the JSON Schema of the configuration file
+    ///
+    /// Point an editor at it for completion and validation of railgun.toml,
+    /// or validate configs with it before distributing them.
+    Schema,
+
+    /// Measure policy load time and p50/p95/p99 inspection latency
+    ///
+    /// Runs a built-in corpus of representative tool calls through the
+    /// compiled policy, to check that custom rules keep the hook fast.
+    Bench {
+        /// Passes over the inputs
+        #[arg(short = 'n', long, default_value_t = 1000)]
+        iterations: usize,
+        /// Benchmark the tool calls of a `test --cases` file instead
+        #[arg(long)]
+        cases: Option<PathBuf>,
+    },
+
+    /// Answer the hook's "ask" verdicts from this terminal
+    ///
+    /// Requires `policy.approve.enabled`; the hook falls back to Claude Code's
+    /// prompt while this is not running.
+    Approve,
+
+    /// Keep the compiled policy resident and answer `hook --client` calls
+    ///
+    /// Listens on a socket in the per-user runtime directory
+    /// (`$XDG_RUNTIME_DIR/railgun`), one per project.
+    Daemon {
+        /// Also serve Prometheus metrics at `/metrics` on ADDR, e.g.
+        /// 127.0.0.1:9464
+        #[arg(long, value_name = "ADDR")]
+        metrics: Option<SocketAddr>,
+    },
+
+    /// Generate an Ed25519 key pair for signing policies
+    ///
+    /// Writes the private key to PATH and prints the public key to add to
+    /// `policy.trusted_keys`.
+    Keygen {
+        /// Where to write the private key
+        path: PathBuf,
+    },
+
+    /// Sign config files or policy packs, writing FILE.sig next to each
+    ///
+    /// Example:
+    ///   railgun sign --key ~/railgun.key railgun.toml .railgun/packs/internal.toml
+    Sign {
+        /// Files to sign
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Private key from `railgun keygen`
+        #[arg(short, long)]
+        key: PathBuf,
+    },
+
+    /// Manage the secrets baseline of known false positives
+    Baseline {
+        /// Baseline operation
+        #[command(subcommand)]
+        action: BaselineAction,
     },
 }
 
+/// Secrets baseline operations
+#[derive(Subcommand, Debug)]
+pub enum BaselineAction {
+    /// Baseline every secret detected in TEXT or a file
+    ///
+    /// Example:
+    ///   railgun baseline add --file docs/aws.md --note "AWS docs example keys"
+    Add {
+        /// Text containing the false positives
+        #[arg(required_unless_present = "file")]
+        text: Option<String>,
+        /// Read the text from a file instead
+        #[arg(short, long, conflicts_with = "text")]
+        file: Option<PathBuf>,
+        /// Why these matches are false positives
+        #[arg(short, long)]
+        note: Option<String>,
+    },
+
+    /// List baselined secrets
+    List,
+
+    /// Remove a baselined secret by fingerprint
+    Remove {
+        /// SHA-256 fingerprint shown by `baseline list`
+        fingerprint: String,
+    },
+}
+
+/// Coding agents Railgun can be installed into.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Agent {
+    /// Claude Code (.claude/settings.json)
+    Claude,
+    /// Cursor (.cursor/hooks.json)
+    Cursor,
+    /// Gemini CLI (.gemini/settings.json)
+    Gemini,
+}
+
+/// How the hook reacts to a configuration file that fails to load.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigErrorMode {
+    /// Fall back to the built-in default policy and warn in the hook output
+    SafeMode,
+    /// Deny every tool call until the configuration is fixed
+    Deny,
+}
+
+/// Output format of `railgun test`.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Plain text for the terminal
+    Human,
+    /// A JSON document for CI scripts and editors
+    Json,
+}
+
+/// Output format of `railgun lint`.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintFormat {
+    /// Plain text for the terminal
+    Human,
+    /// A JSON document for CI scripts and editors
+    Json,
+    /// A SARIF 2.1.0 log, for code review tools that annotate findings
+    Sarif,
+}
+
+/// Output format of `railgun log`.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// An aligned table for the terminal
+    Table,
+    /// A JSON array of entries
+    Json,
+}
+
+/// Parse a duration such as `90s`, `30m`, `2h`, `7d`, or `1w`.
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("expected a number and a unit, e.g. 2h, got '{value}'"))?;
+    let secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        "w" => 604_800,
+        _ => return Err(format!("unknown unit in '{value}': use s, m, h, d, or w")),
+    };
+    Ok(Duration::from_secs(amount.saturating_mul(secs)))
+}
+
+/// Starting points for `railgun init`.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preset {
+    /// Deny risky actions outright and turn on the session checks
+    Paranoid,
+    /// The defaults: ask about risky actions, deny destructive ones
+    Balanced,
+    /// Ask only about privilege escalation; deny only destructive actions
+    Permissive,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,25 +425,222 @@ mod tests {
     #[test]
     fn test_cli_hook_command() {
         let cli = Cli::parse_from(["railgun", "hook"]);
-        assert!(matches!(cli.command, Commands::Hook));
+        assert!(matches!(
+            cli.command,
+            Commands::Hook {
+                on_config_error: ConfigErrorMode::SafeMode,
+                client: false,
+                agent: Agent::Claude
+            }
+        ));
+    }
+
+    #[test]
+    fn test_cli_hook_on_config_error() {
+        let cli = Cli::parse_from(["railgun", "hook", "--on-config-error", "deny"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Hook {
+                on_config_error: ConfigErrorMode::Deny,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_cli_hook_client() {
+        let cli = Cli::parse_from(["railgun", "hook", "--client"]);
+        assert!(matches!(cli.command, Commands::Hook { client: true, .. }));
+        let cli = Cli::parse_from(["railgun", "daemon"]);
+        assert!(matches!(cli.command, Commands::Daemon { metrics: None }));
+        let cli = Cli::parse_from(["railgun", "daemon", "--metrics", "127.0.0.1:9464"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Daemon { metrics: Some(addr) } if addr.port() == 9464
+        ));
+    }
+
+    #[test]
+    fn test_cli_init_command() {
+        let cli = Cli::parse_from(["railgun", "init"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Init {
+                preset: Preset::Balanced,
+                force: false
+            }
+        ));
+        let cli = Cli::parse_from(["railgun", "init", "--preset", "paranoid", "--force"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Init {
+                preset: Preset::Paranoid,
+                force: true
+            }
+        ));
+        assert!(Cli::try_parse_from(["railgun", "init", "--preset", "lax"]).is_err());
     }
 
     #[test]
     fn test_cli_install_command() {
         let cli = Cli::parse_from(["railgun", "install"]);
-        assert!(matches!(cli.command, Commands::Install));
+        assert!(matches!(
+            cli.command,
+            Commands::Install {
+                agent: Agent::Claude,
+                project: false,
+                local: false,
+                update: false
+            }
+        ));
+        let cli = Cli::parse_from(["railgun", "install", "--project", "--local"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Install {
+                project: true,
+                local: true,
+                ..
+            }
+        ));
+        assert!(Cli::try_parse_from(["railgun", "install", "--local"]).is_err());
+        let cli = Cli::parse_from(["railgun", "install", "--agent", "cursor"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Install {
+                agent: Agent::Cursor,
+                ..
+            }
+        ));
     }
 
     #[test]
     fn test_cli_uninstall_command() {
         let cli = Cli::parse_from(["railgun", "uninstall"]);
-        assert!(matches!(cli.command, Commands::Uninstall));
+        assert!(matches!(
+            cli.command,
+            Commands::Uninstall {
+                agent: Agent::Claude,
+                project: false,
+                local: false,
+                purge: false,
+                dry_run: false
+            }
+        ));
+        let cli = Cli::parse_from(["railgun", "uninstall", "--project"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Uninstall { project: true, .. }
+        ));
+        let cli = Cli::parse_from(["railgun", "uninstall", "--purge", "--dry-run"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Uninstall {
+                purge: true,
+                dry_run: true,
+                ..
+            }
+        ));
+        assert!(Cli::try_parse_from(["railgun", "uninstall", "--dry-run"]).is_err());
+        assert!(Cli::try_parse_from(["railgun", "uninstall", "--purge", "--project"]).is_err());
     }
 
     #[test]
     fn test_cli_lint_command() {
         let cli = Cli::parse_from(["railgun", "lint"]);
-        assert!(matches!(cli.command, Commands::Lint));
+        assert!(matches!(
+            cli.command,
+            Commands::Lint {
+                run_tests: false,
+                format: LintFormat::Human
+            }
+        ));
+        let cli = Cli::parse_from(["railgun", "lint", "--run-tests", "--format", "json"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Lint {
+                run_tests: true,
+                format: LintFormat::Json
+            }
+        ));
+        let cli = Cli::parse_from(["railgun", "lint", "--format", "sarif"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Lint {
+                format: LintFormat::Sarif,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_cli_doctor_command() {
+        let cli = Cli::parse_from(["railgun", "doctor"]);
+        assert!(matches!(cli.command, Commands::Doctor));
+    }
+
+    #[test]
+    fn test_cli_status_command() {
+        let cli = Cli::parse_from(["railgun", "status"]);
+        assert!(matches!(cli.command, Commands::Status));
+        assert!(cli.profile.is_none());
+
+        let cli = Cli::parse_from(["railgun", "status", "--profile", "work"]);
+        assert_eq!(cli.profile.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn test_cli_explain_command() {
+        let cli = Cli::parse_from(["railgun", "explain", "pipe_to_shell"]);
+        match cli.command {
+            Commands::Explain { rule_id } => assert_eq!(rule_id.as_deref(), Some("pipe_to_shell")),
+            _ => panic!("Expected Explain command"),
+        }
+        let cli = Cli::parse_from(["railgun", "explain"]);
+        assert!(matches!(cli.command, Commands::Explain { rule_id: None }));
+    }
+
+    #[test]
+    fn test_cli_log_command() {
+        let cli = Cli::parse_from([
+            "railgun",
+            "log",
+            "--deny-only",
+            "--tool",
+            "Bash",
+            "--since",
+            "2h",
+            "--rule",
+            "force_push",
+        ]);
+        match cli.command {
+            Commands::Log {
+                deny_only,
+                tool,
+                since,
+                rule,
+                limit,
+                format,
+            } => {
+                assert!(deny_only);
+                assert_eq!(tool.as_deref(), Some("Bash"));
+                assert_eq!(since, Some(Duration::from_secs(7200)));
+                assert_eq!(rule.as_deref(), Some("force_push"));
+                assert_eq!(limit, None);
+                assert_eq!(format, LogFormat::Table);
+            }
+            _ => panic!("Expected Log command"),
+        }
+        assert!(Cli::try_parse_from(["railgun", "log", "--since", "2 hours"]).is_err());
+        assert!(Cli::try_parse_from(["railgun", "log", "--since", "h"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(1800)));
+        assert_eq!(parse_duration("7d"), Ok(Duration::from_secs(604_800)));
+        assert!(parse_duration("2").is_err());
+        assert!(parse_duration("2y").is_err());
     }
 
     #[test]
@@ -84,14 +650,108 @@ mod tests {
             Commands::Test {
                 tool_name,
                 tool_input,
+                cases,
+                format,
             } => {
-                assert_eq!(tool_name, "Bash");
-                assert!(tool_input.contains("command"));
+                assert_eq!(tool_name.as_deref(), Some("Bash"));
+                assert!(tool_input.unwrap().contains("command"));
+                assert!(cases.is_none());
+                assert_eq!(format, OutputFormat::Human);
             }
             _ => panic!("Expected Test command"),
         }
     }
 
+    #[test]
+    fn test_cli_schema_command() {
+        let cli = Cli::parse_from(["railgun", "schema"]);
+        assert!(matches!(cli.command, Commands::Schema));
+    }
+
+    #[test]
+    fn test_cli_bench() {
+        let cli = Cli::parse_from(["railgun", "bench"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Bench {
+                iterations: 1000,
+                cases: None
+            }
+        ));
+
+        let cli = Cli::parse_from(["railgun", "bench", "-n", "50", "--cases", "cases.yaml"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Bench {
+                iterations: 50,
+                cases: Some(_)
+            }
+        ));
+    }
+
+    #[test]
+    fn test_cli_test_cases() {
+        use std::path::Path;
+
+        let cli = Cli::parse_from(["railgun", "test", "--cases", "cases.yaml"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Test { cases: Some(path), tool_name: None, .. } if path == Path::new("cases.yaml")
+        ));
+        let cli = Cli::parse_from(["railgun", "test", "--cases", "c.yaml", "--format", "json"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Test {
+                format: OutputFormat::Json,
+                ..
+            }
+        ));
+        assert!(Cli::try_parse_from(["railgun", "test"]).is_err());
+        assert!(
+            Cli::try_parse_from(["railgun", "test", "Bash", "{}", "--cases", "c.yaml"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_baseline_add() {
+        let cli = Cli::parse_from([
+            "railgun",
+            "baseline",
+            "add",
+            "--file",
+            "docs/aws.md",
+            "--note",
+            "docs example",
+        ]);
+        match cli.command {
+            Commands::Baseline {
+                action: BaselineAction::Add { text, file, note },
+            } => {
+                assert!(text.is_none());
+                assert_eq!(file, Some(PathBuf::from("docs/aws.md")));
+                assert_eq!(note.as_deref(), Some("docs example"));
+            }
+            _ => panic!("Expected Baseline Add command"),
+        }
+
+        assert!(Cli::try_parse_from(["railgun", "baseline", "add"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_sign() {
+        let cli = Cli::parse_from(["railgun", "sign", "-k", "a.key", "railgun.toml", "p.toml"]);
+        match cli.command {
+            Commands::Sign { files, key } => {
+                assert_eq!(files.len(), 2);
+                assert_eq!(key, PathBuf::from("a.key"));
+            }
+            _ => panic!("Expected Sign command"),
+        }
+
+        assert!(Cli::try_parse_from(["railgun", "sign", "-k", "a.key"]).is_err());
+        assert!(Cli::try_parse_from(["railgun", "sign", "railgun.toml"]).is_err());
+    }
+
     #[test]
     fn test_cli_custom_config() {
         let cli = Cli::parse_from(["railgun", "-c", "custom.toml", "hook"]);
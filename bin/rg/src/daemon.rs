@@ -0,0 +1,380 @@
+//! Resident policy server for low-latency hooks.
+//!
+//! `railgun daemon` loads the configuration and compiles the policy once,
+//! then answers hook payloads on a Unix socket in the per-user runtime
+//! directory (see [`config_loader::daemon_socket`]). `railgun hook --client`
+//! forwards its stdin there and prints the answer, skipping config parsing
+//! and pattern compilation; if no daemon answers in time, or the socket's
+//! directory or server isn't this user's, it inspects in-process as usual.
+//!
+//! Before each request the daemon checks the modification times of the
+//! config file and the files it pulls in (rulesets, baselines, blocklists),
+//! and rebuilds the policy if any changed. A config that fails to load is
+//! reported and the previous policy kept.
+//!
+//! With `--metrics ADDR`, decision counts and inspection latency are also
+//! served to Prometheus at `http://ADDR/metrics` (see [`crate::metrics`]).
+//!
+//! The protocol is JSON lines: a [`DaemonRequest`] from the hook, a
+//! [`DaemonAck`] from the daemon as soon as it has read it, then a
+//! [`HookResponse`]. The ack bounds how long the hook waits for the answer,
+//! so a daemon that hangs costs [`FORWARD_TIMEOUT`] and one waiting on
+//! `railgun approve` is given the approval timeout, both well within the
+//! hook timeout [`crate::install`] writes.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::SocketAddr;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use eyre::{Context, Result};
+use rg_policy::RuntimePolicy;
+use rg_types::Config;
+use serde::{Deserialize, Serialize};
+
+use crate::hook::{self, HookResponse, HookState};
+use crate::metrics::{self, Metrics};
+use crate::{approve, config_loader, hook_policy};
+
+/// A hook payload forwarded by `railgun hook --client`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonRequest {
+    /// The hook's stdin, verbatim.
+    pub payload: String,
+}
+
+/// Sent by the daemon once it has read a [`DaemonRequest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonAck {
+    /// How long the answer may take, in milliseconds.
+    pub answer_within_ms: u64,
+}
+
+/// How long `hook --client` waits for the daemon to take a request, and for
+/// an answer that doesn't wait on `railgun approve`, before inspecting
+/// in-process. A few seconds, so a hung daemon leaves the in-process
+/// fallback most of the hook timeout.
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Send a payload to the daemon and wait for its answer.
+pub fn forward(socket: &Path, payload: &str) -> io::Result<HookResponse> {
+    forward_within(socket, payload, FORWARD_TIMEOUT)
+}
+
+/// Send a payload to the daemon and wait up to `timeout` for its answer.
+fn forward_within(socket: &Path, payload: &str, timeout: Duration) -> io::Result<HookResponse> {
+    let mut stream = approve::connect(socket, timeout)?;
+    let request = DaemonRequest {
+        payload: payload.to_string(),
+    };
+    writeln!(stream, "{}", serde_json::to_string(&request)?)?;
+
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    let _ = reader.read_line(&mut line)?;
+    let ack: DaemonAck = serde_json::from_str(&line)?;
+    reader
+        .get_ref()
+        .set_read_timeout(Some(Duration::from_millis(ack.answer_within_ms.max(1))))?;
+
+    line.clear();
+    let _ = reader.read_line(&mut line)?;
+    Ok(serde_json::from_str(&line)?)
+}
+
+/// How long the daemon may take to answer under `config`: an `ask` may wait
+/// out `railgun approve`.
+fn answer_within(config_path: &Path, config: &Config) -> Duration {
+    let approve = approve::ApprovalChannel::from_config(config_path, config)
+        .map_or(Duration::ZERO, |channel| channel.timeout());
+    FORWARD_TIMEOUT + approve
+}
+
+/// The policy and hook state, shared by the connections being served, and
+/// how long an answer may take.
+type Served = Arc<(RuntimePolicy, HookState, Duration)>;
+
+/// The policy the daemon serves, rebuilt when its source files change.
+struct Resident {
+    /// Config file path, as given on the command line.
+    config_path: PathBuf,
+    /// Current policy, and what it was built from.
+    loaded: Mutex<Loaded>,
+    /// Metrics, kept across rebuilds.
+    metrics: Option<Arc<Metrics>>,
+}
+
+/// A built policy and the files it came from.
+struct Loaded {
+    /// Source files (see [`config_loader::source_files`]).
+    files: Vec<PathBuf>,
+    /// Modification times of `files` when last checked.
+    stamps: Vec<Option<SystemTime>>,
+    /// The policy built from them.
+    served: Served,
+}
+
+impl Resident {
+    /// Load the config and build the policy, recording into `metrics`.
+    fn load(config_path: &Path, metrics: Option<Arc<Metrics>>) -> Result<Self> {
+        let config = config_loader::load_config(config_path)?;
+        let files = config_loader::source_files(config_path, &config);
+        Ok(Self {
+            config_path: config_path.to_path_buf(),
+            loaded: Mutex::new(Loaded {
+                stamps: stamps(&files),
+                files,
+                served: build(config_path, &config, metrics.as_ref()),
+            }),
+            metrics,
+        })
+    }
+
+    /// The current policy, rebuilt first if a source file changed.
+    fn current(&self) -> Served {
+        let mut loaded = self
+            .loaded
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let now = stamps(&loaded.files);
+        if now != loaded.stamps {
+            // Record the new times either way, so a broken config is
+            // reported once rather than on every request
+            loaded.stamps = now;
+            match config_loader::load_config(&self.config_path) {
+                Ok(config) => {
+                    loaded.files = config_loader::source_files(&self.config_path, &config);
+                    loaded.stamps = stamps(&loaded.files);
+                    loaded.served = build(&self.config_path, &config, self.metrics.as_ref());
+                    eprintln!("Reloaded {}", self.config_path.display());
+                }
+                Err(e) => eprintln!("Warning: keeping the previous policy: {e:#}"),
+            }
+        }
+        Arc::clone(&loaded.served)
+    }
+}
+
+/// Build the policy and hook state for a config.
+fn build(config_path: &Path, config: &Config, metrics: Option<&Arc<Metrics>>) -> Served {
+    let (policy, mut state) = hook_policy(config_path, config);
+    state.metrics = metrics.cloned();
+    Arc::new((policy, state, answer_within(config_path, config)))
+}
+
+/// Modification times of files; `None` for missing ones.
+fn stamps(files: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    files
+        .iter()
+        .map(|f| std::fs::metadata(f).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+/// Run `railgun daemon`: serve hook payloads until killed, and metrics at
+/// `metrics_addr` if given.
+pub fn run_daemon(config_path: &str, metrics_addr: Option<SocketAddr>) -> Result<()> {
+    let metrics = metrics_addr.map(|_| Arc::new(Metrics::default()));
+    let resident = Arc::new(Resident::load(Path::new(config_path), metrics.clone())?);
+
+    let socket = config_loader::daemon_socket(Path::new(config_path));
+    let listener = approve::bind(&socket, "railgun daemon")?;
+    if let (Some(addr), Some(metrics)) = (metrics_addr, metrics) {
+        let addr = metrics::serve(addr, metrics)?;
+        println!("Serving metrics on http://{addr}/metrics");
+    }
+    println!("Serving hooks on {}", socket.display());
+    println!("Press Ctrl-C to stop.");
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Warning: {e}");
+                continue;
+            }
+        };
+        // Requests are independent; one waiting on `railgun approve` must not
+        // hold up the others
+        let resident = Arc::clone(&resident);
+        let _ = std::thread::spawn(move || {
+            let served = resident.current();
+            let (policy, state, within) = &*served;
+            let result = serve_one(stream, policy, state, *within);
+            if let Err(e) = result {
+                eprintln!("Warning: {e:#}");
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Answer one forwarded payload, promising to within `within`.
+fn serve_one(
+    stream: UnixStream,
+    policy: &RuntimePolicy,
+    state: &HookState,
+    within: Duration,
+) -> Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    let _ = reader.read_line(&mut line)?;
+    let request: DaemonRequest = serde_json::from_str(&line).context("Invalid hook request")?;
+
+    let mut stream = stream;
+    let ack = DaemonAck {
+        answer_within_ms: u64::try_from(within.as_millis()).unwrap_or(u64::MAX),
+    };
+    writeln!(stream, "{}", serde_json::to_string(&ack)?)
+        .context("Failed to acknowledge hook request")?;
+
+    let response = hook::handle(policy, state, None, &request.payload);
+    writeln!(stream, "{}", serde_json::to_string(&response)?)
+        .context("Failed to send hook response")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rg_types::PolicyConfig;
+
+    fn serve(socket: &Path, requests: usize) -> std::thread::JoinHandle<()> {
+        let listener = approve::bind(socket, "railgun daemon").unwrap();
+        std::thread::spawn(move || {
+            let policy = RuntimePolicy::from_config(&PolicyConfig::default());
+            let state = HookState::default();
+            for stream in listener.incoming().take(requests) {
+                serve_one(stream.unwrap(), &policy, &state, FORWARD_TIMEOUT).unwrap();
+            }
+        })
+    }
+
+    #[test]
+    fn test_forward() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("daemon.sock");
+        let server = serve(&socket, 2);
+
+        let allow = forward(
+            &socket,
+            r#"{"tool_name":"Bash","tool_input":{"command":"ls"}}"#,
+        )
+        .unwrap();
+        assert_eq!(allow.code, 0);
+        assert_eq!(
+            allow.output.unwrap()["hookSpecificOutput"]["permissionDecision"],
+            "allow"
+        );
+
+        let deny = forward(
+            &socket,
+            "{\"tool_name\": \"Bash\",\n \"tool_input\": {\"command\": \"rm -rf /\"}}\n",
+        )
+        .unwrap();
+        assert_eq!(deny.code, 2);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_reload_on_config_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("railgun.toml");
+        std::fs::write(&config_path, "").unwrap();
+        let resident = Resident::load(&config_path, None).unwrap();
+
+        let decision = |resident: &Resident| {
+            let served = resident.current();
+            let (policy, state, _) = &*served;
+            let payload =
+                r#"{"tool_name":"Bash","tool_input":{"command":"curl https://example.com"}}"#;
+            hook::handle(policy, state, None, payload).code
+        };
+        assert_eq!(decision(&resident), 0);
+
+        let touch = |content: &str, secs: u64| {
+            std::fs::write(&config_path, content).unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&config_path)
+                .unwrap()
+                .set_modified(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs))
+                .unwrap();
+        };
+        touch("[policy.network]\nblock_domains = [\"example.com\"]\n", 1);
+        assert_eq!(decision(&resident), 2);
+
+        // A broken config keeps the last good policy
+        touch("[policy.network\n", 2);
+        assert_eq!(decision(&resident), 2);
+    }
+
+    #[test]
+    fn test_forward_without_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(forward(&dir.path().join("daemon.sock"), "{}").is_err());
+    }
+
+    #[test]
+    fn test_forward_times_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("daemon.sock");
+        // Listening but never answering
+        let _listener = approve::bind(&socket, "railgun daemon").unwrap();
+
+        let err = forward_within(&socket, "{}", Duration::from_millis(100)).unwrap_err();
+        assert!(
+            matches!(
+                err.kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            ),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_forward_waits_only_as_promised() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("daemon.sock");
+        let listener = approve::bind(&socket, "railgun daemon").unwrap();
+        // Takes the request, then never answers
+        let _server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut line = String::new();
+            let _ = BufReader::new(&stream).read_line(&mut line).unwrap();
+            writeln!(&stream, r#"{{"answer_within_ms":100}}"#).unwrap();
+            std::thread::sleep(Duration::from_secs(5));
+        });
+
+        let start = std::time::Instant::now();
+        assert!(forward(&socket, "{}").is_err());
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_waits_fit_in_hook_timeout() {
+        // Through the daemon, then in-process after it gives up
+        let approve = Duration::from_secs(approve::MAX_TIMEOUT_SECS);
+        let worst = FORWARD_TIMEOUT + (FORWARD_TIMEOUT + approve) + approve;
+        assert!(worst < Duration::from_secs(crate::install::HOOK_TIMEOUT_SECS) / 4 * 3);
+    }
+
+    #[test]
+    fn test_forward_refuses_shared_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("daemon.sock");
+        let server = serve(&socket, 0);
+        server.join().unwrap();
+        std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o777)).unwrap();
+
+        let err = forward(&socket, "{}").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(
+            err.to_string().contains("accessible to other users"),
+            "{err}"
+        );
+    }
+}
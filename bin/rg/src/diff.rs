@@ -0,0 +1,356 @@
+//! `railgun diff`: compare two configs by what they enforce.
+//!
+//! Both configs are loaded as the hook loads them (defaults filled in, packs,
+//! rulesets, and baselines merged), so the comparison is between effective
+//! policies, not TOML text. The report lists list entries added and removed
+//! and settings changed, the protections the new config weakens (the
+//! settings a locked global config would restore, see [`floor::enforce`]),
+//! and the tool calls of a test corpus whose decision changes.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use eyre::Result;
+use rg_policy::{inspect, RuntimePolicy};
+use rg_types::{Config, HookInput, PolicyCase};
+use serde_json::{json, Value};
+
+use crate::{bench, cases, config_loader, floor};
+
+/// A list entry present in only one config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Dotted path of the list, e.g. `policy.commands.block_patterns`.
+    pub path: String,
+    /// The entry.
+    pub value: Value,
+}
+
+/// A setting with different values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Changed {
+    /// Dotted path of the setting.
+    pub path: String,
+    /// Value in the old config.
+    pub old: Value,
+    /// Value in the new config.
+    pub new: Value,
+}
+
+/// A corpus call decided differently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerdictChange {
+    /// The case's name, or its tool call.
+    pub label: String,
+    /// Decision under the old config.
+    pub old: &'static str,
+    /// Decision under the new config.
+    pub new: &'static str,
+    /// Reason for the new decision, or the old one if the new is `allow`.
+    pub reason: Option<String>,
+}
+
+/// Differences between two configs.
+#[derive(Debug, Default)]
+pub struct Diff {
+    /// Protections the new config weakens, as `name: reason` lines.
+    pub weakened: Vec<String>,
+    /// List entries only in the new config.
+    pub added: Vec<Entry>,
+    /// List entries only in the old config.
+    pub removed: Vec<Entry>,
+    /// Settings with different values.
+    pub changed: Vec<Changed>,
+    /// Corpus calls inspected.
+    pub calls: usize,
+    /// Corpus calls decided differently.
+    pub verdicts: Vec<VerdictChange>,
+}
+
+impl Diff {
+    /// Whether the configs enforce the same policy.
+    pub fn is_empty(&self) -> bool {
+        self.weakened.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.verdicts.is_empty()
+    }
+}
+
+/// Compare two configs. The corpus is the built-in one, both configs'
+/// `[[tests]]`, and `cases`.
+pub fn diff(old: &Config, new: &Config, cases: &[PolicyCase]) -> Diff {
+    let mut diff = Diff {
+        weakened: floor::enforce(old, &mut new.clone()),
+        ..Diff::default()
+    };
+
+    // `[[tests]]` describe the policy rather than set it
+    let settings = |config: &Config| {
+        let mut value = serde_json::to_value(config).unwrap_or_default();
+        if let Some(fields) = value.as_object_mut() {
+            let _ = fields.remove("tests");
+        }
+        value
+    };
+    compare("", &settings(old), &settings(new), &mut diff);
+
+    let mut corpus: Vec<(String, HookInput)> = bench::corpus()
+        .into_iter()
+        .map(|input| {
+            let label = cases::label(None, &input.tool_name, &input.tool_input);
+            (label, input)
+        })
+        .collect();
+    for case in old.tests.iter().chain(&new.tests).chain(cases) {
+        let input = HookInput::new(case.tool_name.clone(), case.tool_input.clone());
+        let duplicate = corpus.iter().any(|(_, seen)| {
+            seen.tool_name == input.tool_name && seen.tool_input == input.tool_input
+        });
+        if !duplicate {
+            let label = cases::label(case.name.as_deref(), &case.tool_name, &case.tool_input);
+            corpus.push((label, input));
+        }
+    }
+
+    // Bare policies: monitor mode would decide every call `allow`
+    let (old_policy, new_policy) = (RuntimePolicy::new(old), RuntimePolicy::new(new));
+    diff.calls = corpus.len();
+    for (label, input) in corpus {
+        let (before, _) = inspect(&input, &old_policy);
+        let (after, _) = inspect(&input, &new_policy);
+        let (old, new) = (before.permission_decision(), after.permission_decision());
+        if old != new {
+            diff.verdicts.push(VerdictChange {
+                label,
+                old,
+                new,
+                reason: after.reason().or(before.reason()).map(str::to_string),
+            });
+        }
+    }
+
+    diff
+}
+
+/// Compare two setting values at `path`, recording what differs.
+fn compare(path: &str, old: &Value, new: &Value, diff: &mut Diff) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, value) in old {
+                compare(
+                    &join(key),
+                    value,
+                    new.get(key).unwrap_or(&Value::Null),
+                    diff,
+                );
+            }
+            for (key, value) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+                compare(&join(key), &Value::Null, value, diff);
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            let only = |a: &[Value], b: &[Value]| -> Vec<Entry> {
+                a.iter()
+                    .filter(|value| !b.contains(value))
+                    .map(|value| Entry {
+                        path: path.to_string(),
+                        value: value.clone(),
+                    })
+                    .collect()
+            };
+            diff.added.extend(only(new, old));
+            diff.removed.extend(only(old, new));
+        }
+        (old, new) if old != new => diff.changed.push(Changed {
+            path: path.to_string(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Load both configs and compare them, with the cases in `cases_path`
+/// added to the corpus.
+pub fn run_diff(old_path: &Path, new_path: &Path, cases_path: Option<&Path>) -> Result<Diff> {
+    let old = config_loader::load_config(old_path)?;
+    let new = config_loader::load_config(new_path)?;
+    let cases = match cases_path {
+        Some(path) => cases::load_cases(path)?,
+        None => Vec::new(),
+    };
+    Ok(diff(&old, &new, &cases))
+}
+
+/// Format the differences for the terminal, one section per kind.
+pub fn format_human(diff: &Diff) -> String {
+    if diff.is_empty() {
+        return format!(
+            "No differences ({} corpus calls decided the same).\n",
+            diff.calls
+        );
+    }
+
+    let mut output = String::new();
+    let mut section = |title: &str, lines: Vec<String>| {
+        if !lines.is_empty() {
+            let _ = writeln!(output, "{title}:");
+            for line in lines {
+                let _ = writeln!(output, "  {line}");
+            }
+            output.push('\n');
+        }
+    };
+    section("Weakened", diff.weakened.clone());
+    section(
+        "Added",
+        diff.added
+            .iter()
+            .map(|e| format!("{}: {}", e.path, e.value))
+            .collect(),
+    );
+    section(
+        "Removed",
+        diff.removed
+            .iter()
+            .map(|e| format!("{}: {}", e.path, e.value))
+            .collect(),
+    );
+    section(
+        "Changed",
+        diff.changed
+            .iter()
+            .map(|c| format!("{}: {} -> {}", c.path, c.old, c.new))
+            .collect(),
+    );
+    section(
+        &format!("Verdicts ({} corpus calls)", diff.calls),
+        diff.verdicts
+            .iter()
+            .map(|v| match &v.reason {
+                Some(reason) => format!("{}: {} -> {}\n    {reason}", v.label, v.old, v.new),
+                None => format!("{}: {} -> {}", v.label, v.old, v.new),
+            })
+            .collect(),
+    );
+
+    let _ = writeln!(
+        output,
+        "{} weakened, {} added, {} removed, {} changed, {} verdicts changed",
+        diff.weakened.len(),
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len(),
+        diff.verdicts.len()
+    );
+    output
+}
+
+/// The differences as JSON.
+pub fn to_json(diff: &Diff) -> Value {
+    let entries = |entries: &[Entry]| {
+        entries
+            .iter()
+            .map(|e| json!({ "path": e.path, "value": e.value }))
+            .collect::<Vec<_>>()
+    };
+    json!({
+        "weakened": diff.weakened,
+        "added": entries(&diff.added),
+        "removed": entries(&diff.removed),
+        "changed": diff
+            .changed
+            .iter()
+            .map(|c| json!({ "path": c.path, "old": c.old, "new": c.new }))
+            .collect::<Vec<_>>(),
+        "calls": diff.calls,
+        "verdicts": diff
+            .verdicts
+            .iter()
+            .map(|v| json!({
+                "label": v.label,
+                "old": v.old,
+                "new": v.new,
+                "reason": v.reason,
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rg_types::RuleAction;
+
+    #[test]
+    fn test_identical() {
+        let diff = diff(&Config::default(), &Config::default(), &[]);
+        assert!(diff.is_empty());
+        assert!(diff.calls > 0);
+        assert!(format_human(&diff).starts_with("No differences ("));
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = Config::default();
+        let mut new = Config::default();
+        new.policy.commands.git.action = RuleAction::Allow;
+        new.policy.writes.max_bytes = 1024;
+        new.tools.deny.push("WebFetch".to_string());
+        new.policy
+            .network
+            .block_domains
+            .retain(|d| d != "pastebin.com");
+        let case: PolicyCase = serde_json::from_value(json!({
+            "name": "fetch docs",
+            "tool_name": "WebFetch",
+            "tool_input": { "url": "https://docs.rs", "prompt": "x" },
+            "expect": "allow",
+        }))
+        .unwrap();
+
+        let diff = diff(&old, &new, &[case]);
+        assert!(diff
+            .weakened
+            .contains(&"policy.commands.git.action: relaxed from ask to allow".to_string()));
+        assert_eq!(
+            diff.added,
+            [Entry {
+                path: "tools.deny".to_string(),
+                value: json!("WebFetch"),
+            }]
+        );
+        assert_eq!(diff.removed[0].path, "policy.network.block_domains");
+        assert!(diff.changed.contains(&Changed {
+            path: "policy.writes.max_bytes".to_string(),
+            old: json!(1_048_576),
+            new: json!(1024),
+        }));
+
+        let labels: Vec<&str> = diff.verdicts.iter().map(|v| v.label.as_str()).collect();
+        assert!(labels.contains(&"fetch docs"));
+        let push = diff
+            .verdicts
+            .iter()
+            .find(|v| v.label.contains("git push --force"))
+            .unwrap();
+        assert_eq!((push.old, push.new), ("ask", "allow"));
+
+        let human = format_human(&diff);
+        assert!(human.contains("Added:\n  tools.deny: \"WebFetch\"\n"));
+        assert!(human.contains("  policy.writes.max_bytes: 1048576 -> 1024\n"));
+        assert_eq!(
+            to_json(&diff)["verdicts"].as_array().unwrap().len(),
+            diff.verdicts.len()
+        );
+    }
+}
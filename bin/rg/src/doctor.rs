@@ -0,0 +1,316 @@
+//! Diagnose the installation: hook registration, configuration, and a
+//! sample inspection.
+
+use std::path::Path;
+
+use rg_policy::RuntimePolicy;
+use rg_types::PolicyMode;
+use serde_json::Value;
+
+use crate::hook::{self, HookState};
+use crate::{config_loader, install, lint};
+
+/// Outcome of a check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Working as expected.
+    Ok,
+    /// Works, but probably not as intended.
+    Warn,
+    /// Broken; the hook will not protect anything (or will block everything).
+    Fail,
+}
+
+/// One diagnostic check.
+#[derive(Debug, Clone)]
+pub struct Check {
+    /// What was checked, e.g. `hook`.
+    pub name: &'static str,
+    /// Outcome.
+    pub status: Status,
+    /// What was found.
+    pub detail: String,
+    /// How to fix it, for warnings and failures.
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warn,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// Run every check against a settings file and config path.
+pub fn run_checks(settings_path: &Path, config_path: &Path) -> Vec<Check> {
+    let mut checks = check_hook(settings_path);
+    checks.extend(check_config(config_path));
+    checks
+}
+
+/// Check that the hook is registered for every event, with a binary that
+/// still exists.
+fn check_hook(settings_path: &Path) -> Vec<Check> {
+    let settings: Value = match std::fs::read_to_string(settings_path) {
+        Ok(content) => match serde_json::from_str(&content) {
+            Ok(settings) => settings,
+            Err(e) => {
+                return vec![Check::fail(
+                    "hook",
+                    format!("{} is not valid JSON: {e}", settings_path.display()),
+                    "Fix the JSON syntax, then run `railgun install`",
+                )]
+            }
+        },
+        Err(_) => {
+            return vec![Check::fail(
+                "hook",
+                format!("{} not found", settings_path.display()),
+                "Run `railgun install`",
+            )]
+        }
+    };
+
+    let mut commands = Vec::new();
+    let mut missing = Vec::new();
+    for event in install::HOOK_EVENTS {
+        let command = settings["hooks"][*event]
+            .as_array()
+            .and_then(|entries| entries.iter().find_map(install::railgun_command));
+        match command {
+            Some(command) => commands.push(command),
+            None => missing.push(*event),
+        }
+    }
+    if commands.is_empty() {
+        return vec![Check::fail(
+            "hook",
+            format!("Railgun is not registered in {}", settings_path.display()),
+            "Run `railgun install`",
+        )];
+    }
+
+    let mut checks = Vec::new();
+    commands.sort();
+    commands.dedup();
+    for command in &commands {
+        let binary = install::hook_binary(command);
+        if install::resolve_binary(binary).is_none() {
+            checks.push(Check::fail(
+                "hook",
+                format!("hook binary '{binary}' does not exist"),
+                "Run `railgun install --update` from the current binary to update the path",
+            ));
+        }
+    }
+    if checks.is_empty() {
+        checks.push(Check::ok(
+            "hook",
+            format!("runs `{}`", commands.join("`, `")),
+        ));
+    }
+    if !missing.is_empty() {
+        checks.push(Check::warn(
+            "hook",
+            format!("not registered for {}", missing.join(", ")),
+            "Run `railgun install` to register the missing events",
+        ));
+    }
+    checks
+}
+
+/// Check that the config loads, its patterns compile, and a sample call
+/// round-trips through the hook.
+fn check_config(config_path: &Path) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let config = match config_loader::load_config(config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            return vec![Check::fail(
+                "config",
+                format!("{} failed to load: {e:#}", config_path.display()),
+                "Fix the error (the hook runs in safe mode until then)",
+            )]
+        }
+    };
+    if config_path.exists() {
+        checks.push(Check::ok(
+            "config",
+            format!("{} loads", config_path.display()),
+        ));
+
+        let lint = lint::lint_config(config_path);
+        let errors: Vec<String> = lint
+            .issues
+            .iter()
+            .filter(|issue| issue.severity == lint::Severity::Error)
+            .map(|issue| issue.message.clone())
+            .collect();
+        if errors.is_empty() {
+            checks.push(Check::ok("patterns", "all patterns compile"));
+        } else {
+            checks.push(Check::fail(
+                "patterns",
+                errors.join("; "),
+                "Fix the patterns; invalid ones are skipped, so they match nothing",
+            ));
+        }
+    } else {
+        checks.push(Check::warn(
+            "config",
+            format!(
+                "{} not found; using the global or built-in policy",
+                config_path.display()
+            ),
+            "Run `railgun init` to create a project config",
+        ));
+    }
+
+    if config.policy.mode == PolicyMode::Monitor {
+        checks.push(Check::warn(
+            "mode",
+            "monitor mode: violations are logged, not blocked",
+            "Set `mode = \"strict\"` under [policy] once you are done evaluating",
+        ));
+    }
+
+    let policy = RuntimePolicy::new(&config);
+    let payload = r#"{"tool_name":"Bash","tool_input":{"command":"rm -rf /"}}"#;
+    let response = hook::handle(&policy, &HookState::default(), None, payload);
+    let decision = response
+        .output
+        .as_ref()
+        .and_then(|o| o["hookSpecificOutput"]["permissionDecision"].as_str())
+        .unwrap_or_default()
+        .to_string();
+    checks.push(match decision.as_str() {
+        "deny" if response.code == 2 => Check::ok("inspect", "sample `rm -rf /` is denied"),
+        "" => Check::fail(
+            "inspect",
+            "sample call produced no decision",
+            "Report this as a bug, with the output of `railgun test Bash '{\"command\":\"rm -rf /\"}'`",
+        ),
+        other => Check::warn(
+            "inspect",
+            format!("sample `rm -rf /` got '{other}' instead of 'deny'"),
+            "Check that [policy.commands] is enabled and `rm -rf /` is not in allow_patterns",
+        ),
+    });
+    checks
+}
+
+/// Format checks for the terminal.
+pub fn format_human(checks: &[Check]) -> String {
+    use std::fmt::Write;
+
+    let mut output = String::new();
+    for check in checks {
+        let label = match check.status {
+            Status::Ok => "ok",
+            Status::Warn => "warn",
+            Status::Fail => "fail",
+        };
+        let _ = writeln!(output, "[{label}] {}: {}", check.name, check.detail);
+        if let Some(fix) = &check.fix {
+            let _ = writeln!(output, "       fix: {fix}");
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statuses(checks: &[Check]) -> Vec<(&'static str, Status)> {
+        checks.iter().map(|c| (c.name, c.status)).collect()
+    }
+
+    #[test]
+    fn test_missing_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let checks = check_hook(&dir.path().join("settings.json"));
+        assert_eq!(statuses(&checks), [("hook", Status::Fail)]);
+        assert_eq!(checks[0].fix.as_deref(), Some("Run `railgun install`"));
+    }
+
+    #[test]
+    fn test_stale_binary_and_missing_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = dir.path().join("settings.json");
+        let entry = |command: &str| serde_json::json!([{ "hooks": [{ "command": command }] }]);
+        std::fs::write(
+            &settings,
+            serde_json::json!({ "hooks": {
+                "PreToolUse": entry("/nonexistent/railgun hook"),
+                "PostToolUse": entry("/nonexistent/railgun hook"),
+            }})
+            .to_string(),
+        )
+        .unwrap();
+
+        let checks = check_hook(&settings);
+        assert_eq!(
+            statuses(&checks),
+            [("hook", Status::Fail), ("hook", Status::Warn)]
+        );
+        assert!(checks[1].detail.contains("SessionStart, SessionEnd"));
+    }
+
+    #[test]
+    fn test_config_checks() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("railgun.toml");
+
+        assert_eq!(
+            statuses(&check_config(&config)),
+            [("config", Status::Warn), ("inspect", Status::Ok)]
+        );
+
+        std::fs::write(
+            &config,
+            "[policy.commands]\nblock_patterns = [\"(unclosed\"]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            statuses(&check_config(&config)),
+            [
+                ("config", Status::Ok),
+                ("patterns", Status::Fail),
+                ("inspect", Status::Ok)
+            ]
+        );
+
+        std::fs::write(&config, "[policy.commands]\nenabled = false\n").unwrap();
+        assert_eq!(
+            check_config(&config).last().map(|c| c.status),
+            Some(Status::Warn)
+        );
+
+        std::fs::write(&config, "[policy\n").unwrap();
+        assert_eq!(statuses(&check_config(&config)), [("config", Status::Fail)]);
+    }
+}
@@ -0,0 +1,598 @@
+//! Keep a project config from weakening a locked global config.
+//!
+//! With `locked = true` under `[policy]` in the global config, the global
+//! policy is a floor: after the project config is merged over it, every
+//! setting the project made weaker is put back. Scanners can't be turned
+//! off, remembered approvals can't be turned on, actions can't move toward
+//! `allow`, size caps and the latency budget can't be raised, block lists
+//! can't lose entries, and allow lists (including secrets baselines) can't
+//! gain any.
+//!
+//! `railgun lint` runs the same comparison against the built-in defaults to
+//! flag configs that weaken them.
+
+use rg_types::{Config, PolicyMode, RuleAction, SecretAction};
+
+/// Settings put back to the global value, as `name: reason` lines.
+#[derive(Debug, Default)]
+struct Floor {
+    restored: Vec<String>,
+}
+
+impl Floor {
+    /// A scanner or check the global config turns on stays on.
+    fn keep_on(&mut self, name: &str, global: bool, project: &mut bool) {
+        if global && !*project {
+            *project = true;
+            self.restored.push(format!("{name}: turned off"));
+        }
+    }
+
+    /// A feature that allows more, off in the global config, stays off.
+    fn keep_off(&mut self, name: &str, global: bool, project: &mut bool) {
+        if !global && *project {
+            *project = false;
+            self.restored.push(format!("{name}: turned on"));
+        }
+    }
+
+    /// A size cap stays at most the global one.
+    fn keep_cap(&mut self, name: &str, global: usize, project: &mut usize) {
+        if *project > global {
+            *project = global;
+            self.restored.push(format!("{name}: raised"));
+        }
+    }
+
+    /// An action stays at least as strict as the global one.
+    fn keep_action(&mut self, name: &str, global: RuleAction, project: &mut RuleAction) {
+        if *project < global {
+            self.restored
+                .push(format!("{name}: relaxed from {global} to {project}"));
+            *project = global;
+        }
+    }
+
+    /// A block list keeps every global entry.
+    fn keep_entries<T: PartialEq + Clone>(
+        &mut self,
+        name: &str,
+        global: &[T],
+        project: &mut Vec<T>,
+    ) {
+        let missing: Vec<T> = global
+            .iter()
+            .filter(|entry| !project.contains(entry))
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            self.restored
+                .push(format!("{name}: drops {} entries", missing.len()));
+            project.extend(missing);
+        }
+    }
+
+    /// An allow list gains no entries beyond the global ones.
+    fn drop_entries<T: PartialEq>(&mut self, name: &str, global: &[T], project: &mut Vec<T>) {
+        let before = project.len();
+        project.retain(|entry| global.contains(entry));
+        if project.len() < before {
+            self.restored
+                .push(format!("{name}: adds {} entries", before - project.len()));
+        }
+    }
+}
+
+/// Order secret actions by strictness: `deny` over `ask` and `redact`.
+fn secret_strictness(action: SecretAction) -> u8 {
+    match action {
+        SecretAction::Redact | SecretAction::Ask => 0,
+        SecretAction::Deny => 1,
+    }
+}
+
+/// Put back every setting of `config` that is weaker than in `global`.
+/// Returns a line per restored setting.
+pub fn enforce(global: &Config, config: &mut Config) -> Vec<String> {
+    let mut floor = Floor::default();
+    let (g, p) = (&global.policy, &mut config.policy);
+
+    if g.mode == PolicyMode::Strict && p.mode == PolicyMode::Monitor {
+        p.mode = PolicyMode::Strict;
+        floor
+            .restored
+            .push("policy.mode: relaxed to monitor".to_string());
+    }
+    floor.keep_on("policy.fail_closed", g.fail_closed, &mut p.fail_closed);
+    floor.keep_on(
+        "policy.normalize_unicode",
+        g.normalize_unicode,
+        &mut p.normalize_unicode,
+    );
+    floor.keep_entries("policy.packs", &g.packs, &mut p.packs);
+    // Past the budget, checks are skipped for a fail-closed verdict; without
+    // one, a slow hook can outlast the agent's hook timeout
+    if let Some(budget) = g.max_latency_ms {
+        if p.max_latency_ms.map_or(true, |ms| ms > budget) {
+            p.max_latency_ms = Some(budget);
+            floor
+                .restored
+                .push("policy.max_latency_ms: raised".to_string());
+        }
+    }
+    floor.keep_cap(
+        "policy.limits.max_input_bytes",
+        g.limits.max_input_bytes,
+        &mut p.limits.max_input_bytes,
+    );
+    floor.keep_cap(
+        "policy.limits.max_field_bytes",
+        g.limits.max_field_bytes,
+        &mut p.limits.max_field_bytes,
+    );
+    floor.keep_action(
+        "policy.limits.action",
+        g.limits.action,
+        &mut p.limits.action,
+    );
+
+    // Secrets
+    let (gs, ps) = (&g.secrets, &mut p.secrets);
+    floor.keep_on("policy.secrets.enabled", gs.enabled, &mut ps.enabled);
+    if secret_strictness(ps.action) < secret_strictness(gs.action) {
+        ps.action = gs.action;
+        floor
+            .restored
+            .push("policy.secrets.action: relaxed from deny".to_string());
+    }
+    for (name, global, project) in [
+        (
+            "detect_aws_keys",
+            gs.detect_aws_keys,
+            &mut ps.detect_aws_keys,
+        ),
+        (
+            "detect_github_tokens",
+            gs.detect_github_tokens,
+            &mut ps.detect_github_tokens,
+        ),
+        (
+            "detect_openai_keys",
+            gs.detect_openai_keys,
+            &mut ps.detect_openai_keys,
+        ),
+        (
+            "detect_private_keys",
+            gs.detect_private_keys,
+            &mut ps.detect_private_keys,
+        ),
+        (
+            "detect_npm_tokens",
+            gs.detect_npm_tokens,
+            &mut ps.detect_npm_tokens,
+        ),
+        (
+            "detect_pypi_tokens",
+            gs.detect_pypi_tokens,
+            &mut ps.detect_pypi_tokens,
+        ),
+        ("detect_jwts", gs.detect_jwts, &mut ps.detect_jwts),
+    ] {
+        floor.keep_on(&format!("policy.secrets.{name}"), global, project);
+    }
+    floor.keep_entries("policy.secrets.rules", &gs.rules, &mut ps.rules);
+    floor.drop_entries("policy.secrets.baseline", &gs.baseline, &mut ps.baseline);
+    floor.drop_entries(
+        "policy.secrets.detect_secrets_hashes",
+        &gs.detect_secrets_hashes,
+        &mut ps.detect_secrets_hashes,
+    );
+    floor.keep_action(
+        "policy.secrets.search_action",
+        gs.search_action,
+        &mut ps.search_action,
+    );
+
+    // Commands
+    let (gc, pc) = (&g.commands, &mut p.commands);
+    floor.keep_on("policy.commands.enabled", gc.enabled, &mut pc.enabled);
+    floor.keep_entries(
+        "policy.commands.block_patterns",
+        &gc.block_patterns,
+        &mut pc.block_patterns,
+    );
+    floor.keep_entries(
+        "policy.commands.ask_patterns",
+        &gc.ask_patterns,
+        &mut pc.ask_patterns,
+    );
+    floor.drop_entries(
+        "policy.commands.allow_patterns",
+        &gc.allow_patterns,
+        &mut pc.allow_patterns,
+    );
+    floor.keep_action(
+        "policy.commands.default_action",
+        gc.default_action,
+        &mut pc.default_action,
+    );
+    floor.keep_on(
+        "policy.commands.detect_obfuscation",
+        gc.detect_obfuscation,
+        &mut pc.detect_obfuscation,
+    );
+    floor.keep_on(
+        "policy.commands.detect_reverse_shells",
+        gc.detect_reverse_shells,
+        &mut pc.detect_reverse_shells,
+    );
+    floor.keep_action(
+        "policy.commands.pipe_to_shell",
+        gc.pipe_to_shell,
+        &mut pc.pipe_to_shell,
+    );
+    floor.keep_on(
+        "policy.commands.sudo.enabled",
+        gc.sudo.enabled,
+        &mut pc.sudo.enabled,
+    );
+    floor.keep_action(
+        "policy.commands.sudo.action",
+        gc.sudo.action,
+        &mut pc.sudo.action,
+    );
+    floor.drop_entries(
+        "policy.commands.sudo.allow",
+        &gc.sudo.allow,
+        &mut pc.sudo.allow,
+    );
+    floor.keep_on(
+        "policy.commands.git.enabled",
+        gc.git.enabled,
+        &mut pc.git.enabled,
+    );
+    floor.keep_action(
+        "policy.commands.git.action",
+        gc.git.action,
+        &mut pc.git.action,
+    );
+    for (name, global, project) in [
+        ("force_push", gc.git.force_push, &mut pc.git.force_push),
+        ("reset_hard", gc.git.reset_hard, &mut pc.git.reset_hard),
+        ("clean", gc.git.clean, &mut pc.git.clean),
+        (
+            "checkout_discard",
+            gc.git.checkout_discard,
+            &mut pc.git.checkout_discard,
+        ),
+    ] {
+        floor.keep_on(&format!("policy.commands.git.{name}"), global, project);
+    }
+    floor.keep_entries(
+        "policy.commands.git.protected_branches",
+        &gc.git.protected_branches,
+        &mut pc.git.protected_branches,
+    );
+    floor.keep_on(
+        "policy.commands.permissions.enabled",
+        gc.permissions.enabled,
+        &mut pc.permissions.enabled,
+    );
+    floor.keep_action(
+        "policy.commands.permissions.action",
+        gc.permissions.action,
+        &mut pc.permissions.action,
+    );
+    floor.keep_entries(
+        "policy.commands.permissions.paths",
+        &gc.permissions.paths,
+        &mut pc.permissions.paths,
+    );
+    floor.keep_on(
+        "policy.commands.transfers.enabled",
+        gc.transfers.enabled,
+        &mut pc.transfers.enabled,
+    );
+    floor.keep_action(
+        "policy.commands.transfers.action",
+        gc.transfers.action,
+        &mut pc.transfers.action,
+    );
+    floor.drop_entries(
+        "policy.commands.transfers.allowed_hosts",
+        &gc.transfers.allowed_hosts,
+        &mut pc.transfers.allowed_hosts,
+    );
+
+    // Protected paths
+    let (gp, pp) = (&g.protected_paths, &mut p.protected_paths);
+    floor.keep_on(
+        "policy.protected_paths.enabled",
+        gp.enabled,
+        &mut pp.enabled,
+    );
+    floor.keep_entries(
+        "policy.protected_paths.blocked",
+        &gp.blocked,
+        &mut pp.blocked,
+    );
+    floor.keep_entries(
+        "policy.protected_paths.blocked_all",
+        &gp.blocked_all,
+        &mut pp.blocked_all,
+    );
+    floor.keep_entries(
+        "policy.protected_paths.blocked_read",
+        &gp.blocked_read,
+        &mut pp.blocked_read,
+    );
+    floor.keep_entries(
+        "policy.protected_paths.blocked_write",
+        &gp.blocked_write,
+        &mut pp.blocked_write,
+    );
+    floor.drop_entries(
+        "policy.protected_paths.allowed",
+        &gp.allowed,
+        &mut pp.allowed,
+    );
+    floor.keep_action(
+        "policy.protected_paths.glob_action",
+        gp.glob_action,
+        &mut pp.glob_action,
+    );
+    floor.keep_on(
+        "policy.protected_paths.protect_git_internals",
+        gp.protect_git_internals,
+        &mut pp.protect_git_internals,
+    );
+
+    // Network
+    let (gn, pn) = (&g.network, &mut p.network);
+    floor.keep_on("policy.network.enabled", gn.enabled, &mut pn.enabled);
+    floor.keep_entries(
+        "policy.network.block_domains",
+        &gn.block_domains,
+        &mut pn.block_domains,
+    );
+    floor.keep_entries(
+        "policy.network.blocklist_files",
+        &gn.blocklist_files,
+        &mut pn.blocklist_files,
+    );
+    floor.keep_action(
+        "policy.network.public_ip_action",
+        gn.public_ip_action,
+        &mut pn.public_ip_action,
+    );
+    floor.keep_action(
+        "policy.network.private_ip_action",
+        gn.private_ip_action,
+        &mut pn.private_ip_action,
+    );
+    floor.keep_entries(
+        "policy.network.unusual_schemes",
+        &gn.unusual_schemes,
+        &mut pn.unusual_schemes,
+    );
+    floor.keep_action(
+        "policy.network.scheme_action",
+        gn.scheme_action,
+        &mut pn.scheme_action,
+    );
+    floor.drop_entries(
+        "policy.network.allowed_ports",
+        &gn.allowed_ports,
+        &mut pn.allowed_ports,
+    );
+    floor.keep_action(
+        "policy.network.port_action",
+        gn.port_action,
+        &mut pn.port_action,
+    );
+
+    // Remaining scanners
+    floor.keep_on(
+        "policy.executables.enabled",
+        g.executables.enabled,
+        &mut p.executables.enabled,
+    );
+    floor.keep_action(
+        "policy.executables.action",
+        g.executables.action,
+        &mut p.executables.action,
+    );
+    floor.keep_on(
+        "policy.packages.enabled",
+        g.packages.enabled,
+        &mut p.packages.enabled,
+    );
+    floor.drop_entries(
+        "policy.packages.allow",
+        &g.packages.allow,
+        &mut p.packages.allow,
+    );
+    floor.keep_entries(
+        "policy.packages.deny",
+        &g.packages.deny,
+        &mut p.packages.deny,
+    );
+    floor.keep_action(
+        "policy.packages.unknown",
+        g.packages.unknown,
+        &mut p.packages.unknown,
+    );
+    floor.keep_on(
+        "policy.workspace.enabled",
+        g.workspace.enabled,
+        &mut p.workspace.enabled,
+    );
+    floor.keep_action(
+        "policy.workspace.action",
+        g.workspace.action,
+        &mut p.workspace.action,
+    );
+    floor.drop_entries(
+        "policy.workspace.allowed",
+        &g.workspace.allowed,
+        &mut p.workspace.allowed,
+    );
+    floor.keep_on(
+        "policy.writes.enabled",
+        g.writes.enabled,
+        &mut p.writes.enabled,
+    );
+    floor.keep_action(
+        "policy.writes.action",
+        g.writes.action,
+        &mut p.writes.action,
+    );
+    floor.keep_cap(
+        "policy.writes.max_bytes",
+        g.writes.max_bytes,
+        &mut p.writes.max_bytes,
+    );
+    floor.keep_on(
+        "policy.control_chars.enabled",
+        g.control_chars.enabled,
+        &mut p.control_chars.enabled,
+    );
+    floor.keep_action(
+        "policy.control_chars.action",
+        g.control_chars.action,
+        &mut p.control_chars.action,
+    );
+    floor.keep_on(
+        "policy.injection.enabled",
+        g.injection.enabled,
+        &mut p.injection.enabled,
+    );
+    floor.keep_action(
+        "policy.injection.action",
+        g.injection.action,
+        &mut p.injection.action,
+    );
+    floor.keep_entries(
+        "policy.injection.extra_patterns",
+        &g.injection.extra_patterns,
+        &mut p.injection.extra_patterns,
+    );
+
+    // Approvals let the user's earlier answers stand in for a verdict
+    floor.keep_off(
+        "policy.approvals.enabled",
+        g.approvals.enabled,
+        &mut p.approvals.enabled,
+    );
+    floor.keep_off(
+        "policy.session.allow_approved",
+        g.session.allow_approved,
+        &mut p.session.allow_approved,
+    );
+
+    // Tools
+    let (gt, pt) = (&global.tools, &mut config.tools);
+    floor.drop_entries("tools.allow", &gt.allow, &mut pt.allow);
+    floor.keep_entries("tools.deny", &gt.deny, &mut pt.deny);
+    floor.keep_entries("tools.ask", &gt.ask, &mut pt.ask);
+    floor.keep_action("tools.default", gt.default, &mut pt.default);
+    floor.drop_entries(
+        "tools.mcp.allow_servers",
+        &gt.mcp.allow_servers,
+        &mut pt.mcp.allow_servers,
+    );
+    floor.keep_entries(
+        "tools.mcp.deny_servers",
+        &gt.mcp.deny_servers,
+        &mut pt.mcp.deny_servers,
+    );
+    floor.keep_entries(
+        "tools.mcp.ask_servers",
+        &gt.mcp.ask_servers,
+        &mut pt.mcp.ask_servers,
+    );
+
+    floor.restored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tightening_is_kept() {
+        let global = Config::default();
+        let mut config = Config::default();
+        config.policy.commands.git.action = RuleAction::Deny;
+        config
+            .policy
+            .network
+            .block_domains
+            .push("example.com".into());
+        config.policy.workspace.enabled = true;
+
+        assert_eq!(enforce(&global, &mut config), Vec::<String>::new());
+        assert_eq!(config.policy.commands.git.action, RuleAction::Deny);
+        assert!(config.policy.workspace.enabled);
+    }
+
+    #[test]
+    fn test_weakening_is_restored() {
+        let mut global = Config::default();
+        global.policy.commands.allow_patterns = vec!["^ls".into()];
+        let mut config = Config::default();
+        config.policy.mode = PolicyMode::Monitor;
+        config.policy.secrets.enabled = false;
+        config.policy.commands.git.action = RuleAction::Allow;
+        config.policy.commands.allow_patterns = vec!["^ls".into(), ".*".into()];
+        config.policy.network.block_domains.clear();
+        config.tools.allow = vec!["Bash".into()];
+
+        let restored = enforce(&global, &mut config);
+        assert_eq!(restored.len(), 6, "{restored:?}");
+        assert!(restored[2].starts_with("policy.commands.allow_patterns"));
+        assert_eq!(config.policy.mode, PolicyMode::Strict);
+        assert!(config.policy.secrets.enabled);
+        assert_eq!(config.policy.commands.git.action, RuleAction::Ask);
+        assert_eq!(config.policy.commands.allow_patterns, ["^ls"]);
+        assert_eq!(
+            config.policy.network.block_domains,
+            global.policy.network.block_domains
+        );
+        assert_eq!(config.tools.allow, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_allowances_are_restored() {
+        let mut global = Config::default();
+        global.policy.max_latency_ms = Some(200);
+        global.policy.secrets.baseline = vec!["known".into()];
+        let mut config = global.clone();
+        config.policy.max_latency_ms = None;
+        config.policy.limits.max_field_bytes *= 2;
+        config.policy.limits.action = RuleAction::Allow;
+        config.policy.secrets.baseline.push("project".into());
+        config.policy.secrets.detect_secrets_hashes = vec!["hash".into()];
+        config.policy.approvals.enabled = true;
+        config.policy.session.allow_approved = true;
+
+        let restored = enforce(&global, &mut config);
+        assert_eq!(restored.len(), 7, "{restored:?}");
+        assert_eq!(config.policy.max_latency_ms, Some(200));
+        assert_eq!(config.policy.limits, global.policy.limits);
+        assert_eq!(config.policy.secrets.baseline, ["known"]);
+        assert_eq!(
+            config.policy.secrets.detect_secrets_hashes,
+            Vec::<String>::new()
+        );
+        assert!(!config.policy.approvals.enabled);
+        assert!(!config.policy.session.allow_approved);
+
+        // A tighter budget and smaller caps are kept
+        config.policy.max_latency_ms = Some(50);
+        config.policy.limits.max_input_bytes = 1024;
+        config.policy.limits.action = RuleAction::Deny;
+        assert_eq!(enforce(&global, &mut config), Vec::<String>::new());
+        assert_eq!(config.policy.max_latency_ms, Some(50));
+    }
+}
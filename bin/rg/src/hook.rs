@@ -13,113 +13,653 @@
 //!     "hookEventName": "PreToolUse",
 //!     "permissionDecision": "allow" | "deny" | "ask",
 //!     "permissionDecisionReason": "...",  // for deny/ask
-//!     "additionalContext": "..."          // for deny
+//!     "additionalContext": "...",         // for deny, or safe-mode warnings
+//!     "updatedInput": { ... }             // for redact (secrets replaced)
 //!   }
 //! }
 //! ```
+//!
+//! `SessionStart` events get a `SessionStart` hookSpecificOutput whose
+//! `additionalContext` advertises the policy's hard limits (when
+//! `policy.advertise` is enabled), so the model can plan around them.
+//!
+//! `PostToolUse` events produce no output. They mark a previously asked
+//! call as approved for approval memory and session allows. `SessionEnd`
+//! removes the session's state.
+//!
+//! # Other agents
+//!
+//! With `--agent`, payloads from another agent are translated into a
+//! `PreToolUse` payload before inspection, and the response back into that
+//! agent's format (see [`adapt`]). Cursor's `beforeShellExecution`,
+//! `beforeMCPExecution`, and `beforeReadFile` events become `Bash`,
+//! `mcp__cursor__TOOL`, and `Read` calls, answered with
+//! `{"permission": ..., "userMessage": ..., "agentMessage": ...}`.
+//! Gemini CLI's `BeforeTool`, `AfterTool`, `SessionStart`, and `SessionEnd`
+//! events map onto Claude Code's, with its built-in tools renamed (e.g.
+//! `run_shell_command` to `Bash`); decisions are answered with
+//! `{"decision": ..., "reason": ...}`.
 
-use std::io::{self, BufRead};
+use std::io::{self, BufRead, Read};
 use std::process::ExitCode;
+use std::sync::Arc;
+
+use rg_policy::approvals::unix_now;
+use rg_policy::{
+    inspect, ApprovalMemory, Middleware, MonitorMode, RuntimePolicy, SecretScanner, SessionAllows,
+    SessionStore, TaintTracker,
+};
+use rg_types::{HookInput, LimitsConfig, RuleAction, Verdict};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::audit::AuditLog;
+use crate::cli::Agent;
+use crate::metrics::Metrics;
+use crate::otel::OtelExporter;
+use crate::statsd::StatsdClient;
+use crate::syslog::SyslogSink;
+use crate::webhook::Webhook;
 
-use rg_policy::{inspect, RuntimePolicy};
-use rg_types::{HookInput, Verdict};
+/// What the hook answers for one payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HookResponse {
+    /// JSON written to stdout, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<serde_json::Value>,
+    /// Process exit code: 0 = allow/ask/redact, 2 = deny.
+    pub code: u8,
+}
+
+impl HookResponse {
+    /// A response with output.
+    fn new(output: serde_json::Value, code: u8) -> Self {
+        Self {
+            output: Some(output),
+            code,
+        }
+    }
+
+    /// A successful response without output.
+    fn silent() -> Self {
+        Self {
+            output: None,
+            code: 0,
+        }
+    }
+
+    /// An error, reported as a deny (fail closed).
+    pub fn error(message: &str) -> Self {
+        Self::new(error_json(message), 2)
+    }
+
+    /// Write the output to stdout and return the exit code.
+    pub fn emit(&self) -> ExitCode {
+        if let Some(output) = &self.output {
+            // JSON serialization of simple JSON values cannot fail
+            #[allow(clippy::expect_used)]
+            let json = serde_json::to_string(output).expect("JSON serialization failed");
+            println!("{json}");
+        }
+        ExitCode::from(self.code)
+    }
+}
+
+/// The most `read_stdin` reads, in bytes. `policy.limits` is only known once
+/// the config loads; this keeps a runaway payload from filling memory first.
+const MAX_STDIN_BYTES: usize = 64 * 1024 * 1024;
+
+/// Read the hook payload from stdin, failing past [`MAX_STDIN_BYTES`].
+pub fn read_stdin() -> io::Result<String> {
+    let mut input_str = String::new();
+    for line in io::stdin().lock().take(MAX_STDIN_BYTES as u64 + 1).lines() {
+        input_str.push_str(&line?);
+        input_str.push('\n');
+        if input_str.len() > MAX_STDIN_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("hook payload is over {MAX_STDIN_BYTES} bytes"),
+            ));
+        }
+    }
+    Ok(input_str)
+}
 
-/// Run as a Claude Code hook.
+/// Handle one Claude Code hook payload.
 ///
-/// - Reads JSON from stdin
-/// - Parses as `HookInput`
+/// - Parses the payload as `HookInput`
 /// - Inspects against policy
-/// - Outputs hookSpecificOutput JSON to stdout
-/// - Exit codes: 0 = allow/ask, 2 = deny
-pub fn run_hook(policy: &RuntimePolicy) -> ExitCode {
-    // Read from stdin
-    let stdin = io::stdin();
-    let mut input_str = String::new();
+/// - Answers with hookSpecificOutput JSON
+/// - Exit codes: 0 = allow/ask/redact, 2 = deny
+///
+/// `warning` is appended to `additionalContext` on every response, e.g. when
+/// running in safe mode because the configuration failed to load.
+/// `state` receives the session lifecycle and `PostToolUse` events.
+pub fn handle(
+    policy: &RuntimePolicy,
+    state: &HookState,
+    warning: Option<&str>,
+    input_str: &str,
+) -> HookResponse {
+    // Parse JSON
+    let payload: serde_json::Value = match serde_json::from_str(input_str) {
+        Ok(v) => v,
+        // Fail closed on parse errors
+        Err(e) => return HookResponse::error(&format!("Failed to parse JSON: {e}")),
+    };
 
-    for line in stdin.lock().lines() {
-        match line {
-            Ok(l) => {
-                input_str.push_str(&l);
-                input_str.push('\n');
-            }
-            Err(e) => {
-                output_error(&format!("Failed to read stdin: {e}"));
-                return ExitCode::from(2); // Fail closed on errors
+    // SessionStart carries no tool call; advertise policy limits instead
+    let event = payload.get("hook_event_name").and_then(|e| e.as_str());
+    if event == Some("SessionStart") {
+        if let Some(sessions) = &state.sessions {
+            let _ = sessions.prune();
+        }
+        return match session_start_json(policy.capabilities.as_deref(), warning) {
+            Some(output) => HookResponse::new(output, 0),
+            None => HookResponse::silent(),
+        };
+    }
+
+    if event == Some("SessionEnd") {
+        let session_id = payload.get("session_id").and_then(|s| s.as_str());
+        if let (Some(sessions), Some(session_id)) = (&state.sessions, session_id) {
+            if let Err(e) = sessions.end(session_id) {
+                tracing::warn!(error = %e, "failed to remove session state");
             }
         }
+        return HookResponse::silent();
     }
 
-    // Parse JSON
-    let input: HookInput = match serde_json::from_str(&input_str) {
+    // PostToolUse reports a call that already ran; there is nothing to decide
+    if event == Some("PostToolUse") {
+        if let Ok(input) = serde_json::from_value::<HookInput>(payload) {
+            state.record_run(&input);
+        }
+        return HookResponse::silent();
+    }
+
+    let input: HookInput = match serde_json::from_value(payload) {
         Ok(i) => i,
-        Err(e) => {
-            output_error(&format!("Failed to parse JSON: {e}"));
-            return ExitCode::from(2); // Fail closed on parse errors
+        // Fail closed on parse errors
+        Err(e) => return HookResponse::error(&format!("Failed to parse JSON: {e}")),
+    };
+
+    // Inspect, unless the input is too big to scan quickly
+    let (verdict, latency) = match oversized(input_str.len(), &input, &policy.limits) {
+        Some(verdict) => (policy.middleware.apply(&input, verdict), 0),
+        None => inspect(&input, policy),
+    };
+    // Sinks see what would have been enforced, before monitor mode relaxes it
+    state.record_decision(&input, &verdict, latency, &policy.secrets);
+    let verdict = if state.monitor {
+        MonitorMode.process(&input, verdict)
+    } else {
+        verdict
+    };
+
+    // Exit code: 0 = allow/ask/redact, 2 = deny
+    let code = match verdict {
+        Verdict::Allow | Verdict::Ask { .. } | Verdict::Redact { .. } => 0,
+        Verdict::Deny { .. } => 2,
+    };
+    // Claude Code-native format
+    HookResponse::new(verdict_json(&verdict, warning), code)
+}
+
+/// The verdict for a call over `limits`, if it is: the tool call is not
+/// inspected, and gets the limits' action instead.
+fn oversized(payload_len: usize, input: &HookInput, limits: &LimitsConfig) -> Option<Verdict> {
+    if limits.action == RuleAction::Allow {
+        return None;
+    }
+    let reason = if payload_len > limits.max_input_bytes {
+        format!(
+            "Hook payload is {payload_len} bytes, over the {} byte limit \
+             (policy.limits.max_input_bytes); the tool call was not inspected",
+            limits.max_input_bytes
+        )
+    } else {
+        let (field, len) = oversized_field(&input.tool_input, limits.max_field_bytes)?;
+        format!(
+            "Tool input field `{field}` is {len} bytes, over the {} byte limit \
+             (policy.limits.max_field_bytes); the tool call was not inspected",
+            limits.max_field_bytes
+        )
+    };
+    Some(if limits.action == RuleAction::Deny {
+        Verdict::deny(reason)
+    } else {
+        Verdict::ask(reason)
+    })
+}
+
+/// The path (e.g. `edits[1].new_string`) and length of the first string in
+/// `value` longer than `max` bytes.
+fn oversized_field(value: &Value, max: usize) -> Option<(String, usize)> {
+    // Join a parent's key or index to a child's path
+    let join = |parent: String, path: String| {
+        if path.is_empty() || path.starts_with('[') {
+            parent + &path
+        } else {
+            format!("{parent}.{path}")
+        }
+    };
+    match value {
+        Value::String(text) if text.len() > max => Some((String::new(), text.len())),
+        Value::Array(items) => items.iter().enumerate().find_map(|(i, item)| {
+            oversized_field(item, max).map(|(path, len)| (join(format!("[{i}]"), path), len))
+        }),
+        Value::Object(fields) => fields.iter().find_map(|(key, item)| {
+            oversized_field(item, max).map(|(path, len)| (join(key.clone(), path), len))
+        }),
+        _ => None,
+    }
+}
+
+/// Answer a payload from `agent` with `respond`, which answers Claude Code
+/// payloads, translating the payload on the way in and the response on the
+/// way out.
+pub fn adapt(
+    agent: Agent,
+    input_str: &str,
+    respond: impl FnOnce(&str) -> HookResponse,
+) -> HookResponse {
+    let translate = match agent {
+        Agent::Claude => return respond(input_str),
+        Agent::Cursor => cursor_payload,
+        Agent::Gemini => gemini_payload,
+    };
+    let payload = serde_json::from_str(input_str)
+        .map_err(|e| format!("Failed to parse JSON: {e}"))
+        .and_then(|payload| translate(&payload));
+    let response = match &payload {
+        Ok(payload) => respond(&payload.to_string()),
+        // Fail closed on payloads we can't read
+        Err(message) => HookResponse::error(message),
+    };
+
+    match agent {
+        Agent::Claude => response,
+        Agent::Cursor => {
+            cursor_response(&response, payload.is_ok_and(|p| p["tool_name"] == "Read"))
+        }
+        Agent::Gemini => gemini_response(response),
+    }
+}
+
+/// A decision for an agent that reads `allow`, `ask`, or `deny` and can't
+/// rewrite a call.
+#[derive(Debug)]
+struct PlainDecision {
+    /// `allow`, `ask`, or `deny`.
+    permission: &'static str,
+    /// Why, for the user; empty for `allow`.
+    reason: String,
+    /// The reason plus any context, for the model.
+    context: String,
+}
+
+impl PlainDecision {
+    /// Read the decision from a Claude Code response. A redacted call is
+    /// denied, as is one that asks where `agent` can't.
+    fn from_response(response: &HookResponse, agent: &str, can_ask: bool) -> Self {
+        let output = response
+            .output
+            .as_ref()
+            .map_or(&Value::Null, |output| &output["hookSpecificOutput"]);
+        let text = |name: &str| output[name].as_str().unwrap_or_default();
+        let decision = match text("permissionDecision") {
+            "" if response.code == 2 => "deny",
+            "" => "allow",
+            decision => decision,
+        };
+
+        let reason = text("permissionDecisionReason");
+        let (permission, reason) = match decision {
+            "allow" if !output["updatedInput"].is_null() => {
+                ("deny", format!("{reason} ({agent} can't redact a call)"))
+            }
+            "allow" => ("allow", String::new()),
+            "ask" if !can_ask => ("deny", format!("{reason} ({agent} can't ask here)")),
+            "ask" => ("ask", reason.to_string()),
+            _ if reason.is_empty() => ("deny", "Blocked by Railgun".to_string()),
+            _ => ("deny", reason.to_string()),
+        };
+        let context = match text("additionalContext") {
+            "" => reason.clone(),
+            context => format!("{reason}\n{context}"),
+        };
+        Self {
+            permission,
+            reason,
+            context,
+        }
+    }
+}
+
+/// Translate a Cursor hook payload into a `PreToolUse` payload.
+fn cursor_payload(payload: &Value) -> Result<Value, String> {
+    let field = |name: &str| payload.get(name).and_then(Value::as_str);
+    let required = |name: &str| field(name).ok_or_else(|| format!("Cursor payload has no {name}"));
+    let event = field("hook_event_name").unwrap_or_default();
+
+    let (tool_name, tool_input) = match event {
+        "beforeShellExecution" => (
+            "Bash".to_string(),
+            json!({ "command": required("command")? }),
+        ),
+        // Cursor doesn't name the server, only the tool
+        "beforeMCPExecution" => {
+            let input = match payload.get("tool_input") {
+                // The arguments usually arrive as a JSON string
+                Some(Value::String(raw)) => {
+                    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.clone()))
+                }
+                Some(input) => input.clone(),
+                None => json!({}),
+            };
+            (format!("mcp__cursor__{}", required("tool_name")?), input)
+        }
+        "beforeReadFile" => (
+            "Read".to_string(),
+            json!({ "file_path": required("file_path")? }),
+        ),
+        other => return Err(format!("Unsupported Cursor hook event '{other}'")),
+    };
+
+    let cwd = field("cwd").or_else(|| payload["workspace_roots"][0].as_str());
+    Ok(json!({
+        "hook_event_name": "PreToolUse",
+        "tool_name": tool_name,
+        "tool_input": tool_input,
+        "cwd": cwd,
+        "session_id": field("conversation_id"),
+    }))
+}
+
+/// Translate a response into Cursor's `{"permission": ...}` output. Cursor
+/// reads the decision from the output, so the exit code is always 0.
+fn cursor_response(response: &HookResponse, read: bool) -> HookResponse {
+    // Cursor can't ask before a file read
+    let decision = PlainDecision::from_response(response, "Cursor", !read);
+    let mut cursor = json!({ "permission": decision.permission });
+    if decision.permission != "allow" {
+        cursor["userMessage"] = Value::String(decision.reason);
+        cursor["agentMessage"] = Value::String(decision.context);
+    }
+    HookResponse::new(cursor, 0)
+}
+
+/// Translate a Gemini CLI hook payload. Its events and payloads follow
+/// Claude Code's, with its own event and tool names.
+fn gemini_payload(payload: &Value) -> Result<Value, String> {
+    let event = match payload["hook_event_name"].as_str().unwrap_or_default() {
+        "BeforeTool" => "PreToolUse",
+        "AfterTool" => "PostToolUse",
+        event @ ("SessionStart" | "SessionEnd") => event,
+        other => return Err(format!("Unsupported Gemini CLI hook event '{other}'")),
+    };
+
+    let mut translated = payload.clone();
+    translated["hook_event_name"] = json!(event);
+    if let Some(tool) = payload["tool_name"].as_str() {
+        let input = &payload["tool_input"];
+        let (tool_name, tool_input) = gemini_tool(tool, input);
+        translated["tool_name"] = json!(tool_name);
+        translated["tool_input"] = tool_input;
+        if let Some(directory) = input["directory"].as_str() {
+            translated["cwd"] = json!(directory);
+        }
+    }
+    Ok(translated)
+}
+
+/// The Claude Code tool a Gemini CLI built-in tool corresponds to, with its
+/// input renamed to match. Other tools, including MCP tools, keep their
+/// names and input.
+fn gemini_tool(tool: &str, input: &Value) -> (String, Value) {
+    let (name, input) = match tool {
+        "run_shell_command" => ("Bash", json!({ "command": input["command"] })),
+        "write_file" => (
+            "Write",
+            json!({ "file_path": input["file_path"], "content": input["content"] }),
+        ),
+        "replace" => (
+            "Edit",
+            json!({
+                "file_path": input["file_path"],
+                "old_string": input["old_string"],
+                "new_string": input["new_string"],
+            }),
+        ),
+        "read_file" => ("Read", json!({ "file_path": input["absolute_path"] })),
+        "glob" => (
+            "Glob",
+            json!({ "pattern": input["pattern"], "path": input["path"] }),
+        ),
+        "search_file_content" => (
+            "Grep",
+            json!({ "pattern": input["pattern"], "path": input["path"] }),
+        ),
+        "google_web_search" => ("WebSearch", json!({ "query": input["query"] })),
+        // The URLs are part of the prompt
+        "web_fetch" => {
+            let prompt = input["prompt"].as_str().unwrap_or_default();
+            let url = prompt
+                .split_whitespace()
+                .find(|word| word.starts_with("http://") || word.starts_with("https://"));
+            ("WebFetch", json!({ "url": url, "prompt": prompt }))
         }
+        _ => return (tool.to_string(), input.clone()),
     };
+    (name.to_string(), input)
+}
+
+/// Translate a response into Gemini CLI's `{"decision": ..., "reason": ...}`
+/// output. `SessionStart` context and silent responses pass through.
+fn gemini_response(response: HookResponse) -> HookResponse {
+    let event = response
+        .output
+        .as_ref()
+        .and_then(|output| output["hookSpecificOutput"]["hookEventName"].as_str());
+    if event == Some("SessionStart") || (response.output.is_none() && response.code == 0) {
+        return response;
+    }
 
-    // Inspect
-    let (verdict, _latency) = inspect(&input, policy);
+    let decision = PlainDecision::from_response(&response, "Gemini CLI", true);
+    let mut gemini = json!({ "decision": decision.permission });
+    if decision.permission != "allow" {
+        gemini["reason"] = Value::String(decision.context);
+    }
+    HookResponse::new(gemini, 0)
+}
 
-    // Output Claude Code-native format
-    output_verdict(&verdict);
+/// State the hook keeps across invocations.
+#[derive(Debug, Default)]
+pub struct HookState {
+    /// Approval memory, if `policy.approvals` is enabled.
+    pub approvals: Option<ApprovalMemory>,
+    /// Session allows, if `policy.session.allow_approved` is enabled.
+    pub session_allows: Option<SessionAllows>,
+    /// Taint tracking, if `policy.session.taint` is enabled.
+    pub taint: Option<TaintTracker>,
+    /// Session state files, if session allows, risk scoring, or taint
+    /// tracking is enabled.
+    pub sessions: Option<SessionStore>,
+    /// Audit log, if `policy.audit` is enabled.
+    pub audit: Option<AuditLog>,
+    /// Prometheus metrics, under `railgun daemon --metrics`.
+    pub metrics: Option<Arc<Metrics>>,
+    /// `StatsD` metrics, if `policy.statsd` is enabled.
+    pub statsd: Option<StatsdClient>,
+    /// Span export, if `policy.otel` is enabled.
+    pub otel: Option<OtelExporter>,
+    /// Alerts, if `policy.webhook` is enabled.
+    pub webhook: Option<Webhook>,
+    /// Syslog or journald records, if `policy.syslog` is enabled.
+    pub syslog: Option<SyslogSink>,
+    /// Whether `policy.mode` is `monitor`: every verdict is recorded as
+    /// decided, then answered as `allow`.
+    pub monitor: bool,
+}
 
-    // Exit code: 0 = allow/ask, 2 = deny
-    match verdict {
-        Verdict::Allow | Verdict::Ask { .. } => ExitCode::SUCCESS,
-        Verdict::Deny { .. } => ExitCode::from(2),
+impl HookState {
+    /// Hand a decision to every enabled sink: metrics, traces, logs, and
+    /// alerts. Failures are logged and never change the decision.
+    fn record_decision(
+        &self,
+        input: &HookInput,
+        verdict: &Verdict,
+        latency: u64,
+        secrets: &SecretScanner,
+    ) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record(verdict, latency);
+        }
+        if let Some(statsd) = &self.statsd {
+            if let Err(e) = statsd.record(verdict, latency) {
+                tracing::warn!(error = %e, "failed to send StatsD metrics");
+            }
+        }
+        if let Some(otel) = &self.otel {
+            if let Err(e) = otel.export(input, verdict, latency) {
+                tracing::warn!(error = %e, "failed to export span");
+            }
+        }
+        if let Some(audit) = &self.audit {
+            if let Err(e) = audit.record(input, verdict, secrets) {
+                tracing::warn!(error = %e, "failed to write audit log");
+            }
+        }
+        if let Some(syslog) = &self.syslog {
+            if let Err(e) = syslog.record(input, verdict, secrets) {
+                tracing::warn!(error = %e, "failed to write to syslog");
+            }
+        }
+        if let Some(webhook) = &self.webhook {
+            webhook.notify(input, verdict, secrets);
+        }
+    }
+
+    /// Record a `PostToolUse` event: the call ran, so any `ask` was approved
+    /// and any sensitive file it reads was read.
+    fn record_run(&self, input: &HookInput) {
+        let now = unix_now();
+        if let Some(approvals) = &self.approvals {
+            if let Err(e) = approvals.record_run(input, now) {
+                tracing::warn!(error = %e, "failed to record approval");
+            }
+        }
+        if let Some(session_allows) = &self.session_allows {
+            if let Err(e) = session_allows.record_run(input, now) {
+                tracing::warn!(error = %e, "failed to record session approval");
+            }
+        }
+        if let Some(taint) = &self.taint {
+            if let Err(e) = taint.record_run(input) {
+                tracing::warn!(error = %e, "failed to record sensitive read");
+            }
+        }
     }
 }
 
-/// Output a verdict as Claude Code-native hookSpecificOutput JSON.
-fn output_verdict(verdict: &Verdict) {
-    let output = match verdict {
+/// Build the hookSpecificOutput JSON for a verdict.
+fn verdict_json(verdict: &Verdict, warning: Option<&str>) -> serde_json::Value {
+    use std::fmt::Write;
+
+    let mut output = match verdict {
         Verdict::Allow => serde_json::json!({
             "hookSpecificOutput": {
                 "hookEventName": "PreToolUse",
                 "permissionDecision": "allow"
             }
         }),
-        Verdict::Deny { reason, context } => {
+        Verdict::Deny {
+            reason,
+            context,
+            violations,
+        } => {
             let mut hook_output = serde_json::json!({
                 "hookEventName": "PreToolUse",
                 "permissionDecision": "deny",
                 "permissionDecisionReason": reason
             });
-            if let Some(ctx) = context {
-                hook_output["additionalContext"] = serde_json::Value::String(ctx.clone());
+            let mut ctx = context.clone().unwrap_or_default();
+            if violations.len() > 1 {
+                ctx.push_str("\nViolations by severity:");
+                for v in violations {
+                    let _ = write!(ctx, "\n- [{}] {}: {v}", v.severity(), v.code());
+                }
+            }
+            if !ctx.is_empty() {
+                hook_output["additionalContext"] = serde_json::Value::String(ctx);
             }
             serde_json::json!({ "hookSpecificOutput": hook_output })
         }
-        Verdict::Ask { reason } => serde_json::json!({
+        Verdict::Ask { reason, .. } => serde_json::json!({
             "hookSpecificOutput": {
                 "hookEventName": "PreToolUse",
                 "permissionDecision": "ask",
                 "permissionDecisionReason": reason
             }
         }),
+        Verdict::Redact {
+            reason,
+            updated_input,
+        } => serde_json::json!({
+            "hookSpecificOutput": {
+                "hookEventName": "PreToolUse",
+                "permissionDecision": "allow",
+                "permissionDecisionReason": reason,
+                "updatedInput": updated_input
+            }
+        }),
     };
 
-    // JSON serialization of simple JSON values cannot fail
-    #[allow(clippy::expect_used)]
-    let json = serde_json::to_string(&output).expect("JSON serialization failed");
-    println!("{json}");
+    if let Some(warning) = warning {
+        let hook_output = &mut output["hookSpecificOutput"];
+        let context = match hook_output
+            .get("additionalContext")
+            .and_then(|c| c.as_str())
+        {
+            Some(existing) => format!("{warning}\n{existing}"),
+            None => warning.to_string(),
+        };
+        hook_output["additionalContext"] = serde_json::Value::String(context);
+    }
+
+    output
 }
 
-/// Output an error as a deny verdict.
-fn output_error(message: &str) {
-    let output = serde_json::json!({
+/// Build the `SessionStart` hookSpecificOutput JSON, if there is any context.
+fn session_start_json(
+    capabilities: Option<&str>,
+    warning: Option<&str>,
+) -> Option<serde_json::Value> {
+    let context = match (warning, capabilities) {
+        (Some(w), Some(c)) => format!("{w}\n{c}"),
+        (Some(only), None) | (None, Some(only)) => only.to_string(),
+        (None, None) => return None,
+    };
+
+    Some(serde_json::json!({
+        "hookSpecificOutput": {
+            "hookEventName": "SessionStart",
+            "additionalContext": context
+        }
+    }))
+}
+
+/// Build the deny output for an error.
+fn error_json(message: &str) -> serde_json::Value {
+    serde_json::json!({
         "hookSpecificOutput": {
             "hookEventName": "PreToolUse",
             "permissionDecision": "deny",
             "permissionDecisionReason": message,
             "additionalContext": "Railgun encountered an error and is operating in fail-closed mode."
         }
-    });
-    // JSON serialization of simple JSON values cannot fail
-    #[allow(clippy::expect_used)]
-    let json = serde_json::to_string(&output).expect("JSON serialization failed");
-    println!("{json}");
+    })
 }
 
 #[cfg(test)]
@@ -132,10 +672,7 @@ mod tests {
         let config = PolicyConfig::default();
         let policy = RuntimePolicy::from_config(&config);
 
-        let input = HookInput {
-            tool_name: "Bash".to_string(),
-            tool_input: serde_json::json!({ "command": "ls -la" }),
-        };
+        let input = HookInput::new("Bash", serde_json::json!({ "command": "ls -la" }));
 
         let (verdict, _) = inspect(&input, &policy);
         assert!(verdict.is_allow());
@@ -146,15 +683,63 @@ mod tests {
         let config = PolicyConfig::default();
         let policy = RuntimePolicy::from_config(&config);
 
-        let input = HookInput {
-            tool_name: "Bash".to_string(),
-            tool_input: serde_json::json!({ "command": "rm -rf /" }),
-        };
+        let input = HookInput::new("Bash", serde_json::json!({ "command": "rm -rf /" }));
 
         let (verdict, _) = inspect(&input, &policy);
         assert!(verdict.is_deny());
     }
 
+    #[test]
+    fn test_oversized_field() {
+        let input = json!({
+            "file_path": "a.txt",
+            "edits": [{ "old_string": "x", "new_string": "y".repeat(11) }]
+        });
+        assert_eq!(
+            oversized_field(&input, 10),
+            Some(("edits[0].new_string".to_string(), 11))
+        );
+        assert_eq!(oversized_field(&input, 11), None);
+        assert_eq!(
+            oversized_field(&json!([["long string"]]), 4),
+            Some(("[0][0]".to_string(), 11))
+        );
+    }
+
+    #[test]
+    fn test_oversized() {
+        let input = HookInput::new(
+            "Write",
+            json!({ "file_path": "a.txt", "content": "x".repeat(100) }),
+        );
+        let mut limits = LimitsConfig {
+            max_input_bytes: 1000,
+            max_field_bytes: 50,
+            action: RuleAction::Ask,
+        };
+
+        let verdict = oversized(200, &input, &limits).unwrap();
+        assert!(verdict.is_ask());
+        assert_eq!(
+            verdict.reason().unwrap(),
+            "Tool input field `content` is 100 bytes, over the 50 byte limit \
+             (policy.limits.max_field_bytes); the tool call was not inspected"
+        );
+
+        limits.action = RuleAction::Deny;
+        let verdict = oversized(2000, &input, &limits).unwrap();
+        assert!(verdict.is_deny());
+        assert!(verdict
+            .reason()
+            .unwrap()
+            .starts_with("Hook payload is 2000 bytes, over the 1000 byte limit"));
+
+        limits.max_field_bytes = 100;
+        assert!(oversized(200, &input, &limits).is_none());
+        limits.action = RuleAction::Allow;
+        assert!(oversized(2000, &input, &limits).is_none());
+    }
+
     #[test]
     fn test_verdict_output_allow() {
         let verdict = Verdict::allow();
@@ -176,7 +761,9 @@ mod tests {
     fn test_verdict_output_deny() {
         let verdict = Verdict::deny_with_context("Blocked", "Context");
         let output = match &verdict {
-            Verdict::Deny { reason, context } => {
+            Verdict::Deny {
+                reason, context, ..
+            } => {
                 let mut hook_output = serde_json::json!({
                     "hookEventName": "PreToolUse",
                     "permissionDecision": "deny",
@@ -194,11 +781,52 @@ mod tests {
         assert!(json.contains("\"additionalContext\":\"Context\""));
     }
 
+    #[test]
+    fn test_verdict_json_with_warning() {
+        let output = verdict_json(&Verdict::allow(), Some("SAFE MODE"));
+        assert_eq!(
+            output["hookSpecificOutput"]["additionalContext"],
+            serde_json::json!("SAFE MODE")
+        );
+
+        let output = verdict_json(&Verdict::deny_with_context("Blocked", "Context"), Some("W"));
+        assert_eq!(
+            output["hookSpecificOutput"]["additionalContext"],
+            serde_json::json!("W\nContext")
+        );
+
+        let output = verdict_json(&Verdict::allow(), None);
+        assert!(output["hookSpecificOutput"]
+            .get("additionalContext")
+            .is_none());
+    }
+
+    #[test]
+    fn test_verdict_json_lists_violations() {
+        let verdict = Verdict::deny_from_block_reasons(&[
+            rg_types::BlockReason::NetworkExfiltration {
+                domain: "pastebin.com".to_string(),
+            },
+            rg_types::BlockReason::SecretDetected {
+                secret_type: "aws_access_key".to_string(),
+                redacted: "AKIA...MPLE".to_string(),
+            },
+        ]);
+        let output = verdict_json(&verdict, None);
+        let ctx = output["hookSpecificOutput"]["additionalContext"]
+            .as_str()
+            .unwrap();
+
+        let secret_pos = ctx.find("[critical] secret_detected").unwrap();
+        let network_pos = ctx.find("[high] network_exfiltration").unwrap();
+        assert!(secret_pos < network_pos);
+    }
+
     #[test]
     fn test_verdict_output_ask() {
         let verdict = Verdict::ask("Confirm?");
         let output = match &verdict {
-            Verdict::Ask { reason } => serde_json::json!({
+            Verdict::Ask { reason, .. } => serde_json::json!({
                 "hookSpecificOutput": {
                     "hookEventName": "PreToolUse",
                     "permissionDecision": "ask",
@@ -210,4 +838,150 @@ mod tests {
         let json = serde_json::to_string(&output).unwrap();
         assert!(json.contains("\"permissionDecision\":\"ask\""));
     }
+
+    #[test]
+    fn test_verdict_json_redact() {
+        let verdict = Verdict::redact(
+            "Redacted 1 secret (aws_access_key) from tool input",
+            serde_json::json!({ "command": "echo [REDACTED:aws_access_key]" }),
+        );
+        let output = verdict_json(&verdict, None);
+
+        assert_eq!(output["hookSpecificOutput"]["permissionDecision"], "allow");
+        assert_eq!(
+            output["hookSpecificOutput"]["updatedInput"]["command"],
+            "echo [REDACTED:aws_access_key]"
+        );
+    }
+
+    #[test]
+    fn test_cursor_response() {
+        let ask = HookResponse::new(verdict_json(&Verdict::ask("Needs a look"), None), 0);
+        assert_eq!(
+            cursor_response(&ask, false).output,
+            Some(json!({
+                "permission": "ask",
+                "userMessage": "Needs a look",
+                "agentMessage": "Needs a look"
+            }))
+        );
+        // Reads can't be asked about
+        let read = cursor_response(&ask, true).output.unwrap();
+        assert_eq!(read["permission"], "deny");
+
+        let redact = HookResponse::new(
+            verdict_json(
+                &Verdict::Redact {
+                    reason: "Secret redacted".into(),
+                    updated_input: json!({ "command": "echo [REDACTED]" }),
+                },
+                None,
+            ),
+            0,
+        );
+        assert_eq!(
+            cursor_response(&redact, false).output.unwrap()["permission"],
+            "deny"
+        );
+
+        // A config error in deny mode answers with no output
+        let silent = HookResponse {
+            output: None,
+            code: 2,
+        };
+        let denied = cursor_response(&silent, false);
+        assert_eq!(denied.output.unwrap()["permission"], "deny");
+        assert_eq!(denied.code, 0);
+    }
+
+    #[test]
+    fn test_cursor_payload() {
+        let payload = cursor_payload(&json!({
+            "hook_event_name": "beforeMCPExecution",
+            "conversation_id": "c1",
+            "tool_name": "create_issue",
+            "tool_input": "{\"title\": \"x\"}",
+            "workspace_roots": ["/repo"]
+        }))
+        .unwrap();
+        assert_eq!(payload["tool_name"], "mcp__cursor__create_issue");
+        assert_eq!(payload["tool_input"], json!({ "title": "x" }));
+        assert_eq!(payload["cwd"], "/repo");
+        assert_eq!(payload["session_id"], "c1");
+
+        assert!(cursor_payload(&json!({ "hook_event_name": "beforeShellExecution" })).is_err());
+    }
+
+    #[test]
+    fn test_gemini_payload() {
+        let payload = gemini_payload(&json!({
+            "hook_event_name": "BeforeTool",
+            "session_id": "s1",
+            "cwd": "/repo",
+            "tool_name": "run_shell_command",
+            "tool_input": { "command": "ls", "directory": "/repo/src" }
+        }))
+        .unwrap();
+        assert_eq!(payload["hook_event_name"], "PreToolUse");
+        assert_eq!(payload["tool_name"], "Bash");
+        assert_eq!(payload["tool_input"], json!({ "command": "ls" }));
+        assert_eq!(payload["cwd"], "/repo/src");
+        assert_eq!(payload["session_id"], "s1");
+
+        let (tool, input) = gemini_tool("read_file", &json!({ "absolute_path": "/repo/.env" }));
+        assert_eq!(
+            (tool.as_str(), input),
+            ("Read", json!({ "file_path": "/repo/.env" }))
+        );
+        let (tool, input) = gemini_tool(
+            "web_fetch",
+            &json!({ "prompt": "Summarize https://example.com/a please" }),
+        );
+        assert_eq!(tool, "WebFetch");
+        assert_eq!(input["url"], "https://example.com/a");
+        let (tool, _) = gemini_tool("mcp_tool", &json!({}));
+        assert_eq!(tool, "mcp_tool");
+
+        assert!(gemini_payload(&json!({ "hook_event_name": "BeforeModel" })).is_err());
+    }
+
+    #[test]
+    fn test_gemini_response() {
+        let deny = HookResponse::new(verdict_json(&Verdict::deny("No"), None), 2);
+        let response = gemini_response(deny);
+        assert_eq!(
+            response.output,
+            Some(json!({ "decision": "deny", "reason": "No" }))
+        );
+        assert_eq!(response.code, 0);
+
+        let allow = HookResponse::new(verdict_json(&Verdict::Allow, None), 0);
+        assert_eq!(
+            gemini_response(allow).output,
+            Some(json!({ "decision": "allow" }))
+        );
+
+        // Lifecycle events pass through
+        assert_eq!(
+            gemini_response(HookResponse::silent()),
+            HookResponse::silent()
+        );
+        let start = HookResponse::new(session_start_json(Some("Limits"), None).unwrap(), 0);
+        assert_eq!(gemini_response(start.clone()), start);
+    }
+
+    #[test]
+    fn test_session_start_json() {
+        assert!(session_start_json(None, None).is_none());
+
+        let output = session_start_json(Some("limits"), Some("SAFE MODE")).unwrap();
+        assert_eq!(
+            output["hookSpecificOutput"]["hookEventName"],
+            "SessionStart"
+        );
+        assert_eq!(
+            output["hookSpecificOutput"]["additionalContext"],
+            "SAFE MODE\nlimits"
+        );
+    }
 }
@@ -0,0 +1,259 @@
+//! Scaffold a starter configuration file.
+
+use std::path::Path;
+
+use eyre::{Context, Result};
+
+use crate::cli::Preset;
+
+/// Actions and switches that differ between presets.
+struct Settings {
+    sudo: &'static str,
+    git: &'static str,
+    transfers: &'static str,
+    public_ip: &'static str,
+    scheme: &'static str,
+    port: &'static str,
+    executables: &'static str,
+    writes: &'static str,
+    packages: &'static str,
+    workspace: bool,
+    injection: &'static str,
+    risk: bool,
+    taint: bool,
+    taint_action: &'static str,
+}
+
+impl Settings {
+    fn for_preset(preset: Preset) -> Self {
+        match preset {
+            Preset::Paranoid => Self {
+                sudo: "deny",
+                git: "deny",
+                transfers: "deny",
+                public_ip: "deny",
+                scheme: "deny",
+                port: "ask",
+                executables: "ask",
+                writes: "ask",
+                packages: "ask",
+                workspace: true,
+                injection: "deny",
+                risk: true,
+                taint: true,
+                taint_action: "deny",
+            },
+            Preset::Balanced => Self {
+                sudo: "ask",
+                git: "ask",
+                transfers: "ask",
+                public_ip: "ask",
+                scheme: "ask",
+                port: "allow",
+                executables: "ask",
+                writes: "ask",
+                packages: "ask",
+                workspace: false,
+                injection: "ask",
+                risk: false,
+                taint: false,
+                taint_action: "ask",
+            },
+            Preset::Permissive => Self {
+                sudo: "ask",
+                git: "allow",
+                transfers: "allow",
+                public_ip: "allow",
+                scheme: "allow",
+                port: "allow",
+                executables: "allow",
+                writes: "allow",
+                packages: "allow",
+                workspace: false,
+                injection: "ask",
+                risk: false,
+                taint: false,
+                taint_action: "ask",
+            },
+        }
+    }
+}
+
+/// Render the starter configuration for a preset.
+///
+/// Only the most commonly tuned settings are written; everything else keeps
+/// its default (see `railgun.example.toml` for the full reference).
+pub fn render(preset: Preset) -> String {
+    let s = Settings::for_preset(preset);
+    let name = match preset {
+        Preset::Paranoid => "paranoid",
+        Preset::Balanced => "balanced",
+        Preset::Permissive => "permissive",
+    };
+    format!(
+        r#"# Railgun configuration ({name} preset), generated by `railgun init`.
+#
+# Actions are "allow", "ask" (Claude Code prompts you), or "deny".
+# Every setting not listed here keeps its default; see railgun.example.toml
+# for the full reference, and run `railgun lint` after editing.
+
+version = 1
+
+[policy]
+# "strict" blocks violations; "monitor" only logs them (good for a trial run)
+mode = "strict"
+# Block the call if Railgun itself fails
+fail_closed = true
+
+# Secrets (API keys, tokens, private keys) in tool inputs
+[policy.secrets]
+enabled = true
+# "deny", "ask", or "redact" (replace the secret and let the call proceed)
+action = "deny"
+
+# Dangerous shell commands (rm -rf /, fork bombs, curl | sh, ...)
+[policy.commands]
+enabled = true
+
+# sudo, doas, pkexec, su -c
+[policy.commands.sudo]
+action = "{sudo}"
+# Commands that may run elevated, e.g. ["apt-get install"]
+allow = []
+
+# git push --force, reset --hard, clean -f, checkout -- .
+[policy.commands.git]
+action = "{git}"
+protected_branches = ["main", "master"]
+
+# scp/rsync/ssh uploads to hosts not in allowed_hosts
+[policy.commands.transfers]
+action = "{transfers}"
+allowed_hosts = []
+
+# Paste sites, tunnels, and request catchers are blocked by default; setting
+# block_domains replaces that list (see railgun.example.toml)
+[policy.network]
+enabled = true
+# blocklist_files = ["blocklists/denylist.txt"]
+public_ip_action = "{public_ip}"
+scheme_action = "{scheme}"
+port_action = "{port}"
+
+# Scripts written outside script_dirs
+[policy.executables]
+action = "{executables}"
+
+# Writes over max_bytes, or binary content
+[policy.writes]
+action = "{writes}"
+
+# npm/pip/cargo installs of packages on neither list
+[policy.packages]
+allow = []
+deny = []
+unknown = "{packages}"
+
+# Reads and writes outside the project directory
+[policy.workspace]
+enabled = {workspace}
+allowed = []
+
+# "ignore previous instructions" and friends in Task and WebFetch prompts
+[policy.injection]
+action = "{injection}"
+
+# Per-session state (kept in ~/.local/state/railgun, outside the project)
+[policy.session.risk]
+enabled = {risk}
+
+[policy.session.taint]
+enabled = {taint}
+action = "{taint_action}"
+"#,
+        sudo = s.sudo,
+        git = s.git,
+        transfers = s.transfers,
+        public_ip = s.public_ip,
+        scheme = s.scheme,
+        port = s.port,
+        executables = s.executables,
+        writes = s.writes,
+        packages = s.packages,
+        workspace = s.workspace,
+        injection = s.injection,
+        risk = s.risk,
+        taint = s.taint,
+        taint_action = s.taint_action,
+    )
+}
+
+/// Run `railgun init`: write the starter configuration to `config_path`.
+pub fn run_init(config_path: &str, preset: Preset, force: bool) -> Result<()> {
+    let path = Path::new(config_path);
+    if path.exists() && !force {
+        return Err(eyre::eyre!(
+            "{} already exists (use --force to overwrite)",
+            path.display()
+        ));
+    }
+
+    std::fs::write(path, render(preset))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!("Wrote {}", path.display());
+    println!("Run `railgun install` to add the hook to Claude Code.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rg_types::{Config, RuleAction};
+
+    fn parse(preset: Preset) -> Config {
+        toml::from_str(&render(preset)).unwrap()
+    }
+
+    #[test]
+    fn test_presets_parse() {
+        let paranoid = parse(Preset::Paranoid);
+        assert_eq!(paranoid.policy.commands.sudo.action, RuleAction::Deny);
+        assert!(paranoid.policy.workspace.enabled);
+        assert!(paranoid.policy.session.taint.enabled);
+
+        let balanced = parse(Preset::Balanced);
+        assert_eq!(balanced.policy.commands.sudo.action, RuleAction::Ask);
+        assert!(!balanced.policy.session.taint.enabled);
+        // Unlisted settings keep their defaults
+        assert_eq!(
+            balanced.policy.network.block_domains,
+            Config::default().policy.network.block_domains
+        );
+
+        let permissive = parse(Preset::Permissive);
+        assert_eq!(permissive.policy.commands.git.action, RuleAction::Allow);
+        assert_eq!(
+            permissive.policy.secrets.action,
+            Config::default().policy.secrets.action
+        );
+    }
+
+    #[test]
+    fn test_init_refuses_to_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("railgun.toml");
+        let path = path.to_str().unwrap();
+
+        run_init(path, Preset::Balanced, false).unwrap();
+        assert!(!crate::lint::lint_config(Path::new(path)).has_errors());
+        assert!(run_init(path, Preset::Paranoid, false).is_err());
+        assert!(std::fs::read_to_string(path)
+            .unwrap()
+            .contains("balanced preset"));
+
+        run_init(path, Preset::Paranoid, true).unwrap();
+        assert!(std::fs::read_to_string(path)
+            .unwrap()
+            .contains("paranoid preset"));
+    }
+}
@@ -1,35 +1,373 @@
-//! Install Railgun as a Claude Code hook.
+//! Install Railgun as a hook of a coding agent.
+//!
+//! Each supported agent is an [`AgentTarget`]: where its settings live and
+//! how a hook entry looks. For Claude Code the hook goes into the user's
+//! `~/.claude/settings.json`, or with `--project` into the current
+//! repository's `.claude/settings.json` (shared through version control) or
+//! `.claude/settings.local.json` (just for you).
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use eyre::{Context, Result};
 use serde_json::{json, Value};
 
+use crate::cli::Agent;
+
+/// Seconds Claude Code gives the hook before killing it, written into each
+/// entry. A killed hook doesn't block the call, so this is kept above
+/// everything the hook waits on: the daemon, `railgun approve`, and the
+/// in-process fallback after them.
+pub const HOOK_TIMEOUT_SECS: u64 = 90;
+
+/// Which settings file the hook is written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// The user's settings, for every project.
+    User,
+    /// The repository's settings, committed with it.
+    Project,
+    /// The repository's local settings, not committed.
+    ProjectLocal,
+}
+
+/// An agent client that runs command hooks before tool calls.
+///
+/// Every target keeps its hooks as `hooks.EVENT = [entry, ...]` in a JSON
+/// settings file; they differ in where that file is, which events Railgun
+/// registers for, and the shape of an entry.
+pub trait AgentTarget {
+    /// The agent's name, for messages.
+    fn name(&self) -> &'static str;
+    /// The settings file for a scope, with `home` the user's home directory
+    /// and `root` the repository root.
+    fn settings_file(&self, scope: Scope, home: &Path, root: &Path) -> Result<PathBuf>;
+    /// The hook events Railgun registers for.
+    fn events(&self) -> &'static [&'static str];
+    /// Arguments of the hook subcommand, e.g. `hook --agent cursor`.
+    fn hook_args(&self) -> &'static str;
+    /// A hook entry running `command`.
+    fn entry(&self, command: &str) -> Value;
+    /// Fill in anything else a new settings file needs.
+    fn prepare(&self, _settings: &mut Value) {}
+}
+
+/// Claude Code: `.claude/settings.json`.
+#[derive(Debug)]
+pub struct ClaudeCode;
+
+impl AgentTarget for ClaudeCode {
+    fn name(&self) -> &'static str {
+        "Claude Code"
+    }
+
+    fn settings_file(&self, scope: Scope, home: &Path, root: &Path) -> Result<PathBuf> {
+        Ok(match scope {
+            Scope::User => home.join(".claude").join("settings.json"),
+            Scope::Project => root.join(".claude").join("settings.json"),
+            Scope::ProjectLocal => root.join(".claude").join("settings.local.json"),
+        })
+    }
+
+    fn events(&self) -> &'static [&'static str] {
+        HOOK_EVENTS
+    }
+
+    fn hook_args(&self) -> &'static str {
+        "hook"
+    }
+
+    fn entry(&self, command: &str) -> Value {
+        // No matcher = all tools
+        json!({
+            "hooks": [
+                {
+                    "type": "command",
+                    "command": command,
+                    "timeout": HOOK_TIMEOUT_SECS
+                }
+            ]
+        })
+    }
+}
+
+/// Cursor: `.cursor/hooks.json`.
+#[derive(Debug)]
+pub struct Cursor;
+
+impl AgentTarget for Cursor {
+    fn name(&self) -> &'static str {
+        "Cursor"
+    }
+
+    fn settings_file(&self, scope: Scope, home: &Path, root: &Path) -> Result<PathBuf> {
+        match scope {
+            Scope::User => Ok(home.join(".cursor").join("hooks.json")),
+            Scope::Project => Ok(root.join(".cursor").join("hooks.json")),
+            Scope::ProjectLocal => Err(eyre::eyre!(
+                "Cursor has no local hooks file; use --project without --local"
+            )),
+        }
+    }
+
+    fn events(&self) -> &'static [&'static str] {
+        &[
+            "beforeShellExecution",
+            "beforeMCPExecution",
+            "beforeReadFile",
+        ]
+    }
+
+    fn hook_args(&self) -> &'static str {
+        "hook --agent cursor"
+    }
+
+    fn entry(&self, command: &str) -> Value {
+        json!({ "command": command })
+    }
+
+    fn prepare(&self, settings: &mut Value) {
+        if settings.get("version").is_none() {
+            settings["version"] = json!(1);
+        }
+    }
+}
+
+/// Gemini CLI: `.gemini/settings.json`, with hooks shaped like Claude
+/// Code's.
+#[derive(Debug)]
+pub struct GeminiCli;
+
+impl AgentTarget for GeminiCli {
+    fn name(&self) -> &'static str {
+        "Gemini CLI"
+    }
+
+    fn settings_file(&self, scope: Scope, home: &Path, root: &Path) -> Result<PathBuf> {
+        match scope {
+            Scope::User => Ok(home.join(".gemini").join("settings.json")),
+            Scope::Project => Ok(root.join(".gemini").join("settings.json")),
+            Scope::ProjectLocal => Err(eyre::eyre!(
+                "Gemini CLI has no local settings file; use --project without --local"
+            )),
+        }
+    }
+
+    fn events(&self) -> &'static [&'static str] {
+        &["BeforeTool", "AfterTool", "SessionStart", "SessionEnd"]
+    }
+
+    fn hook_args(&self) -> &'static str {
+        "hook --agent gemini"
+    }
+
+    fn entry(&self, command: &str) -> Value {
+        ClaudeCode.entry(command)
+    }
+}
+
+/// The target for an agent.
+pub fn target(agent: Agent) -> &'static dyn AgentTarget {
+    match agent {
+        Agent::Claude => &ClaudeCode,
+        Agent::Cursor => &Cursor,
+        Agent::Gemini => &GeminiCli,
+    }
+}
+
+fn home_dir() -> Result<PathBuf> {
+    dirs_next::home_dir().ok_or_else(|| eyre::eyre!("Could not determine home directory"))
+}
+
 /// Get the path to Claude Code settings file.
-fn get_settings_path() -> Result<PathBuf> {
-    let home =
-        dirs_next::home_dir().ok_or_else(|| eyre::eyre!("Could not determine home directory"))?;
-    Ok(home.join(".claude").join("settings.json"))
+pub(crate) fn get_settings_path() -> Result<PathBuf> {
+    settings_path(&ClaudeCode, Scope::User)
+}
+
+/// The settings file of a target for a scope.
+pub(crate) fn settings_path(target: &dyn AgentTarget, scope: Scope) -> Result<PathBuf> {
+    let root = if scope == Scope::User {
+        PathBuf::new()
+    } else {
+        project_root()?
+    };
+    target.settings_file(scope, &home_dir()?, &root)
+}
+
+/// The Claude Code settings file Railgun is registered in, checking the
+/// user file and then the project ones; the user file if none has it.
+pub(crate) fn registered_settings_path() -> Result<PathBuf> {
+    for scope in [Scope::User, Scope::Project, Scope::ProjectLocal] {
+        let path = settings_path(&ClaudeCode, scope)?;
+        if is_registered(&ClaudeCode, &path) {
+            return Ok(path);
+        }
+    }
+    get_settings_path()
+}
+
+/// The repository containing the current directory, or the directory
+/// itself outside a repository.
+fn project_root() -> Result<PathBuf> {
+    let cwd = std::env::current_dir().with_context(|| "Could not determine current directory")?;
+    Ok(cwd
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(&cwd)
+        .to_path_buf())
+}
+
+/// Whether a settings file registers Railgun for any of the target's events.
+fn is_registered(target: &dyn AgentTarget, path: &Path) -> bool {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .is_some_and(|settings| {
+            target.events().iter().any(|event| {
+                settings["hooks"][*event]
+                    .as_array()
+                    .is_some_and(|entries| entries.iter().any(is_railgun_entry))
+            })
+        })
+}
+
+/// Claude Code hook events Railgun registers for.
+pub(crate) const HOOK_EVENTS: &[&str] =
+    &["PreToolUse", "PostToolUse", "SessionStart", "SessionEnd"];
+
+/// Check if a hook entry runs Railgun.
+fn is_railgun_entry(entry: &Value) -> bool {
+    railgun_command(entry).is_some()
+}
+
+/// The Railgun command of a hook entry, if it has one: the entry's own
+/// `command`, or one inside its nested `hooks` array.
+pub(crate) fn railgun_command(entry: &Value) -> Option<String> {
+    let nested = entry.get("hooks").and_then(|h| h.as_array());
+    std::iter::once(entry)
+        .chain(nested.into_iter().flatten())
+        .filter_map(|hook| hook.get("command").and_then(|c| c.as_str()))
+        .find(|s| s.contains("railgun"))
+        .map(str::to_string)
+}
+
+/// The binary of a hook command: everything before the `hook` subcommand.
+pub(crate) fn hook_binary(command: &str) -> &str {
+    command
+        .rsplit_once(" hook")
+        .map_or(command, |(binary, _)| binary)
+        .trim_matches(['"', '\''])
+}
+
+/// Find a binary by path, or on `PATH` if it is a bare name.
+pub(crate) fn resolve_binary(binary: &str) -> Option<PathBuf> {
+    let path = Path::new(binary);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(binary))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Why a hook command no longer runs this Railgun reliably, if it doesn't:
+/// its binary is gone, or is a cargo build output other than the current
+/// binary (which `cargo clean` or the next build replaces).
+fn stale_reason(command: &str) -> Option<String> {
+    let binary = hook_binary(command);
+    let Some(path) = resolve_binary(binary) else {
+        return Some(format!("'{binary}' no longer exists"));
+    };
+    let in_target = path
+        .parent()
+        .and_then(Path::parent)
+        .and_then(Path::file_name)
+        .is_some_and(|dir| dir == "target");
+    let current = std::env::current_exe().ok();
+    (in_target && current.as_deref() != Some(path.as_path()))
+        .then(|| format!("'{binary}' is a cargo build output"))
+}
+
+/// Point the Railgun hooks of an entry at `command`.
+fn set_railgun_command(entry: &mut Value, command: &str) {
+    let nested = entry
+        .get_mut("hooks")
+        .and_then(Value::as_array_mut)
+        .map(|hooks| hooks.iter_mut().collect::<Vec<_>>())
+        .unwrap_or_default();
+    for hook in nested {
+        if hook["command"]
+            .as_str()
+            .is_some_and(|c| c.contains("railgun"))
+        {
+            hook["command"] = json!(command);
+        }
+    }
+    if entry["command"]
+        .as_str()
+        .is_some_and(|c| c.contains("railgun"))
+    {
+        entry["command"] = json!(command);
+    }
 }
 
-/// Install Railgun as a Claude Code hook.
-pub fn run_install() -> Result<()> {
-    let settings_path = get_settings_path()?;
+/// Register a hook entry for an event. Returns `false` if already present.
+fn add_hook(hooks: &mut serde_json::Map<String, Value>, event: &str, entry: Value) -> bool {
+    let event_hooks = hooks.entry(event).or_insert(json!([]));
+    if let Some(arr) = event_hooks.as_array_mut() {
+        if arr.iter().any(is_railgun_entry) {
+            return false;
+        }
+        arr.push(entry);
+    } else {
+        // Event exists but isn't an array - replace it
+        *event_hooks = json!([entry]);
+    }
+    true
+}
+
+fn read_settings(path: &Path) -> Result<Value> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn write_settings(path: &Path, settings: &Value) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let content =
+        serde_json::to_string_pretty(settings).with_context(|| "Failed to serialize settings")?;
+    std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Install Railgun as a hook of an agent. With `update`, existing Railgun
+/// entries that run some other command are pointed at this binary;
+/// without it, an entry whose binary is gone is an error.
+pub fn run_install(agent: Agent, scope: Scope, update: bool) -> Result<()> {
+    let target = target(agent);
+    let user_path = settings_path(target, Scope::User)?;
+    let settings_path = settings_path(target, scope)?;
 
     // Get current binary path
     let binary_path =
         std::env::current_exe().with_context(|| "Could not determine current executable path")?;
 
-    let binary_str = binary_path.to_string_lossy();
+    // A committed file is used on other machines, where the binary lives
+    // elsewhere, so it runs whichever `railgun` is on PATH
+    let binary_str = if scope == Scope::Project {
+        "railgun".into()
+    } else {
+        binary_path.to_string_lossy()
+    };
 
     // Read existing settings or create new
     let mut settings: Value = if settings_path.exists() {
-        let content = std::fs::read_to_string(&settings_path)
-            .with_context(|| format!("Failed to read {}", settings_path.display()))?;
-        serde_json::from_str(&content).with_context(|| "Failed to parse settings.json")?
+        read_settings(&settings_path)?
     } else {
         json!({})
     };
+    target.prepare(&mut settings);
 
     // Ensure hooks object exists
     if settings.get("hooks").is_none() {
@@ -37,127 +375,167 @@ pub fn run_install() -> Result<()> {
     }
 
     // Create hook command
-    let hook_command = format!("{binary_str} hook");
+    let hook_command = format!("{binary_str} {}", target.hook_args());
 
-    // Check if PreToolUse already has our hook
     let hooks = settings["hooks"]
         .as_object_mut()
         .ok_or_else(|| eyre::eyre!("hooks is not an object"))?;
 
-    let pre_tool_use = hooks.entry("PreToolUse").or_insert(json!([]));
-
-    if let Some(arr) = pre_tool_use.as_array_mut() {
-        // Check if hook already exists (look inside hooks arrays)
-        let already_installed = arr.iter().any(|entry| {
-            if let Some(obj) = entry.as_object() {
-                // Check nested hooks array
-                if let Some(hooks_arr) = obj.get("hooks").and_then(|h| h.as_array()) {
-                    return hooks_arr.iter().any(|hook| {
-                        hook.get("command")
-                            .and_then(|c| c.as_str())
-                            .is_some_and(|s| s.contains("railgun"))
-                    });
-                }
+    // For Claude Code, PreToolUse enforces the policy, PostToolUse confirms
+    // approvals, SessionStart advertises its limits, and SessionEnd clears
+    // session state
+    let mut added = false;
+    let mut updated = false;
+    for event in target.events() {
+        added |= add_hook(hooks, event, target.entry(&hook_command));
+        let entries = hooks.get_mut(*event).and_then(Value::as_array_mut);
+        for entry in entries.into_iter().flatten() {
+            let Some(existing) = railgun_command(entry) else {
+                continue;
+            };
+            if existing == hook_command {
+                continue;
+            }
+            if update {
+                set_railgun_command(entry, &hook_command);
+                updated = true;
+            } else if let Some(reason) = stale_reason(&existing) {
+                return Err(eyre::eyre!(
+                    "Railgun is installed in {}, but its hook runs `{existing}` and {reason}; \
+                     run `railgun install --update` to point it at this binary",
+                    settings_path.display()
+                ));
             }
-            false
-        });
-
-        if already_installed {
-            println!("Railgun hook is already installed.");
-            return Ok(());
         }
-
-        // Add new hook entry with correct format (hooks array wrapper, no matcher = all tools)
-        arr.push(json!({
-            "hooks": [
-                {
-                    "type": "command",
-                    "command": hook_command
-                }
-            ]
-        }));
-    } else {
-        // PreToolUse exists but isn't an array - replace it
-        *pre_tool_use = json!([{
-            "hooks": [
-                {
-                    "type": "command",
-                    "command": hook_command
-                }
-            ]
-        }]);
     }
 
-    // Ensure parent directory exists
-    if let Some(parent) = settings_path.parent() {
-        std::fs::create_dir_all(parent)
-            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    if !added && !updated {
+        println!("Railgun hook is already installed.");
+        return Ok(());
     }
 
-    // Write settings back
-    let content =
-        serde_json::to_string_pretty(&settings).with_context(|| "Failed to serialize settings")?;
-    std::fs::write(&settings_path, content)
-        .with_context(|| format!("Failed to write {}", settings_path.display()))?;
+    write_settings(&settings_path, &settings)?;
 
-    println!("Successfully installed Railgun hook!");
+    if added {
+        println!("Successfully installed Railgun hook for {}!", target.name());
+    } else {
+        println!("Updated Railgun hook for {}.", target.name());
+    }
     println!();
     println!("Hook added to: {}", settings_path.display());
     println!("Command: {hook_command}");
     println!();
     println!("Configuration file: railgun.toml (in current directory)");
     println!();
-    println!(
-        "To test: echo '{{\"tool_name\":\"Bash\",\"tool_input\":{{\"command\":\"ls\"}}}}' | {binary_str} hook"
-    );
+    if scope == Scope::Project {
+        println!(
+            "Commit {} to protect everyone working in this repository;",
+            settings_path.display()
+        );
+        println!("each of them needs `railgun` on PATH.");
+        println!();
+    }
+    if scope != Scope::User && is_registered(target, &user_path) {
+        println!(
+            "Note: Railgun is also installed in {}, so each call is inspected twice.",
+            user_path.display()
+        );
+        println!("Run `railgun uninstall` to remove that one.");
+        println!();
+    }
+    if agent == Agent::Claude {
+        println!(
+            "To test: echo '{{\"tool_name\":\"Bash\",\"tool_input\":{{\"command\":\"ls\"}}}}' | {binary_str} hook"
+        );
+    }
 
     Ok(())
 }
 
-/// Uninstall Railgun hook from Claude Code settings.
-pub fn run_uninstall() -> Result<()> {
-    let settings_path = get_settings_path()?;
+/// Uninstall Railgun hook from an agent's settings.
+pub fn run_uninstall(agent: Agent, scope: Scope) -> Result<()> {
+    let target = target(agent);
+    let settings_path = settings_path(target, scope)?;
 
     if !settings_path.exists() {
         println!("No settings file found at {}", settings_path.display());
         return Ok(());
     }
 
-    let content = std::fs::read_to_string(&settings_path)
-        .with_context(|| format!("Failed to read {}", settings_path.display()))?;
+    let _ = remove_hooks(target, &settings_path)?;
+
+    println!("Successfully uninstalled Railgun hook.");
 
-    let mut settings: Value =
-        serde_json::from_str(&content).with_context(|| "Failed to parse settings.json")?;
+    Ok(())
+}
 
-    // Remove railgun from PreToolUse
+/// Remove Railgun from every event a target registers it for. Returns
+/// whether any entry was removed.
+fn remove_hooks(target: &dyn AgentTarget, settings_path: &Path) -> Result<bool> {
+    let mut settings = read_settings(settings_path)?;
+    let mut removed = false;
     if let Some(hooks) = settings.get_mut("hooks") {
-        if let Some(pre_tool_use) = hooks.get_mut("PreToolUse") {
-            if let Some(arr) = pre_tool_use.as_array_mut() {
-                arr.retain(|entry| {
-                    if let Some(obj) = entry.as_object() {
-                        // Check nested hooks array for railgun
-                        if let Some(hooks_arr) = obj.get("hooks").and_then(|h| h.as_array()) {
-                            let has_railgun = hooks_arr.iter().any(|hook| {
-                                hook.get("command")
-                                    .and_then(|c| c.as_str())
-                                    .is_some_and(|s| s.contains("railgun"))
-                            });
-                            return !has_railgun;
-                        }
-                    }
-                    true
-                });
+        for event in target.events() {
+            if let Some(arr) = hooks.get_mut(*event).and_then(|h| h.as_array_mut()) {
+                let before = arr.len();
+                arr.retain(|entry| !is_railgun_entry(entry));
+                removed |= arr.len() != before;
             }
         }
     }
+    write_settings(settings_path, &settings)?;
+    Ok(removed)
+}
 
-    let content =
-        serde_json::to_string_pretty(&settings).with_context(|| "Failed to serialize settings")?;
-    std::fs::write(&settings_path, content)
-        .with_context(|| format!("Failed to write {}", settings_path.display()))?;
+/// Uninstall everywhere and delete what Railgun has written: its hooks in
+/// the user and project settings of the agent, the global config
+/// directory, and the state and caches next to `config_path`. With
+/// `dry_run`, only list them.
+pub fn run_purge(agent: Agent, config_path: &Path, dry_run: bool) -> Result<()> {
+    let target = target(agent);
+    let settings: Vec<PathBuf> = [Scope::User, Scope::Project, Scope::ProjectLocal]
+        .into_iter()
+        .filter_map(|scope| settings_path(target, scope).ok())
+        .filter(|path| is_registered(target, path))
+        .collect();
 
-    println!("Successfully uninstalled Railgun hook.");
+    // A config that no longer loads still has its state at the defaults
+    let config = crate::config_loader::load_config(config_path).unwrap_or_default();
+    let mut files: Vec<PathBuf> = crate::config_loader::global_config_dir()
+        .into_iter()
+        .chain(crate::config_loader::state_files(config_path, &config))
+        .filter(|path| path.exists())
+        .collect();
+    files.dedup();
 
+    if settings.is_empty() && files.is_empty() {
+        println!("Nothing to remove.");
+        return Ok(());
+    }
+    if dry_run {
+        println!("Would remove:");
+        for path in &settings {
+            println!("  Railgun hooks in {}", path.display());
+        }
+        for path in &files {
+            println!("  {}", path.display());
+        }
+        return Ok(());
+    }
+
+    for path in &settings {
+        let _ = remove_hooks(target, path)?;
+        println!("Removed Railgun hooks from {}", path.display());
+    }
+    for path in &files {
+        if path.is_dir() {
+            std::fs::remove_dir_all(path)
+        } else {
+            std::fs::remove_file(path)
+        }
+        .with_context(|| format!("Failed to remove {}", path.display()))?;
+        println!("Removed {}", path.display());
+    }
     Ok(())
 }
 
@@ -165,6 +543,16 @@ pub fn run_uninstall() -> Result<()> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_add_hook_idempotent() {
+        let mut hooks = serde_json::Map::new();
+        let entry = ClaudeCode.entry("/bin/railgun hook");
+
+        assert!(add_hook(&mut hooks, "SessionStart", entry.clone()));
+        assert!(!add_hook(&mut hooks, "SessionStart", entry));
+        assert_eq!(hooks["SessionStart"].as_array().unwrap().len(), 1);
+    }
+
     #[test]
     fn test_get_settings_path() {
         let path = get_settings_path();
@@ -173,4 +561,81 @@ mod tests {
         assert!(path.to_string_lossy().contains(".claude"));
         assert!(path.to_string_lossy().ends_with("settings.json"));
     }
+
+    #[test]
+    fn test_hook_binary() {
+        assert_eq!(
+            hook_binary("/usr/local/bin/railgun hook"),
+            "/usr/local/bin/railgun"
+        );
+        assert_eq!(
+            hook_binary("\"/opt/my tools/railgun\" hook --client"),
+            "/opt/my tools/railgun"
+        );
+    }
+
+    #[test]
+    fn test_stale_reason() {
+        let dir = tempfile::tempdir().unwrap();
+        let build = dir.path().join("target").join("debug");
+        std::fs::create_dir_all(&build).unwrap();
+        let binary = build.join("railgun");
+        std::fs::write(&binary, "").unwrap();
+
+        let current = std::env::current_exe().unwrap();
+        assert_eq!(stale_reason(&format!("{} hook", current.display())), None);
+        assert!(stale_reason("/nonexistent/railgun hook")
+            .unwrap()
+            .contains("no longer exists"));
+        assert!(stale_reason(&format!("{} hook", binary.display()))
+            .unwrap()
+            .contains("cargo build output"));
+    }
+
+    #[test]
+    fn test_set_railgun_command() {
+        let mut entry = json!({
+            "hooks": [
+                { "type": "command", "command": "other-hook" },
+                { "type": "command", "command": "/old/railgun hook" }
+            ]
+        });
+        set_railgun_command(&mut entry, "/new/railgun hook");
+        assert_eq!(entry["hooks"][0]["command"], "other-hook");
+        assert_eq!(entry["hooks"][1]["command"], "/new/railgun hook");
+
+        let mut entry = Cursor.entry("/old/railgun hook --agent cursor");
+        set_railgun_command(&mut entry, "/new/railgun hook --agent cursor");
+        assert_eq!(entry["command"], "/new/railgun hook --agent cursor");
+    }
+
+    #[test]
+    fn test_railgun_command() {
+        let command = "/bin/railgun hook --agent cursor";
+        assert_eq!(
+            railgun_command(&Cursor.entry(command)).as_deref(),
+            Some(command)
+        );
+        assert_eq!(
+            railgun_command(&ClaudeCode.entry(command)).as_deref(),
+            Some(command)
+        );
+        assert_eq!(railgun_command(&json!({ "command": "other-hook" })), None);
+    }
+
+    #[test]
+    fn test_cursor_settings_file() {
+        let (home, root) = (Path::new("/home/me"), Path::new("/src/app"));
+        assert_eq!(
+            Cursor.settings_file(Scope::User, home, root).unwrap(),
+            home.join(".cursor/hooks.json")
+        );
+        assert_eq!(
+            Cursor.settings_file(Scope::Project, home, root).unwrap(),
+            root.join(".cursor/hooks.json")
+        );
+        assert!(Cursor
+            .settings_file(Scope::ProjectLocal, home, root)
+            .is_err());
+    }
 }
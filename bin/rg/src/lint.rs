@@ -2,8 +2,13 @@
 
 use std::path::Path;
 
+use regex_syntax::hir::{Class, Hir, HirKind};
+use rg_policy::safe_regex;
+use rg_types::Config;
 use serde::{Deserialize, Serialize};
 
+use crate::{config_loader, floor, migrate};
+
 /// Severity of a lint issue.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -60,7 +65,7 @@ impl LintIssue {
 }
 
 /// Result of running the linter.
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LintResult {
     /// All issues found.
     pub issues: Vec<LintIssue>,
@@ -103,7 +108,7 @@ pub fn lint_config(path: &Path) -> LintResult {
     };
 
     // Parse TOML
-    let config: toml::Value = match toml::from_str(&content) {
+    let mut config: toml::Value = match toml::from_str(&content) {
         Ok(c) => c,
         Err(e) => {
             result.add(LintIssue::error(
@@ -114,6 +119,16 @@ pub fn lint_config(path: &Path) -> LintResult {
         }
     };
 
+    // Upgrade older schemas first, so the checks below see current keys
+    match migrate::migrate(&mut config) {
+        Ok(upgrade) => {
+            for warning in upgrade.warnings {
+                result.add(LintIssue::warning("deprecated_config", warning));
+            }
+        }
+        Err(e) => result.add(LintIssue::error("config_version", e.to_string())),
+    }
+
     // Validate policy section exists
     if config.get("policy").is_none() {
         result.add(LintIssue::warning(
@@ -122,26 +137,154 @@ pub fn lint_config(path: &Path) -> LintResult {
         ));
     }
 
+    lint_sections(&config, &mut result);
+    let downgrades = check_downgrades(&config, &[], &mut result);
+
+    // Profiles hold the same sections, and are linted the same way
+    if let Some(profiles) = config.get("profile").and_then(toml::Value::as_table) {
+        for (name, profile) in profiles {
+            let mut profile_result = LintResult::default();
+            lint_sections(profile, &mut profile_result);
+            let mut merged = config.clone();
+            if config_loader::apply_profile(&mut merged, name).is_ok() {
+                let _ = check_downgrades(&merged, &downgrades, &mut profile_result);
+            }
+            for mut issue in profile_result.issues {
+                issue.location = Some(format!("profile.{name}"));
+                result.add(issue);
+            }
+        }
+    }
+
+    result
+}
+
+/// Warn about every setting weaker than the built-in defaults, as a locked
+/// global config would restore it (see [`floor::enforce`]), except those in
+/// `known`. Returns the settings found.
+fn check_downgrades(
+    config: &toml::Value,
+    known: &[String],
+    result: &mut LintResult,
+) -> Vec<String> {
+    // Configs that don't deserialize fail to load, which doctor reports
+    let Ok(mut parsed) = config.clone().try_into::<Config>() else {
+        return Vec::new();
+    };
+    let downgrades = floor::enforce(&Config::default(), &mut parsed);
+    for downgrade in downgrades.iter().filter(|d| !known.contains(d)) {
+        result.add(LintIssue::warning(
+            "security_downgrade",
+            format!("Weaker than the built-in default: {downgrade}"),
+        ));
+    }
+    downgrades
+}
+
+/// Validate the patterns of the `[policy]` and `[tools]` sections.
+fn lint_sections(config: &toml::Value, result: &mut LintResult) {
     // Validate patterns if commands section exists
     if let Some(policy) = config.get("policy") {
         if let Some(commands) = policy.get("commands") {
-            validate_patterns(commands, "block_patterns", &mut result);
-            validate_patterns(commands, "allow_patterns", &mut result);
+            validate_patterns(commands, "block_patterns", result);
+            validate_patterns(commands, "ask_patterns", result);
+            validate_patterns(commands, "allow_patterns", result);
+            for field in ["block_patterns", "ask_patterns", "allow_patterns"] {
+                check_duplicates(commands, &format!("policy.commands.{field}"), field, result);
+            }
+            check_shadowed_blocks(commands, result);
+        }
+        if let Some(injection) = policy.get("injection") {
+            validate_patterns(injection, "extra_patterns", result);
+            check_duplicates(
+                injection,
+                "policy.injection.extra_patterns",
+                "extra_patterns",
+                result,
+            );
         }
         if let Some(protected_paths) = policy.get("protected_paths") {
-            validate_glob_patterns(protected_paths, "blocked", &mut result);
+            for field in [
+                "blocked",
+                "blocked_all",
+                "blocked_read",
+                "blocked_write",
+                "allowed",
+            ] {
+                validate_glob_patterns(protected_paths, field, result);
+                check_duplicates(
+                    protected_paths,
+                    &format!("policy.protected_paths.{field}"),
+                    field,
+                    result,
+                );
+            }
         }
     }
 
-    result
+    if let Some(tools) = config.get("tools") {
+        for field in ["allow", "deny", "ask", "inspect"] {
+            check_duplicates(tools, &format!("tools.{field}"), field, result);
+        }
+        check_shadowed_globs(tools, "tools", "allow", &["deny", "ask"], result);
+        if let Some(mcp) = tools.get("mcp") {
+            for field in ["allow_servers", "deny_servers"] {
+                check_duplicates(mcp, &format!("tools.mcp.{field}"), field, result);
+            }
+            check_shadowed_globs(mcp, "tools.mcp", "allow_servers", &["deny_servers"], result);
+        }
+    }
+
+    if let Some(rules) = config
+        .get("tools")
+        .and_then(|tools| tools.get("mcp"))
+        .and_then(|mcp| mcp.get("arg_rules"))
+        .and_then(toml::Value::as_array)
+    {
+        validate_mcp_arg_rules(rules, result);
+    }
+}
+
+fn validate_mcp_arg_rules(rules: &[toml::Value], result: &mut LintResult) {
+    for (i, rule) in rules.iter().enumerate() {
+        let field = |name: &str| rule.get(name).and_then(toml::Value::as_str);
+        if field("regex").is_none() && field("glob").is_none() {
+            result.add(LintIssue::error(
+                "missing_condition",
+                format!("tools.mcp.arg_rules[{i}] needs a regex or glob"),
+            ));
+        }
+        if let Some(Err(e)) = field("regex").map(safe_regex::compile) {
+            result.add(LintIssue::error(
+                "invalid_regex",
+                format!("Invalid regex in tools.mcp.arg_rules[{i}]: {e}"),
+            ));
+        }
+        for name in ["glob", "tool"] {
+            if let Some(Err(e)) = field(name).map(glob::Pattern::new) {
+                result.add(LintIssue::error(
+                    "invalid_glob",
+                    format!("Invalid glob pattern in tools.mcp.arg_rules[{i}].{name}: {e}"),
+                ));
+            }
+        }
+        if let Some(Err(e)) = field("path").map(rg_policy::mcp_args::parse_selector) {
+            result.add(LintIssue::error(
+                "invalid_selector",
+                format!("Invalid path in tools.mcp.arg_rules[{i}]: {e}"),
+            ));
+        }
+    }
 }
 
 fn validate_patterns(commands: &toml::Value, field: &str, result: &mut LintResult) {
     if let Some(patterns) = commands.get(field) {
         if let Some(arr) = patterns.as_array() {
             for (i, pattern) in arr.iter().enumerate() {
+                // Entries are bare regexes or tables with a `pattern` key
+                let pattern = pattern.get("pattern").unwrap_or(pattern);
                 if let Some(p) = pattern.as_str() {
-                    if let Err(e) = regex::Regex::new(p) {
+                    if let Err(e) = safe_regex::compile(p) {
                         result.add(LintIssue::error(
                             "invalid_regex",
                             format!("Invalid regex in {field}[{i}]: {e}"),
@@ -170,6 +313,189 @@ fn validate_glob_patterns(protected_paths: &toml::Value, field: &str, result: &m
     }
 }
 
+/// Upper bound on the sample strings generated for one block pattern;
+/// patterns needing more are not checked for shadowing.
+const MAX_SAMPLES: usize = 256;
+
+/// The string entries of an array field, with their indices. Table entries
+/// contribute their `pattern`.
+fn entries<'a>(section: &'a toml::Value, field: &str) -> Vec<(usize, &'a str)> {
+    section
+        .get(field)
+        .and_then(toml::Value::as_array)
+        .map(|arr| {
+            arr.iter()
+                .enumerate()
+                .filter_map(|(i, entry)| {
+                    let pattern = entry.get("pattern").unwrap_or(entry);
+                    pattern.as_str().map(|p| (i, p))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn check_duplicates(section: &toml::Value, label: &str, field: &str, result: &mut LintResult) {
+    let entries = entries(section, field);
+    for (n, (j, pattern)) in entries.iter().enumerate() {
+        if let Some((i, _)) = entries[..n].iter().find(|(_, p)| p == pattern) {
+            result.add(LintIssue::warning(
+                "duplicate_pattern",
+                format!("{label}[{j}] duplicates {label}[{i}] ('{pattern}')"),
+            ));
+        }
+    }
+}
+
+/// Allow patterns exempt the segments they match before block patterns are
+/// checked, so a block pattern whose every match an allow pattern also
+/// matches can never fire. Superset checks on regexes are approximated by
+/// testing the allow patterns against sample strings the block pattern
+/// matches.
+fn check_shadowed_blocks(commands: &toml::Value, result: &mut LintResult) {
+    let allows: Vec<(usize, regex::Regex)> = entries(commands, "allow_patterns")
+        .into_iter()
+        .filter_map(|(i, p)| regex::Regex::new(p).ok().map(|re| (i, re)))
+        .collect();
+    if allows.is_empty() {
+        return;
+    }
+
+    for (j, block) in entries(commands, "block_patterns") {
+        let Some(samples) = regex_samples(block) else {
+            continue;
+        };
+        if let Some((i, _)) = allows
+            .iter()
+            .find(|(_, allow)| samples.iter().all(|s| allow.is_match(s)))
+        {
+            result.add(LintIssue::warning(
+                "shadowed_pattern",
+                format!(
+                    "policy.commands.block_patterns[{j}] is unreachable: \
+                     policy.commands.allow_patterns[{i}] exempts everything it matches \
+                     (e.g. '{}')",
+                    samples[0]
+                ),
+            ));
+        }
+    }
+}
+
+/// Strings a regex matches: the shortest and a longer form of each
+/// repetition, and every branch of each alternation. `None` if the pattern
+/// doesn't parse or needs more than [`MAX_SAMPLES`] strings.
+fn regex_samples(pattern: &str) -> Option<Vec<String>> {
+    let re = regex::Regex::new(pattern).ok()?;
+    let mut samples = expand(&regex_syntax::parse(pattern).ok()?)?;
+    samples.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+    samples.dedup();
+    // Assertions like `\b` are expanded as nothing, which may not hold
+    samples.iter().all(|s| re.is_match(s)).then_some(samples)
+}
+
+fn expand(hir: &Hir) -> Option<Vec<String>> {
+    let samples = match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => vec![String::new()],
+        HirKind::Literal(literal) => vec![String::from_utf8(literal.0.to_vec()).ok()?],
+        HirKind::Class(class) => vec![class_sample(class)?.to_string()],
+        HirKind::Capture(capture) => expand(&capture.sub)?,
+        HirKind::Repetition(repetition) => {
+            let sub = expand(&repetition.sub)?;
+            let longer = repetition
+                .max
+                .map_or(repetition.min + 2, |max| max.min(repetition.min + 2));
+            let mut samples = Vec::new();
+            for count in [repetition.min, longer] {
+                let count = usize::try_from(count).ok()?;
+                samples.extend(sub.iter().map(|s| s.repeat(count)));
+            }
+            samples
+        }
+        HirKind::Concat(subs) => subs.iter().try_fold(vec![String::new()], |acc, sub| {
+            let next = expand(sub)?;
+            (acc.len() * next.len() <= MAX_SAMPLES).then(|| {
+                acc.iter()
+                    .flat_map(|a| next.iter().map(move |b| format!("{a}{b}")))
+                    .collect()
+            })
+        })?,
+        HirKind::Alternation(subs) => {
+            let mut samples = Vec::new();
+            for sub in subs {
+                samples.extend(expand(sub)?);
+            }
+            samples
+        }
+    };
+    (samples.len() <= MAX_SAMPLES).then_some(samples)
+}
+
+/// A representative character of a class: a letter for broad classes like
+/// `.` or `\w`, a space for `\s`.
+fn class_sample(class: &Class) -> Option<char> {
+    let ranges: Vec<(char, char)> = match class {
+        Class::Unicode(class) => class.iter().map(|r| (r.start(), r.end())).collect(),
+        Class::Bytes(class) => class
+            .iter()
+            .filter(|r| r.start().is_ascii())
+            .map(|r| (char::from(r.start()), char::from(r.end().min(0x7f))))
+            .collect(),
+    };
+    let contains = |c: char| ranges.iter().any(|&(start, end)| start <= c && c <= end);
+    ['x', ' ', '0']
+        .into_iter()
+        .find(|&c| contains(c))
+        .or_else(|| ranges.first().map(|&(start, _)| start))
+}
+
+/// Warn about `allow_field` entries that an entry of an earlier-checked
+/// list always matches first, e.g. `tools.allow` entries under a broader
+/// `tools.deny` glob.
+fn check_shadowed_globs(
+    section: &toml::Value,
+    label: &str,
+    allow_field: &str,
+    earlier: &[&str],
+    result: &mut LintResult,
+) {
+    for (i, allow) in entries(section, allow_field) {
+        let samples = glob_samples(allow);
+        let shadowing = earlier.iter().find_map(|field| {
+            entries(section, field)
+                .into_iter()
+                .find_map(|(j, pattern)| {
+                    let glob = glob::Pattern::new(pattern).ok()?;
+                    samples
+                        .iter()
+                        .all(|s| glob.matches(s))
+                        .then(|| format!("{label}.{field}[{j}] ('{pattern}')"))
+                })
+        });
+        if let Some(shadowing) = shadowing {
+            result.add(LintIssue::warning(
+                "shadowed_pattern",
+                format!(
+                    "{label}.{allow_field}[{i}] ('{allow}') is unreachable: \
+                     {shadowing} matches everything it does first"
+                ),
+            ));
+        }
+    }
+}
+
+/// Names a glob matches: the pattern itself, and with its wildcards filled
+/// in short and long.
+fn glob_samples(pattern: &str) -> Vec<String> {
+    let mut samples = vec![pattern.to_string()];
+    if !pattern.contains('[') {
+        for fill in ["", "xx"] {
+            samples.push(pattern.replace('*', fill).replace('?', "x"));
+        }
+    }
+    samples
+}
+
 /// Format lint result for human-readable output.
 pub fn format_human(result: &LintResult) -> String {
     use std::fmt::Write;
@@ -204,11 +530,62 @@ pub fn format_human(result: &LintResult) -> String {
 }
 
 /// Format lint result as JSON.
-#[allow(dead_code)] // Used in tests; kept for future --json flag
 pub fn format_json(result: &LintResult) -> String {
     serde_json::to_string_pretty(result).unwrap_or_else(|_| "{}".to_string())
 }
 
+/// Format lint result as a SARIF 2.1.0 log. Every result points at the
+/// config file; issues in a profile also name it as a logical location.
+pub fn format_sarif(result: &LintResult, path: &Path) -> String {
+    let mut rules: Vec<&str> = result.issues.iter().map(|i| i.code.as_str()).collect();
+    rules.sort_unstable();
+    rules.dedup();
+
+    let uri = path.to_string_lossy().replace('\\', "/");
+    let results: Vec<serde_json::Value> = result
+        .issues
+        .iter()
+        .map(|issue| {
+            let mut location = serde_json::json!({
+                "physicalLocation": {
+                    "artifactLocation": { "uri": uri },
+                    "region": { "startLine": 1 }
+                }
+            });
+            if let Some(loc) = &issue.location {
+                location["logicalLocations"] = serde_json::json!([{ "fullyQualifiedName": loc }]);
+            }
+            serde_json::json!({
+                "ruleId": issue.code,
+                "ruleIndex": rules.binary_search(&issue.code.as_str()).unwrap_or_default(),
+                "level": issue.severity.to_string(),
+                "message": { "text": issue.message },
+                "locations": [location]
+            })
+        })
+        .collect();
+
+    let log = serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "railgun",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/douglance/railgun",
+                    "rules": rules
+                        .iter()
+                        .map(|id| serde_json::json!({ "id": id }))
+                        .collect::<Vec<_>>()
+                }
+            },
+            "results": results
+        }]
+    });
+    serde_json::to_string_pretty(&log).unwrap_or_else(|_| "{}".to_string())
+}
+
 #[cfg(test)]
 #[allow(clippy::needless_raw_string_hashes)]
 mod tests {
@@ -247,7 +624,7 @@ block_patterns = ["rm\\s+-rf\\s+/"]
         let result = lint_str("[invalid");
 
         assert!(result.has_errors());
-        assert!(result.issues[0].code == "toml_parse_error");
+        assert_eq!(result.issues[0].code, "toml_parse_error");
     }
 
     #[test]
@@ -263,6 +640,106 @@ block_patterns = ["rm\\s+-rf\\s+/"]
         assert!(result.warning_count > 0);
     }
 
+    #[test]
+    fn test_lint_config_version() {
+        let result = lint_str("version = 1\n[policy]\nmode = \"strict\"\n");
+        assert!(!result.has_errors(), "Expected no errors: {result:?}");
+
+        let result = lint_str("version = 99\n[policy]\nmode = \"strict\"\n");
+        assert!(result.has_errors());
+        assert!(result.issues.iter().any(|i| i.code == "config_version"));
+    }
+
+    #[test]
+    fn test_lint_shadowed_patterns() {
+        let result = lint_str(
+            r#"
+[policy.commands]
+block_patterns = [
+    "rm\\s+-rf\\s+/",
+    { name = "curl_pipe", pattern = "curl\\s+\\S+\\s*\\|\\s*(ba)?sh" },
+    "git\\s+push\\s+--force",
+    "rm\\s+-rf\\s+/",
+]
+allow_patterns = ["^rm\\b", "^git push --force-with-lease"]
+
+[tools]
+deny = ["mcp__*"]
+ask = ["Web*"]
+allow = ["Read", "mcp__github__*", "WebFetch", "Read"]
+"#,
+        );
+        let messages: Vec<&str> = result
+            .issues
+            .iter()
+            .filter(|i| i.code == "duplicate_pattern" || i.code == "shadowed_pattern")
+            .map(|i| i.message.as_str())
+            .collect();
+
+        assert!(!result.has_errors(), "Expected no errors: {result:?}");
+        assert_eq!(messages.len(), 6, "{messages:?}");
+        assert!(messages[0].starts_with(
+            "policy.commands.block_patterns[3] duplicates policy.commands.block_patterns[0]"
+        ));
+        // `^rm\b` exempts every `rm -rf /`, and the duplicate
+        assert!(messages[1].starts_with("policy.commands.block_patterns[0] is unreachable"));
+        assert!(messages[1].contains("allow_patterns[0]"));
+        assert!(messages[2].starts_with("policy.commands.block_patterns[3] is unreachable"));
+        assert!(messages[3].starts_with("tools.allow[3] duplicates tools.allow[0]"));
+        assert!(messages[4].starts_with("tools.allow[1] ('mcp__github__*') is unreachable"));
+        assert!(messages[5].starts_with("tools.allow[2] ('WebFetch') is unreachable: tools.ask[0]"));
+    }
+
+    #[test]
+    fn test_lint_security_downgrade() {
+        let result = lint_str(
+            r#"
+[policy]
+fail_closed = false
+
+[policy.secrets]
+enabled = false
+
+[policy.protected_paths]
+blocked = []
+
+[profile.oss.policy]
+mode = "monitor"
+fail_closed = false
+"#,
+        );
+        let downgrades: Vec<(&str, Option<&str>)> = result
+            .issues
+            .iter()
+            .filter(|i| i.code == "security_downgrade")
+            .map(|i| (i.message.as_str(), i.location.as_deref()))
+            .collect();
+
+        assert!(!result.has_errors(), "Expected no errors: {result:?}");
+        assert_eq!(
+            downgrades,
+            [
+                (
+                    "Weaker than the built-in default: policy.fail_closed: turned off",
+                    None
+                ),
+                (
+                    "Weaker than the built-in default: policy.secrets.enabled: turned off",
+                    None
+                ),
+                (
+                    "Weaker than the built-in default: policy.protected_paths.blocked: drops 9 entries",
+                    None
+                ),
+                // The profile's own downgrades only
+                (
+                    "Weaker than the built-in default: policy.mode: relaxed to monitor",
+                    Some("profile.oss")
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn test_lint_invalid_regex() {
         let result = lint_str(
@@ -280,6 +757,76 @@ block_patterns = ["[invalid regex"]
         assert!(result.issues.iter().any(|i| i.code == "invalid_regex"));
     }
 
+    #[test]
+    fn test_lint_regex_over_limits() {
+        let result = lint_str(
+            r#"
+[policy.commands]
+allow_patterns = ["\\w{1000}{1000}"]
+"#,
+        );
+
+        let issue = result
+            .issues
+            .iter()
+            .find(|i| i.code == "invalid_regex")
+            .unwrap();
+        assert!(issue.message.contains("allow_patterns[0]"));
+        assert!(issue.message.contains("size limit"), "{}", issue.message);
+    }
+
+    #[test]
+    fn test_lint_invalid_regex_in_table() {
+        let result = lint_str(
+            r#"
+[policy.commands]
+block_patterns = [{ pattern = "[invalid regex", severity = "high" }]
+"#,
+        );
+
+        assert!(result.issues.iter().any(|i| i.code == "invalid_regex"));
+    }
+
+    #[test]
+    fn test_lint_mcp_arg_rules() {
+        let result = lint_str(
+            r#"
+[[tools.mcp.arg_rules]]
+path = "sql"
+regex = "[unclosed"
+
+[[tools.mcp.arg_rules]]
+path = "$..sql"
+
+[[tools.mcp.arg_rules]]
+path = "$.repo"
+glob = "acme/secret-*"
+"#,
+        );
+
+        let codes: Vec<&str> = result.issues.iter().map(|i| i.code.as_str()).collect();
+        assert!(codes.contains(&"invalid_regex"));
+        assert!(codes.contains(&"invalid_selector"));
+        assert!(codes.contains(&"missing_condition"));
+        assert_eq!(result.error_count, 3);
+    }
+
+    #[test]
+    fn test_lint_profiles() {
+        let result = lint_str(
+            r#"
+[policy]
+mode = "strict"
+
+[profile.oss.policy.commands]
+block_patterns = ["[invalid regex"]
+"#,
+        );
+
+        assert_eq!(result.error_count, 1);
+        assert_eq!(result.issues[0].location.as_deref(), Some("profile.oss"));
+    }
+
     #[test]
     fn test_format_json() {
         let mut result = LintResult::default();
@@ -290,6 +837,37 @@ block_patterns = ["[invalid regex"]
         assert!(json.contains("Test message"));
     }
 
+    #[test]
+    fn test_format_sarif() {
+        let mut result = LintResult::default();
+        result.add(LintIssue::error("invalid_regex", "Invalid regex"));
+        let mut issue = LintIssue::warning("duplicate_pattern", "Duplicate");
+        issue.location = Some("profile.ci".to_string());
+        result.add(issue);
+
+        let sarif: serde_json::Value =
+            serde_json::from_str(&format_sarif(&result, Path::new("railgun.toml"))).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "railgun");
+        assert_eq!(run["tool"]["driver"]["rules"][1]["id"], "invalid_regex");
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "invalid_regex");
+        assert_eq!(results[0]["ruleIndex"], 1);
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "railgun.toml"
+        );
+        assert_eq!(results[1]["level"], "warning");
+        assert_eq!(
+            results[1]["locations"][0]["logicalLocations"][0]["fullyQualifiedName"],
+            "profile.ci"
+        );
+    }
+
     #[test]
     fn test_format_human() {
         let mut result = LintResult::default();
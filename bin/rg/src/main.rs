@@ -1,52 +1,363 @@
 //! Railgun CLI - Claude Code LLM Protection Hook
 
+#[cfg(unix)]
+mod approve;
+mod audit;
+mod baseline;
+mod bench;
+mod cases;
 mod cli;
 mod config_loader;
+#[cfg(unix)]
+mod daemon;
+mod diff;
+mod doctor;
+mod floor;
 mod hook;
+mod init;
 mod install;
 mod lint;
+mod metrics;
+mod migrate;
+mod otel;
+mod remote;
+mod replay;
+mod signing;
+mod statsd;
+mod status;
+mod syslog;
+mod transcript;
+mod webhook;
 
 use std::process::ExitCode;
 
 use clap::Parser;
-use cli::{Cli, Commands};
-use rg_policy::RuntimePolicy;
-use rg_types::HookInput;
+use cli::{
+    Agent, BaselineAction, Cli, Commands, ConfigErrorMode, LintFormat, LogFormat, OutputFormat,
+    Preset,
+};
+use std::path::{Path, PathBuf};
+
+use rg_policy::{
+    ApprovalMemory, LogVerdicts, MonitorMode, RiskScorer, RuntimePolicy, SessionAllows,
+    SessionStore, TaintTracker,
+};
+use rg_types::{Config, HookInput, PolicyMode};
+
+use audit::AuditLog;
+use otel::OtelExporter;
+use statsd::StatsdClient;
+use syslog::SyslogSink;
+use webhook::Webhook;
 
 fn main() -> ExitCode {
     let cli = Cli::parse();
 
+    config_loader::select_profile(cli.profile.clone());
+
+    // Diagnostics go to stderr so they never corrupt hook JSON on stdout
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_env("RAILGUN_LOG")
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("warn")),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
     match cli.command {
-        Commands::Hook => run_hook(&cli.config),
-        Commands::Install => run_install(),
-        Commands::Uninstall => run_uninstall(),
-        Commands::Lint => run_lint(&cli.config),
+        Commands::Hook {
+            on_config_error,
+            client,
+            agent,
+        } => run_hook(&cli.config, on_config_error, client, agent),
+        Commands::Init { preset, force } => run_init(&cli.config, preset, force),
+        Commands::Install {
+            agent,
+            project,
+            local,
+            update,
+        } => run_install(agent, install_scope(project, local), update),
+        Commands::Uninstall {
+            agent,
+            purge: true,
+            dry_run,
+            ..
+        } => run_purge(agent, &cli.config, dry_run),
+        Commands::Uninstall {
+            agent,
+            project,
+            local,
+            ..
+        } => run_uninstall(agent, install_scope(project, local)),
+        Commands::Lint { run_tests, format } => run_lint(&cli.config, run_tests, format),
+        Commands::Explain { rule_id } => run_explain(rule_id.as_deref()),
+        Commands::Doctor => run_doctor(&cli.config),
+        Commands::Status => run_status(&cli.config),
+        Commands::Log {
+            deny_only,
+            tool,
+            since,
+            rule,
+            limit,
+            format,
+        } => run_log(
+            &cli.config,
+            &audit::Filter {
+                deny_only,
+                tool,
+                since,
+                rule,
+            },
+            limit,
+            format,
+        ),
+        Commands::Replay {
+            policy,
+            deny_only,
+            tool,
+            since,
+            rule,
+            format,
+        } => run_replay(
+            &cli.config,
+            policy.as_deref(),
+            &audit::Filter {
+                deny_only,
+                tool,
+                since,
+                rule,
+            },
+            format,
+        ),
         Commands::Test {
             tool_name,
             tool_input,
-        } => run_test(&cli.config, &tool_name, &tool_input),
+            cases,
+            format,
+        } => match (cases, tool_name, tool_input) {
+            (Some(cases), _, _) => run_test_cases(&cli.config, &cases, format),
+            (None, Some(tool_name), Some(tool_input)) => {
+                run_test(&cli.config, &tool_name, &tool_input, format)
+            }
+            // clap requires both positionals without --cases
+            _ => ExitCode::FAILURE,
+        },
+        Commands::ScanTranscript { path, format } => {
+            run_scan_transcript(&cli.config, &path, format)
+        }
+        Commands::Diff {
+            old,
+            new,
+            cases,
+            format,
+        } => run_diff(&old, &new, cases.as_deref(), format),
+        Commands::Schema => run_schema(),
+        Commands::Bench { iterations, cases } => {
+            run_bench(&cli.config, cases.as_deref(), iterations)
+        }
+        Commands::Approve => run_approve(&cli.config),
+        Commands::Daemon { metrics } => run_daemon(&cli.config, metrics),
+        Commands::Keygen { path } => run_keygen(&path),
+        Commands::Sign { files, key } => run_sign(&files, &key),
+        Commands::Baseline { action } => run_baseline(&cli.config, action),
     }
 }
 
-fn run_hook(config_path: &str) -> ExitCode {
-    // Load config
-    let config = match config_loader::load_config(config_path) {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!(r#"{{"error": "Failed to load config: {e}"}}"#);
-            return ExitCode::from(2);
+fn run_hook(
+    config_path: &str,
+    on_config_error: ConfigErrorMode,
+    client: bool,
+    agent: Agent,
+) -> ExitCode {
+    let input = match hook::read_stdin() {
+        Ok(input) => input,
+        // Fail closed on errors
+        Err(e) => return hook::HookResponse::error(&format!("Failed to read stdin: {e}")).emit(),
+    };
+
+    hook::adapt(agent, &input, |payload| {
+        hook_response(config_path, on_config_error, client, payload)
+    })
+    .emit()
+}
+
+/// Answer a Claude Code hook payload.
+fn hook_response(
+    config_path: &str,
+    on_config_error: ConfigErrorMode,
+    client: bool,
+    input: &str,
+) -> hook::HookResponse {
+    // A running daemon answers without loading config or compiling patterns
+    #[cfg(unix)]
+    if client {
+        let socket = config_loader::daemon_socket(Path::new(config_path));
+        match daemon::forward(&socket, input) {
+            Ok(response) => return response,
+            Err(e) => tracing::debug!(error = %e, "daemon unavailable; inspecting in-process"),
         }
+    }
+    #[cfg(not(unix))]
+    let _ = client;
+
+    // Load config, falling back to the built-in policy in safe mode
+    let (config, warning) = match config_loader::load_config(config_path) {
+        Ok(c) => (c, None),
+        Err(e) => match on_config_error {
+            ConfigErrorMode::Deny => {
+                eprintln!(r#"{{"error": "Failed to load config: {e}"}}"#);
+                return hook::HookResponse {
+                    output: None,
+                    code: 2,
+                };
+            }
+            ConfigErrorMode::SafeMode => {
+                let warning = format!(
+                    "RAILGUN SAFE MODE: failed to load config ({e:#}); \
+                     enforcing the built-in default policy until it is fixed."
+                );
+                eprintln!("{warning}");
+                (Config::default(), Some(warning))
+            }
+        },
     };
 
+    let (policy, state) = hook_policy(Path::new(config_path), &config);
+    hook::handle(&policy, &state, warning.as_deref(), input)
+}
+
+/// Build the hook's policy: the runtime policy plus the middleware backed by
+/// cross-invocation state.
+fn hook_policy(config_path: &Path, config: &Config) -> (RuntimePolicy, hook::HookState) {
+    let mut policy = base_policy(config);
+
+    // Cross-invocation state is hook-only so `railgun test` never records asks
+    let state = hook_state(config_path, config);
+    // Taint asks come first so approvals and session allows can cover them
+    if config.policy.session.taint.enabled {
+        if let Some(sessions) = &state.sessions {
+            policy.middleware.push(TaintTracker::new(
+                sessions.clone(),
+                &config.policy.session.taint,
+            ));
+        }
+    }
+    if let Some(approvals) = &state.approvals {
+        policy.middleware.push(approvals.clone());
+    }
+    if let Some(session_allows) = &state.session_allows {
+        policy.middleware.push(session_allows.clone());
+    }
+    if config.policy.session.risk.enabled {
+        if let Some(sessions) = &state.sessions {
+            policy.middleware.push(RiskScorer::new(
+                sessions.clone(),
+                &config.policy.session.risk,
+            ));
+        }
+    }
+    // Only asks that nothing remembered reach the human
+    #[cfg(unix)]
+    if let Some(channel) = approve::ApprovalChannel::from_config(config_path, config) {
+        policy.middleware.push(channel);
+    }
+    // Monitor mode is applied by the hook after recording the real verdict
+
+    (policy, state)
+}
+
+/// Build the state the hook keeps across invocations.
+fn hook_state(config_path: &Path, config: &Config) -> hook::HookState {
+    let approvals = &config.policy.approvals;
+    let session = &config.policy.session;
+    let sessions =
+        (session.allow_approved || session.risk.enabled || session.taint.enabled).then(|| {
+            SessionStore::new(
+                config_loader::session_dir(config_path, config),
+                session.max_age_secs,
+            )
+        });
+
+    hook::HookState {
+        approvals: approvals.enabled.then(|| {
+            ApprovalMemory::new(
+                config_loader::approvals_path(config_path, config),
+                approvals.ttl_secs,
+            )
+        }),
+        session_allows: session
+            .allow_approved
+            .then(|| sessions.clone().map(SessionAllows::new))
+            .flatten(),
+        taint: session
+            .taint
+            .enabled
+            .then(|| {
+                sessions
+                    .clone()
+                    .map(|store| TaintTracker::new(store, &session.taint))
+            })
+            .flatten(),
+        sessions,
+        audit: config
+            .policy
+            .audit
+            .enabled
+            .then(|| AuditLog::new(config_loader::audit_path(config_path, config))),
+        metrics: None,
+        statsd: StatsdClient::from_config(&config.policy.statsd),
+        otel: OtelExporter::from_config(&config.policy.otel),
+        webhook: Webhook::from_config(config_path, config),
+        syslog: SyslogSink::from_config(&config.policy.syslog),
+        monitor: config.policy.mode == PolicyMode::Monitor,
+    }
+}
+
+/// Build the runtime policy and wire the binary's middleware chain.
+fn build_policy(config: &Config) -> RuntimePolicy {
+    let mut policy = base_policy(config);
+    push_monitor_mode(&mut policy, config);
+    policy
+}
+
+/// The runtime policy and verdict logging, without monitor mode.
+fn base_policy(config: &Config) -> RuntimePolicy {
     // Build policy (using full config to include tool-level permissions)
-    let policy = RuntimePolicy::new(&config);
+    let mut policy = RuntimePolicy::new(config);
+
+    // Notifications see the real verdict, before monitor mode relaxes it
+    policy.middleware.push(LogVerdicts);
+    policy
+}
+
+/// Relax every verdict in monitor mode. Pushed last, so no later middleware
+/// can turn the relaxed verdict back into a deny or ask.
+fn push_monitor_mode(policy: &mut RuntimePolicy, config: &Config) {
+    if config.policy.mode == PolicyMode::Monitor {
+        policy.middleware.push(MonitorMode);
+    }
+}
+
+fn run_init(config_path: &str, preset: Preset, force: bool) -> ExitCode {
+    match init::run_init(config_path, preset, force) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    // Run hook
-    hook::run_hook(&policy)
+fn install_scope(project: bool, local: bool) -> install::Scope {
+    match (project, local) {
+        (false, _) => install::Scope::User,
+        (true, false) => install::Scope::Project,
+        (true, true) => install::Scope::ProjectLocal,
+    }
 }
 
-fn run_install() -> ExitCode {
-    match install::run_install() {
+fn run_install(agent: Agent, scope: install::Scope, update: bool) -> ExitCode {
+    match install::run_install(agent, scope, update) {
         Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
             eprintln!("Error: {e}");
@@ -55,8 +366,8 @@ fn run_install() -> ExitCode {
     }
 }
 
-fn run_uninstall() -> ExitCode {
-    match install::run_uninstall() {
+fn run_uninstall(agent: Agent, scope: install::Scope) -> ExitCode {
+    match install::run_uninstall(agent, scope) {
         Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
             eprintln!("Error: {e}");
@@ -65,20 +376,370 @@ fn run_uninstall() -> ExitCode {
     }
 }
 
-fn run_lint(config_path: &str) -> ExitCode {
+fn run_log(
+    config_path: &str,
+    filter: &audit::Filter,
+    limit: Option<usize>,
+    format: LogFormat,
+) -> ExitCode {
+    match audit::run_log(config_path, filter, limit, format) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_replay(
+    config_path: &str,
+    policy_path: Option<&Path>,
+    filter: &audit::Filter,
+    format: LogFormat,
+) -> ExitCode {
+    match replay::run_replay(config_path, policy_path, filter, format) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_purge(agent: Agent, config_path: &str, dry_run: bool) -> ExitCode {
+    match install::run_purge(agent, Path::new(config_path), dry_run) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_daemon(config_path: &str, metrics: Option<std::net::SocketAddr>) -> ExitCode {
+    #[cfg(unix)]
+    let result = daemon::run_daemon(config_path, metrics);
+    #[cfg(not(unix))]
+    let result: eyre::Result<()> = {
+        let _ = (config_path, metrics);
+        Err(eyre::eyre!("railgun daemon requires Unix domain sockets"))
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_approve(config_path: &str) -> ExitCode {
+    #[cfg(unix)]
+    let result = approve::run_approve(config_path);
+    #[cfg(not(unix))]
+    let result: eyre::Result<()> = {
+        let _ = config_path;
+        Err(eyre::eyre!("railgun approve requires Unix domain sockets"))
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_keygen(path: &Path) -> ExitCode {
+    match signing::generate_key(path) {
+        Ok(public) => {
+            println!("Private key written to {}", path.display());
+            println!("Public key (add to policy.trusted_keys):\n{public}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_sign(files: &[PathBuf], key: &Path) -> ExitCode {
+    let result = files.iter().try_for_each(|file| {
+        let sig_path = signing::sign_file(file, key)?;
+        println!("Signed {} -> {}", file.display(), sig_path.display());
+        Ok::<_, eyre::Report>(())
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_baseline(config_path: &str, action: BaselineAction) -> ExitCode {
+    let result = match action {
+        BaselineAction::Add { text, file, note } => baseline::run_add(
+            config_path,
+            text.as_deref(),
+            file.as_deref(),
+            note.as_deref(),
+        ),
+        BaselineAction::List => baseline::run_list(config_path),
+        BaselineAction::Remove { fingerprint } => baseline::run_remove(config_path, &fingerprint),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_lint(config_path: &str, run_tests: bool, format: LintFormat) -> ExitCode {
     let path = std::path::Path::new(config_path);
     let result = lint::lint_config(path);
 
-    print!("{}", lint::format_human(&result));
+    // A config with errors would run its tests against a different policy
+    let tests = if run_tests && !result.has_errors() {
+        match config_loader::load_config(config_path) {
+            Ok(config) => Some(cases::run_cases(&build_policy(&config), &config.tests)),
+            Err(e) => {
+                eprintln!("Error: {e:#}");
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        None
+    };
 
-    if result.has_errors() {
+    match format {
+        LintFormat::Human => {
+            print!("{}", lint::format_human(&result));
+            match &tests {
+                Some(results) if results.is_empty() => {
+                    println!("No [[tests]] in {config_path}");
+                }
+                Some(results) => {
+                    println!();
+                    print!("{}", cases::format_human(results));
+                }
+                None => {}
+            }
+        }
+        LintFormat::Json => match &tests {
+            Some(results) => {
+                let mut doc = serde_json::to_value(&result).unwrap_or_default();
+                doc["tests"] = cases::to_json(results);
+                println!("{doc:#}");
+            }
+            None => println!("{}", lint::format_json(&result)),
+        },
+        LintFormat::Sarif => {
+            // Failed assertions are findings too
+            let mut result = result.clone();
+            for case in tests.iter().flatten().filter(|case| !case.passed()) {
+                result.add(lint::LintIssue::error(
+                    "failed_test",
+                    format!(
+                        "{}: expected {}, got {}",
+                        case.label, case.expected, case.actual
+                    ),
+                ));
+            }
+            println!("{}", lint::format_sarif(&result, path));
+        }
+    }
+
+    let tests_passed = tests.iter().flatten().all(cases::CaseResult::passed);
+    if result.has_errors() || !tests_passed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn run_explain(rule_id: Option<&str>) -> ExitCode {
+    use rg_policy::explain::{explain, RULES};
+
+    let Some(rule_id) = rule_id else {
+        for doc in RULES {
+            println!("{:<22} {}", doc.code, doc.severity);
+        }
+        return ExitCode::SUCCESS;
+    };
+    let Some(explanation) = explain(rule_id) else {
+        eprintln!("Error: unknown rule '{rule_id}'; run `railgun explain` to list the codes");
+        return ExitCode::FAILURE;
+    };
+
+    let doc = explanation.doc;
+    println!("{} (severity: {})", doc.code, doc.severity);
+    if let Some(rule) = explanation.rule {
+        println!("Rule: {rule}");
+    }
+    println!();
+    println!("Matches: {}", doc.matches);
+    println!("Why: {}", doc.why);
+    println!("Examples:");
+    for example in doc.examples {
+        println!("  {example}");
+    }
+    println!("Override: {}", doc.overrides);
+    ExitCode::SUCCESS
+}
+
+fn run_doctor(config_path: &str) -> ExitCode {
+    let settings_path = match install::registered_settings_path() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let checks = doctor::run_checks(&settings_path, Path::new(config_path));
+    print!("{}", doctor::format_human(&checks));
+
+    if checks.iter().any(|c| c.status == doctor::Status::Fail) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn run_status(config_path: &str) -> ExitCode {
+    let source = config_loader::config_source(Path::new(config_path));
+    match config_loader::load_config(config_path) {
+        Ok(config) => {
+            let profile = config_loader::active_profile();
+            print!(
+                "{}",
+                status::format_human(&source, profile.as_deref(), &config)
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_test_cases(config_path: &str, cases_path: &Path, format: OutputFormat) -> ExitCode {
+    let result = config_loader::load_config(config_path)
+        .and_then(|config| Ok((config, cases::load_cases(cases_path)?)));
+    let (config, cases) = match result {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let results = cases::run_cases(&build_policy(&config), &cases);
+    match format {
+        OutputFormat::Human => print!("{}", cases::format_human(&results)),
+        OutputFormat::Json => println!("{:#}", cases::to_json(&results)),
+    }
+
+    if results.iter().all(cases::CaseResult::passed) {
+        ExitCode::SUCCESS
+    } else {
         ExitCode::FAILURE
+    }
+}
+
+fn run_scan_transcript(config_path: &str, path: &Path, format: OutputFormat) -> ExitCode {
+    // Bare policy: monitor mode and other middleware must not hide findings
+    let result = config_loader::load_config(config_path)
+        .and_then(|config| transcript::scan(&RuntimePolicy::new(&config), path));
+    let report = match result {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match format {
+        OutputFormat::Human => print!("{}", transcript::format_human(path, &report)),
+        OutputFormat::Json => println!("{:#}", transcript::to_json(&report)),
+    }
+
+    if report.findings.is_empty() {
+        ExitCode::SUCCESS
     } else {
+        ExitCode::from(2)
+    }
+}
+
+fn run_diff(old: &Path, new: &Path, cases: Option<&Path>, format: OutputFormat) -> ExitCode {
+    let diff = match diff::run_diff(old, new, cases) {
+        Ok(diff) => diff,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match format {
+        OutputFormat::Human => print!("{}", diff::format_human(&diff)),
+        OutputFormat::Json => println!("{:#}", diff::to_json(&diff)),
+    }
+
+    if diff.weakened.is_empty() {
         ExitCode::SUCCESS
+    } else {
+        ExitCode::from(2)
+    }
+}
+
+fn run_schema() -> ExitCode {
+    match serde_json::to_string_pretty(&config_schema()) {
+        Ok(schema) => {
+            println!("{schema}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// JSON Schema of `railgun.toml`, generated from the config types.
+fn config_schema() -> schemars::schema::RootSchema {
+    let mut schema = schemars::schema_for!(Config);
+    schema.schema.metadata().title = Some("Railgun configuration".to_string());
+    schema
+}
+
+fn run_bench(config_path: &str, cases_path: Option<&Path>, iterations: usize) -> ExitCode {
+    match bench::run_bench(Path::new(config_path), cases_path, iterations) {
+        Ok(report) => {
+            print!("{}", bench::format_human(&report));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::FAILURE
+        }
     }
 }
 
-fn run_test(config_path: &str, tool_name: &str, tool_input_json: &str) -> ExitCode {
+fn run_test(
+    config_path: &str,
+    tool_name: &str,
+    tool_input_json: &str,
+    format: OutputFormat,
+) -> ExitCode {
     // Load config
     let config = match config_loader::load_config(config_path) {
         Ok(c) => c,
@@ -88,8 +749,7 @@ fn run_test(config_path: &str, tool_name: &str, tool_input_json: &str) -> ExitCo
         }
     };
 
-    // Build policy (using full config to include tool-level permissions)
-    let policy = RuntimePolicy::new(&config);
+    let policy = build_policy(&config);
 
     // Parse tool input
     let tool_input: serde_json::Value = match serde_json::from_str(tool_input_json) {
@@ -100,13 +760,20 @@ fn run_test(config_path: &str, tool_name: &str, tool_input_json: &str) -> ExitCo
         }
     };
 
-    let input = HookInput {
-        tool_name: tool_name.to_string(),
-        tool_input,
-    };
+    let input = HookInput::new(tool_name, tool_input);
 
     // Inspect
     let (verdict, latency_us) = rg_policy::inspect(&input, &policy);
+    let code = if verdict.is_deny() {
+        ExitCode::from(2)
+    } else {
+        ExitCode::SUCCESS // Ask is not an error
+    };
+
+    if format == OutputFormat::Json {
+        println!("{:#}", verdict_json(tool_name, &verdict, latency_us));
+        return code;
+    }
 
     // Output result
     println!("Tool: {tool_name}");
@@ -114,24 +781,66 @@ fn run_test(config_path: &str, tool_name: &str, tool_input_json: &str) -> ExitCo
     println!();
 
     match &verdict {
-        rg_types::Verdict::Allow => {
-            println!("Result: ALLOWED");
-            ExitCode::SUCCESS
-        }
-        rg_types::Verdict::Deny { reason, context } => {
+        rg_types::Verdict::Allow => println!("Result: ALLOWED"),
+        rg_types::Verdict::Deny {
+            reason,
+            context,
+            violations,
+        } => {
             println!("Result: DENIED");
             println!("Reason: {reason}");
             if let Some(ctx) = context {
                 println!("Context: {ctx}");
             }
-            ExitCode::from(2)
+            if violations.len() > 1 {
+                println!("Violations:");
+                for v in violations {
+                    println!("  [{}] {}: {v}", v.severity(), v.code());
+                }
+            }
         }
-        rg_types::Verdict::Ask { reason } => {
+        rg_types::Verdict::Ask { reason, .. } => {
             println!("Result: ASK");
             println!("Reason: {reason}");
-            ExitCode::SUCCESS // Ask is not an error
+        }
+        rg_types::Verdict::Redact {
+            reason,
+            updated_input,
+        } => {
+            println!("Result: REDACTED");
+            println!("Reason: {reason}");
+            println!("Updated input: {updated_input}");
         }
     }
+    code
+}
+
+/// The `railgun test --format json` document for a verdict.
+fn verdict_json(
+    tool_name: &str,
+    verdict: &rg_types::Verdict,
+    latency_us: u64,
+) -> serde_json::Value {
+    let violations: Vec<_> = verdict
+        .violations()
+        .iter()
+        .map(|v| {
+            serde_json::json!({
+                "code": v.code(),
+                "severity": v.severity().to_string(),
+                "message": v.to_string(),
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "tool_name": tool_name,
+        "decision": verdict.permission_decision(),
+        "reason": verdict.reason(),
+        "context": verdict.context(),
+        "violations": violations,
+        "updated_input": verdict.updated_input(),
+        "latency_us": latency_us,
+    })
 }
 
 #[cfg(test)]
@@ -145,25 +854,64 @@ mod tests {
         let config = rg_types::Config::default();
         let policy = RuntimePolicy::from_config(&config.policy);
 
-        let input = HookInput {
-            tool_name: "Bash".to_string(),
-            tool_input: serde_json::json!({ "command": "ls -la" }),
-        };
+        let input = HookInput::new("Bash", serde_json::json!({ "command": "ls -la" }));
+
+        let (verdict, _) = rg_policy::inspect(&input, &policy);
+        assert!(verdict.is_allow());
+    }
+
+    #[test]
+    fn test_build_policy_monitor_mode() {
+        let mut config = Config::default();
+        config.policy.mode = PolicyMode::Monitor;
+        let policy = build_policy(&config);
+
+        let input = HookInput::new("Bash", serde_json::json!({ "command": "rm -rf /" }));
 
         let (verdict, _) = rg_policy::inspect(&input, &policy);
         assert!(verdict.is_allow());
     }
 
+    #[test]
+    fn test_verdict_json() {
+        let policy = RuntimePolicy::from_config(&Config::default().policy);
+        let input = HookInput::new("Bash", serde_json::json!({ "command": "rm -rf /" }));
+
+        let (verdict, _) = rg_policy::inspect(&input, &policy);
+        let json = verdict_json("Bash", &verdict, 12);
+        assert_eq!(json["decision"], "deny");
+        assert_eq!(json["violations"][0]["code"], "dangerous_command");
+        assert_eq!(json["latency_us"], 12);
+        assert!(json["updated_input"].is_null());
+
+        let json = verdict_json("Bash", &rg_types::Verdict::Allow, 3);
+        assert_eq!(json["decision"], "allow");
+        assert!(json["reason"].is_null());
+        assert_eq!(json["violations"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_config_schema() {
+        let schema = serde_json::to_value(config_schema()).unwrap();
+        assert_eq!(schema["title"], "Railgun configuration");
+        assert!(schema["properties"]["policy"].is_object());
+        assert!(schema["properties"]["tools"].is_object());
+        let actions: Vec<_> = schema["definitions"]["RuleAction"]["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|variant| variant["enum"][0].clone())
+            .collect();
+        assert_eq!(actions, ["allow", "ask", "deny"]);
+    }
+
     #[test]
     fn test_run_test_denied() {
         // Test that run_test works with denied input
         let config = rg_types::Config::default();
         let policy = RuntimePolicy::from_config(&config.policy);
 
-        let input = HookInput {
-            tool_name: "Bash".to_string(),
-            tool_input: serde_json::json!({ "command": "rm -rf /" }),
-        };
+        let input = HookInput::new("Bash", serde_json::json!({ "command": "rm -rf /" }));
 
         let (verdict, _) = rg_policy::inspect(&input, &policy);
         assert!(verdict.is_deny());
@@ -0,0 +1,213 @@
+//! Prometheus metrics for `railgun daemon --metrics`.
+//!
+//! The daemon counts every decision it makes, by decision and by the rules
+//! behind it, and keeps a histogram of inspection latency. [`serve`] exposes
+//! them in the Prometheus text format at `/metrics`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write as _};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex};
+
+use eyre::{Context, Result};
+use rg_types::Verdict;
+
+/// Upper bounds of the latency histogram buckets, in seconds.
+const LATENCY_BUCKETS: &[f64] = &[
+    0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1,
+];
+
+/// Decision counters and the latency histogram.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// Everything recorded so far.
+    counts: Mutex<Counts>,
+}
+
+#[derive(Debug, Default)]
+struct Counts {
+    /// Decisions by `allow`, `ask`, `deny`, or `redact`.
+    decisions: BTreeMap<&'static str, u64>,
+    /// Violations by rule ID.
+    rules: BTreeMap<String, u64>,
+    /// Inspections per latency bucket (not cumulative), plus one past the last.
+    buckets: Vec<u64>,
+    /// Total inspection time, in microseconds.
+    latency_sum_us: u64,
+    /// Inspections recorded.
+    inspections: u64,
+}
+
+impl Metrics {
+    /// Count a decision that took `latency_us` to inspect.
+    pub fn record(&self, verdict: &Verdict, latency_us: u64) {
+        let decision = match verdict {
+            Verdict::Redact { .. } => "redact",
+            verdict => verdict.permission_decision(),
+        };
+        let mut counts = self
+            .counts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        *counts.decisions.entry(decision).or_default() += 1;
+        for violation in verdict.violations() {
+            *counts.rules.entry(violation.rule_id()).or_default() += 1;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let secs = latency_us as f64 / 1e6;
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| secs <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        counts.buckets.resize(LATENCY_BUCKETS.len() + 1, 0);
+        counts.buckets[bucket] += 1;
+        counts.latency_sum_us += latency_us;
+        counts.inspections += 1;
+    }
+
+    /// The metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let counts = self
+            .counts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let mut out = String::new();
+
+        out.push_str("# HELP railgun_decisions_total Tool calls decided, by decision.\n");
+        out.push_str("# TYPE railgun_decisions_total counter\n");
+        for decision in ["allow", "ask", "deny", "redact"] {
+            let count = counts.decisions.get(decision).copied().unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "railgun_decisions_total{{decision=\"{decision}\"}} {count}"
+            );
+        }
+
+        out.push_str("# HELP railgun_rule_hits_total Violations behind ask and deny decisions, by rule ID.\n");
+        out.push_str("# TYPE railgun_rule_hits_total counter\n");
+        for (rule, count) in &counts.rules {
+            let _ = writeln!(
+                out,
+                "railgun_rule_hits_total{{rule=\"{}\"}} {count}",
+                escape(rule)
+            );
+        }
+
+        out.push_str("# HELP railgun_inspection_duration_seconds Time to inspect a tool call.\n");
+        out.push_str("# TYPE railgun_inspection_duration_seconds histogram\n");
+        let mut cumulative = 0;
+        for (i, bound) in LATENCY_BUCKETS.iter().enumerate() {
+            cumulative += counts.buckets.get(i).copied().unwrap_or_default();
+            let _ = writeln!(
+                out,
+                "railgun_inspection_duration_seconds_bucket{{le=\"{bound}\"}} {cumulative}"
+            );
+        }
+        let _ = writeln!(
+            out,
+            "railgun_inspection_duration_seconds_bucket{{le=\"+Inf\"}} {}",
+            counts.inspections
+        );
+        #[allow(clippy::cast_precision_loss)]
+        let sum = counts.latency_sum_us as f64 / 1e6;
+        let _ = writeln!(out, "railgun_inspection_duration_seconds_sum {sum}");
+        let _ = writeln!(
+            out,
+            "railgun_inspection_duration_seconds_count {}",
+            counts.inspections
+        );
+        out
+    }
+}
+
+/// Escape a Prometheus label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Serve `metrics` at `http://ADDR/metrics` on a background thread.
+pub fn serve(addr: SocketAddr, metrics: Arc<Metrics>) -> Result<SocketAddr> {
+    let listener =
+        TcpListener::bind(addr).with_context(|| format!("Failed to listen on {addr}"))?;
+    let local = listener.local_addr()?;
+    let _ = std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut request = String::new();
+            let _ = BufReader::new(&stream).read_line(&mut request);
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            let response = if path == "/metrics" {
+                let body = metrics.render();
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_string()
+            };
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    Ok(local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rg_types::BlockReason;
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::default();
+        metrics.record(&Verdict::Allow, 50);
+        metrics.record(&Verdict::Allow, 3000);
+        metrics.record(
+            &Verdict::deny_from_block_reason(&BlockReason::GitDestructive {
+                rule: "force_push".to_string(),
+                branch: None,
+                matched: "git push --force".to_string(),
+            }),
+            200_000,
+        );
+
+        let text = metrics.render();
+        assert!(text.contains("railgun_decisions_total{decision=\"allow\"} 2\n"));
+        assert!(text.contains("railgun_decisions_total{decision=\"deny\"} 1\n"));
+        assert!(text.contains("railgun_decisions_total{decision=\"ask\"} 0\n"));
+        assert!(text.contains("railgun_rule_hits_total{rule=\"git_destructive:force_push\"} 1\n"));
+        assert!(text.contains("railgun_inspection_duration_seconds_bucket{le=\"0.0001\"} 1\n"));
+        assert!(text.contains("railgun_inspection_duration_seconds_bucket{le=\"0.005\"} 2\n"));
+        assert!(text.contains("railgun_inspection_duration_seconds_bucket{le=\"0.1\"} 2\n"));
+        assert!(text.contains("railgun_inspection_duration_seconds_bucket{le=\"+Inf\"} 3\n"));
+        assert!(text.contains("railgun_inspection_duration_seconds_sum 0.20305\n"));
+        assert!(text.contains("railgun_inspection_duration_seconds_count 3\n"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(escape("a\nb"), "a\\nb");
+    }
+
+    #[test]
+    fn test_serve() {
+        let metrics = Arc::new(Metrics::default());
+        metrics.record(&Verdict::Allow, 10);
+        let addr = serve("127.0.0.1:0".parse().unwrap(), metrics).unwrap();
+
+        let body = ureq::get(&format!("http://{addr}/metrics"))
+            .call()
+            .unwrap()
+            .into_string()
+            .unwrap();
+        assert!(body.contains("railgun_decisions_total{decision=\"allow\"} 1"));
+        assert!(ureq::get(&format!("http://{addr}/other")).call().is_err());
+    }
+}
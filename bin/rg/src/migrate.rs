@@ -0,0 +1,245 @@
+//! Config schema versions: upgrade files written for an older schema on
+//! load, and warn about deprecated settings.
+//!
+//! When a release renames a key or moves a section, it bumps
+//! [`CONFIG_VERSION`] and adds a [`Rename`] for it, so files that declare an
+//! older `version` (or none) keep loading. Settings that still work but are
+//! on their way out get a [`Deprecation`].
+
+use eyre::Result;
+use rg_types::CONFIG_VERSION;
+
+/// A key renamed, or a section moved, in a schema version.
+#[derive(Debug, Clone, Copy)]
+pub struct Rename {
+    /// The version that made the change.
+    pub version: u32,
+    /// Dotted path of the old key, e.g. `policy.secrets.custom_patterns`.
+    pub from: &'static str,
+    /// Dotted path of the new key.
+    pub to: &'static str,
+}
+
+/// A setting that still works but will be removed.
+#[derive(Debug, Clone, Copy)]
+pub struct Deprecation {
+    /// Dotted path of the key.
+    pub path: &'static str,
+    /// What to use instead.
+    pub note: &'static str,
+}
+
+/// Renames, oldest first. Version 1 is the first versioned schema; files
+/// without `version` predate it and are read as version 1.
+const RENAMES: &[Rename] = &[];
+
+/// Deprecated settings.
+const DEPRECATIONS: &[Deprecation] = &[];
+
+/// What [`migrate`] did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Upgrade {
+    /// Whether any key was moved.
+    pub changed: bool,
+    /// One per renamed or deprecated key found.
+    pub warnings: Vec<String>,
+}
+
+/// Upgrade a parsed config to [`CONFIG_VERSION`] in place, including its
+/// `[profile.NAME]` sections.
+///
+/// Files declaring a newer version than this build reads are rejected,
+/// since their settings could silently mean something else.
+pub fn migrate(value: &mut toml::Value) -> Result<Upgrade> {
+    migrate_with(value, CONFIG_VERSION, RENAMES, DEPRECATIONS)
+}
+
+fn migrate_with(
+    value: &mut toml::Value,
+    current: u32,
+    renames: &[Rename],
+    deprecations: &[Deprecation],
+) -> Result<Upgrade> {
+    let version = match value.get("version") {
+        None => 1,
+        Some(toml::Value::Integer(version)) => u32::try_from(*version)
+            .ok()
+            .filter(|version| *version >= 1)
+            .ok_or_else(|| eyre::eyre!("Invalid config version {version}"))?,
+        Some(other) => {
+            return Err(eyre::eyre!(
+                "Invalid config version {other}: expected an integer"
+            ))
+        }
+    };
+    if version > current {
+        return Err(eyre::eyre!(
+            "Config version {version} is newer than this Railgun supports \
+             ({current}); upgrade Railgun"
+        ));
+    }
+
+    let mut upgrade = Upgrade::default();
+    let mut sections: Vec<(String, &mut toml::Value)> = Vec::new();
+    let Some(table) = value.as_table_mut() else {
+        return Ok(upgrade);
+    };
+    let mut profiles = table.remove("profile");
+    if let Some(toml::Value::Table(profiles)) = &mut profiles {
+        sections.extend(
+            profiles
+                .iter_mut()
+                .map(|(name, profile)| (format!("profile.{name}."), profile)),
+        );
+    }
+
+    let mut root = toml::Value::Table(std::mem::take(table));
+    sections.insert(0, (String::new(), &mut root));
+    for (prefix, section) in sections {
+        for rename in renames.iter().filter(|rename| rename.version > version) {
+            let Some(moved) = take(section, rename.from) else {
+                continue;
+            };
+            upgrade.changed = true;
+            upgrade.warnings.push(format!(
+                "`{prefix}{}` was renamed to `{prefix}{}` (config version {}); \
+                 update the file and set `version = {current}`",
+                rename.from, rename.to, rename.version
+            ));
+            // A file setting both keeps the new one
+            if get(section, rename.to).is_none() {
+                put(section, rename.to, moved);
+            }
+        }
+        for deprecation in deprecations {
+            if get(section, deprecation.path).is_some() {
+                upgrade.warnings.push(format!(
+                    "`{prefix}{}` is deprecated: {}",
+                    deprecation.path, deprecation.note
+                ));
+            }
+        }
+    }
+
+    if let toml::Value::Table(root) = root {
+        *table = root;
+    }
+    if let Some(profiles) = profiles {
+        let _ = table.insert("profile".to_string(), profiles);
+    }
+    Ok(upgrade)
+}
+
+fn get<'a>(value: &'a toml::Value, path: &str) -> Option<&'a toml::Value> {
+    path.split('.').try_fold(value, |value, key| value.get(key))
+}
+
+fn take(value: &mut toml::Value, path: &str) -> Option<toml::Value> {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (
+            parent
+                .split('.')
+                .try_fold(value, |value, key| value.get_mut(key))?,
+            key,
+        ),
+        None => (value, path),
+    };
+    parent.as_table_mut()?.remove(key)
+}
+
+fn put(value: &mut toml::Value, path: &str, new: toml::Value) {
+    let mut keys: Vec<&str> = path.split('.').collect();
+    let Some(last) = keys.pop() else {
+        return;
+    };
+    let mut current = value;
+    for key in keys {
+        let Some(table) = current.as_table_mut() else {
+            return;
+        };
+        current = table
+            .entry(key)
+            .or_insert_with(|| toml::Value::Table(toml::map::Map::new()));
+    }
+    if let Some(table) = current.as_table_mut() {
+        let _ = table.insert(last.to_string(), new);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RENAMES: &[Rename] = &[
+        Rename {
+            version: 2,
+            from: "policy.secrets.custom_patterns",
+            to: "policy.secrets.rules",
+        },
+        Rename {
+            version: 2,
+            from: "mcp",
+            to: "tools.mcp",
+        },
+    ];
+    const DEPRECATIONS: &[Deprecation] = &[Deprecation {
+        path: "policy.secrets.entropy_threshold",
+        note: "set `entropy` on each rule instead",
+    }];
+
+    fn parse(content: &str) -> toml::Value {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_current_config_unchanged() {
+        let mut value = parse("version = 1\n[policy]\nmode = \"monitor\"\n");
+        let original = value.clone();
+        assert_eq!(migrate(&mut value).unwrap(), Upgrade::default());
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn test_renames() {
+        let mut value = parse(
+            r#"
+[policy.secrets]
+custom_patterns = ["a"]
+entropy_threshold = 4.0
+
+[mcp]
+deny_servers = ["shell"]
+
+[profile.ci.mcp]
+allow_servers = ["github"]
+"#,
+        );
+        let upgrade = migrate_with(&mut value, 2, RENAMES, DEPRECATIONS).unwrap();
+        let warnings = upgrade.warnings;
+        assert!(upgrade.changed);
+
+        assert_eq!(
+            get(&value, "policy.secrets.rules"),
+            Some(&parse("x = [\"a\"]")["x"])
+        );
+        assert!(get(&value, "policy.secrets.custom_patterns").is_none());
+        assert!(get(&value, "tools.mcp.deny_servers").is_some());
+        assert!(get(&value, "profile.ci.tools.mcp.allow_servers").is_some());
+        assert!(get(&value, "mcp").is_none());
+        assert_eq!(warnings.len(), 4);
+        assert!(warnings[0].starts_with("`policy.secrets.custom_patterns` was renamed"));
+        assert!(warnings[3].starts_with("`profile.ci.mcp` was renamed to `profile.ci.tools.mcp`"));
+
+        // Files already on the new version are left alone
+        let mut value = parse("version = 2\n[mcp]\nx = 1\n");
+        let _ = migrate_with(&mut value, 2, RENAMES, DEPRECATIONS);
+        assert!(get(&value, "mcp").is_some());
+    }
+
+    #[test]
+    fn test_invalid_versions() {
+        assert!(migrate(&mut parse(&format!("version = {}", CONFIG_VERSION + 1))).is_err());
+        assert!(migrate(&mut parse("version = 0")).is_err());
+        assert!(migrate(&mut parse("version = \"1\"")).is_err());
+    }
+}
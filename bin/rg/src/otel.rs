@@ -0,0 +1,255 @@
+//! `OpenTelemetry` span export.
+//!
+//! With `policy.otel` enabled, each inspection is sent to a collector as one
+//! span over OTLP/HTTP with the JSON encoding, so Railgun decisions show up
+//! next to the rest of a team's traces. The hook is short-lived, so spans
+//! are posted as they finish rather than batched, with a short timeout; a
+//! collector that is down costs at most `timeout_ms` per call.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand_core::{OsRng, RngCore};
+use rg_types::{HookInput, OtelConfig, Verdict};
+use serde_json::{json, Value};
+
+/// Collector used when neither the config nor the environment names one.
+const DEFAULT_ENDPOINT: &str = "http://localhost:4318";
+
+/// Sends inspection spans to an OTLP/HTTP collector.
+#[derive(Debug, Clone)]
+pub struct OtelExporter {
+    /// `{endpoint}/v1/traces`.
+    url: String,
+    /// Extra request headers.
+    headers: Vec<(String, String)>,
+    /// `service.name` resource attribute.
+    service_name: String,
+    /// Request timeout.
+    timeout: Duration,
+}
+
+impl OtelExporter {
+    /// Build the exporter from configuration, if enabled.
+    pub fn from_config(config: &OtelConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let endpoint = config
+            .endpoint
+            .clone()
+            .or_else(|| std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok())
+            .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string());
+        Some(Self {
+            url: format!("{}/v1/traces", endpoint.trim_end_matches('/')),
+            headers: config
+                .headers
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect(),
+            service_name: config.service_name.clone(),
+            timeout: Duration::from_millis(config.timeout_ms),
+        })
+    }
+
+    /// Export the span of an inspection that ended now after `latency_us`.
+    pub fn export(
+        &self,
+        input: &HookInput,
+        verdict: &Verdict,
+        latency_us: u64,
+    ) -> Result<(), Box<ureq::Error>> {
+        let end = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let body = self.request(input, verdict, latency_us, end);
+        let mut request = ureq::AgentBuilder::new()
+            .timeout(self.timeout)
+            .build()
+            .post(&self.url)
+            .set("Content-Type", "application/json");
+        for (name, value) in &self.headers {
+            request = request.set(name, value);
+        }
+        let _ = request.send_string(&body.to_string()).map_err(Box::new)?;
+        Ok(())
+    }
+
+    /// The `ExportTraceServiceRequest` holding one span.
+    fn request(
+        &self,
+        input: &HookInput,
+        verdict: &Verdict,
+        latency_us: u64,
+        end: Duration,
+    ) -> Value {
+        let end_ns = end.as_nanos();
+        let start_ns = end_ns.saturating_sub(u128::from(latency_us) * 1000);
+        let decision = match verdict {
+            Verdict::Redact { .. } => "redact",
+            verdict => verdict.permission_decision(),
+        };
+        let rules: Vec<String> = verdict
+            .violations()
+            .iter()
+            .map(rg_types::BlockReason::rule_id)
+            .collect();
+
+        let mut attributes = vec![
+            string("railgun.tool", &input.tool_name),
+            string("railgun.verdict", decision),
+            json!({ "key": "railgun.latency_us", "value": { "intValue": latency_us.to_string() } }),
+        ];
+        if let Some(rule) = rules.first() {
+            attributes.push(string("railgun.rule_id", rule));
+        }
+        if rules.len() > 1 {
+            let values: Vec<Value> = rules
+                .iter()
+                .map(|rule| json!({ "stringValue": rule }))
+                .collect();
+            attributes.push(json!({
+                "key": "railgun.rule_ids",
+                "value": { "arrayValue": { "values": values } }
+            }));
+        }
+        if let Some(reason) = verdict.reason() {
+            attributes.push(string("railgun.reason", reason));
+        }
+        if let Some(session_id) = &input.session_id {
+            attributes.push(string("session.id", session_id));
+        }
+
+        json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [string("service.name", &self.service_name)]
+                },
+                "scopeSpans": [{
+                    "scope": { "name": "railgun", "version": env!("CARGO_PKG_VERSION") },
+                    "spans": [{
+                        "traceId": random_hex(16),
+                        "spanId": random_hex(8),
+                        "name": "railgun.inspect",
+                        // SPAN_KIND_INTERNAL
+                        "kind": 1,
+                        "startTimeUnixNano": start_ns.to_string(),
+                        "endTimeUnixNano": end_ns.to_string(),
+                        "attributes": attributes
+                    }]
+                }]
+            }]
+        })
+    }
+}
+
+/// A string attribute.
+fn string(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+/// `bytes` random bytes, hex-encoded, as trace and span IDs are.
+fn random_hex(bytes: usize) -> String {
+    use std::fmt::Write;
+
+    let mut id = vec![0u8; bytes];
+    OsRng.fill_bytes(&mut id);
+    id.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote::tests::capture;
+    use rg_types::BlockReason;
+
+    fn input() -> HookInput {
+        HookInput {
+            session_id: Some("s1".to_string()),
+            ..HookInput::new("Bash", json!({ "command": "git push --force" }))
+        }
+    }
+
+    fn exporter(endpoint: &str) -> OtelExporter {
+        OtelExporter::from_config(&OtelConfig {
+            enabled: true,
+            endpoint: Some(endpoint.to_string()),
+            headers: [("x-api-key".to_string(), "secret".to_string())].into(),
+            ..OtelConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_from_config() {
+        assert!(OtelExporter::from_config(&OtelConfig::default()).is_none());
+        assert_eq!(
+            exporter("http://collector:4318/").url,
+            "http://collector:4318/v1/traces"
+        );
+    }
+
+    #[test]
+    fn test_span() {
+        let verdict = Verdict::deny_from_block_reason(&BlockReason::GitDestructive {
+            rule: "force_push".to_string(),
+            branch: None,
+            matched: "git push --force".to_string(),
+        });
+        let request = exporter("http://collector:4318").request(
+            &input(),
+            &verdict,
+            250,
+            Duration::from_secs(10),
+        );
+
+        let span = &request["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+        assert_eq!(span["name"], "railgun.inspect");
+        assert_eq!(span["traceId"].as_str().unwrap().len(), 32);
+        assert_eq!(span["spanId"].as_str().unwrap().len(), 16);
+        assert_eq!(span["startTimeUnixNano"], "9999750000");
+        assert_eq!(span["endTimeUnixNano"], "10000000000");
+        let attribute = |key: &str| {
+            span["attributes"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|attribute| attribute["key"] == key)
+                .map(|attribute| attribute["value"].clone())
+        };
+        assert_eq!(attribute("railgun.tool").unwrap()["stringValue"], "Bash");
+        assert_eq!(attribute("railgun.verdict").unwrap()["stringValue"], "deny");
+        assert_eq!(
+            attribute("railgun.rule_id").unwrap()["stringValue"],
+            "git_destructive:force_push"
+        );
+        assert_eq!(attribute("railgun.latency_us").unwrap()["intValue"], "250");
+        assert_eq!(attribute("session.id").unwrap()["stringValue"], "s1");
+        assert!(attribute("railgun.rule_ids").is_none());
+        assert_eq!(
+            request["resourceSpans"][0]["resource"]["attributes"][0]["value"]["stringValue"],
+            "railgun"
+        );
+    }
+
+    #[test]
+    fn test_export() {
+        let (endpoint, requests) = capture(vec![200]);
+        exporter(&endpoint)
+            .export(&input(), &Verdict::Allow, 10)
+            .unwrap();
+        let request = requests.recv().unwrap();
+        assert!(request.head[0].starts_with("POST /v1/traces "));
+        assert!(request
+            .head
+            .iter()
+            .any(|h| h.to_lowercase() == "x-api-key: secret"));
+        let body: Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(
+            body["resourceSpans"][0]["scopeSpans"][0]["spans"][0]["name"],
+            "railgun.inspect"
+        );
+    }
+}
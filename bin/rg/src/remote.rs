@@ -0,0 +1,380 @@
+//! Fetch the organization policy at `policy.remote_url`.
+//!
+//! The policy is cached in the project's state directory, private to the
+//! user and outside the project the agent writes to, and refetched once it
+//! is older than `remote_ttl_secs`, so the hook only reaches the network
+//! when the cache is stale. If a refetch fails, the stale copy is used for
+//! another TTL; with no copy at all, `remote_fail_closed` decides whether
+//! loading fails. With trusted keys, the cached copy is verified every time
+//! it is used, and a download that doesn't verify is never cached.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use ed25519_dalek::VerifyingKey;
+use eyre::{Context, Result};
+use rg_types::PolicyConfig;
+
+use crate::signing;
+
+/// Fetch the remote policy into `cache` unless the cached copy is fresh.
+///
+/// Returns the cache path, or `None` if the config sets no remote URL, or
+/// the fetch failed with nothing cached and `remote_fail_closed` is off.
+/// With `keys`, the detached signature at `URL.sig` is fetched too, into
+/// `CACHE.sig`, and the returned copy is always signed by one of them.
+pub fn fetch(
+    policy: &PolicyConfig,
+    cache: &Path,
+    keys: Option<&[VerifyingKey]>,
+) -> Result<Option<PathBuf>> {
+    let Some(url) = policy.remote_url.as_deref() else {
+        return Ok(None);
+    };
+    check_url(url)?;
+
+    let verified = |path: &Path| keys.map_or(Ok(()), |keys| signing::verify_file(path, keys));
+    let sig_cache = signing::signature_path(cache);
+    let ttl = Duration::from_secs(policy.remote_ttl_secs);
+    if is_fresh(cache, ttl) && verified(cache).is_ok() {
+        return Ok(Some(cache.to_path_buf()));
+    }
+
+    let timeout = Duration::from_millis(policy.remote_timeout_ms);
+    let result = download(url, timeout).and_then(|body| {
+        if let Some(keys) = keys {
+            let sig = download(&format!("{url}.sig"), timeout)?;
+            if !signing::verify(body.as_bytes(), &sig, keys)
+                .with_context(|| format!("Invalid signature at {url}.sig"))?
+            {
+                return Err(eyre::eyre!(
+                    "Signature at {url}.sig does not match a trusted key"
+                ));
+            }
+            write_private(&sig_cache, &sig)?;
+        }
+        write_private(cache, &body)
+    });
+
+    match result {
+        Ok(()) => Ok(Some(cache.to_path_buf())),
+        Err(e) if cache.exists() && verified(cache).is_ok() => {
+            tracing::warn!(
+                "Failed to fetch remote policy {url}: {e:#}; using the copy cached at {}",
+                cache.display()
+            );
+            // Don't retry on every hook call while the server is down
+            let _ = std::fs::File::options()
+                .write(true)
+                .open(cache)
+                .and_then(|file| file.set_modified(SystemTime::now()));
+            Ok(Some(cache.to_path_buf()))
+        }
+        Err(e) if policy.remote_fail_closed => {
+            Err(e.wrap_err(format!("Failed to fetch remote policy {url}")))
+        }
+        Err(e) => {
+            tracing::warn!("Failed to fetch remote policy {url}: {e:#}; continuing without it");
+            Ok(None)
+        }
+    }
+}
+
+/// Only HTTPS, except to this machine.
+fn check_url(url: &str) -> Result<()> {
+    if url.starts_with("https://") {
+        return Ok(());
+    }
+    let host = url
+        .strip_prefix("http://")
+        .map(|rest| rest.split(['/', '?', '#']).next().unwrap_or_default())
+        .map(|authority| match authority.rsplit_once(':') {
+            Some((host, port)) if !host.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => {
+                host
+            }
+            _ => authority,
+        });
+    match host {
+        Some("localhost" | "127.0.0.1" | "[::1]") => Ok(()),
+        _ => Err(eyre::eyre!(
+            "policy.remote_url must be an https:// URL, got '{url}'"
+        )),
+    }
+}
+
+fn is_fresh(cache: &Path, ttl: Duration) -> bool {
+    std::fs::metadata(cache)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age < ttl)
+}
+
+fn download(url: &str, timeout: Duration) -> Result<String> {
+    ureq::AgentBuilder::new()
+        .timeout(timeout)
+        .build()
+        .get(url)
+        .call()
+        .with_context(|| format!("GET {url}"))?
+        .into_string()
+        .with_context(|| format!("Failed to read the response from {url}"))
+}
+
+/// Replace `path` with `content`, readable and writable only by this user,
+/// creating its directory the same way.
+fn write_private(path: &Path, content: &str) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        create_private_dir(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let mut tmp = path.as_os_str().to_os_string();
+    tmp.push(".tmp");
+    let _ = std::fs::remove_file(&tmp);
+    create_private(Path::new(&tmp))
+        .and_then(|mut file| std::io::Write::write_all(&mut file, content.as_bytes()))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)
+}
+
+#[cfg(unix)]
+fn create_private(path: &Path) -> std::io::Result<std::fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn create_private(path: &Path) -> std::io::Result<std::fs::File> {
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    /// Serve `routes` (path, body) over HTTP on loopback for the rest of the
+    /// test run, answering 404 for anything else. Returns the base URL.
+    pub fn serve(routes: Vec<(&'static str, String)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let _ = std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut request = String::new();
+                let _ = BufReader::new(&stream).read_line(&mut request);
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let response = match routes.iter().find(|(route, _)| *route == path) {
+                    Some((_, body)) => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    ),
+                    None => {
+                        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_string()
+                    }
+                };
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        url
+    }
+
+    /// A request received by [`capture`].
+    #[derive(Debug)]
+    pub struct Captured {
+        /// Request line and headers, one per entry, without line endings.
+        pub head: Vec<String>,
+        /// Request body.
+        pub body: String,
+    }
+
+    /// Accept requests on loopback for the rest of the test run, answering
+    /// each with the next of `statuses` (the last one repeats) and passing
+    /// it on to the returned receiver. Returns the base URL.
+    pub fn capture(statuses: Vec<u16>) -> (String, std::sync::mpsc::Receiver<Captured>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let _ = std::thread::spawn(move || {
+            for (i, stream) in listener.incoming().enumerate() {
+                let Ok(mut stream) = stream else { continue };
+                let mut reader = BufReader::new(&stream);
+                let mut head = Vec::new();
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap_or_default() > 2 {
+                    head.push(std::mem::take(&mut line).trim_end().to_string());
+                }
+                let length = head
+                    .iter()
+                    .find_map(|h| {
+                        h.to_lowercase()
+                            .strip_prefix("content-length:")
+                            .and_then(|v| v.trim().parse().ok())
+                    })
+                    .unwrap_or_default();
+                let mut body = vec![0; length];
+                let _ = std::io::Read::read_exact(&mut reader, &mut body);
+
+                let status = statuses[i.min(statuses.len() - 1)];
+                let _ = stream.write_all(
+                    format!(
+                        "HTTP/1.1 {status} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    )
+                    .as_bytes(),
+                );
+                let _ = sender.send(Captured {
+                    head,
+                    body: String::from_utf8_lossy(&body).into_owned(),
+                });
+            }
+        });
+        (url, receiver)
+    }
+
+    fn policy(url: &str) -> PolicyConfig {
+        PolicyConfig {
+            remote_url: Some(url.to_string()),
+            ..PolicyConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_fetch_and_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join(".railgun-remote.toml");
+        let base = serve(vec![("/policy.toml", "[policy]\nlocked = true\n".into())]);
+
+        let path = fetch(&policy(&format!("{base}/policy.toml")), &cache, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(path, cache);
+        assert_eq!(
+            std::fs::read_to_string(&cache).unwrap(),
+            "[policy]\nlocked = true\n"
+        );
+
+        // Fresh: the cache is used without asking the server
+        let gone = policy("http://127.0.0.1:9/policy.toml");
+        assert_eq!(fetch(&gone, &cache, None).unwrap(), Some(cache.clone()));
+
+        // Stale and unreachable: the stale copy is still used
+        let stale = PolicyConfig {
+            remote_ttl_secs: 0,
+            ..gone
+        };
+        assert_eq!(fetch(&stale, &cache, None).unwrap(), Some(cache.clone()));
+
+        // Signed: the signature is fetched next to it
+        let key = dir.path().join("railgun.key");
+        let keys = signing::parse_keys(&[signing::generate_key(&key).unwrap()]).unwrap();
+        let signed_body = dir.path().join("policy.toml");
+        std::fs::write(&signed_body, "[policy]\n").unwrap();
+        let sig = std::fs::read_to_string(signing::sign_file(&signed_body, &key).unwrap()).unwrap();
+        let base = serve(vec![
+            ("/policy.toml", "[policy]\n".into()),
+            ("/policy.toml.sig", sig.clone()),
+            ("/forged.toml", "[policy]\nmode = \"monitor\"\n".into()),
+            ("/forged.toml.sig", sig.clone()),
+        ]);
+        let signed = PolicyConfig {
+            remote_url: Some(format!("{base}/policy.toml")),
+            remote_ttl_secs: 3600,
+            ..stale
+        };
+        assert_eq!(
+            fetch(&signed, &cache, Some(&keys)).unwrap(),
+            Some(cache.clone())
+        );
+        assert_eq!(
+            std::fs::read_to_string(signing::signature_path(&cache)).unwrap(),
+            sig
+        );
+
+        // A fresh copy that no longer verifies is fetched again
+        std::fs::write(&cache, "[policy]\nmode = \"monitor\"\n").unwrap();
+        assert_eq!(
+            fetch(&signed, &cache, Some(&keys)).unwrap(),
+            Some(cache.clone())
+        );
+        assert_eq!(std::fs::read_to_string(&cache).unwrap(), "[policy]\n");
+
+        // A download that doesn't verify doesn't replace the cached copy,
+        // and a tampered copy isn't used
+        let forged = PolicyConfig {
+            remote_url: Some(format!("{base}/forged.toml")),
+            remote_ttl_secs: 0,
+            ..signed
+        };
+        assert_eq!(
+            fetch(&forged, &cache, Some(&keys)).unwrap(),
+            Some(cache.clone())
+        );
+        assert_eq!(std::fs::read_to_string(&cache).unwrap(), "[policy]\n");
+        std::fs::write(&cache, "[policy]\nmode = \"monitor\"\n").unwrap();
+        assert!(fetch(&forged, &cache, Some(&keys)).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cache_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("state").join("remote.toml");
+        let base = serve(vec![("/policy.toml", "[policy]\n".into())]);
+        let _ = fetch(&policy(&format!("{base}/policy.toml")), &cache, None).unwrap();
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&cache), 0o600);
+        assert_eq!(mode(cache.parent().unwrap()), 0o700);
+    }
+
+    #[test]
+    fn test_fetch_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join(".railgun-remote.toml");
+        let base = serve(vec![]);
+        let mut policy = policy(&format!("{base}/missing.toml"));
+
+        let err = fetch(&policy, &cache, None).unwrap_err();
+        assert!(format!("{err:#}").contains("Failed to fetch remote policy"));
+
+        policy.remote_fail_closed = false;
+        assert_eq!(fetch(&policy, &cache, None).unwrap(), None);
+        assert!(!cache.exists());
+    }
+
+    #[test]
+    fn test_check_url() {
+        assert!(check_url("https://policy.example.com/railgun.toml").is_ok());
+        assert!(check_url("http://localhost:8080/railgun.toml").is_ok());
+        assert!(check_url("http://127.0.0.1/railgun.toml").is_ok());
+        assert!(check_url("http://[::1]:80/railgun.toml").is_ok());
+        assert!(check_url("http://policy.example.com/railgun.toml").is_err());
+        assert!(check_url("http://localhost.evil.example/").is_err());
+        assert!(check_url("file:///etc/railgun.toml").is_err());
+    }
+}
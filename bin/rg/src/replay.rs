@@ -0,0 +1,259 @@
+//! `railgun replay`: re-evaluate audit log entries under a policy.
+//!
+//! Each recorded tool call is inspected again, under the current config or a
+//! candidate one, and calls whose decision would change are listed, so a rule
+//! can be tightened or loosened knowing what it does to real traffic.
+//!
+//! The audit log stores inputs with secrets redacted and truncated at 2000
+//! characters, so entries with a redacted or truncated input can't be
+//! replayed faithfully and are skipped. Session state (remembered approvals,
+//! session allows, taint, and risk) is not replayed either: each call is
+//! decided as the first of its session.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use eyre::Result;
+use rg_policy::approvals::unix_now;
+use rg_policy::{inspect, RuntimePolicy};
+use rg_types::HookInput;
+use serde_json::{json, Value};
+
+use crate::audit::{self, format_time, truncate, AuditEntry, Filter, TABLE_INPUT_CHARS};
+use crate::cli::LogFormat;
+use crate::{build_policy, config_loader};
+
+/// A call whose decision would change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// The entry as recorded.
+    pub before: AuditEntry,
+    /// The entry the policy would record now.
+    pub after: AuditEntry,
+}
+
+/// Result of replaying a log.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Entries inspected again.
+    pub replayed: usize,
+    /// Entries whose input was redacted or truncated.
+    pub skipped: usize,
+    /// Entries whose decision changed, oldest first.
+    pub changes: Vec<Change>,
+}
+
+/// Inspect every entry again under `policy`.
+pub fn replay(policy: &RuntimePolicy, entries: &[AuditEntry]) -> Report {
+    let mut report = Report::default();
+    for entry in entries {
+        let Some(input) = hook_input(entry) else {
+            report.skipped += 1;
+            continue;
+        };
+        report.replayed += 1;
+
+        let (verdict, _) = inspect(&input, policy);
+        let after = AuditEntry::new(&input, &verdict, &policy.secrets, entry.ts);
+        if after.decision != entry.decision {
+            report.changes.push(Change {
+                before: entry.clone(),
+                after,
+            });
+        }
+    }
+    report
+}
+
+/// The recorded call, or `None` if its input was redacted or truncated.
+fn hook_input(entry: &AuditEntry) -> Option<HookInput> {
+    if entry.input.contains("[REDACTED:") {
+        return None;
+    }
+    // A truncated input ends in `...` and no longer parses
+    let tool_input: Value = serde_json::from_str(&entry.input).ok()?;
+    Some(HookInput {
+        cwd: entry.cwd.clone(),
+        session_id: entry.session_id.clone(),
+        ..HookInput::new(entry.tool.clone(), tool_input)
+    })
+}
+
+/// Run `railgun replay`: replay the entries of the config's audit log that
+/// pass `filter`, under the config at `policy_path` (the same config if
+/// `None`).
+pub fn run_replay(
+    config_path: &str,
+    policy_path: Option<&Path>,
+    filter: &Filter,
+    format: LogFormat,
+) -> Result<()> {
+    let config_path = Path::new(config_path);
+    let config = config_loader::load_config(config_path)?;
+    let path = config_loader::audit_path(config_path, &config);
+    if !path.exists() {
+        return Err(eyre::eyre!(
+            "No audit log at {} (set `enabled = true` under [policy.audit] to record decisions)",
+            path.display()
+        ));
+    }
+
+    let policy = match policy_path {
+        Some(policy_path) => build_policy(&config_loader::load_config(policy_path)?),
+        None => build_policy(&config),
+    };
+    let now = unix_now();
+    let entries: Vec<AuditEntry> = audit::read(&path)?
+        .into_iter()
+        .filter(|entry| filter.matches(entry, now))
+        .collect();
+
+    let report = replay(&policy, &entries);
+    match format {
+        LogFormat::Table => print!("{}", format_table(&report)),
+        LogFormat::Json => println!("{:#}", to_json(&report)),
+    }
+    Ok(())
+}
+
+/// Format the changes as an aligned table, then a summary.
+pub fn format_table(report: &Report) -> String {
+    let mut output = String::new();
+    if !report.changes.is_empty() {
+        let tool_width = report
+            .changes
+            .iter()
+            .map(|change| change.before.tool.chars().count())
+            .max()
+            .unwrap_or_default()
+            .max("TOOL".len());
+
+        let _ = writeln!(
+            output,
+            "{:<19}  {:<tool_width$}  {:<6}  {:<6}  {:<TABLE_INPUT_CHARS$}  REASON",
+            "TIME (UTC)", "TOOL", "BEFORE", "AFTER", "INPUT"
+        );
+        for Change { before, after } in &report.changes {
+            let _ = writeln!(
+                output,
+                "{:<19}  {:<tool_width$}  {:<6}  {:<6}  {:<TABLE_INPUT_CHARS$}  {}",
+                format_time(before.ts),
+                before.tool,
+                before.decision,
+                after.decision,
+                truncate(&before.input, TABLE_INPUT_CHARS - 3),
+                after
+                    .reason
+                    .as_deref()
+                    .or(before.reason.as_deref())
+                    .unwrap_or_default(),
+            );
+        }
+        output.push('\n');
+    }
+
+    let _ = write!(
+        output,
+        "{} entries replayed, {} would change",
+        report.replayed,
+        report.changes.len()
+    );
+    if report.skipped > 0 {
+        let _ = write!(
+            output,
+            ", {} skipped (input redacted or truncated)",
+            report.skipped
+        );
+    }
+    output.push('\n');
+    output
+}
+
+/// The report as JSON: totals, then every change.
+pub fn to_json(report: &Report) -> Value {
+    json!({
+        "replayed": report.replayed,
+        "skipped": report.skipped,
+        "changes": report
+            .changes
+            .iter()
+            .map(|change| json!({ "before": change.before, "after": change.after }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rg_types::{PolicyConfig, RuleAction};
+
+    fn entry(tool: &str, decision: &str, input: &str) -> AuditEntry {
+        AuditEntry {
+            ts: 1_700_000_000,
+            session_id: None,
+            cwd: None,
+            tool: tool.to_string(),
+            decision: decision.to_string(),
+            reason: None,
+            rules: Vec::new(),
+            input: input.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_replay() {
+        let entries = [
+            entry("Bash", "allow", r#"{"command":"ls"}"#),
+            entry(
+                "Bash",
+                "allow",
+                r#"{"command":"git push --force origin main"}"#,
+            ),
+            entry("Bash", "deny", r#"{"command":"rm -rf /"}"#),
+            entry(
+                "Write",
+                "deny",
+                r#"{"file_path":".env","content":"[REDACTED:aws_key]"}"#,
+            ),
+            entry("Bash", "allow", r#"{"command":"echo aaaa..."#),
+        ];
+
+        let report = replay(
+            &RuntimePolicy::from_config(&PolicyConfig::default()),
+            &entries,
+        );
+        assert_eq!((report.replayed, report.skipped), (3, 2));
+        assert_eq!(report.changes.len(), 1);
+        let change = &report.changes[0];
+        assert_eq!(change.before.decision, "allow");
+        assert_eq!(change.after.decision, "ask");
+        assert_eq!(change.after.rules, ["git_destructive:force_push"]);
+
+        // Loosening the git rule removes the change
+        let mut config = PolicyConfig::default();
+        config.commands.git.action = RuleAction::Allow;
+        let report = replay(&RuntimePolicy::from_config(&config), &entries);
+        assert_eq!(report.changes, []);
+    }
+
+    #[test]
+    fn test_format() {
+        let entries = [entry("Bash", "allow", r#"{"command":"rm -rf /"}"#)];
+        let report = replay(
+            &RuntimePolicy::from_config(&PolicyConfig::default()),
+            &entries,
+        );
+
+        let table = format_table(&report);
+        assert!(table.starts_with("TIME (UTC)           TOOL  BEFORE  AFTER   INPUT"));
+        assert!(table.contains("  Bash  allow   deny    {\"command\":\"rm -rf /\"}"));
+        assert!(table.ends_with("\n1 entries replayed, 1 would change\n"));
+
+        let json = to_json(&report);
+        assert_eq!(json["changes"][0]["after"]["decision"], "deny");
+        assert_eq!(
+            format_table(&Report::default()),
+            "0 entries replayed, 0 would change\n"
+        );
+    }
+}
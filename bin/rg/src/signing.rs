@@ -0,0 +1,166 @@
+//! Detached Ed25519 signatures for config files and policy packs.
+//!
+//! A signature is stored next to the file it signs, as `FILE.sig`, holding
+//! the base64 signature of the file's exact bytes. Keys are base64 too: the
+//! private key file holds the 32-byte seed, and `policy.trusted_keys` lists
+//! 32-byte public keys.
+
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use eyre::{Context, Result};
+
+/// The detached signature file for a path.
+pub fn signature_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".sig");
+    PathBuf::from(name)
+}
+
+/// Parse `policy.trusted_keys`.
+pub fn parse_keys(keys: &[String]) -> Result<Vec<VerifyingKey>> {
+    if keys.is_empty() {
+        return Err(eyre::eyre!(
+            "policy.require_signed is set but policy.trusted_keys is empty"
+        ));
+    }
+    keys.iter()
+        .map(|key| {
+            let bytes =
+                decode::<32>(key).with_context(|| format!("Invalid trusted key '{key}'"))?;
+            VerifyingKey::from_bytes(&bytes).with_context(|| format!("Invalid trusted key '{key}'"))
+        })
+        .collect()
+}
+
+/// Check that `path` has a signature by one of `keys`.
+pub fn verify_file(path: &Path, keys: &[VerifyingKey]) -> Result<()> {
+    let content =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let sig_path = signature_path(path);
+    let sig = std::fs::read_to_string(&sig_path).with_context(|| {
+        format!(
+            "{} is not signed (policy.require_signed is set; expected {})",
+            path.display(),
+            sig_path.display()
+        )
+    })?;
+    verify(&content, &sig, keys)
+        .with_context(|| format!("Invalid signature in {}", sig_path.display()))
+        .and_then(|matched| {
+            if matched {
+                Ok(())
+            } else {
+                Err(eyre::eyre!(
+                    "Signature of {} does not match a trusted key (modified since signing?)",
+                    path.display()
+                ))
+            }
+        })
+}
+
+/// Whether `sig`, a base64 signature, is a signature of `content` by one of
+/// `keys`. Fails if `sig` isn't a signature at all.
+pub fn verify(content: &[u8], sig: &str, keys: &[VerifyingKey]) -> Result<bool> {
+    let sig = Signature::from_bytes(&decode::<64>(sig.trim())?);
+    Ok(keys.iter().any(|key| key.verify(content, &sig).is_ok()))
+}
+
+/// Write a new private key to `path`, returning the public key to add to
+/// `policy.trusted_keys`.
+pub fn generate_key(path: &Path) -> Result<String> {
+    if path.exists() {
+        return Err(eyre::eyre!("{} already exists", path.display()));
+    }
+    let key = SigningKey::generate(&mut rand_core::OsRng);
+    write_private(path, &BASE64.encode(key.to_bytes()))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(BASE64.encode(key.verifying_key().to_bytes()))
+}
+
+/// Sign `path` with the private key in `key_path`, writing `FILE.sig`.
+pub fn sign_file(path: &Path, key_path: &Path) -> Result<PathBuf> {
+    let key = std::fs::read_to_string(key_path)
+        .with_context(|| format!("Failed to read {}", key_path.display()))?;
+    let key = SigningKey::from_bytes(
+        &decode::<32>(key.trim())
+            .with_context(|| format!("Invalid private key in {}", key_path.display()))?,
+    );
+    let content =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let sig_path = signature_path(path);
+    std::fs::write(
+        &sig_path,
+        BASE64.encode(key.sign(&content).to_bytes()) + "\n",
+    )
+    .with_context(|| format!("Failed to write {}", sig_path.display()))?;
+    Ok(sig_path)
+}
+
+fn decode<const N: usize>(encoded: &str) -> Result<[u8; N]> {
+    let bytes = BASE64.decode(encoded.trim())?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| eyre::eyre!("expected {N} bytes, got {}", bytes.len()))
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, content: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    writeln!(file, "{content}")
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, content: &str) -> std::io::Result<()> {
+    std::fs::write(path, format!("{content}\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("railgun.key");
+        let public = generate_key(&key_path).unwrap();
+        assert!(generate_key(&key_path).is_err());
+
+        let config = dir.path().join("railgun.toml");
+        std::fs::write(&config, "[policy]\nmode = \"strict\"\n").unwrap();
+        let keys = parse_keys(&[public]).unwrap();
+        assert!(verify_file(&config, &keys).is_err());
+
+        assert_eq!(
+            sign_file(&config, &key_path).unwrap(),
+            dir.path().join("railgun.toml.sig")
+        );
+        verify_file(&config, &keys).unwrap();
+
+        std::fs::write(&config, "[policy]\nmode = \"monitor\"\n").unwrap();
+        let err = verify_file(&config, &keys).unwrap_err().to_string();
+        assert!(err.contains("does not match a trusted key"));
+
+        // Signed, but by someone else
+        let other = generate_key(&dir.path().join("other.key")).unwrap();
+        let _ = sign_file(&config, &key_path).unwrap();
+        assert!(verify_file(&config, &parse_keys(&[other]).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert!(parse_keys(&[]).is_err());
+        assert!(parse_keys(&["not base64!".to_string()]).is_err());
+        assert!(parse_keys(&[BASE64.encode([0u8; 16])]).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+//! `StatsD` metrics over UDP.
+//!
+//! With `policy.statsd` enabled, each inspection sends one datagram holding
+//! a counter for its decision, a counter per scanner behind it, and its
+//! latency as a timer. UDP needs no connection or reply, and the socket is
+//! non-blocking, so the one-shot hook pays for a single `sendto` and a
+//! server that is down costs nothing.
+
+use std::fmt::Write as _;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+use rg_types::{StatsdConfig, Verdict};
+
+/// Sends inspection metrics to a `StatsD` server.
+#[derive(Debug)]
+pub struct StatsdClient {
+    /// Unconnected, non-blocking socket.
+    socket: UdpSocket,
+    /// The server.
+    address: SocketAddr,
+    /// Prefix of every metric name.
+    prefix: String,
+    /// Use `DogStatsD` tags for the decision and scanner.
+    tags: bool,
+}
+
+impl StatsdClient {
+    /// Build the client from configuration, if enabled.
+    pub fn from_config(config: &StatsdConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        match Self::connect(config) {
+            Ok(client) => Some(client),
+            Err(e) => {
+                tracing::warn!(error = %e, address = %config.address, "failed to set up StatsD");
+                None
+            }
+        }
+    }
+
+    /// Resolve the server and open the socket.
+    fn connect(config: &StatsdConfig) -> io::Result<Self> {
+        let address =
+            config.address.to_socket_addrs()?.next().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "address did not resolve")
+            })?;
+        let local: SocketAddr = if address.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0u16; 8], 0).into()
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            address,
+            prefix: config.prefix.trim_end_matches('.').to_string(),
+            tags: config.tags,
+        })
+    }
+
+    /// Send the metrics of an inspection that took `latency_us`.
+    pub fn record(&self, verdict: &Verdict, latency_us: u64) -> io::Result<()> {
+        let _ = self
+            .socket
+            .send_to(self.payload(verdict, latency_us).as_bytes(), self.address)?;
+        Ok(())
+    }
+
+    /// The metric lines of an inspection.
+    fn payload(&self, verdict: &Verdict, latency_us: u64) -> String {
+        let decision = match verdict {
+            Verdict::Redact { .. } => "redact",
+            verdict => verdict.permission_decision(),
+        };
+        let prefix = &self.prefix;
+        let mut lines = Vec::new();
+        if self.tags {
+            lines.push(format!("{prefix}.decisions:1|c|#decision:{decision}"));
+        } else {
+            lines.push(format!("{prefix}.decisions.{decision}:1|c"));
+        }
+        for violation in verdict.violations() {
+            let scanner = violation.code();
+            if self.tags {
+                lines.push(format!("{prefix}.scanner:1|c|#scanner:{scanner}"));
+            } else {
+                lines.push(format!("{prefix}.scanner.{scanner}:1|c"));
+            }
+        }
+
+        let mut timer = format!(
+            "{prefix}.inspection:{}.{:03}|ms",
+            latency_us / 1000,
+            latency_us % 1000
+        );
+        if self.tags {
+            let _ = write!(timer, "|#decision:{decision}");
+        }
+        lines.push(timer);
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rg_types::BlockReason;
+
+    fn force_push() -> Verdict {
+        Verdict::deny_from_block_reason(&BlockReason::GitDestructive {
+            rule: "force_push".to_string(),
+            branch: None,
+            matched: "git push --force".to_string(),
+        })
+    }
+
+    fn client(address: &str, tags: bool) -> StatsdClient {
+        StatsdClient::from_config(&StatsdConfig {
+            enabled: true,
+            address: address.to_string(),
+            tags,
+            ..StatsdConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_from_config() {
+        assert!(StatsdClient::from_config(&StatsdConfig::default()).is_none());
+        assert!(StatsdClient::from_config(&StatsdConfig {
+            enabled: true,
+            address: "not an address".to_string(),
+            ..StatsdConfig::default()
+        })
+        .is_none());
+    }
+
+    #[test]
+    fn test_payload() {
+        let plain = client("127.0.0.1:8125", false);
+        assert_eq!(
+            plain.payload(&Verdict::Allow, 1234),
+            "railgun.decisions.allow:1|c\nrailgun.inspection:1.234|ms"
+        );
+        assert_eq!(
+            plain.payload(&force_push(), 56),
+            "railgun.decisions.deny:1|c\n\
+             railgun.scanner.git_destructive:1|c\n\
+             railgun.inspection:0.056|ms"
+        );
+
+        let tagged = client("127.0.0.1:8125", true);
+        assert_eq!(
+            tagged.payload(&force_push(), 56),
+            "railgun.decisions:1|c|#decision:deny\n\
+             railgun.scanner:1|c|#scanner:git_destructive\n\
+             railgun.inspection:0.056|ms|#decision:deny"
+        );
+    }
+
+    #[test]
+    fn test_record() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let client = client(&server.local_addr().unwrap().to_string(), false);
+        client.record(&Verdict::Allow, 10).unwrap();
+
+        let mut buf = [0; 512];
+        let len = server.recv(&mut buf).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&buf[..len]).unwrap(),
+            "railgun.decisions.allow:1|c\nrailgun.inspection:0.010|ms"
+        );
+    }
+}
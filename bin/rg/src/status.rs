@@ -0,0 +1,274 @@
+//! Summarize the effective policy: which config was loaded, and what it
+//! turns on.
+
+use rg_types::{Config, PolicyMode, RuleAction, SecretAction};
+
+use crate::config_loader::ConfigSource;
+
+/// One scanner and how it is configured.
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    /// Config section name, e.g. `commands`.
+    pub name: &'static str,
+    /// Whether it runs.
+    pub enabled: bool,
+    /// Its action and rule counts.
+    pub detail: String,
+}
+
+/// The scanners of a loaded config, in inspection order.
+pub fn scanners(config: &Config) -> Vec<Scanner> {
+    let policy = &config.policy;
+    let secrets = &policy.secrets;
+    let detectors = [
+        secrets.detect_aws_keys,
+        secrets.detect_github_tokens,
+        secrets.detect_openai_keys,
+        secrets.detect_private_keys,
+        secrets.detect_npm_tokens,
+        secrets.detect_pypi_tokens,
+        secrets.detect_jwts,
+    ]
+    .iter()
+    .filter(|on| **on)
+    .count();
+    let secret_action = match secrets.action {
+        SecretAction::Deny => "deny",
+        SecretAction::Ask => "ask",
+        SecretAction::Redact => "redact",
+    };
+
+    let commands = &policy.commands;
+    let check = |name: &str, enabled: bool, action: RuleAction| {
+        if enabled {
+            format!("{name} {action}")
+        } else {
+            format!("{name} off")
+        }
+    };
+
+    let paths = &policy.protected_paths;
+    let blocked = paths.blocked.len()
+        + paths.blocked_all.len()
+        + paths.blocked_read.len()
+        + paths.blocked_write.len();
+
+    vec![
+        Scanner {
+            name: "secrets",
+            enabled: secrets.enabled,
+            detail: format!(
+                "{secret_action}; {detectors} built-in detectors, {} custom rules, {} baselined",
+                secrets.rules.len(),
+                secrets.baseline.len() + secrets.detect_secrets_hashes.len()
+            ),
+        },
+        Scanner {
+            name: "commands",
+            enabled: commands.enabled,
+            detail: format!(
+                "{} block, {} ask, {} allow patterns; {}, {}, {}, {}",
+                commands.block_patterns.len(),
+                commands.ask_patterns.len(),
+                commands.allow_patterns.len(),
+                check("sudo", commands.sudo.enabled, commands.sudo.action),
+                check("git", commands.git.enabled, commands.git.action),
+                check(
+                    "permissions",
+                    commands.permissions.enabled,
+                    commands.permissions.action
+                ),
+                check(
+                    "transfers",
+                    commands.transfers.enabled,
+                    commands.transfers.action
+                ),
+            ),
+        },
+        Scanner {
+            name: "protected_paths",
+            enabled: paths.enabled,
+            detail: format!("{blocked} blocked, {} allowed", paths.allowed.len()),
+        },
+        Scanner {
+            name: "network",
+            enabled: policy.network.enabled,
+            detail: format!("{} blocked domains", policy.network.block_domains.len()),
+        },
+        Scanner {
+            name: "executables",
+            enabled: policy.executables.enabled,
+            detail: policy.executables.action.to_string(),
+        },
+        Scanner {
+            name: "packages",
+            enabled: policy.packages.enabled,
+            detail: format!(
+                "{} allowed, {} denied; unknown {}",
+                policy.packages.allow.len(),
+                policy.packages.deny.len(),
+                policy.packages.unknown
+            ),
+        },
+        Scanner {
+            name: "workspace",
+            enabled: policy.workspace.enabled,
+            detail: format!(
+                "{}; {} extra allowed roots",
+                policy.workspace.action,
+                policy.workspace.allowed.len()
+            ),
+        },
+        Scanner {
+            name: "writes",
+            enabled: policy.writes.enabled,
+            detail: format!(
+                "{} over {} bytes",
+                policy.writes.action, policy.writes.max_bytes
+            ),
+        },
+        Scanner {
+            name: "control_chars",
+            enabled: policy.control_chars.enabled,
+            detail: policy.control_chars.action.to_string(),
+        },
+        Scanner {
+            name: "injection",
+            enabled: policy.injection.enabled,
+            detail: format!(
+                "{}; {} extra patterns",
+                policy.injection.action,
+                policy.injection.extra_patterns.len()
+            ),
+        },
+    ]
+}
+
+/// Format the summary for the terminal.
+pub fn format_human(source: &ConfigSource, profile: Option<&str>, config: &Config) -> String {
+    use std::fmt::Write;
+
+    let on = |enabled: bool| if enabled { "on" } else { "off" };
+    let policy = &config.policy;
+    let mut output = String::new();
+
+    let source = match source {
+        ConfigSource::Project(path) => format!("{} (project)", path.display()),
+        ConfigSource::Global(path) => format!("{} (global)", path.display()),
+        ConfigSource::Layered { global, project } => format!(
+            "{} (project) over {} (global{})",
+            project.display(),
+            global.display(),
+            if policy.locked { ", locked" } else { "" }
+        ),
+        ConfigSource::Default => "built-in defaults (no project or global config found)".into(),
+    };
+    let _ = writeln!(output, "Config:  {source}");
+    if let Some(profile) = profile {
+        let _ = writeln!(output, "Profile: {profile}");
+    }
+    if !policy.packs.is_empty() {
+        let _ = writeln!(output, "Packs:   {}", policy.packs.join(", "));
+    }
+    let mode = match policy.mode {
+        PolicyMode::Strict => "strict",
+        PolicyMode::Monitor => "monitor (violations are logged, not blocked)",
+    };
+    let failure = if policy.fail_closed {
+        "fail closed"
+    } else {
+        "fail open"
+    };
+    let _ = writeln!(output, "Mode:    {mode}, {failure}");
+    if let Some(url) = &policy.remote_url {
+        let _ = writeln!(
+            output,
+            "Remote:  {url} (refreshed every {}s)",
+            policy.remote_ttl_secs
+        );
+    }
+    if policy.require_signed {
+        let _ = writeln!(
+            output,
+            "Signed:  required, {} trusted keys",
+            policy.trusted_keys.len()
+        );
+    }
+
+    let _ = writeln!(output, "\nScanners:");
+    for scanner in scanners(config) {
+        let _ = writeln!(
+            output,
+            "  {:<5} {:<16} {}",
+            format!("[{}]", on(scanner.enabled)),
+            scanner.name,
+            scanner.detail
+        );
+    }
+
+    let tools = &config.tools;
+    let _ = writeln!(
+        output,
+        "\nTools:   default {}; {} allow, {} deny, {} ask; {} MCP tool rules, {} MCP argument rules",
+        tools.default,
+        tools.allow.len(),
+        tools.deny.len(),
+        tools.ask.len(),
+        tools.mcp.rules.len(),
+        tools.mcp.arg_rules.len()
+    );
+    let session = &policy.session;
+    let _ = writeln!(
+        output,
+        "Session: risk {}, taint {}, approval memory {}, railgun approve {}",
+        on(session.risk.enabled),
+        on(session.taint.enabled),
+        on(policy.approvals.enabled),
+        on(policy.approve.enabled)
+    );
+    let _ = writeln!(output, "Tests:   {} [[tests]]", config.tests.len());
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_format_human() {
+        let mut config: Config = toml::from_str(
+            r#"
+[policy]
+mode = "monitor"
+packs = ["aws"]
+
+[policy.commands]
+allow_patterns = ["^cargo "]
+
+[policy.commands.git]
+enabled = false
+
+[policy.workspace]
+enabled = true
+"#,
+        )
+        .unwrap();
+        config.policy.network.block_domains = vec!["example.com".into()];
+
+        let output = format_human(
+            &ConfigSource::Project(PathBuf::from("railgun.toml")),
+            Some("work"),
+            &config,
+        );
+        assert!(output.starts_with("Config:  railgun.toml (project)\nProfile: work\n"));
+        assert!(output.contains("Packs:   aws\nMode:    monitor"));
+        assert!(output.contains("1 allow patterns; sudo ask, git off,"));
+        assert!(output.contains("[on]  workspace "));
+        assert!(output.contains("[on]  network          1 blocked domains\n"));
+
+        let output = format_human(&ConfigSource::Default, None, &Config::default());
+        assert!(output.contains("built-in defaults"));
+        assert!(output.contains("[off] workspace "));
+    }
+}
@@ -0,0 +1,295 @@
+//! Decision records for syslog and journald.
+//!
+//! With `policy.syslog` enabled, each call whose decision is listed in
+//! `events` is written to the host's log daemon, so Railgun decisions reach
+//! existing log pipelines without a collector of their own. The record is
+//! the [`AuditEntry`] the audit log stores, secrets redacted:
+//!
+//! - `syslog`: an RFC 5424 message on `/dev/log`, with the tool, decision,
+//!   and rules as structured data and the full entry as JSON in the message.
+//! - `journald`: a native journal entry on `/run/systemd/journal/socket`,
+//!   with each field of the entry as a `RAILGUN_*` journal field.
+
+use std::fmt::Write as _;
+use std::io;
+use std::os::unix::net::UnixDatagram;
+use std::path::PathBuf;
+
+use rg_policy::approvals::unix_now;
+use rg_policy::SecretScanner;
+use rg_types::{HookInput, RuleAction, SyslogConfig, SyslogFacility, SyslogTarget, Verdict};
+
+use crate::audit::{self, AuditEntry};
+
+/// The local syslog socket.
+const SYSLOG_SOCKET: &str = "/dev/log";
+
+/// journald's native protocol socket.
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// Private enterprise number reserved for documentation (RFC 5612), used as
+/// the structured data ID's suffix.
+const SD_ID: &str = "railgun@32473";
+
+/// Writes decision records to syslog or journald.
+#[derive(Debug, Clone)]
+pub struct SyslogSink {
+    /// Message format and default socket.
+    target: SyslogTarget,
+    /// Facility of every record.
+    facility: SyslogFacility,
+    /// App name / `SYSLOG_IDENTIFIER`.
+    ident: String,
+    /// Decisions that are recorded.
+    events: Vec<RuleAction>,
+    /// Datagram socket of the log daemon.
+    socket: PathBuf,
+}
+
+impl SyslogSink {
+    /// Build the sink from configuration, if enabled.
+    pub fn from_config(config: &SyslogConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let socket = config.socket.clone().unwrap_or_else(|| {
+            match config.target {
+                SyslogTarget::Syslog => SYSLOG_SOCKET,
+                SyslogTarget::Journald => JOURNALD_SOCKET,
+            }
+            .to_string()
+        });
+        Some(Self {
+            target: config.target,
+            facility: config.facility,
+            ident: config.ident.clone(),
+            events: config.events.clone(),
+            socket: socket.into(),
+        })
+    }
+
+    /// Write the record of a call if its decision is one of `events`.
+    /// `secrets` redacts its input.
+    pub fn record(
+        &self,
+        input: &HookInput,
+        verdict: &Verdict,
+        secrets: &SecretScanner,
+    ) -> io::Result<()> {
+        let (event, severity) = match verdict {
+            // warning, notice, info
+            Verdict::Deny { .. } => (RuleAction::Deny, 4),
+            Verdict::Ask { .. } => (RuleAction::Ask, 5),
+            Verdict::Allow | Verdict::Redact { .. } => (RuleAction::Allow, 6),
+        };
+        if !self.events.contains(&event) {
+            return Ok(());
+        }
+        let entry = AuditEntry::new(input, verdict, secrets, unix_now());
+        let datagram = match self.target {
+            SyslogTarget::Syslog => self.rfc5424(&entry, severity)?,
+            SyslogTarget::Journald => self.journal(&entry, severity),
+        };
+        let _ = UnixDatagram::unbound()?.send_to(&datagram, &self.socket)?;
+        Ok(())
+    }
+
+    /// An RFC 5424 message for an entry.
+    fn rfc5424(&self, entry: &AuditEntry, severity: u8) -> io::Result<Vec<u8>> {
+        let timestamp = audit::format_time(entry.ts).replace(' ', "T");
+        let mut params = vec![
+            ("tool", entry.tool.as_str()),
+            ("decision", entry.decision.as_str()),
+        ];
+        params.extend(entry.rules.iter().map(|rule| ("rule", rule.as_str())));
+        let params = params.iter().fold(String::new(), |mut out, (name, value)| {
+            let _ = write!(out, " {name}=\"{}\"", escape_param(value));
+            out
+        });
+        Ok(format!(
+            "<{}>1 {timestamp}Z - {} {} - [{SD_ID}{params}] {}",
+            u16::from(self.facility.code()) * 8 + u16::from(severity),
+            self.ident,
+            std::process::id(),
+            serde_json::to_string(entry)?
+        )
+        .into_bytes())
+    }
+
+    /// A journald native protocol entry.
+    fn journal(&self, entry: &AuditEntry, severity: u8) -> Vec<u8> {
+        let message = match &entry.reason {
+            Some(reason) => format!("{} {}: {reason}", entry.decision, entry.tool),
+            None => format!("{} {}", entry.decision, entry.tool),
+        };
+        let mut fields = vec![
+            ("MESSAGE", message),
+            ("PRIORITY", severity.to_string()),
+            ("SYSLOG_FACILITY", self.facility.code().to_string()),
+            ("SYSLOG_IDENTIFIER", self.ident.clone()),
+            ("RAILGUN_TOOL", entry.tool.clone()),
+            ("RAILGUN_DECISION", entry.decision.clone()),
+            ("RAILGUN_INPUT", entry.input.clone()),
+        ];
+        if let Some(reason) = &entry.reason {
+            fields.push(("RAILGUN_REASON", reason.clone()));
+        }
+        fields.extend(
+            entry
+                .rules
+                .iter()
+                .map(|rule| ("RAILGUN_RULE", rule.clone())),
+        );
+        if let Some(session_id) = &entry.session_id {
+            fields.push(("RAILGUN_SESSION_ID", session_id.clone()));
+        }
+        if let Some(cwd) = &entry.cwd {
+            fields.push(("RAILGUN_CWD", cwd.clone()));
+        }
+
+        let mut datagram = Vec::new();
+        for (name, value) in fields {
+            datagram.extend_from_slice(name.as_bytes());
+            if value.contains('\n') {
+                // Values with newlines are length-prefixed instead
+                datagram.push(b'\n');
+                datagram.extend_from_slice(&(value.len() as u64).to_le_bytes());
+            } else {
+                datagram.push(b'=');
+            }
+            datagram.extend_from_slice(value.as_bytes());
+            datagram.push(b'\n');
+        }
+        datagram
+    }
+}
+
+/// Escape an RFC 5424 structured data parameter value.
+fn escape_param(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rg_types::{BlockReason, SecretsConfig};
+    use serde_json::json;
+
+    fn input() -> HookInput {
+        HookInput {
+            cwd: Some("/repo".to_string()),
+            session_id: Some("s1".to_string()),
+            ..HookInput::new("Bash", json!({ "command": "git push --force" }))
+        }
+    }
+
+    fn force_push() -> Verdict {
+        Verdict::deny_from_block_reason(&BlockReason::GitDestructive {
+            rule: "force_push".to_string(),
+            branch: None,
+            matched: "git push --force".to_string(),
+        })
+    }
+
+    /// A sink writing to a fresh socket, and the socket.
+    fn sink(target: SyslogTarget) -> (SyslogSink, UnixDatagram, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.sock");
+        let receiver = UnixDatagram::bind(&path).unwrap();
+        let sink = SyslogSink::from_config(&SyslogConfig {
+            enabled: true,
+            target,
+            facility: SyslogFacility::Local0,
+            socket: Some(path.display().to_string()),
+            ..SyslogConfig::default()
+        })
+        .unwrap();
+        (sink, receiver, dir)
+    }
+
+    fn receive(receiver: &UnixDatagram) -> String {
+        let mut buf = vec![0; 65536];
+        let len = receiver.recv(&mut buf).unwrap();
+        String::from_utf8_lossy(&buf[..len]).into_owned()
+    }
+
+    #[test]
+    fn test_from_config() {
+        assert!(SyslogSink::from_config(&SyslogConfig::default()).is_none());
+        let journald = SyslogSink::from_config(&SyslogConfig {
+            enabled: true,
+            target: SyslogTarget::Journald,
+            ..SyslogConfig::default()
+        })
+        .unwrap();
+        assert_eq!(journald.socket, PathBuf::from(JOURNALD_SOCKET));
+    }
+
+    #[test]
+    fn test_syslog_record() {
+        let (sink, receiver, _dir) = sink(SyslogTarget::Syslog);
+        let secrets = SecretScanner::new(&SecretsConfig::default());
+        sink.record(&input(), &Verdict::Allow, &secrets).unwrap();
+        sink.record(&input(), &force_push(), &secrets).unwrap();
+
+        let message = receive(&receiver);
+        // local0.warning
+        assert!(message.starts_with("<132>1 20"), "{message}");
+        assert!(message.contains(&format!(
+            "Z - railgun {} - [railgun@32473 tool=\"Bash\" decision=\"deny\" \
+             rule=\"git_destructive:force_push\"] {{",
+            std::process::id()
+        )));
+        let json = &message[message.find("] ").unwrap() + 2..];
+        let entry: AuditEntry = serde_json::from_str(json).unwrap();
+        assert_eq!(entry.tool, "Bash");
+        assert_eq!(entry.session_id.as_deref(), Some("s1"));
+
+        // Only the deny was sent
+        receiver.set_nonblocking(true).unwrap();
+        assert!(receiver.recv(&mut [0; 16]).is_err());
+    }
+
+    #[test]
+    fn test_journald_record() {
+        let (sink, receiver, _dir) = sink(SyslogTarget::Journald);
+        let secrets = SecretScanner::new(&SecretsConfig::default());
+        sink.record(&input(), &force_push(), &secrets).unwrap();
+
+        let entry = receive(&receiver);
+        assert!(entry.contains("PRIORITY=4\n"));
+        assert!(entry.contains("SYSLOG_FACILITY=16\n"));
+        assert!(entry.contains("SYSLOG_IDENTIFIER=railgun\n"));
+        assert!(entry.contains("RAILGUN_DECISION=deny\n"));
+        assert!(entry.contains("RAILGUN_RULE=git_destructive:force_push\n"));
+        assert!(entry.contains("RAILGUN_SESSION_ID=s1\n"));
+        assert!(entry.contains("RAILGUN_INPUT={\"command\":\"git push --force\"}\n"));
+    }
+
+    #[test]
+    fn test_journal_multiline_value() {
+        let (sink, _receiver, _dir) = sink(SyslogTarget::Journald);
+        let mut entry = AuditEntry::new(
+            &input(),
+            &Verdict::deny("line one\nline two"),
+            &SecretScanner::new(&SecretsConfig::default()),
+            0,
+        );
+        entry.rules.clear();
+        let datagram = sink.journal(&entry, 4);
+        let mut expected = b"RAILGUN_REASON\n".to_vec();
+        expected.extend_from_slice(&17u64.to_le_bytes());
+        expected.extend_from_slice(b"line one\nline two\n");
+        assert!(datagram
+            .windows(expected.len())
+            .any(|window| window == expected));
+    }
+
+    #[test]
+    fn test_escape_param() {
+        assert_eq!(escape_param(r#"a"b\c]"#), r#"a\"b\\c\]"#);
+    }
+}
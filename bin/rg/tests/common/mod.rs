@@ -0,0 +1,197 @@
+//! Shared harness for integration tests that spawn the real `railgun` binary.
+//!
+//! Every [`Railgun`] gets its own temporary `HOME` and working directory so
+//! tests never touch the developer's `~/.claude/settings.json`, global
+//! `~/.config/railgun/railgun.toml`, or state in `~/.local/state/railgun`.
+
+#![allow(dead_code)] // Not every test binary uses every helper
+#![allow(clippy::expect_used, clippy::panic)] // Harness failures should abort the test
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+/// Upper bound for a single hook invocation, including process startup.
+///
+/// Generous enough for slow CI machines, tight enough to catch a hook that
+/// hangs on stdin or does unbounded work.
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An isolated environment for running the binary.
+#[derive(Debug)]
+pub struct Railgun {
+    /// Temporary home directory.
+    home: TempDir,
+    /// Temporary working directory (where `railgun.toml` is looked up).
+    workdir: TempDir,
+}
+
+/// Result of a single hook invocation.
+#[derive(Debug)]
+pub struct HookRun {
+    /// Process exit code.
+    pub code: i32,
+    /// Parsed stdout JSON (`Null` if the hook wrote nothing).
+    pub json: Value,
+    /// Raw stderr.
+    pub stderr: String,
+    /// Wall-clock time including process startup.
+    pub elapsed: Duration,
+}
+
+impl HookRun {
+    /// The `permissionDecision` field.
+    pub fn decision(&self) -> &str {
+        self.json["hookSpecificOutput"]["permissionDecision"]
+            .as_str()
+            .unwrap_or_default()
+    }
+
+    /// The `permissionDecisionReason` field.
+    pub fn reason(&self) -> &str {
+        self.json["hookSpecificOutput"]["permissionDecisionReason"]
+            .as_str()
+            .unwrap_or_default()
+    }
+
+    /// The `additionalContext` field.
+    pub fn context(&self) -> &str {
+        self.json["hookSpecificOutput"]["additionalContext"]
+            .as_str()
+            .unwrap_or_default()
+    }
+}
+
+impl Railgun {
+    /// Create an environment that uses the built-in default policy.
+    pub fn new() -> Self {
+        Self {
+            home: tempfile::tempdir().expect("failed to create temp HOME"),
+            workdir: tempfile::tempdir().expect("failed to create temp workdir"),
+        }
+    }
+
+    /// Create an environment with `railgun.toml` in the working directory.
+    pub fn with_config(config: &str) -> Self {
+        let env = Self::new();
+        env.write_file("railgun.toml", config);
+        env
+    }
+
+    /// Temporary home directory.
+    pub fn home(&self) -> &Path {
+        self.home.path()
+    }
+
+    /// Temporary working directory.
+    pub fn workdir(&self) -> &Path {
+        self.workdir.path()
+    }
+
+    /// Railgun's state directory for the working directory's project, inside
+    /// the temporary home (created by the first state the hook writes).
+    pub fn state_dir(&self) -> PathBuf {
+        let state = self.home().join(".local").join("state").join("railgun");
+        std::fs::read_dir(&state)
+            .expect("no state written")
+            .next()
+            .expect("no project state dir")
+            .expect("failed to read state dir")
+            .path()
+    }
+
+    /// Path to Claude Code settings inside the temporary home.
+    pub fn settings_path(&self) -> PathBuf {
+        self.home().join(".claude").join("settings.json")
+    }
+
+    /// Write a file relative to the working directory.
+    pub fn write_file(&self, relative: &str, content: &str) {
+        let path = self.workdir().join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("failed to create parent dir");
+        }
+        std::fs::write(path, content).expect("failed to write file");
+    }
+
+    /// A command for the binary with an isolated environment.
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_railgun"));
+        let _ = cmd
+            .current_dir(self.workdir())
+            .env("HOME", self.home())
+            .env("USERPROFILE", self.home())
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("XDG_STATE_HOME")
+            .env_remove("CLAUDE_PROJECT_DIR")
+            .env_remove("RAILGUN_LOG")
+            .env_remove("RAILGUN_ON_CONFIG_ERROR");
+        cmd
+    }
+
+    /// Run a subcommand to completion.
+    pub fn run(&self, args: &[&str]) -> Output {
+        self.command()
+            .args(args)
+            .output()
+            .expect("failed to spawn railgun")
+    }
+
+    /// Feed a raw payload to `railgun hook` over stdin.
+    pub fn hook_raw(&self, args: &[&str], stdin: &str) -> HookRun {
+        run_hook(self.command().arg("hook").args(args), stdin)
+    }
+
+    /// Feed a hook payload for `tool_name` and `tool_input`.
+    pub fn hook(&self, tool_name: &str, tool_input: &Value) -> HookRun {
+        let payload = serde_json::json!({
+            "session_id": "integration-test",
+            "hook_event_name": "PreToolUse",
+            "tool_name": tool_name,
+            "tool_input": tool_input,
+        });
+        self.hook_raw(&[], &payload.to_string())
+    }
+}
+
+/// Feed a raw payload over stdin to `cmd`, a [`Railgun::command`] for
+/// `railgun hook` with extra arguments or environment.
+pub fn run_hook(cmd: &mut Command, stdin: &str) -> HookRun {
+    let start = Instant::now();
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn railgun hook");
+
+    // The hook may exit before reading stdin (e.g. config errors in deny
+    // mode), so a broken pipe here is not a failure
+    let _ = child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(stdin.as_bytes());
+
+    let output = child.wait_with_output().expect("failed to wait for hook");
+    let elapsed = start.elapsed();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json = if stdout.trim().is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_str(&stdout)
+            .unwrap_or_else(|e| panic!("hook stdout is not JSON ({e}): {stdout:?}"))
+    };
+
+    HookRun {
+        code: output.status.code().expect("hook terminated by signal"),
+        json,
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        elapsed,
+    }
+}
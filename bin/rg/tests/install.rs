@@ -0,0 +1,306 @@
+//! End-to-end tests for `railgun install`, `railgun uninstall`, and
+//! `railgun doctor` against a temporary `HOME`.
+
+mod common;
+
+use common::Railgun;
+use serde_json::{json, Value};
+
+fn read_settings(rg: &Railgun) -> Option<Value> {
+    let content = std::fs::read_to_string(rg.settings_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn railgun_hooks(settings: &Value) -> usize {
+    settings["hooks"]["PreToolUse"]
+        .as_array()
+        .map_or(0, |entries| {
+            entries
+                .iter()
+                .filter(|entry| {
+                    entry["hooks"][0]["command"]
+                        .as_str()
+                        .is_some_and(|c| c.contains("railgun"))
+                })
+                .count()
+        })
+}
+
+#[test]
+fn test_install_creates_settings() {
+    let rg = Railgun::new();
+
+    let output = rg.run(&["install"]);
+    assert!(output.status.success());
+
+    let settings = read_settings(&rg).unwrap();
+    assert_eq!(railgun_hooks(&settings), 1);
+    assert_eq!(
+        settings["hooks"]["SessionStart"][0]["hooks"][0]["command"],
+        settings["hooks"]["PreToolUse"][0]["hooks"][0]["command"]
+    );
+    assert_eq!(
+        settings["hooks"]["PostToolUse"][0]["hooks"][0]["command"],
+        settings["hooks"]["PreToolUse"][0]["hooks"][0]["command"]
+    );
+    assert_eq!(
+        settings["hooks"]["SessionEnd"][0]["hooks"][0]["command"],
+        settings["hooks"]["PreToolUse"][0]["hooks"][0]["command"]
+    );
+    let command = settings["hooks"]["PreToolUse"][0]["hooks"][0]["command"]
+        .as_str()
+        .unwrap();
+    assert!(command.ends_with(" hook"), "{command}");
+    // Longer than anything the hook waits on, so it isn't killed first
+    assert_eq!(
+        settings["hooks"]["PreToolUse"][0]["hooks"][0]["timeout"],
+        90
+    );
+}
+
+#[test]
+fn test_install_is_idempotent() {
+    let rg = Railgun::new();
+
+    assert!(rg.run(&["install"]).status.success());
+    assert!(rg.run(&["install"]).status.success());
+
+    assert_eq!(railgun_hooks(&read_settings(&rg).unwrap()), 1);
+}
+
+#[test]
+fn test_install_preserves_existing_settings() {
+    let rg = Railgun::new();
+    std::fs::create_dir_all(rg.settings_path().parent().unwrap()).unwrap();
+    std::fs::write(
+        rg.settings_path(),
+        json!({
+            "model": "opus",
+            "hooks": {
+                "PreToolUse": [
+                    { "matcher": "Bash", "hooks": [{ "type": "command", "command": "other-hook" }] }
+                ]
+            }
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    assert!(rg.run(&["install"]).status.success());
+
+    let settings = read_settings(&rg).unwrap();
+    assert_eq!(settings["model"], "opus");
+    assert_eq!(settings["hooks"]["PreToolUse"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_install_update_rewrites_stale_path() {
+    let rg = Railgun::new();
+    assert!(rg.run(&["install"]).status.success());
+    let current = read_settings(&rg).unwrap()["hooks"]["PreToolUse"][0]["hooks"][0]["command"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let content = std::fs::read_to_string(rg.settings_path()).unwrap();
+    std::fs::write(
+        rg.settings_path(),
+        content.replace(&current, "/nonexistent/railgun hook"),
+    )
+    .unwrap();
+
+    let output = rg.run(&["install"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no longer exists"), "{stderr}");
+    assert!(stderr.contains("--update"), "{stderr}");
+
+    assert!(rg.run(&["install", "--update"]).status.success());
+    let settings = read_settings(&rg).unwrap();
+    assert_eq!(railgun_hooks(&settings), 1);
+    for event in ["PreToolUse", "PostToolUse", "SessionStart", "SessionEnd"] {
+        assert_eq!(settings["hooks"][event][0]["hooks"][0]["command"], current);
+    }
+}
+
+#[test]
+fn test_uninstall_removes_hook() {
+    let rg = Railgun::new();
+
+    assert!(rg.run(&["install"]).status.success());
+    assert!(rg.run(&["uninstall"]).status.success());
+
+    let settings = read_settings(&rg).unwrap();
+    assert_eq!(railgun_hooks(&settings), 0);
+    assert_eq!(settings["hooks"]["SessionStart"], json!([]));
+    assert_eq!(settings["hooks"]["PostToolUse"], json!([]));
+    assert_eq!(settings["hooks"]["SessionEnd"], json!([]));
+}
+
+#[test]
+fn test_uninstall_without_settings() {
+    let rg = Railgun::new();
+
+    let output = rg.run(&["uninstall"]);
+    assert!(output.status.success());
+    assert!(!rg.settings_path().exists());
+}
+
+#[test]
+fn test_install_project() {
+    let rg = Railgun::new();
+    std::fs::create_dir_all(rg.workdir().join(".git")).unwrap();
+    std::fs::create_dir_all(rg.workdir().join("src")).unwrap();
+
+    // From a subdirectory, the settings go to the repository root
+    let output = rg
+        .command()
+        .current_dir(rg.workdir().join("src"))
+        .args(["install", "--project"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let project = rg.workdir().join(".claude").join("settings.json");
+    let settings: Value =
+        serde_json::from_str(&std::fs::read_to_string(&project).unwrap()).unwrap();
+    assert_eq!(railgun_hooks(&settings), 1);
+    // Committed, so it can't name this machine's binary
+    assert_eq!(
+        settings["hooks"]["PreToolUse"][0]["hooks"][0]["command"],
+        "railgun hook"
+    );
+    assert!(!rg.settings_path().exists());
+
+    assert!(rg
+        .run(&["install", "--project", "--local"])
+        .status
+        .success());
+    let local = rg.workdir().join(".claude").join("settings.local.json");
+    let settings: Value = serde_json::from_str(&std::fs::read_to_string(&local).unwrap()).unwrap();
+    let command = settings["hooks"]["PreToolUse"][0]["hooks"][0]["command"]
+        .as_str()
+        .unwrap();
+    assert_ne!(command, "railgun hook");
+    assert!(command.ends_with(" hook"), "{command}");
+
+    assert!(rg.run(&["uninstall", "--project"]).status.success());
+    let settings: Value =
+        serde_json::from_str(&std::fs::read_to_string(&project).unwrap()).unwrap();
+    assert_eq!(railgun_hooks(&settings), 0);
+}
+
+#[test]
+fn test_uninstall_purge() {
+    let rg =
+        Railgun::with_config("[policy]\nmode = \"strict\"\n\n[policy.approvals]\nenabled = true\n");
+    std::fs::create_dir(rg.workdir().join(".git")).unwrap();
+    assert!(rg.run(&["install"]).status.success());
+    assert!(rg.run(&["install", "--project"]).status.success());
+    let global = rg.home().join(".config").join("railgun");
+    std::fs::create_dir_all(&global).unwrap();
+    std::fs::write(global.join("railgun.toml"), "").unwrap();
+    // An ask leaves pending approval state behind
+    let run = rg.hook("Bash", &json!({ "command": "git clean -fdx" }));
+    assert_eq!(run.decision(), "ask");
+    let approvals = rg.state_dir().join("approvals.json");
+    assert!(approvals.exists());
+    let sessions = rg.state_dir().join("sessions");
+    std::fs::create_dir(&sessions).unwrap();
+    std::fs::write(sessions.join("abc.json"), "{}").unwrap();
+    let project = rg.workdir().join(".claude").join("settings.json");
+
+    let output = rg.run(&["uninstall", "--purge", "--dry-run"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for listed in [&rg.settings_path(), &project, &global] {
+        assert!(stdout.contains(&*listed.to_string_lossy()), "{stdout}");
+    }
+    assert!(
+        stdout.contains(&format!("  {}\n", approvals.display())),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains(&format!("  {}\n", sessions.display())),
+        "{stdout}"
+    );
+    assert!(global.exists());
+    assert_eq!(railgun_hooks(&read_settings(&rg).unwrap()), 1);
+
+    assert!(rg.run(&["uninstall", "--purge"]).status.success());
+    assert!(!global.exists());
+    assert!(!approvals.exists());
+    assert!(!sessions.exists());
+    assert_eq!(railgun_hooks(&read_settings(&rg).unwrap()), 0);
+    let settings: Value =
+        serde_json::from_str(&std::fs::read_to_string(&project).unwrap()).unwrap();
+    assert_eq!(railgun_hooks(&settings), 0);
+    // The config itself is the user's
+    assert!(rg.workdir().join("railgun.toml").exists());
+
+    let output = rg.run(&["uninstall", "--purge"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Nothing to remove"));
+}
+
+#[test]
+fn test_install_cursor() {
+    let rg = Railgun::new();
+    let hooks_path = rg.home().join(".cursor").join("hooks.json");
+
+    assert!(rg.run(&["install", "--agent", "cursor"]).status.success());
+    assert!(rg.run(&["install", "--agent", "cursor"]).status.success());
+    let settings: Value =
+        serde_json::from_str(&std::fs::read_to_string(&hooks_path).unwrap()).unwrap();
+    assert_eq!(settings["version"], 1);
+    let entries = settings["hooks"]["beforeShellExecution"]
+        .as_array()
+        .unwrap();
+    assert_eq!(entries.len(), 1);
+    let command = entries[0]["command"].as_str().unwrap();
+    assert!(command.ends_with(" hook --agent cursor"), "{command}");
+    assert!(!rg.settings_path().exists());
+
+    assert!(!rg
+        .run(&["install", "--agent", "cursor", "--project", "--local"])
+        .status
+        .success());
+
+    assert!(rg.run(&["uninstall", "--agent", "cursor"]).status.success());
+    let settings: Value =
+        serde_json::from_str(&std::fs::read_to_string(&hooks_path).unwrap()).unwrap();
+    assert_eq!(settings["hooks"]["beforeShellExecution"], json!([]));
+}
+
+#[test]
+fn test_install_gemini() {
+    let rg = Railgun::new();
+
+    assert!(rg.run(&["install", "--agent", "gemini"]).status.success());
+    let path = rg.home().join(".gemini").join("settings.json");
+    let settings: Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    let command = settings["hooks"]["BeforeTool"][0]["hooks"][0]["command"]
+        .as_str()
+        .unwrap();
+    assert!(command.ends_with(" hook --agent gemini"), "{command}");
+    assert_eq!(
+        settings["hooks"]["SessionEnd"][0]["hooks"][0]["command"],
+        command
+    );
+}
+
+#[test]
+fn test_doctor_before_and_after_install() {
+    let rg = Railgun::new();
+
+    let output = rg.run(&["doctor"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[fail] hook"), "{stdout}");
+    assert!(stdout.contains("fix: Run `railgun install`"), "{stdout}");
+
+    assert!(rg.run(&["install"]).status.success());
+    let output = rg.run(&["doctor"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("[ok] hook"), "{stdout}");
+    assert!(stdout.contains("[ok] inspect"), "{stdout}");
+}
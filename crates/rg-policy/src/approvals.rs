@@ -0,0 +1,359 @@
+//! Approval memory for `ask` verdicts.
+//!
+//! Claude Code never tells a hook how the user answered a prompt, but a tool
+//! call only runs (and fires `PostToolUse`) if it was approved. So the hook
+//! records every `ask` as pending, marks it approved when the matching
+//! `PostToolUse` arrives, and from then on allows the identical call without
+//! asking until the approval expires.
+//!
+//! Calls are identified by a SHA-256 key over the tool name, working
+//! directory, and normalized tool input, so the state file
+//! (`approvals.json` in the project's state directory by default) holds no
+//! commands or file contents:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "entries": [
+//!     { "key": "9b1f...", "tool": "Bash", "asked_at": 1760000000, "approved_at": 1760000004 }
+//!   ]
+//! }
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rg_types::{HookInput, Verdict};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::baseline::fingerprint;
+use crate::lock::{write_atomic, StateLock};
+use crate::middleware::Middleware;
+use crate::PolicyError;
+
+/// Current approval file format version.
+const APPROVALS_VERSION: u32 = 1;
+
+/// Tool input fields that don't change what a call does.
+const IGNORED_FIELDS: &[&str] = &["description"];
+
+/// An approval state file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalFile {
+    /// File format version.
+    #[serde(default = "default_version")]
+    pub version: u32,
+    /// Pending and approved calls.
+    #[serde(default)]
+    pub entries: Vec<ApprovalEntry>,
+}
+
+/// A call that got an `ask` verdict.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalEntry {
+    /// Call key (see [`approval_key`]).
+    pub key: String,
+    /// Tool name, for readability.
+    pub tool: String,
+    /// When the user was last asked (Unix seconds).
+    pub asked_at: u64,
+    /// When the call ran after being asked (Unix seconds).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_at: Option<u64>,
+}
+
+fn default_version() -> u32 {
+    APPROVALS_VERSION
+}
+
+impl Default for ApprovalFile {
+    fn default() -> Self {
+        Self {
+            version: APPROVALS_VERSION,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl ApprovalFile {
+    /// Load an approval file. A missing file yields an empty one.
+    pub fn load(path: &Path) -> Result<Self, PolicyError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            PolicyError::ConfigError(format!("Failed to read {}: {e}", path.display()))
+        })?;
+        serde_json::from_str(&content).map_err(|e| {
+            PolicyError::ConfigError(format!("Invalid approval file {}: {e}", path.display()))
+        })
+    }
+
+    /// Write the approval file as pretty-printed JSON, creating its
+    /// directory if needed.
+    pub fn save(&self, path: &Path) -> Result<(), PolicyError> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                PolicyError::ConfigError(format!("Failed to create {}: {e}", dir.display()))
+            })?;
+        }
+        let mut content = serde_json::to_string_pretty(self)
+            .map_err(|e| PolicyError::ConfigError(format!("Failed to serialize approvals: {e}")))?;
+        content.push('\n');
+        write_atomic(path, &content)
+    }
+
+    /// Drop entries whose last activity is more than `ttl_secs` before `now`.
+    pub fn prune(&mut self, now: u64, ttl_secs: u64) {
+        self.entries
+            .retain(|e| now.saturating_sub(e.approved_at.unwrap_or(e.asked_at)) <= ttl_secs);
+    }
+
+    /// The entry for a key.
+    fn get(&self, key: &str) -> Option<&ApprovalEntry> {
+        self.entries.iter().find(|e| e.key == key)
+    }
+
+    /// The entry for a key, mutably.
+    fn get_mut(&mut self, key: &str) -> Option<&mut ApprovalEntry> {
+        self.entries.iter_mut().find(|e| e.key == key)
+    }
+}
+
+/// Compute the key identifying a tool call.
+///
+/// The input is normalized first: object keys are sorted, fields that don't
+/// affect the call (Bash `description`) are dropped, and whitespace in Bash
+/// commands is collapsed.
+pub fn approval_key(input: &HookInput) -> String {
+    let mut tool_input = input.tool_input.clone();
+    if let Value::Object(map) = &mut tool_input {
+        map.retain(|k, _| !IGNORED_FIELDS.contains(&k.as_str()));
+        if let Some(Value::String(command)) = map.get_mut("command") {
+            *command = command.split_whitespace().collect::<Vec<_>>().join(" ");
+        }
+    }
+
+    let canonical = serde_json::json!([
+        input.tool_name,
+        input.cwd.as_deref().unwrap_or_default(),
+        canonicalize(&tool_input),
+    ]);
+    fingerprint(&canonical.to_string())
+}
+
+/// Rebuild a value with object keys in sorted order.
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let sorted: serde_json::Map<String, Value> = keys
+                .into_iter()
+                .map(|k| (k.clone(), canonicalize(&map[k])))
+                .collect();
+            Value::Object(sorted)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonicalize).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Current time in Unix seconds.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Remembers approved `ask` decisions in a project-local state file.
+///
+/// As [`Middleware`], turns an `ask` for a recently approved call into an
+/// allow, and records other `ask`s as pending. [`record_run`] marks a pending
+/// call approved when its `PostToolUse` event arrives.
+///
+/// State file errors never change a verdict; they are logged and the call is
+/// asked about as usual.
+///
+/// [`record_run`]: ApprovalMemory::record_run
+#[derive(Debug, Clone)]
+pub struct ApprovalMemory {
+    /// State file path.
+    path: PathBuf,
+    /// How long an approval stays valid, in seconds.
+    ttl_secs: u64,
+}
+
+impl ApprovalMemory {
+    /// Create an approval memory backed by `path`.
+    pub fn new(path: impl Into<PathBuf>, ttl_secs: u64) -> Self {
+        Self {
+            path: path.into(),
+            ttl_secs,
+        }
+    }
+
+    /// Whether a call was approved within the TTL.
+    pub fn is_approved(&self, input: &HookInput, now: u64) -> Result<bool, PolicyError> {
+        let mut file = ApprovalFile::load(&self.path)?;
+        file.prune(now, self.ttl_secs);
+        Ok(file
+            .get(&approval_key(input))
+            .is_some_and(|e| e.approved_at.is_some()))
+    }
+
+    /// Record that the user is being asked about a call.
+    pub fn record_ask(&self, input: &HookInput, now: u64) -> Result<(), PolicyError> {
+        let _lock = StateLock::acquire(&self.path)?;
+        let mut file = ApprovalFile::load(&self.path)?;
+        file.prune(now, self.ttl_secs);
+        let key = approval_key(input);
+        match file.get_mut(&key) {
+            Some(entry) => {
+                entry.asked_at = now;
+                entry.approved_at = None;
+            }
+            None => file.entries.push(ApprovalEntry {
+                key,
+                tool: input.tool_name.clone(),
+                asked_at: now,
+                approved_at: None,
+            }),
+        }
+        file.save(&self.path)
+    }
+
+    /// Record that a call ran. Returns `true` if it was pending, i.e. the
+    /// user approved an `ask` for it.
+    pub fn record_run(&self, input: &HookInput, now: u64) -> Result<bool, PolicyError> {
+        let _lock = StateLock::acquire(&self.path)?;
+        let mut file = ApprovalFile::load(&self.path)?;
+        file.prune(now, self.ttl_secs);
+        let Some(entry) = file.get_mut(&approval_key(input)) else {
+            return Ok(false);
+        };
+        if entry.approved_at.is_some() {
+            return Ok(false);
+        }
+        entry.approved_at = Some(now);
+        file.save(&self.path)?;
+        Ok(true)
+    }
+}
+
+impl Middleware for ApprovalMemory {
+    fn process(&self, input: &HookInput, verdict: Verdict) -> Verdict {
+        if !matches!(verdict, Verdict::Ask { .. }) {
+            return verdict;
+        }
+
+        let now = unix_now();
+        match self.is_approved(input, now) {
+            Ok(true) => {
+                tracing::info!(tool = %input.tool_name, "allowed by remembered approval");
+                return Verdict::Allow;
+            }
+            Ok(false) => {}
+            Err(e) => tracing::warn!(error = %e, "failed to read approvals"),
+        }
+        if let Err(e) = self.record_ask(input, now) {
+            tracing::warn!(error = %e, "failed to record ask");
+        }
+        verdict
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bash(command: &str) -> HookInput {
+        HookInput {
+            cwd: Some("/work/app".to_string()),
+            ..HookInput::new(
+                "Bash",
+                serde_json::json!({ "command": command, "description": "Run it" }),
+            )
+        }
+    }
+
+    #[test]
+    fn test_key_normalization() {
+        let key = approval_key(&bash("git push  origin main"));
+        assert_eq!(key, approval_key(&bash(" git push origin\tmain ")));
+
+        let mut described = bash("git push origin main");
+        described.tool_input["description"] = "Push the branch".into();
+        assert_eq!(key, approval_key(&described));
+
+        assert_ne!(key, approval_key(&bash("git push origin dev")));
+        let mut other_project = bash("git push origin main");
+        other_project.cwd = Some("/work/other".to_string());
+        assert_ne!(key, approval_key(&other_project));
+    }
+
+    #[test]
+    fn test_ask_then_run_approves() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = ApprovalMemory::new(dir.path().join("approvals.json"), 60);
+        let input = bash("git push origin main");
+
+        // Running a call that was never asked about is not an approval
+        assert!(!memory.record_run(&input, 100).unwrap());
+        assert!(!memory.is_approved(&input, 100).unwrap());
+
+        memory.record_ask(&input, 100).unwrap();
+        assert!(!memory.is_approved(&input, 101).unwrap());
+        assert!(memory.record_run(&input, 105).unwrap());
+        assert!(memory.is_approved(&input, 110).unwrap());
+        assert!(!memory
+            .is_approved(&bash("git push origin dev"), 110)
+            .unwrap());
+
+        // Approvals expire after the TTL
+        assert!(memory.is_approved(&input, 165).unwrap());
+        assert!(!memory.is_approved(&input, 166).unwrap());
+    }
+
+    #[test]
+    fn test_concurrent_asks_are_all_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = ApprovalMemory::new(dir.path().join("approvals.json"), 60);
+
+        let hooks: Vec<_> = (0..16)
+            .map(|i| {
+                let memory = memory.clone();
+                std::thread::spawn(move || {
+                    memory
+                        .record_ask(&bash(&format!("git push origin b{i}")), 100)
+                        .unwrap();
+                })
+            })
+            .collect();
+        for hook in hooks {
+            hook.join().unwrap();
+        }
+
+        let file = ApprovalFile::load(&dir.path().join("approvals.json")).unwrap();
+        assert_eq!(file.entries.len(), 16);
+    }
+
+    #[test]
+    fn test_middleware() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = ApprovalMemory::new(dir.path().join("approvals.json"), 3600);
+        let input = bash("git push origin main");
+
+        let asked = memory.process(&input, Verdict::ask("Force push"));
+        assert!(matches!(asked, Verdict::Ask { .. }));
+        assert!(memory.record_run(&input, unix_now()).unwrap());
+
+        assert!(memory
+            .process(&input, Verdict::ask("Force push"))
+            .is_allow());
+        // Denials are never remembered
+        assert!(memory.process(&input, Verdict::deny("blocked")).is_deny());
+    }
+}
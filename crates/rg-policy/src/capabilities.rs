@@ -0,0 +1,237 @@
+//! Policy capability advertisement.
+//!
+//! Builds a compact, machine-readable summary of the policy's hard limits so
+//! the model can plan around them. The summary names categories and counts
+//! only: blocked domains, path patterns, and command regexes are never
+//! included, so advertising the policy does not reveal how to route around it.
+
+use rg_types::{PolicyConfig, PolicyMode, RuleAction, ToolsConfig};
+use serde::Serialize;
+
+/// Prefix identifying the summary in `additionalContext`.
+const CONTEXT_PREFIX: &str = "Railgun policy limits (JSON): ";
+
+/// Summary of the policy's hard limits.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Capabilities {
+    /// Policy mode.
+    pub mode: &'static str,
+    /// Categories of action that are denied.
+    pub deny: Vec<&'static str>,
+    /// Categories of action that require confirmation.
+    pub ask: Vec<&'static str>,
+    /// Number of blocked domains.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub blocked_domains: usize,
+    /// Categories of protected paths.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub protected_paths: Vec<&'static str>,
+    /// Number of denied tool patterns.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub denied_tools: usize,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // Signature required by serde
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+impl Capabilities {
+    /// Record a category under `ask` or `deny` according to its action.
+    fn push_action(&mut self, category: &'static str, action: RuleAction) {
+        match action {
+            RuleAction::Ask => self.ask.push(category),
+            RuleAction::Deny => self.deny.push(category),
+            RuleAction::Allow => {}
+        }
+    }
+
+    /// Summarize a policy configuration.
+    pub fn from_config(policy: &PolicyConfig, tools: &ToolsConfig) -> Self {
+        let mut caps = Self {
+            mode: match policy.mode {
+                PolicyMode::Strict => "strict",
+                PolicyMode::Monitor => "monitor",
+            },
+            ..Self::default()
+        };
+
+        if policy.secrets.enabled {
+            caps.deny.push("secrets");
+            caps.push_action("secret_searches", policy.secrets.search_action);
+        }
+        if policy.commands.enabled {
+            if !policy.commands.block_patterns.is_empty() {
+                caps.deny.push("dangerous_commands");
+            }
+            if !policy.commands.ask_patterns.is_empty() {
+                caps.ask.push("destructive_commands");
+            }
+            caps.push_action("unlisted_commands", policy.commands.default_action);
+        }
+        let paths = &policy.protected_paths;
+        let blocked_paths: Vec<&String> = paths
+            .blocked
+            .iter()
+            .chain(&paths.blocked_all)
+            .chain(&paths.blocked_read)
+            .chain(&paths.blocked_write)
+            .collect();
+        if paths.enabled && !blocked_paths.is_empty() {
+            caps.deny.push("protected_paths");
+            for pattern in blocked_paths {
+                let category = path_category(pattern);
+                if !caps.protected_paths.contains(&category) {
+                    caps.protected_paths.push(category);
+                }
+            }
+        }
+        if policy.network.enabled {
+            if !policy.network.block_domains.is_empty() {
+                caps.deny.push("network_exfiltration");
+                caps.blocked_domains = policy.network.block_domains.len();
+            }
+            caps.push_action("public_ip_requests", policy.network.public_ip_action);
+            caps.push_action("private_ip_requests", policy.network.private_ip_action);
+            if !policy.network.unusual_schemes.is_empty() {
+                caps.push_action("unusual_url_schemes", policy.network.scheme_action);
+            }
+            caps.push_action("non_standard_ports", policy.network.port_action);
+        }
+        if policy.executables.enabled {
+            if !policy.executables.deny_content_patterns.is_empty() {
+                caps.deny.push("download_and_execute_scripts");
+            }
+            caps.push_action("executable_writes", policy.executables.action);
+        }
+        if policy.packages.enabled {
+            if !policy.packages.deny.is_empty() {
+                caps.deny.push("denied_packages");
+            }
+            caps.push_action("package_installs", policy.packages.unknown);
+        }
+        if policy.writes.enabled {
+            caps.push_action("large_writes", policy.writes.action);
+        }
+        if policy.control_chars.enabled {
+            caps.push_action("control_characters", policy.control_chars.action);
+        }
+        if policy.workspace.enabled {
+            caps.push_action("outside_workspace", policy.workspace.action);
+        }
+        if policy.injection.enabled {
+            caps.push_action("prompt_injection", policy.injection.action);
+        }
+        let mcp_rules = |action| tools.mcp.rules.iter().filter(move |r| r.action == action);
+        if !tools.ask.is_empty()
+            || !tools.mcp.ask_servers.is_empty()
+            || mcp_rules(RuleAction::Ask).next().is_some()
+        {
+            caps.ask.push("tools");
+        }
+        caps.denied_tools =
+            tools.deny.len() + tools.mcp.deny_servers.len() + mcp_rules(RuleAction::Deny).count();
+
+        caps
+    }
+
+    /// Render the summary for `additionalContext`, capped at `max_bytes`.
+    ///
+    /// Detail is dropped progressively to fit; returns `None` if even the
+    /// minimal summary does not fit.
+    pub fn to_context(&self, max_bytes: usize) -> Option<String> {
+        let mut caps = self.clone();
+        loop {
+            let json = serde_json::to_string(&caps).ok()?;
+            let context = format!("{CONTEXT_PREFIX}{json}");
+            if context.len() <= max_bytes {
+                return Some(context);
+            }
+
+            if !caps.protected_paths.is_empty() {
+                caps.protected_paths.clear();
+            } else if caps.blocked_domains > 0 || caps.denied_tools > 0 {
+                caps.blocked_domains = 0;
+                caps.denied_tools = 0;
+            } else {
+                return None;
+            }
+        }
+    }
+}
+
+/// Map a protected path glob to a coarse category.
+fn path_category(pattern: &str) -> &'static str {
+    let p = pattern.to_lowercase();
+    if p.contains(".env") {
+        "env_files"
+    } else if p.contains(".ssh") {
+        "ssh"
+    } else if p.contains(".aws")
+        || p.contains("gcloud")
+        || p.contains(".azure")
+        || p.contains(".kube")
+    {
+        "cloud_credentials"
+    } else if p.contains(".pem")
+        || p.contains(".key")
+        || p.contains("id_rsa")
+        || p.contains("id_ed25519")
+    {
+        "private_keys"
+    } else if p.contains(".git") {
+        "git_internals"
+    } else {
+        "other"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_caps() -> Capabilities {
+        Capabilities::from_config(&PolicyConfig::default(), &ToolsConfig::default())
+    }
+
+    #[test]
+    fn test_default_capabilities() {
+        let caps = default_caps();
+
+        assert_eq!(caps.mode, "strict");
+        assert!(caps.deny.contains(&"secrets"));
+        assert!(caps.ask.contains(&"executable_writes"));
+        assert_eq!(caps.blocked_domains, 9);
+        assert_eq!(
+            caps.protected_paths,
+            vec![
+                "env_files",
+                "private_keys",
+                "ssh",
+                "cloud_credentials",
+                "git_internals"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_context_redacts_specifics() {
+        let context = default_caps().to_context(4096).unwrap();
+
+        assert!(context.starts_with(CONTEXT_PREFIX));
+        assert!(!context.contains("pastebin"));
+        assert!(!context.contains("**/"));
+    }
+
+    #[test]
+    fn test_context_size_cap() {
+        let caps = default_caps();
+        let full = caps.to_context(4096).unwrap();
+
+        let capped = caps.to_context(full.len() - 1).unwrap();
+        assert!(capped.len() < full.len());
+        assert!(!capped.contains("protected_paths\":["));
+
+        assert!(caps.to_context(16).is_none());
+    }
+}
@@ -0,0 +1,192 @@
+//! Destructive git operation detection.
+//!
+//! Recognizes git commands that discard work or rewrite shared history:
+//!
+//! - `force_push`: `git push --force` (or `-f`, `--force-with-lease`, a
+//!   `+refspec`), with the target branch when it is given
+//! - `reset_hard`: `git reset --hard`
+//! - `clean`: `git clean -f...`
+//! - `checkout_discard`: `git checkout -- .` and `git restore .`
+//!
+//! Global options such as `git -C dir` are skipped.
+
+use crate::shell::{self, Word};
+
+/// Git global options that take a separate argument.
+const GLOBAL_ARG_OPTIONS: &[&str] = &["-C", "-c", "--git-dir", "--work-tree", "--namespace"];
+
+/// A destructive git operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitOperation {
+    /// Rule identifier, e.g. `force_push`.
+    pub rule: &'static str,
+    /// Target branch, when detectable.
+    pub branch: Option<String>,
+    /// The git command.
+    pub matched: String,
+}
+
+/// Find every destructive git operation in a command.
+pub fn detect(command: &str) -> Vec<GitOperation> {
+    shell::simple_commands(&shell::tokenize(command))
+        .iter()
+        .filter_map(|words| operation(words))
+        .collect()
+}
+
+/// The destructive operation in a simple command, if any.
+fn operation(words: &[Word]) -> Option<GitOperation> {
+    let program = shell::program_index(words)?;
+    if words[program].program_name() != "git" {
+        return None;
+    }
+
+    let args: Vec<&str> = words[program + 1..]
+        .iter()
+        .map(|w| w.text.as_str())
+        .collect();
+    let sub = subcommand(&args)?;
+    let (name, rest) = (args[sub], &args[sub + 1..]);
+
+    let (rule, branch) = match name {
+        "push" => ("force_push", force_push_branch(rest)?),
+        "reset" if rest.contains(&"--hard") => ("reset_hard", None),
+        "clean" if is_forced_clean(rest) => ("clean", None),
+        "checkout" | "restore" if discards_all(name, rest) => ("checkout_discard", None),
+        _ => return None,
+    };
+
+    let parts: Vec<&str> = words[program..].iter().map(|w| w.text.as_str()).collect();
+    Some(GitOperation {
+        rule,
+        branch,
+        matched: parts.join(" "),
+    })
+}
+
+/// Index of the git subcommand, past global options.
+fn subcommand(args: &[&str]) -> Option<usize> {
+    let mut skip_next = false;
+    for (i, arg) in args.iter().enumerate() {
+        if std::mem::take(&mut skip_next) {
+            continue;
+        }
+        if arg.starts_with('-') {
+            skip_next = GLOBAL_ARG_OPTIONS.contains(arg);
+            continue;
+        }
+        return Some(i);
+    }
+    None
+}
+
+/// For a forced push, the target branch (`Some(None)` if not given).
+///
+/// Returns `None` if the push is not forced.
+#[allow(clippy::option_option)] // Forced-but-unknown differs from not forced
+fn force_push_branch(args: &[&str]) -> Option<Option<String>> {
+    let mut forced = false;
+    let mut positional = Vec::new();
+    for arg in args {
+        if *arg == "-f" || *arg == "--force" || arg.starts_with("--force-with-lease") {
+            forced = true;
+        } else if let Some(flags) = arg.strip_prefix('-').filter(|f| !f.starts_with('-')) {
+            forced |= flags.contains('f');
+        } else if !arg.starts_with('-') {
+            positional.push(*arg);
+        }
+    }
+
+    // The first positional is the remote; the rest are refspecs
+    let refspecs = positional.get(1..).unwrap_or_default();
+    forced |= refspecs.iter().any(|r| r.starts_with('+'));
+    if !forced {
+        return None;
+    }
+
+    let branch = refspecs.first().map(|refspec| {
+        let dst = refspec.rsplit(':').next().unwrap_or(refspec);
+        dst.trim_start_matches('+')
+            .trim_start_matches("refs/heads/")
+            .to_string()
+    });
+    Some(branch)
+}
+
+/// Whether `git clean` is forced (`-f`, `--force`).
+fn is_forced_clean(args: &[&str]) -> bool {
+    args.iter().any(|arg| {
+        *arg == "--force"
+            || arg
+                .strip_prefix('-')
+                .is_some_and(|f| !f.starts_with('-') && f.contains('f'))
+    })
+}
+
+/// Whether `git checkout`/`git restore` discards changes to the whole tree.
+fn discards_all(subcommand: &str, args: &[&str]) -> bool {
+    let paths: &[&str] = if subcommand == "checkout" {
+        let Some(dashes) = args.iter().position(|a| *a == "--") else {
+            // `git checkout .` without `--`
+            return args.contains(&".");
+        };
+        &args[dashes + 1..]
+    } else {
+        args
+    };
+    paths.iter().any(|p| matches!(*p, "." | "*" | ":/" | "./"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(command: &str) -> Vec<(&'static str, Option<String>)> {
+        detect(command)
+            .into_iter()
+            .map(|op| (op.rule, op.branch))
+            .collect()
+    }
+
+    #[test]
+    fn test_force_push() {
+        assert_eq!(
+            rules("git push --force origin main"),
+            [("force_push", Some("main".to_string()))]
+        );
+        assert_eq!(
+            rules("git push -uf origin HEAD:refs/heads/release"),
+            [("force_push", Some("release".to_string()))]
+        );
+        assert_eq!(
+            rules("git push origin +main"),
+            [("force_push", Some("main".to_string()))]
+        );
+        assert_eq!(
+            rules("git -C repo push --force-with-lease"),
+            [("force_push", None)]
+        );
+        assert_eq!(rules("git push origin main").len(), 0);
+    }
+
+    #[test]
+    fn test_reset_clean_checkout() {
+        assert_eq!(rules("git reset --hard HEAD~3"), [("reset_hard", None)]);
+        assert_eq!(rules("git clean -fdx"), [("clean", None)]);
+        assert_eq!(rules("git checkout -- ."), [("checkout_discard", None)]);
+        assert_eq!(rules("git restore ."), [("checkout_discard", None)]);
+
+        assert_eq!(rules("git reset --soft HEAD~1").len(), 0);
+        assert_eq!(rules("git clean -n").len(), 0);
+        assert_eq!(rules("git checkout -- src/main.rs").len(), 0);
+        assert_eq!(rules("git checkout main").len(), 0);
+    }
+
+    #[test]
+    fn test_compound() {
+        assert_eq!(
+            rules("git fetch && git reset --hard origin/main"),
+            [("reset_hard", None)]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! Gitleaks ruleset import.
+//!
+//! Converts a gitleaks-compatible TOML ruleset into [`SecretRule`]s so the
+//! community rule corpus can be reused by the [`SecretScanner`].
+//!
+//! Only the detection fields are imported (`id`, `description`, `regex`,
+//! `secretGroup`, `entropy`, `keywords`). Path-only rules and allowlists are
+//! skipped because Railgun scans tool inputs rather than files.
+//!
+//! [`SecretScanner`]: crate::SecretScanner
+
+use rg_types::SecretRule;
+use serde::Deserialize;
+
+use crate::safe_regex;
+use crate::PolicyError;
+
+/// Top-level gitleaks configuration file.
+#[derive(Debug, Deserialize)]
+struct GitleaksConfig {
+    #[serde(default)]
+    rules: Vec<GitleaksRule>,
+}
+
+/// A single gitleaks rule.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GitleaksRule {
+    id: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    regex: Option<String>,
+    #[serde(default)]
+    secret_group: usize,
+    #[serde(default)]
+    entropy: Option<f64>,
+    #[serde(default)]
+    keywords: Vec<String>,
+}
+
+/// Parse a gitleaks TOML ruleset into secret rules.
+///
+/// Rules without a `regex` (path-only rules) are skipped. Rules whose regex
+/// does not compile are rejected so a broken ruleset is noticed at load time.
+pub fn parse_ruleset(content: &str) -> Result<Vec<SecretRule>, PolicyError> {
+    let config: GitleaksConfig = toml::from_str(content)
+        .map_err(|e| PolicyError::ConfigError(format!("Invalid gitleaks ruleset: {e}")))?;
+
+    let mut rules = Vec::with_capacity(config.rules.len());
+    for rule in config.rules {
+        let Some(regex) = rule.regex else {
+            continue;
+        };
+
+        let _ = safe_regex::compile(&regex).map_err(|e| {
+            PolicyError::InvalidPattern(format!("gitleaks rule '{}': {e}", rule.id))
+        })?;
+
+        rules.push(SecretRule {
+            id: rule.id,
+            description: rule.description,
+            regex,
+            secret_group: rule.secret_group,
+            entropy: rule.entropy,
+            keywords: rule.keywords,
+        });
+    }
+
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULESET: &str = r#"
+title = "test rules"
+
+[[rules]]
+id = "slack-bot-token"
+description = "Slack Bot token"
+regex = '''(xoxb-[0-9]{10,13}-[0-9]{10,13}[a-zA-Z0-9-]*)'''
+secretGroup = 1
+keywords = ["xoxb"]
+
+[[rules]]
+id = "generic-api-key"
+regex = '''(?i)api_key\s*=\s*['"]?([a-z0-9]{20,})'''
+secretGroup = 1
+entropy = 3.5
+keywords = ["api_key"]
+
+[[rules]]
+id = "pkcs12-file"
+path = '''(?i)\.p12$'''
+
+[allowlist]
+paths = ['''vendor''']
+"#;
+
+    #[test]
+    fn test_parse_ruleset() {
+        let rules = parse_ruleset(RULESET).unwrap();
+
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].id, "slack-bot-token");
+        assert_eq!(rules[0].secret_group, 1);
+        assert_eq!(rules[0].keywords, vec!["xoxb"]);
+        assert_eq!(rules[1].entropy, Some(3.5));
+    }
+
+    #[test]
+    fn test_invalid_regex_rejected() {
+        let result = parse_ruleset(
+            r#"
+[[rules]]
+id = "broken"
+regex = '''(unclosed'''
+"#,
+        );
+
+        assert!(matches!(result, Err(PolicyError::InvalidPattern(_))));
+    }
+
+    #[test]
+    fn test_invalid_toml_rejected() {
+        assert!(matches!(
+            parse_ruleset("[[rules"),
+            Err(PolicyError::ConfigError(_))
+        ));
+    }
+}
@@ -0,0 +1,177 @@
+//! Host name normalization for network checks.
+//!
+//! Hosts are reduced to a canonical ASCII form before blocklist matching, so
+//! that `pastebin%2Ecom`, `PASTEBIN.com.`, full-width letters, and `xn--`
+//! punycode labels spelling the domain with look-alike Cyrillic or Greek
+//! letters all match `pastebin.com`.
+
+use crate::normalize::homoglyph;
+
+/// Normalize a host for blocklist matching.
+///
+/// Percent-decodes, lowercases, maps alternative dots and full-width
+/// characters to ASCII, decodes punycode labels, replaces common homoglyphs
+/// with the Latin letters they imitate, and strips trailing dots.
+pub fn normalize_host(host: &str) -> String {
+    let decoded = percent_decode(host);
+
+    let folded: String = decoded
+        .chars()
+        .flat_map(char::to_lowercase)
+        .map(fold_char)
+        .collect();
+
+    let labels: Vec<String> = folded
+        .trim_end_matches('.')
+        .split('.')
+        .map(|label| {
+            let label = label
+                .strip_prefix("xn--")
+                .and_then(punycode_decode)
+                .unwrap_or_else(|| label.to_string());
+            label
+                .chars()
+                .flat_map(char::to_lowercase)
+                .map(fold_char)
+                .collect()
+        })
+        .collect();
+
+    labels.join(".")
+}
+
+/// Decode `%XX` escapes; invalid escapes are kept as-is.
+pub fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(b) = u8::from_str_radix(hex, 16) {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Map a lowercase character to the ASCII character it renders as, if any.
+fn fold_char(c: char) -> char {
+    match c {
+        // Ideographic, full-width, and half-width full stops
+        '\u{3002}' | '\u{ff0e}' | '\u{ff61}' => '.',
+        // Full-width ASCII
+        '\u{ff01}'..='\u{ff5e}' => char::from_u32(u32::from(c) - 0xfee0).unwrap_or(c),
+        // Cyrillic, Greek, and Latin look-alikes
+        c => homoglyph(c),
+    }
+}
+
+/// Decode a punycode label (RFC 3492), without its `xn--` prefix.
+fn punycode_decode(input: &str) -> Option<String> {
+    const BASE: u32 = 36;
+    const TMIN: u32 = 1;
+    const TMAX: u32 = 26;
+
+    let (basic, extended) = match input.rfind('-') {
+        Some(i) => (&input[..i], &input[i + 1..]),
+        None => ("", input),
+    };
+    if !basic.is_ascii() {
+        return None;
+    }
+
+    let mut output: Vec<char> = basic.chars().collect();
+    let mut code_point: u32 = 0x80;
+    let mut index: u32 = 0;
+    let mut bias: u32 = 72;
+    let mut digits = extended.bytes().peekable();
+
+    while digits.peek().is_some() {
+        let old_index = index;
+        let mut weight: u32 = 1;
+        let mut k = BASE;
+        loop {
+            let digit = match digits.next()? {
+                c @ b'a'..=b'z' => u32::from(c - b'a'),
+                c @ b'A'..=b'Z' => u32::from(c - b'A'),
+                c @ b'0'..=b'9' => u32::from(c - b'0') + 26,
+                _ => return None,
+            };
+            index = index.checked_add(digit.checked_mul(weight)?)?;
+            let t = if k <= bias {
+                TMIN
+            } else if k >= bias + TMAX {
+                TMAX
+            } else {
+                k - bias
+            };
+            if digit < t {
+                break;
+            }
+            weight = weight.checked_mul(BASE - t)?;
+            k += BASE;
+        }
+
+        let len = u32::try_from(output.len()).ok()? + 1;
+        bias = adapt(index - old_index, len, old_index == 0);
+        code_point = code_point.checked_add(index / len)?;
+        index %= len;
+        output.insert(usize::try_from(index).ok()?, char::from_u32(code_point)?);
+        index += 1;
+    }
+
+    Some(output.into_iter().collect())
+}
+
+/// Punycode bias adaptation (RFC 3492, section 6.1).
+fn adapt(delta: u32, num_points: u32, first: bool) -> u32 {
+    const BASE: u32 = 36;
+    const TMIN: u32 = 1;
+    const TMAX: u32 = 26;
+    const SKEW: u32 = 38;
+    const DAMP: u32 = 700;
+
+    let mut delta = if first { delta / DAMP } else { delta / 2 };
+    delta += delta / num_points;
+    let mut k = 0;
+    while delta > ((BASE - TMIN) * TMAX) / 2 {
+        delta /= BASE - TMIN;
+        k += BASE;
+    }
+    k + (BASE - TMIN + 1) * delta / (delta + SKEW)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_and_case() {
+        assert_eq!(normalize_host("pastebin%2Ecom"), "pastebin.com");
+        assert_eq!(normalize_host("PASTEBIN.com."), "pastebin.com");
+        assert_eq!(normalize_host("%70astebin.com"), "pastebin.com");
+        assert_eq!(normalize_host("100%zz"), "100%zz");
+    }
+
+    #[test]
+    fn test_unicode_folding() {
+        assert_eq!(normalize_host("ｐａｓｔｅｂｉｎ．ｃｏｍ"), "pastebin.com");
+        assert_eq!(normalize_host("pastebin。com"), "pastebin.com");
+        // Cyrillic 'а'
+        assert_eq!(normalize_host("p\u{430}stebin.com"), "pastebin.com");
+    }
+
+    #[test]
+    fn test_punycode() {
+        assert_eq!(punycode_decode("bcher-kva").as_deref(), Some("bücher"));
+        assert_eq!(normalize_host("xn--pstebin-2fg.com"), "pastebin.com");
+        assert_eq!(normalize_host("XN--PSTEBIN-2FG.COM"), "pastebin.com");
+        assert_eq!(normalize_host("xn--!!.com"), "xn--!!.com");
+    }
+}
@@ -0,0 +1,310 @@
+//! Impact estimation for destructive commands.
+//!
+//! Before prompting for an ask-level command, Railgun can summarize what the
+//! command would remove so the user can make an informed decision:
+//!
+//! - `rm -r <paths>`: file count and total size under each existing target
+//! - `git clean -f...`: the paths reported by `git clean --dry-run`
+//!
+//! Estimation never modifies the filesystem. Directory walks are bounded so a
+//! huge tree cannot stall the hook, and both walks and `git clean` stop at
+//! the inspection deadline, if any.
+
+use std::fmt::Write;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::shell;
+
+/// Maximum number of filesystem entries visited per estimate.
+const MAX_ENTRIES: usize = 100_000;
+
+/// Maximum number of paths listed in a `git clean` summary.
+const MAX_LISTED: usize = 10;
+
+/// Estimate the impact of a destructive command run from `cwd`, giving up
+/// at `deadline`.
+///
+/// Returns a short human-readable summary, or `None` if the command is not
+/// recognized, nothing would be removed, or `git clean` outlasted the
+/// deadline. A walk cut short reports what it counted so far.
+pub fn estimate(command: &str, cwd: &Path, deadline: Option<Instant>) -> Option<String> {
+    let commands = shell::simple_commands(&shell::tokenize(command));
+
+    for words in &commands {
+        let Some(program) = shell::program_index(words) else {
+            continue;
+        };
+        let args: Vec<&str> = words[program + 1..]
+            .iter()
+            .map(|w| w.text.as_str())
+            .collect();
+
+        match words[program].program_name() {
+            "git" if args.first() == Some(&"clean") => {
+                return estimate_git_clean(&args[1..], cwd, deadline);
+            }
+            "rm" => return estimate_rm(&args, cwd, deadline),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Running totals for a directory walk.
+#[derive(Debug, Default)]
+struct Tally {
+    /// Regular files and symlinks.
+    files: usize,
+    /// Directories.
+    dirs: usize,
+    /// Total size in bytes.
+    bytes: u64,
+    /// Whether the walk stopped at [`MAX_ENTRIES`] or the deadline.
+    truncated: bool,
+}
+
+impl Tally {
+    /// Walk a path without following symlinks.
+    fn visit(&mut self, path: &Path, deadline: Option<Instant>) {
+        if self.files + self.dirs >= MAX_ENTRIES
+            || deadline.is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.truncated = true;
+            return;
+        }
+
+        let Ok(metadata) = path.symlink_metadata() else {
+            return;
+        };
+
+        if metadata.is_dir() {
+            self.dirs += 1;
+            if let Ok(entries) = std::fs::read_dir(path) {
+                for entry in entries.flatten() {
+                    self.visit(&entry.path(), deadline);
+                }
+            }
+        } else {
+            self.files += 1;
+            self.bytes += metadata.len();
+        }
+    }
+}
+
+/// Summarize what `rm` would remove.
+fn estimate_rm(args: &[&str], cwd: &Path, deadline: Option<Instant>) -> Option<String> {
+    let mut after_double_dash = false;
+    let mut targets = Vec::new();
+    for arg in args {
+        if !after_double_dash && *arg == "--" {
+            after_double_dash = true;
+        } else if after_double_dash || !arg.starts_with('-') {
+            targets.push(*arg);
+        }
+    }
+
+    let mut tally = Tally::default();
+    let mut existing = Vec::new();
+    for target in targets {
+        let path = cwd.join(target);
+        if path.symlink_metadata().is_ok() {
+            tally.visit(&path, deadline);
+            existing.push(target);
+        }
+    }
+
+    if existing.is_empty() {
+        return None;
+    }
+
+    let mut summary = format!(
+        "would remove {}{} files",
+        if tally.truncated { "at least " } else { "" },
+        tally.files
+    );
+    if tally.dirs > 0 {
+        let _ = write!(summary, " in {} directories", tally.dirs);
+    }
+    let _ = write!(
+        summary,
+        " ({}) under {}",
+        format_bytes(tally.bytes),
+        existing.join(", ")
+    );
+    Some(summary)
+}
+
+/// Summarize what `git clean` would remove by running it with `--dry-run`.
+fn estimate_git_clean(args: &[&str], cwd: &Path, deadline: Option<Instant>) -> Option<String> {
+    let mut dry_run_args = vec!["clean".to_string(), "--dry-run".to_string()];
+    for arg in args {
+        if *arg == "--force" {
+            continue;
+        }
+        if let Some(flags) = arg.strip_prefix('-').filter(|f| !f.starts_with('-')) {
+            // Keep combined short flags such as -dx, minus the force flag
+            let kept: String = flags.chars().filter(|c| *c != 'f').collect();
+            if !kept.is_empty() {
+                dry_run_args.push(format!("-{kept}"));
+            }
+            continue;
+        }
+        dry_run_args.push((*arg).to_string());
+    }
+
+    let stdout = run_until(
+        Command::new("git").args(&dry_run_args).current_dir(cwd),
+        deadline,
+    )?;
+    let stdout = String::from_utf8_lossy(&stdout);
+    let paths: Vec<&str> = stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("Would remove "))
+        .collect();
+
+    if paths.is_empty() {
+        return None;
+    }
+
+    let mut summary = format!("would remove {} paths: ", paths.len());
+    summary.push_str(&paths[..paths.len().min(MAX_LISTED)].join(", "));
+    if paths.len() > MAX_LISTED {
+        let _ = write!(summary, ", and {} more", paths.len() - MAX_LISTED);
+    }
+    Some(summary)
+}
+
+/// Run a command to completion and return its stdout, or `None` if it fails
+/// or is still running at `deadline`, in which case it is killed.
+fn run_until(command: &mut Command, deadline: Option<Instant>) -> Option<Vec<u8>> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // Drained on another thread, so a full pipe can't block the command
+    let mut stdout = child.stdout.take()?;
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stdout.read_to_end(&mut output);
+        output
+    });
+
+    let status = loop {
+        if let Some(status) = child.try_wait().ok()? {
+            break status;
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+        std::thread::sleep(Duration::from_millis(2));
+    };
+    let output = reader.join().ok()?;
+    status.success().then_some(output)
+}
+
+/// Format a byte count for display.
+#[allow(clippy::cast_precision_loss)] // Display only
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("build/nested")).unwrap();
+        std::fs::write(dir.path().join("build/a.o"), vec![0u8; 1024]).unwrap();
+        std::fs::write(dir.path().join("build/nested/b.o"), vec![0u8; 1024]).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_estimate_rm() {
+        let dir = make_tree();
+
+        let summary = estimate("rm -rf build", dir.path(), None).unwrap();
+        assert_eq!(
+            summary,
+            "would remove 2 files in 2 directories (2.0 KB) under build"
+        );
+
+        // Past the deadline, the walk stops where it is
+        let summary = estimate("rm -rf build", dir.path(), Some(Instant::now())).unwrap();
+        assert!(
+            summary.starts_with("would remove at least 0 files"),
+            "{summary}"
+        );
+    }
+
+    #[test]
+    fn test_estimate_rm_stops_at_separator() {
+        let dir = make_tree();
+
+        let summary = estimate("rm -rf build/nested && ls build", dir.path(), None).unwrap();
+        assert!(summary.starts_with("would remove 1 files"), "{summary}");
+    }
+
+    #[test]
+    fn test_estimate_rm_missing_target() {
+        let dir = make_tree();
+        assert!(estimate("rm -rf missing", dir.path(), None).is_none());
+    }
+
+    #[test]
+    fn test_estimate_unrecognized_command() {
+        let dir = make_tree();
+        assert!(estimate("ls -la", dir.path(), None).is_none());
+    }
+
+    #[test]
+    fn test_estimate_git_clean() {
+        let dir = make_tree();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(dir.path())
+                .output()
+                .is_ok_and(|o| o.status.success())
+        };
+        if !git(&["init", "-q"]) {
+            return; // git unavailable
+        }
+
+        let summary = estimate("git clean -fdx", dir.path(), None).unwrap();
+        assert_eq!(summary, "would remove 1 paths: build/");
+        // Nothing was actually removed
+        assert!(dir.path().join("build/a.o").exists());
+
+        assert!(estimate("git clean -fdx", dir.path(), Some(Instant::now())).is_none());
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MB");
+    }
+}
@@ -0,0 +1,159 @@
+//! Exclusive locks and atomic writes for state files.
+//!
+//! Every hook call is a separate process, and Claude Code runs hooks for
+//! parallel tool calls at the same time, so a read-modify-write of a state
+//! file must not interleave with another: the second writer would drop the
+//! first one's update. Writers hold a [`StateLock`] around the whole update,
+//! and write with [`write_atomic`] so readers that don't lock never see a
+//! half-written file.
+//!
+//! The lock is a `FILE.lock` file created exclusively, which works the same
+//! on every platform. A lock left behind by a killed process is taken over
+//! once it is older than [`STALE_AFTER`].
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::PolicyError;
+
+/// Age after which a lock is assumed to be left over from a killed process.
+/// Updates take milliseconds.
+pub const STALE_AFTER: Duration = Duration::from_secs(5);
+
+/// How long to wait for a lock before giving up.
+const WAIT: Duration = Duration::from_secs(2);
+
+/// How often to retry a held lock.
+const RETRY: Duration = Duration::from_millis(5);
+
+/// An exclusive lock on a state file, released when dropped.
+#[derive(Debug)]
+pub struct StateLock {
+    /// The lock file.
+    path: PathBuf,
+}
+
+impl StateLock {
+    /// Lock `path`, creating its directory if needed, waiting while another
+    /// process holds the lock.
+    pub fn acquire(path: &Path) -> Result<Self, PolicyError> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                PolicyError::ConfigError(format!("Failed to create {}: {e}", dir.display()))
+            })?;
+        }
+        let mut lock = path.as_os_str().to_os_string();
+        lock.push(".lock");
+        let lock = PathBuf::from(lock);
+
+        let start = Instant::now();
+        loop {
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&lock)
+            {
+                Ok(_) => return Ok(Self { path: lock }),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if is_stale(&lock) {
+                        let _ = std::fs::remove_file(&lock);
+                        continue;
+                    }
+                    if start.elapsed() >= WAIT {
+                        return Err(PolicyError::ConfigError(format!(
+                            "Timed out waiting for {}",
+                            lock.display()
+                        )));
+                    }
+                    std::thread::sleep(RETRY);
+                }
+                Err(e) => {
+                    return Err(PolicyError::ConfigError(format!(
+                        "Failed to lock {}: {e}",
+                        path.display()
+                    )))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Whether a lock file is older than [`STALE_AFTER`].
+fn is_stale(lock: &Path) -> bool {
+    std::fs::metadata(lock)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > STALE_AFTER)
+}
+
+/// Replace `path` with `content` by writing a temporary file next to it and
+/// renaming it into place.
+pub fn write_atomic(path: &Path, content: &str) -> Result<(), PolicyError> {
+    let mut tmp = path.as_os_str().to_os_string();
+    tmp.push(format!(".{}.tmp", std::process::id()));
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, content)
+        .and_then(|()| std::fs::rename(&tmp, path))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&tmp);
+            PolicyError::ConfigError(format!("Failed to write {}: {e}", path.display()))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_is_exclusive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("approvals.json");
+
+        let lock = StateLock::acquire(&path).unwrap();
+        let lock_file = dir.path().join("state").join("approvals.json.lock");
+        assert!(lock_file.exists());
+
+        let waiter = {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                let start = Instant::now();
+                let _lock = StateLock::acquire(&path).unwrap();
+                start.elapsed()
+            })
+        };
+        std::thread::sleep(Duration::from_millis(50));
+        drop(lock);
+        assert!(waiter.join().unwrap() >= Duration::from_millis(40));
+        assert!(!lock_file.exists());
+    }
+
+    #[test]
+    fn test_stale_lock_is_taken_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("approvals.json");
+        let lock_file = dir.path().join("approvals.json.lock");
+        std::fs::File::create(&lock_file)
+            .unwrap()
+            .set_modified(SystemTime::now() - STALE_AFTER * 2)
+            .unwrap();
+
+        let _lock = StateLock::acquire(&path).unwrap();
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        write_atomic(&path, "{}").unwrap();
+        write_atomic(&path, "{\"a\":1}").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"a\":1}");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,379 @@
+//! Argument-aware rules for MCP tools.
+//!
+//! MCP tool inputs are arbitrary JSON, so the parameter scanners don't know
+//! which fields matter. These rules select values with a small `JSONPath`
+//! subset and match every string beneath them against a regex or glob, e.g.
+//! deny any argument containing `DROP TABLE`, or a `repo` naming a private
+//! repository.
+//!
+//! Supported selector syntax: `$` (root), `.key`, `['key']`, `[0]`, `[*]`,
+//! `.*`, and `..` (recursive descent, as in `$..query`).
+
+use glob::Pattern;
+use regex::Regex;
+use rg_types::{McpArgRule, RuleAction};
+use serde_json::Value;
+
+use crate::safe_regex;
+
+/// One step of a parsed selector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// An object member.
+    Key(String),
+    /// An array element.
+    Index(usize),
+    /// Every member or element.
+    Any,
+    /// The node and all of its descendants.
+    Descend,
+}
+
+/// A matched MCP argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpArgMatch {
+    /// The rule name.
+    pub rule: String,
+    /// Concrete path of the matching value (e.g., `$.query`).
+    pub path: String,
+    /// Action to take for this match.
+    pub action: RuleAction,
+}
+
+/// A compiled argument rule.
+#[derive(Debug)]
+struct CompiledArgRule {
+    /// Rule name.
+    name: String,
+    /// Tool name pattern.
+    tool: Pattern,
+    /// Parsed selector.
+    path: Vec<Step>,
+    /// Regex condition.
+    regex: Option<Regex>,
+    /// Glob condition.
+    glob: Option<Pattern>,
+    /// Action for matches.
+    action: RuleAction,
+}
+
+impl CompiledArgRule {
+    /// Compile a rule; `None` if any part is invalid or it has no condition.
+    fn new(rule: &McpArgRule) -> Option<Self> {
+        if rule.regex.is_none() && rule.glob.is_none() {
+            return None;
+        }
+        let name = rule.name.clone().unwrap_or_else(|| {
+            rule.regex
+                .clone()
+                .or_else(|| rule.glob.clone())
+                .unwrap_or_default()
+        });
+        Some(Self {
+            name,
+            tool: Pattern::new(&rule.tool).ok()?,
+            path: parse_selector(&rule.path).ok()?,
+            regex: match &rule.regex {
+                Some(r) => Some(safe_regex::compile(r).ok()?),
+                None => None,
+            },
+            glob: match &rule.glob {
+                Some(g) => Some(Pattern::new(g).ok()?),
+                None => None,
+            },
+            action: rule.action,
+        })
+    }
+
+    /// Whether a string satisfies every condition of the rule.
+    fn matches(&self, text: &str) -> bool {
+        self.regex.as_ref().map_or(true, |r| r.is_match(text))
+            && self.glob.as_ref().map_or(true, |g| g.matches(text))
+    }
+}
+
+/// Checker for MCP argument rules.
+#[derive(Debug, Default)]
+pub struct McpArgChecker {
+    /// Compiled rules.
+    rules: Vec<CompiledArgRule>,
+}
+
+impl McpArgChecker {
+    /// Compile argument rules, skipping invalid ones.
+    pub fn new(rules: &[McpArgRule]) -> Self {
+        Self {
+            rules: rules.iter().filter_map(CompiledArgRule::new).collect(),
+        }
+    }
+
+    /// Check a tool's arguments; the most restrictive match wins.
+    pub fn check(&self, tool_name: &str, args: &Value) -> Option<McpArgMatch> {
+        let mut found: Option<McpArgMatch> = None;
+
+        for rule in &self.rules {
+            if rule.action == RuleAction::Allow || !rule.tool.matches(tool_name) {
+                continue;
+            }
+            if found
+                .as_ref()
+                .is_some_and(|m| m.action == RuleAction::Deny || m.action == rule.action)
+            {
+                continue;
+            }
+            let hit = select(args, &rule.path)
+                .into_iter()
+                .flat_map(|(path, value)| strings(path, value))
+                .find(|(_, text)| rule.matches(text));
+            if let Some((path, _)) = hit {
+                found = Some(McpArgMatch {
+                    rule: rule.name.clone(),
+                    path,
+                    action: rule.action,
+                });
+            }
+        }
+
+        found
+    }
+}
+
+/// Parse a JSONPath-style selector.
+pub fn parse_selector(selector: &str) -> Result<Vec<Step>, String> {
+    let rest = selector
+        .strip_prefix('$')
+        .ok_or_else(|| format!("selector must start with '$': {selector}"))?;
+    let mut chars = rest.chars().peekable();
+    let mut steps = Vec::new();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                if chars.peek() == Some(&'.') {
+                    let _ = chars.next();
+                    steps.push(Step::Descend);
+                    // `$..[0]` continues with a bracket; `$..key` with a name
+                    if chars.peek() == Some(&'[') {
+                        continue;
+                    }
+                }
+                let name: String =
+                    std::iter::from_fn(|| chars.next_if(|c| *c != '.' && *c != '[')).collect();
+                match name.as_str() {
+                    "" => return Err(format!("empty member name in {selector}")),
+                    "*" => steps.push(Step::Any),
+                    _ => steps.push(Step::Key(name)),
+                }
+            }
+            '[' => {
+                let inner: String = std::iter::from_fn(|| chars.next_if(|c| *c != ']')).collect();
+                if chars.next() != Some(']') {
+                    return Err(format!("unterminated '[' in {selector}"));
+                }
+                let inner = inner.trim();
+                let quoted = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                if let Some(key) = quoted {
+                    steps.push(Step::Key(key.to_string()));
+                } else if inner == "*" {
+                    steps.push(Step::Any);
+                } else {
+                    let index = inner
+                        .parse()
+                        .map_err(|_| format!("invalid index '{inner}' in {selector}"))?;
+                    steps.push(Step::Index(index));
+                }
+            }
+            _ => return Err(format!("unexpected '{c}' in {selector}")),
+        }
+    }
+
+    Ok(steps)
+}
+
+/// Evaluate a selector, returning each selected value with its path.
+fn select<'a>(root: &'a Value, steps: &[Step]) -> Vec<(String, &'a Value)> {
+    let mut nodes = vec![("$".to_string(), root)];
+
+    for step in steps {
+        let mut next = Vec::new();
+        for (path, node) in nodes {
+            match step {
+                Step::Key(key) => {
+                    if let Some(child) = node.get(key.as_str()) {
+                        next.push((format!("{path}.{key}"), child));
+                    }
+                }
+                Step::Index(index) => {
+                    if let Some(child) = node.get(*index) {
+                        next.push((format!("{path}[{index}]"), child));
+                    }
+                }
+                Step::Any => next.extend(children(&path, node)),
+                Step::Descend => descend(path, node, &mut next),
+            }
+        }
+        nodes = next;
+    }
+
+    nodes
+}
+
+/// The direct children of a node, with their paths.
+fn children<'a>(path: &str, node: &'a Value) -> Vec<(String, &'a Value)> {
+    match node {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, child)| (format!("{path}.{key}"), child))
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, child)| (format!("{path}[{i}]"), child))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// A node followed by all of its descendants, depth first.
+fn descend<'a>(path: String, node: &'a Value, out: &mut Vec<(String, &'a Value)>) {
+    let kids = children(&path, node);
+    out.push((path, node));
+    for (child_path, child) in kids {
+        descend(child_path, child, out);
+    }
+}
+
+/// Every string (and number or boolean, as text) at or under a node.
+fn strings(path: String, node: &Value) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    let mut nodes = Vec::new();
+    descend(path, node, &mut nodes);
+    for (path, value) in nodes {
+        match value {
+            Value::String(s) => out.push((path, s.clone())),
+            Value::Number(n) => out.push((path, n.to_string())),
+            Value::Bool(b) => out.push((path, b.to_string())),
+            _ => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(tool: &str, path: &str, regex: Option<&str>, glob: Option<&str>) -> McpArgRule {
+        McpArgRule {
+            name: None,
+            tool: tool.to_string(),
+            path: path.to_string(),
+            regex: regex.map(ToString::to_string),
+            glob: glob.map(ToString::to_string),
+            action: RuleAction::Deny,
+        }
+    }
+
+    #[test]
+    fn test_parse_selector() {
+        assert_eq!(parse_selector("$"), Ok(vec![]));
+        assert_eq!(
+            parse_selector("$.items[*].name"),
+            Ok(vec![
+                Step::Key("items".to_string()),
+                Step::Any,
+                Step::Key("name".to_string())
+            ])
+        );
+        assert_eq!(
+            parse_selector("$..['query'][0]"),
+            Ok(vec![
+                Step::Descend,
+                Step::Key("query".to_string()),
+                Step::Index(0)
+            ])
+        );
+        assert_eq!(
+            parse_selector("$..sql"),
+            Ok(vec![Step::Descend, Step::Key("sql".to_string())])
+        );
+        assert!(parse_selector("query").is_err());
+        assert!(parse_selector("$.a[").is_err());
+        assert!(parse_selector("$[x]").is_err());
+    }
+
+    #[test]
+    fn test_regex_anywhere() {
+        let checker = McpArgChecker::new(&[rule("mcp__*", "$", Some(r"(?i)drop\s+table"), None)]);
+
+        let m = checker
+            .check(
+                "mcp__postgres__query",
+                &json!({ "params": { "sql": ["SELECT 1", "drop  TABLE users"] } }),
+            )
+            .unwrap();
+        assert_eq!(m.path, "$.params.sql[1]");
+        assert_eq!(m.rule, r"(?i)drop\s+table");
+        assert_eq!(m.action, RuleAction::Deny);
+
+        assert!(checker
+            .check("mcp__postgres__query", &json!({ "sql": "SELECT 1" }))
+            .is_none());
+        // Non-MCP tools are out of scope for the default tool pattern
+        assert!(checker
+            .check("Bash", &json!({ "command": "drop table x" }))
+            .is_none());
+    }
+
+    #[test]
+    fn test_glob_on_selected_field() {
+        let checker = McpArgChecker::new(&[rule(
+            "mcp__github__*",
+            "$.repo",
+            None,
+            Some("acme/secret-*"),
+        )]);
+
+        assert!(checker
+            .check(
+                "mcp__github__create_issue",
+                &json!({ "repo": "acme/secret-infra" })
+            )
+            .is_some());
+        assert!(checker
+            .check(
+                "mcp__github__create_issue",
+                &json!({ "repo": "acme/website" })
+            )
+            .is_none());
+        // Only the selected field is checked
+        assert!(checker
+            .check(
+                "mcp__github__create_issue",
+                &json!({ "repo": "acme/website", "body": "acme/secret-infra" })
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn test_most_restrictive_wins() {
+        let mut ask = rule("mcp__*", "$..title", Some("urgent"), None);
+        ask.action = RuleAction::Ask;
+        let mut deny = rule("mcp__*", "$..body", Some("password"), None);
+        deny.name = Some("no-passwords".to_string());
+        let checker = McpArgChecker::new(&[ask, deny]);
+
+        let m = checker
+            .check(
+                "mcp__github__create_issue",
+                &json!({ "title": "urgent", "body": "the password is hunter2" }),
+            )
+            .unwrap();
+        assert_eq!(m.rule, "no-passwords");
+        assert_eq!(m.action, RuleAction::Deny);
+    }
+}
@@ -0,0 +1,192 @@
+//! Verdict post-processing middleware.
+//!
+//! Middleware runs after the scanners have produced a verdict and may
+//! transform it: downgrade a deny to an ask for trusted sessions, attach
+//! context, emit metrics, and so on. Layers run in registration order, each
+//! receiving the verdict returned by the previous one.
+//!
+//! # Example
+//!
+//! ```rust
+//! use rg_policy::{inspect, MonitorMode, RuntimePolicy};
+//! use rg_types::{HookInput, PolicyConfig, Verdict};
+//!
+//! let mut policy = RuntimePolicy::from_config(&PolicyConfig::default());
+//! policy.middleware.push(MonitorMode);
+//! policy.middleware.push(|_input: &HookInput, verdict: Verdict| verdict);
+//!
+//! let input = HookInput::new("Bash", serde_json::json!({ "command": "rm -rf /" }));
+//! let (verdict, _) = inspect(&input, &policy);
+//! assert!(verdict.is_allow());
+//! ```
+
+use std::fmt;
+
+use rg_types::{HookInput, Verdict};
+
+/// A verdict post-processor.
+pub trait Middleware: Send + Sync {
+    /// Transform the verdict produced for `input`.
+    fn process(&self, input: &HookInput, verdict: Verdict) -> Verdict;
+
+    /// Name used in debug output.
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+}
+
+impl<F> Middleware for F
+where
+    F: Fn(&HookInput, Verdict) -> Verdict + Send + Sync,
+{
+    fn process(&self, input: &HookInput, verdict: Verdict) -> Verdict {
+        self(input, verdict)
+    }
+}
+
+/// Ordered chain of middleware applied to every verdict.
+#[derive(Default)]
+pub struct MiddlewareChain {
+    /// Registered layers, in execution order.
+    layers: Vec<Box<dyn Middleware>>,
+}
+
+impl MiddlewareChain {
+    /// Create an empty chain.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a middleware to the end of the chain.
+    pub fn push(&mut self, middleware: impl Middleware + 'static) {
+        self.layers.push(Box::new(middleware));
+    }
+
+    /// Append a middleware, returning the chain (builder style).
+    #[must_use]
+    pub fn with(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.push(middleware);
+        self
+    }
+
+    /// Run every layer in order over a verdict.
+    pub fn apply(&self, input: &HookInput, verdict: Verdict) -> Verdict {
+        self.layers
+            .iter()
+            .fold(verdict, |verdict, layer| layer.process(input, verdict))
+    }
+
+    /// Number of registered layers.
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Check if no layers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+}
+
+impl fmt::Debug for MiddlewareChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.layers.iter().map(|layer| layer.name()))
+            .finish()
+    }
+}
+
+/// Monitor mode: log violations but allow every action.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MonitorMode;
+
+impl Middleware for MonitorMode {
+    fn process(&self, input: &HookInput, verdict: Verdict) -> Verdict {
+        if let Some(reason) = verdict.reason() {
+            tracing::warn!(
+                tool = %input.tool_name,
+                decision = verdict.permission_decision(),
+                reason,
+                "monitor mode: allowing policy violation"
+            );
+            return Verdict::Allow;
+        }
+        verdict
+    }
+}
+
+/// Emit a tracing event for every deny and ask verdict.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogVerdicts;
+
+impl Middleware for LogVerdicts {
+    fn process(&self, input: &HookInput, verdict: Verdict) -> Verdict {
+        match &verdict {
+            Verdict::Allow => {
+                tracing::debug!(tool = %input.tool_name, "allowed");
+            }
+            Verdict::Deny { reason, .. } => {
+                tracing::info!(tool = %input.tool_name, reason, "denied");
+            }
+            Verdict::Ask { reason, .. } => {
+                tracing::info!(tool = %input.tool_name, reason, "asking user");
+            }
+            Verdict::Redact { reason, .. } => {
+                tracing::info!(tool = %input.tool_name, reason, "redacted");
+            }
+        }
+        verdict
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_input() -> HookInput {
+        HookInput::new("Bash", serde_json::json!({ "command": "ls" }))
+    }
+
+    #[test]
+    fn test_empty_chain_passthrough() {
+        let chain = MiddlewareChain::new();
+        let verdict = chain.apply(&make_input(), Verdict::deny("blocked"));
+        assert!(verdict.is_deny());
+        assert!(chain.is_empty());
+    }
+
+    #[test]
+    fn test_layers_run_in_order() {
+        let chain = MiddlewareChain::new()
+            .with(|_: &HookInput, v: Verdict| match v {
+                Verdict::Deny { reason, .. } => Verdict::ask(reason),
+                other => other,
+            })
+            .with(|_: &HookInput, v: Verdict| match v {
+                Verdict::Ask { reason, .. } => Verdict::ask(format!("{reason} (trusted session)")),
+                other => other,
+            });
+
+        let verdict = chain.apply(&make_input(), Verdict::deny("blocked"));
+        assert_eq!(verdict, Verdict::ask("blocked (trusted session)"));
+        assert_eq!(chain.len(), 2);
+    }
+
+    #[test]
+    fn test_monitor_mode_allows() {
+        let chain = MiddlewareChain::new().with(MonitorMode);
+        assert!(chain
+            .apply(&make_input(), Verdict::deny("blocked"))
+            .is_allow());
+        assert!(chain
+            .apply(&make_input(), Verdict::ask("confirm"))
+            .is_allow());
+    }
+
+    #[test]
+    fn test_log_verdicts_passthrough() {
+        let chain = MiddlewareChain::new().with(LogVerdicts);
+        assert!(chain
+            .apply(&make_input(), Verdict::deny("blocked"))
+            .is_deny());
+    }
+}
@@ -1,11 +1,23 @@
 //! Network exfiltration detection for Claude Code hook inputs.
 //!
 //! Detects URLs pointing to blocked domains that could be used
-//! for data exfiltration (paste sites, webhook services, etc.)
+//! for data exfiltration (paste sites, webhook services, etc.), and
+//! requests to raw IP addresses, which bypass domain blocking entirely, and
+//! URLs with unusual schemes or non-standard ports.
+//!
+//! Blocked domains are found in text with an Aho-Corasick automaton over the
+//! blocklist, so a blocklist of thousands of domains costs one linear pass
+//! over a command rather than a lookup per URL.
+
+use std::collections::BTreeMap;
 
+use aho_corasick::AhoCorasick;
 use regex::Regex;
-use rg_types::NetworkConfig;
+use rg_types::{NetworkConfig, RuleAction};
+
+use crate::host::{normalize_host, percent_decode};
 use std::collections::HashSet;
+use std::net::IpAddr;
 
 /// A matched network exfiltration attempt.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -16,37 +28,105 @@ pub struct NetworkMatch {
     pub url: String,
 }
 
+/// A matched request to a raw IP address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpMatch {
+    /// The IP address, without brackets.
+    pub address: String,
+    /// Whether the address is loopback, private, or link-local.
+    pub private: bool,
+    /// Action to take for this match.
+    pub action: RuleAction,
+}
+
+/// A matched URL with an unusual scheme or port.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlMatch {
+    /// The URL.
+    pub url: String,
+    /// What is unusual about it (e.g., "scheme 'ftp'").
+    pub detail: String,
+    /// Action to take for this match.
+    pub action: RuleAction,
+}
+
 /// Network checker for blocked domains.
 #[derive(Debug)]
 pub struct NetworkChecker {
     /// Configuration.
     config: NetworkConfig,
-    /// Set of blocked domains for O(1) lookup.
-    blocked_domains: HashSet<String>,
+    /// Set of blocked domains for O(1) lookup. Boxed to drop the spare
+    /// capacity of each entry, since imported blocklists can be large.
+    blocked_domains: HashSet<Box<str>>,
+    /// Suffixes from `*.suffix` entries (e.g., `ru`), which block every
+    /// domain under them.
+    blocked_suffixes: HashSet<Box<str>>,
+    /// Case-insensitive automaton over the blocked domains and `.suffix`es,
+    /// finding candidate hosts in text. `None` if it could not be built.
+    domain_search: Option<AhoCorasick>,
     /// URL extraction regex.
     url_pattern: Regex,
+    /// Raw IP host extraction regex.
+    ip_pattern: Regex,
+    /// Any-scheme URL extraction regex, including `data:` URLs.
+    scheme_pattern: Regex,
 }
 
 impl NetworkChecker {
     /// Create a new network checker from configuration.
     pub fn new(config: &NetworkConfig) -> Self {
-        let blocked_domains: HashSet<String> = config
+        let (suffixes, domains): (Vec<String>, Vec<String>) = config
             .block_domains
             .iter()
-            .map(|d| d.to_lowercase())
-            .collect();
+            .map(|d| d.trim_end_matches('.').to_lowercase())
+            .partition(|d| d.starts_with("*."));
+        let blocked_domains: HashSet<Box<str>> =
+            domains.into_iter().map(String::into_boxed_str).collect();
+        let blocked_suffixes: HashSet<Box<str>> = suffixes.iter().map(|d| d[2..].into()).collect();
+        let domain_search = AhoCorasick::builder()
+            .ascii_case_insensitive(true)
+            .build(
+                blocked_domains
+                    .iter()
+                    .map(ToString::to_string)
+                    .chain(blocked_suffixes.iter().map(|s| format!(".{s}"))),
+            )
+            .ok();
 
         // Pattern to extract URLs from text
-        // This is intentionally simple - matches http(s)://domain...
+        // This is intentionally simple - matches http(s)://host... where the
+        // host may be percent-encoded or non-ASCII (decoded before matching)
+        #[allow(clippy::expect_used)] // Fallback regex is a compile-time constant that cannot fail
+        let url_pattern = Regex::new(r#"(?i)https?://[^\s/?#"'<>]+(?:[/?#][^\s"'<>]*)?"#)
+            .unwrap_or_else(|_| Regex::new(r"^$").expect("fallback regex"));
+
+        // IPv4 or bracketed IPv6 hosts after a scheme (and optional userinfo);
+        // bare client targets are extracted by `clients`
+        #[allow(clippy::expect_used)] // Fallback regex is a compile-time constant that cannot fail
+        let ip_pattern = Regex::new(
+            r#"(?i)\bhttps?://(?:[^\s/@"'<>]*@)?(\[[0-9a-f:.]+\]|\d{1,3}(?:\.\d{1,3}){3})(?:[:/\s"'?#]|$)"#,
+        )
+        .unwrap_or_else(|_| Regex::new(r"^$").expect("fallback regex"));
+
+        // `scheme://authority...` or `data:type/subtype...`
         #[allow(clippy::expect_used)] // Fallback regex is a compile-time constant that cannot fail
-        let url_pattern =
-            Regex::new(r#"(?i)https?://([a-z0-9][-a-z0-9]*\.)+[a-z]{2,}(?:[:/][^\s"'<>]*)?"#)
-                .unwrap_or_else(|_| Regex::new(r"^$").expect("fallback regex"));
+        let scheme_pattern = Regex::new(
+            r#"(?i)\b(?:([a-z][a-z0-9+.-]*)://([^\s/?#"'<>]*)|(data):[a-z]+/[-a-z0-9.+]+[;,])[^\s"'<>]*"#,
+        )
+        .unwrap_or_else(|_| Regex::new(r"^$").expect("fallback regex"));
 
         Self {
-            config: config.clone(),
+            // The domains live in the set; don't keep a second copy
+            config: NetworkConfig {
+                block_domains: Vec::new(),
+                ..config.clone()
+            },
             blocked_domains,
+            blocked_suffixes,
+            domain_search,
             url_pattern,
+            ip_pattern,
+            scheme_pattern,
         }
     }
 
@@ -70,17 +150,226 @@ impl NetworkChecker {
     }
 
     /// Scan text for URLs pointing to blocked domains.
+    ///
+    /// Each blocklist entry found in the text marks a candidate host, which
+    /// is checked only if it is the host of an `http(s)://` URL. Hosts that
+    /// need normalizing first (percent escapes, non-ASCII, punycode) are
+    /// extracted with the URL regex instead.
     pub fn check_text(&self, text: &str) -> Vec<NetworkMatch> {
         if !self.config.enabled {
             return Vec::new();
         }
 
+        // Keyed by URL position, so matches come out in text order
+        let mut matches = BTreeMap::new();
+
+        let Some(search) = &self.domain_search else {
+            for url_match in self.url_pattern.find_iter(text) {
+                if let Some(m) = self.check_url(url_match.as_str()) {
+                    let _ = matches.insert(url_match.start(), m);
+                }
+            }
+            return matches.into_values().collect();
+        };
+
+        let bytes = text.as_bytes();
+        let mut last_host = None;
+        for found in search.find_iter(text) {
+            let (start, end) = host_span(bytes, found.start(), found.end());
+            if last_host.replace(start) == Some(start) {
+                continue;
+            }
+            if let Some((url_start, m)) = self.check_host_in_url(text, start, end) {
+                let _ = matches.insert(url_start, m);
+            }
+        }
+
+        if needs_normalizing(text) {
+            for url_match in self.url_pattern.find_iter(text) {
+                if let Some(m) = self.check_url(url_match.as_str()) {
+                    let _ = matches.entry(url_match.start()).or_insert(m);
+                }
+            }
+        }
+
+        matches.into_values().collect()
+    }
+
+    /// Check the plain host at `text[start..end]`, if it is the host of an
+    /// `http(s)://` URL. Returns the URL's position and the match.
+    fn check_host_in_url(
+        &self,
+        text: &str,
+        start: usize,
+        end: usize,
+    ) -> Option<(usize, NetworkMatch)> {
+        let bytes = text.as_bytes();
+        if bytes.get(end).is_some_and(|&b| !ends_host(b)) {
+            return None;
+        }
+
+        // Only userinfo may sit between the scheme and the host
+        let authority_start = bytes[..start]
+            .iter()
+            .rposition(|&b| b == b'/' || b == b'?' || b == b'#' || ends_url(b))
+            .map_or(0, |i| i + 1);
+        if authority_start < start && bytes[start - 1] != b'@' {
+            return None;
+        }
+        let before = &bytes[..authority_start];
+        let url_start = [b"https://".as_slice(), b"http://".as_slice()]
+            .iter()
+            .find(|scheme| {
+                before.len() >= scheme.len()
+                    && before[before.len() - scheme.len()..].eq_ignore_ascii_case(scheme)
+            })
+            .map(|scheme| authority_start - scheme.len())?;
+
+        let domain = text[start..end].trim_end_matches('.').to_ascii_lowercase();
+        if domain.is_empty() || !self.is_domain_blocked(&domain) {
+            return None;
+        }
+        let url_end = bytes[end..]
+            .iter()
+            .position(|&b| ends_url(b))
+            .map_or(text.len(), |i| end + i);
+        Some((
+            url_start,
+            NetworkMatch {
+                domain,
+                url: text[url_start..url_end].to_string(),
+            },
+        ))
+    }
+
+    /// Extract `http(s)://` URLs from text, paired with their domain and
+    /// percent-decoded path and query (see [`url_payload`]).
+    pub fn url_payloads(&self, text: &str) -> Vec<(String, String)> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+
+        self.url_pattern
+            .find_iter(text)
+            .filter_map(|m| url_payload(m.as_str()))
+            .collect()
+    }
+
+    /// Extract the normalized domains of `http(s)://` URLs in text.
+    pub fn domains(&self, text: &str) -> Vec<String> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+
+        self.url_pattern
+            .find_iter(text)
+            .filter_map(|m| extract_domain(m.as_str()))
+            .collect()
+    }
+
+    /// Check a host name (not a URL) against the blocked domains.
+    pub fn check_host(&self, host: &str) -> Option<NetworkMatch> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        let domain = normalize_host(host);
+        self.is_domain_blocked(&domain).then(|| NetworkMatch {
+            domain,
+            url: host.to_string(),
+        })
+    }
+
+    /// Check whether a host is a raw IP address, with or without brackets.
+    ///
+    /// Returns `None` for host names, and for addresses whose public or
+    /// private action is `Allow`.
+    pub fn check_ip_host(&self, host: &str) -> Option<IpMatch> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let ip = host.parse::<IpAddr>().ok()?;
+        let private = is_private_ip(&ip);
+        let action = if private {
+            self.config.private_ip_action
+        } else {
+            self.config.public_ip_action
+        };
+        (action != RuleAction::Allow).then(|| IpMatch {
+            address: ip.to_string(),
+            private,
+            action,
+        })
+    }
+
+    /// Scan text for URLs whose host is a raw IP address.
+    ///
+    /// Each address is reported once (see [`check_ip_host`](Self::check_ip_host)).
+    pub fn check_ips(&self, text: &str) -> Vec<IpMatch> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+
+        let mut matches: Vec<IpMatch> = Vec::new();
+
+        for caps in self.ip_pattern.captures_iter(text) {
+            if let Some(m) = self.check_ip_host(&caps[1]) {
+                if !matches.iter().any(|seen| seen.address == m.address) {
+                    matches.push(m);
+                }
+            }
+        }
+
+        matches
+    }
+
+    /// Scan text for URLs with an unusual scheme, or an `http(s)://` port
+    /// outside `allowed_ports` on a non-loopback host.
+    pub fn check_schemes_and_ports(&self, text: &str) -> Vec<UrlMatch> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+
         let mut matches = Vec::new();
 
-        for url_match in self.url_pattern.find_iter(text) {
-            let url = url_match.as_str();
-            if let Some(m) = self.check_url(url) {
-                matches.push(m);
+        for caps in self.scheme_pattern.captures_iter(text) {
+            let url = caps[0].to_string();
+            let scheme = caps
+                .get(1)
+                .or_else(|| caps.get(3))
+                .map_or(String::new(), |m| m.as_str().to_lowercase());
+
+            if self.config.scheme_action != RuleAction::Allow
+                && self
+                    .config
+                    .unusual_schemes
+                    .iter()
+                    .any(|s| s.eq_ignore_ascii_case(&scheme))
+            {
+                matches.push(UrlMatch {
+                    url,
+                    detail: format!("scheme '{scheme}'"),
+                    action: self.config.scheme_action,
+                });
+                continue;
+            }
+
+            if self.config.port_action == RuleAction::Allow
+                || !matches!(scheme.as_str(), "http" | "https")
+            {
+                continue;
+            }
+            let authority = caps.get(2).map_or("", |m| m.as_str());
+            if let Some((host, port)) = split_port(authority) {
+                if !is_loopback_host(host) && !self.config.allowed_ports.contains(&port) {
+                    matches.push(UrlMatch {
+                        url,
+                        detail: format!("port {port}"),
+                        action: self.config.port_action,
+                    });
+                }
             }
         }
 
@@ -92,7 +381,7 @@ impl NetworkChecker {
         let domain_lower = domain.to_lowercase();
 
         // Check exact match
-        if self.blocked_domains.contains(&domain_lower) {
+        if self.blocked_domains.contains(domain_lower.as_str()) {
             return true;
         }
 
@@ -100,22 +389,142 @@ impl NetworkChecker {
         let parts: Vec<&str> = domain_lower.split('.').collect();
         for i in 1..parts.len().saturating_sub(1) {
             let parent = parts[i..].join(".");
-            if self.blocked_domains.contains(&parent) {
+            if self.blocked_domains.contains(parent.as_str()) {
                 return true;
             }
         }
 
-        false
+        // Check suffix patterns, down to the TLD (e.g., "*.ru", "*.co.uk")
+        (1..parts.len()).any(|i| {
+            self.blocked_suffixes
+                .contains(parts[i..].join(".").as_str())
+        })
     }
 }
 
-/// Extract the domain from a URL.
+/// Parse a domain blocklist in hosts-file or plain-list format.
+///
+/// Accepts `0.0.0.0 evil.example` and `127.0.0.1 a.example b.example` lines,
+/// bare `evil.example` lines, and `*.suffix` patterns. Comments (`#`), blank
+/// lines, and `localhost` entries are skipped.
+pub fn parse_blocklist(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or(line))
+        .flat_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // Hosts-file lines start with the address the names resolve to
+            let names = match fields.first() {
+                Some(first) if first.parse::<IpAddr>().is_ok() => &fields[1..],
+                _ => &fields[..],
+            };
+            names
+                .iter()
+                .map(|name| name.trim_end_matches('.').to_lowercase())
+                .filter(|name| {
+                    !name.is_empty()
+                        && !matches!(
+                            name.as_str(),
+                            "localhost" | "localhost.localdomain" | "broadcasthost" | "local"
+                        )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Whether an IP address is loopback, private, link-local, or unspecified.
+fn is_private_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            v4.is_loopback() || v4.is_private() || v4.is_link_local() || v4.is_unspecified()
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_private_ip(&IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || (first & 0xfe00) == 0xfc00 // unique local, fc00::/7
+                || (first & 0xffc0) == 0xfe80 // link-local, fe80::/10
+        }
+    }
+}
+
+/// Split a URL into its normalized domain and its percent-decoded path,
+/// query, and fragment, for secret scanning.
+///
+/// Returns `None` if the URL has no host or nothing after it.
+pub fn url_payload(url: &str) -> Option<(String, String)> {
+    let domain = extract_domain(url)?;
+    let without_protocol = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let start = without_protocol.find(['/', '?', '#'])?;
+    let payload = percent_decode(&without_protocol[start..]);
+    Some((domain, payload))
+}
+
+/// Split an explicit port off a URL authority, returning the host and port.
+fn split_port(authority: &str) -> Option<(&str, u16)> {
+    let host_port = authority.rsplit('@').next()?;
+    let (host, port) = if let Some(rest) = host_port.strip_prefix('[') {
+        let (host, after) = rest.split_once(']')?;
+        (host, after.strip_prefix(':')?)
+    } else {
+        host_port.rsplit_once(':')?
+    };
+    Some((host, port.parse().ok()?))
+}
+
+/// Whether a host is `localhost` or a loopback address.
+fn is_loopback_host(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Whether a byte can appear in a plain (ASCII, unescaped) host name.
+fn is_host_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_')
+}
+
+/// Whether a byte ends a URL, as in the URL regex.
+fn ends_url(b: u8) -> bool {
+    b.is_ascii_whitespace() || matches!(b, b'"' | b'\'' | b'<' | b'>')
+}
+
+/// Whether a byte can follow the host of a URL.
+fn ends_host(b: u8) -> bool {
+    ends_url(b) || matches!(b, b':' | b'/' | b'?' | b'#')
+}
+
+/// Widen a match to the plain host name around it.
+fn host_span(bytes: &[u8], start: usize, end: usize) -> (usize, usize) {
+    let start = bytes[..start]
+        .iter()
+        .rposition(|&b| !is_host_byte(b))
+        .map_or(0, |i| i + 1);
+    let end = bytes[end..]
+        .iter()
+        .position(|&b| !is_host_byte(b))
+        .map_or(bytes.len(), |i| end + i);
+    (start, end)
+}
+
+/// Whether text may hold hosts that only match once normalized: percent
+/// escapes, non-ASCII characters, or punycode labels.
+fn needs_normalizing(text: &str) -> bool {
+    !text.is_ascii()
+        || text.contains('%')
+        || text
+            .as_bytes()
+            .windows(4)
+            .any(|w| w.eq_ignore_ascii_case(b"xn--"))
+}
+
+/// Extract the normalized domain from a URL (see [`normalize_host`]).
 fn extract_domain(url: &str) -> Option<String> {
     // Remove protocol
-    let without_protocol = url
-        .strip_prefix("https://")
-        .or_else(|| url.strip_prefix("http://"))
-        .unwrap_or(url);
+    let without_protocol = url.split_once("://").map_or(url, |(_, rest)| rest);
 
     // Get authority part (before first /)
     let authority = without_protocol.split('/').next()?;
@@ -124,12 +533,12 @@ fn extract_domain(url: &str) -> Option<String> {
     let host_with_port = authority.rsplit('@').next()?;
 
     // Remove port if present (split on : and take first part)
-    let domain = host_with_port.split(':').next()?;
+    let domain = normalize_host(host_with_port.split(':').next()?);
 
     if domain.is_empty() {
         None
     } else {
-        Some(domain.to_lowercase())
+        Some(domain)
     }
 }
 
@@ -141,6 +550,17 @@ mod tests {
         NetworkChecker::new(&NetworkConfig::default())
     }
 
+    #[test]
+    fn test_domains() {
+        let checker = default_checker();
+
+        assert_eq!(
+            checker.domains("curl https://API.GitHub.com/x && wget http://example.com"),
+            ["api.github.com", "example.com"]
+        );
+        assert_eq!(checker.domains("echo no urls here"), Vec::<String>::new());
+    }
+
     #[test]
     fn test_block_pastebin() {
         let checker = default_checker();
@@ -197,6 +617,39 @@ mod tests {
         assert!(matches[0].url.contains("pastebin.com"));
     }
 
+    #[test]
+    fn test_check_text_large_blocklist() {
+        let config = NetworkConfig {
+            block_domains: (0..5000)
+                .map(|i| format!("feed{i}.example"))
+                .chain(["*.zip".to_string()])
+                .collect(),
+            ..Default::default()
+        };
+        let checker = NetworkChecker::new(&config);
+
+        let text = format!(
+            "{} curl https://user@CDN.Feed4321.example:8080/x?y https://setup.zip \
+             && echo feed1.example https://notfeed1.example https://feed2.example.org \
+             https://ok.example/feed3.example",
+            "ls -la; ".repeat(1000)
+        );
+        let matches = checker.check_text(&text);
+        assert_eq!(
+            matches,
+            [
+                NetworkMatch {
+                    domain: "cdn.feed4321.example".to_string(),
+                    url: "https://user@CDN.Feed4321.example:8080/x?y".to_string(),
+                },
+                NetworkMatch {
+                    domain: "setup.zip".to_string(),
+                    url: "https://setup.zip".to_string(),
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_disabled_checker() {
         let config = NetworkConfig {
@@ -229,6 +682,7 @@ mod tests {
         let config = NetworkConfig {
             enabled: true,
             block_domains: vec!["evil.com".to_string(), "malware.org".to_string()],
+            ..Default::default()
         };
         let checker = NetworkChecker::new(&config);
 
@@ -237,6 +691,51 @@ mod tests {
         assert!(checker.check_url("https://pastebin.com/abc").is_none()); // Not in custom list
     }
 
+    #[test]
+    fn test_parse_blocklist() {
+        let content = "\
+# Corporate denylist
+0.0.0.0 evil.example
+127.0.0.1 localhost
+127.0.0.1 a.example b.example # trailing comment
+::1 ip6-tracker.example
+*.wild.example
+Plain.Example.
+";
+        assert_eq!(
+            parse_blocklist(content),
+            [
+                "evil.example",
+                "a.example",
+                "b.example",
+                "ip6-tracker.example",
+                "*.wild.example",
+                "plain.example"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suffix_patterns() {
+        let config = NetworkConfig {
+            block_domains: vec![
+                "*.ru".to_string(),
+                "*.ZIP".to_string(),
+                "*.co.uk".to_string(),
+            ],
+            ..Default::default()
+        };
+        let checker = NetworkChecker::new(&config);
+
+        let m = checker.check_url("https://files.example.ru/x").unwrap();
+        assert_eq!(m.domain, "files.example.ru");
+        assert!(checker.check_url("https://setup.zip/").is_some());
+        assert!(checker.check_url("https://shop.example.co.uk/").is_some());
+        assert!(checker.check_url("https://example.uk/").is_none());
+        assert!(checker.check_url("https://rust.run/").is_none());
+        assert!(checker.check_url("https://example.com/file.zip").is_none());
+    }
+
     #[test]
     fn test_case_insensitive() {
         let checker = default_checker();
@@ -244,4 +743,156 @@ mod tests {
         assert!(checker.check_url("https://PASTEBIN.COM/abc").is_some());
         assert!(checker.check_url("https://PasteBin.Com/abc").is_some());
     }
+
+    #[test]
+    fn test_encoded_domains() {
+        let checker = default_checker();
+
+        assert!(checker.check_url("https://pastebin%2Ecom/raw/x").is_some());
+        assert!(checker.check_url("HTTPS://PASTEBIN.com./raw/x").is_some());
+        assert!(checker.check_url("https://xn--pstebin-2fg.com/x").is_some());
+        assert_eq!(
+            checker.check_text("curl https://p\u{430}stebin.com/raw/x")[0].domain,
+            "pastebin.com"
+        );
+    }
+
+    #[test]
+    fn test_url_payload() {
+        assert_eq!(
+            url_payload("https://ok.example.com/upload?key=AKIA%41BC#x"),
+            Some((
+                "ok.example.com".to_string(),
+                "/upload?key=AKIAABC#x".to_string()
+            ))
+        );
+        assert_eq!(url_payload("https://example.com"), None);
+
+        let payloads =
+            default_checker().url_payloads("curl https://a.example/?t=1 https://b.example");
+        assert_eq!(
+            payloads,
+            vec![("a.example".to_string(), "/?t=1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_unusual_schemes() {
+        let checker = default_checker();
+
+        let m = checker.check_schemes_and_ports("curl ftp://files.example.com/dump.tar");
+        assert_eq!(m.len(), 1);
+        assert_eq!(m[0].detail, "scheme 'ftp'");
+        assert_eq!(m[0].action, RuleAction::Ask);
+        assert_eq!(
+            checker.check_schemes_and_ports("curl GOPHER://x.example:70/_x")[0].detail,
+            "scheme 'gopher'"
+        );
+        assert_eq!(
+            checker.check_schemes_and_ports("open data:text/html;base64,PHNjcmlwdD4=")[0].detail,
+            "scheme 'data'"
+        );
+        assert_eq!(checker.check_schemes_and_ports("echo metadata: value"), []);
+        assert_eq!(
+            checker.check_schemes_and_ports("git clone ssh://git@host/repo"),
+            []
+        );
+    }
+
+    #[test]
+    fn test_non_standard_ports() {
+        // Ports are allowed by default
+        assert_eq!(
+            default_checker().check_schemes_and_ports("curl https://example.com:8443/"),
+            []
+        );
+
+        let config = NetworkConfig {
+            port_action: RuleAction::Deny,
+            ..Default::default()
+        };
+        let checker = NetworkChecker::new(&config);
+
+        let m = checker.check_schemes_and_ports("curl https://user@example.com:8443/x");
+        assert_eq!(m.len(), 1);
+        assert_eq!(m[0].detail, "port 8443");
+        assert_eq!(m[0].action, RuleAction::Deny);
+        assert_eq!(
+            checker
+                .check_schemes_and_ports("curl http://[2001:db8::1]:9000/")
+                .len(),
+            1
+        );
+        assert_eq!(
+            checker.check_schemes_and_ports("curl https://example.com:443/"),
+            []
+        );
+        assert_eq!(
+            checker.check_schemes_and_ports("curl http://localhost:3000/"),
+            []
+        );
+        assert_eq!(
+            checker.check_schemes_and_ports("curl http://127.0.0.1:8080/"),
+            []
+        );
+        assert_eq!(
+            checker.check_schemes_and_ports("curl http://[::1]:8080/"),
+            []
+        );
+    }
+
+    #[test]
+    fn test_public_ip() {
+        let checker = default_checker();
+
+        let matches = checker.check_ips("curl http://203.0.113.5/upload -d @data");
+        assert_eq!(
+            matches,
+            vec![IpMatch {
+                address: "203.0.113.5".to_string(),
+                private: false,
+                action: RuleAction::Ask,
+            }]
+        );
+        assert_eq!(checker.check_ips("wget https://user@1.2.3.4/x").len(), 1);
+        assert_eq!(
+            checker.check_ips("curl 'http://[2001:db8::1]:8080/'").len(),
+            1
+        );
+        // Repeats are reported once
+        assert_eq!(
+            checker
+                .check_ips("curl http://1.2.3.4/a && curl http://1.2.3.4/b")
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_private_ip() {
+        let checker = default_checker();
+
+        assert_eq!(checker.check_ips("curl http://127.0.0.1:3000/health"), []);
+        assert_eq!(checker.check_ips("curl http://192.168.1.10/"), []);
+        assert_eq!(checker.check_ips("curl http://[::1]:8080/"), []);
+
+        let config = NetworkConfig {
+            private_ip_action: RuleAction::Deny,
+            ..Default::default()
+        };
+        let m =
+            NetworkChecker::new(&config).check_ips("curl http://169.254.169.254/latest/meta-data/");
+        assert_eq!(m.len(), 1);
+        assert!(m[0].private);
+        assert_eq!(m[0].action, RuleAction::Deny);
+    }
+
+    #[test]
+    fn test_ip_not_host() {
+        let checker = default_checker();
+
+        assert_eq!(checker.check_ips("echo version 1.2.3.4"), []);
+        assert_eq!(checker.check_ips("https://example.com/v/1.2.3.4"), []);
+        assert_eq!(checker.check_ips("curl http://999.1.1.1/"), []);
+    }
 }
@@ -0,0 +1,175 @@
+//! Unicode normalization of inspected text.
+//!
+//! Tool input is normalized before it is matched, so text that renders like
+//! a pattern matches it: `rm －rf /` (full-width hyphen-minus),
+//! `r\u{200b}m -rf /` (zero-width space), and `сurl` (Cyrillic `с`) are
+//! checked as `rm -rf /` and `curl`. Normalization is NFKC, which maps
+//! full-width and other compatibility forms to ASCII, after dropping
+//! invisible format characters, and followed by folding dashes and common
+//! Cyrillic and Greek look-alikes to ASCII.
+//!
+//! Only the copy that is inspected is normalized; the tool input that runs,
+//! or that redaction rewrites, keeps its original text.
+
+use std::borrow::Cow;
+
+use serde_json::Value;
+use unicode_normalization::UnicodeNormalization;
+
+/// Normalize text for matching. ASCII text is returned as-is.
+pub fn normalize(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    let normalized: String = text
+        .chars()
+        .filter(|c| !is_invisible(*c))
+        .nfkc()
+        .map(fold)
+        .collect();
+    if normalized == text {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(normalized)
+    }
+}
+
+/// Normalize every string in a JSON value, object keys included. `None`
+/// if nothing changed.
+pub fn normalize_value(value: &Value) -> Option<Value> {
+    match value {
+        Value::String(text) => match normalize(text) {
+            Cow::Owned(normalized) => Some(Value::String(normalized)),
+            Cow::Borrowed(_) => None,
+        },
+        Value::Array(items) => {
+            let normalized: Vec<Option<Value>> = items.iter().map(normalize_value).collect();
+            normalized.iter().any(Option::is_some).then(|| {
+                normalized
+                    .into_iter()
+                    .zip(items)
+                    .map(|(normalized, item)| normalized.unwrap_or_else(|| item.clone()))
+                    .collect()
+            })
+        }
+        Value::Object(fields) => {
+            let mut changed = false;
+            let normalized = fields
+                .iter()
+                .map(|(key, item)| {
+                    let key = normalize(key);
+                    let normalized = normalize_value(item);
+                    changed |= matches!(key, Cow::Owned(_)) || normalized.is_some();
+                    (key.into_owned(), normalized.unwrap_or_else(|| item.clone()))
+                })
+                .collect();
+            changed.then_some(Value::Object(normalized))
+        }
+        _ => None,
+    }
+}
+
+/// Characters that render as nothing: zero-width spaces and joiners, bidi
+/// controls, variation selectors, and tag characters.
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00ad}'
+            | '\u{034f}'
+            | '\u{061c}'
+            | '\u{115f}'
+            | '\u{1160}'
+            | '\u{17b4}'
+            | '\u{17b5}'
+            | '\u{180b}'..='\u{180f}'
+            | '\u{200b}'..='\u{200f}'
+            | '\u{202a}'..='\u{202e}'
+            | '\u{2060}'..='\u{206f}'
+            | '\u{3164}'
+            | '\u{fe00}'..='\u{fe0f}'
+            | '\u{feff}'
+            | '\u{ffa0}'
+            | '\u{e0000}'..='\u{e007f}'
+    )
+}
+
+/// Map a character to the ASCII character it renders as, if it is a dash or
+/// a common look-alike letter.
+fn fold(c: char) -> char {
+    match c {
+        '\u{2010}'..='\u{2015}' | '\u{2212}' | '\u{fe58}' | '\u{fe63}' => '-',
+        c => homoglyph(c),
+    }
+}
+
+/// Map a Cyrillic, Greek, or Latin look-alike to the ASCII letter it
+/// imitates.
+pub(crate) fn homoglyph(c: char) -> char {
+    match c {
+        'а' | 'α' => 'a',
+        'с' | 'ϲ' => 'c',
+        'ԁ' => 'd',
+        'е' => 'e',
+        'ɡ' => 'g',
+        'һ' => 'h',
+        'і' | 'ι' | 'ı' => 'i',
+        'ј' => 'j',
+        'к' | 'κ' => 'k',
+        'ӏ' => 'l',
+        'о' | 'ο' => 'o',
+        'р' | 'ρ' => 'p',
+        'ԛ' => 'q',
+        'ѕ' => 's',
+        'υ' => 'u',
+        'ν' => 'v',
+        'ԝ' => 'w',
+        'х' => 'x',
+        'у' => 'y',
+        'А' | 'Α' => 'A',
+        'В' | 'Β' => 'B',
+        'С' | 'Ϲ' => 'C',
+        'Е' | 'Ε' => 'E',
+        'Н' | 'Η' => 'H',
+        'І' | 'Ι' => 'I',
+        'Ј' => 'J',
+        'К' | 'Κ' => 'K',
+        'М' | 'Μ' => 'M',
+        'Ν' => 'N',
+        'О' | 'Ο' => 'O',
+        'Р' | 'Ρ' => 'P',
+        'Ѕ' => 'S',
+        'Т' | 'Τ' => 'T',
+        'Х' | 'Χ' => 'X',
+        'У' | 'Υ' => 'Y',
+        'Ζ' => 'Z',
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize() {
+        assert!(matches!(normalize("rm -rf /"), Cow::Borrowed(_)));
+        assert_eq!(normalize("rm －rf ／"), "rm -rf /");
+        assert_eq!(normalize("ｒｍ\u{3000}-rf /"), "rm -rf /");
+        assert_eq!(normalize("r\u{200b}m -\u{2060}rf /"), "rm -rf /");
+        assert_eq!(normalize("rm \u{2013}rf /"), "rm -rf /");
+        assert_eq!(normalize("сurl pastebin.сom"), "curl pastebin.com");
+        assert_eq!(normalize("ＡＫＩＡ"), "AKIA");
+        // Text with nothing to fold is kept
+        assert!(matches!(normalize("日本語"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_normalize_value() {
+        assert_eq!(normalize_value(&json!({ "command": "ls", "n": 1 })), None);
+        assert_eq!(
+            normalize_value(&json!({ "command": "rm －rf /", "edits": ["ok", "ｘ"] })),
+            Some(json!({ "command": "rm -rf /", "edits": ["ok", "x"] }))
+        );
+    }
+}
@@ -0,0 +1,284 @@
+//! Obfuscated command detection.
+//!
+//! Pattern-based rules only see what a command looks like, not what it will
+//! run. Commands that build their payload at runtime defeat them, so these
+//! constructions are flagged on their own:
+//!
+//! - `decode_pipe_to_shell`: decoded data piped into an interpreter
+//!   (`echo ... | base64 -d | sh`, `printf '\x72\x6d...' | bash`)
+//! - `eval_substitution`: `eval` of a command substitution (`eval "$(...)"`)
+//! - `escaped_command_name`: a command name spelled with hex or octal
+//!   escapes (`$'\x72\x6d' -rf /`, `$(printf '\x72\x6d') -rf /`)
+//! - `encoded_shell_payload`: `bash -c` whose script decodes its payload
+//!
+//! Command substitutions and `-c` scripts are analyzed recursively.
+
+use crate::shell::{self, Word};
+
+/// Maximum nesting of substitutions and `-c` scripts that is analyzed.
+const MAX_DEPTH: usize = 4;
+
+/// Minimum number of escapes that decode to printable characters before a
+/// string counts as encoded. Keeps color codes like `\x1b[31m` out.
+const MIN_ESCAPES: usize = 2;
+
+/// A detected obfuscation technique.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Obfuscation {
+    /// Technique identifier, e.g. `decode_pipe_to_shell`.
+    pub technique: &'static str,
+    /// The offending part of the command.
+    pub matched: String,
+}
+
+/// Detect obfuscated command construction.
+pub fn detect(command: &str) -> Option<Obfuscation> {
+    detect_at(command, 0)
+}
+
+fn detect_at(command: &str, depth: usize) -> Option<Obfuscation> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+
+    for pipeline in shell::pipelines(&shell::tokenize(command)) {
+        if let Some(found) = check_pipeline(&pipeline) {
+            return Some(found);
+        }
+
+        for words in &pipeline {
+            if let Some(found) = check_command(words, depth) {
+                return Some(found);
+            }
+
+            // Recurse into command substitutions
+            for word in words.iter().filter(|w| w.expanded) {
+                for inner in shell::substitutions(&word.text) {
+                    if let Some(found) = detect_at(inner, depth + 1) {
+                        return Some(found);
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Decoded data piped into an interpreter.
+fn check_pipeline(pipeline: &[Vec<Word>]) -> Option<Obfuscation> {
+    let decoder = pipeline.iter().position(|words| is_decoder(words))?;
+    pipeline[decoder + 1..]
+        .iter()
+        .any(|words| shell::is_interpreter(words))
+        .then(|| Obfuscation {
+            technique: "decode_pipe_to_shell",
+            matched: render_pipeline(pipeline),
+        })
+}
+
+/// Obfuscation within a single simple command.
+fn check_command(words: &[Word], depth: usize) -> Option<Obfuscation> {
+    let program = shell::program_index(words)?;
+    let name = &words[program];
+    let args = &words[program + 1..];
+
+    let technique = if count_escapes(&name.text) >= MIN_ESCAPES {
+        "escaped_command_name"
+    } else if name.text == "eval" && args.iter().any(|a| has_substitution(&a.text)) {
+        "eval_substitution"
+    } else if shell::is_interpreter(words) && shell_script(args).is_some_and(|s| encodes(s, depth))
+    {
+        "encoded_shell_payload"
+    } else {
+        return None;
+    };
+
+    Some(Obfuscation {
+        technique,
+        matched: render(words),
+    })
+}
+
+/// Whether a `-c` script decodes or hides its payload.
+fn encodes(script: &str, depth: usize) -> bool {
+    detect_at(script, depth + 1).is_some()
+        || shell::substitutions(script).into_iter().any(decodes)
+        || count_escapes(script) >= MIN_ESCAPES
+}
+
+/// Whether a command's output is decoded data.
+fn decodes(command: &str) -> bool {
+    shell::pipelines(&shell::tokenize(command))
+        .iter()
+        .flatten()
+        .any(|words| is_decoder(words))
+}
+
+/// The script argument of `sh -c SCRIPT`.
+fn shell_script(args: &[Word]) -> Option<&str> {
+    let flag = args.iter().position(|a| {
+        a.text
+            .strip_prefix('-')
+            .is_some_and(|f| !f.starts_with('-') && f.contains('c'))
+    })?;
+    args.get(flag + 1).map(|a| a.text.as_str())
+}
+
+/// Whether a simple command decodes its input.
+fn is_decoder(words: &[Word]) -> bool {
+    let Some(program) = shell::program_index(words) else {
+        return false;
+    };
+    let args = &words[program + 1..];
+    let has_flag = |long: &str, short: char| {
+        args.iter().any(|a| {
+            a.text == long
+                || a.text
+                    .strip_prefix('-')
+                    .is_some_and(|f| !f.starts_with('-') && f.contains(short))
+        })
+    };
+
+    match words[program].program_name() {
+        "base64" | "base32" => has_flag("--decode", 'd') || has_flag("--decode", 'D'),
+        "xxd" => has_flag("--revert", 'r'),
+        "openssl" => has_flag("-d", 'd'),
+        "printf" | "echo" => args.iter().any(|a| count_escapes(&a.text) >= MIN_ESCAPES),
+        _ => false,
+    }
+}
+
+/// Whether text contains a command substitution.
+fn has_substitution(text: &str) -> bool {
+    text.contains("$(") || text.contains('`')
+}
+
+/// Count `\xHH` and `\NNN` escapes that decode to printable ASCII.
+fn count_escapes(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+
+    while i + 1 < bytes.len() {
+        if bytes[i] != b'\\' {
+            i += 1;
+            continue;
+        }
+
+        let (digits, radix, start) = if bytes[i + 1] == b'x' {
+            (2, 16, i + 2)
+        } else {
+            (3, 8, i + 1)
+        };
+        let decoded = text
+            .get(start..start + digits)
+            .and_then(|d| u8::from_str_radix(d, radix).ok());
+        match decoded {
+            Some(byte) if byte.is_ascii_graphic() || byte == b' ' => {
+                count += 1;
+                i = start + digits;
+            }
+            _ => i += 2,
+        }
+    }
+
+    count
+}
+
+/// Render a simple command for display.
+fn render(words: &[Word]) -> String {
+    let parts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+    parts.join(" ")
+}
+
+/// Render a pipeline for display.
+fn render_pipeline(pipeline: &[Vec<Word>]) -> String {
+    let parts: Vec<String> = pipeline.iter().map(|words| render(words)).collect();
+    parts.join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn technique(command: &str) -> Option<&'static str> {
+        detect(command).map(|o| o.technique)
+    }
+
+    #[test]
+    fn test_decode_pipe_to_shell() {
+        assert_eq!(
+            technique("echo cm0gLXJmIC8K | base64 -d | sh"),
+            Some("decode_pipe_to_shell")
+        );
+        assert_eq!(
+            technique("echo cm0gLXJmIC8K | base64 --decode | sudo bash"),
+            Some("decode_pipe_to_shell")
+        );
+        assert_eq!(
+            technique(r"printf '\x72\x6d\x20\x2d\x72\x66' | bash"),
+            Some("decode_pipe_to_shell")
+        );
+        assert_eq!(
+            technique("cat payload.hex | xxd -r -p | python3"),
+            Some("decode_pipe_to_shell")
+        );
+    }
+
+    #[test]
+    fn test_eval_substitution() {
+        assert_eq!(
+            technique(r#"eval "$(echo cm0K | base64 -d)""#),
+            Some("eval_substitution")
+        );
+        assert_eq!(technique("eval `cat cmd.txt`"), Some("eval_substitution"));
+    }
+
+    #[test]
+    fn test_escaped_command_name() {
+        assert_eq!(
+            technique(r"$'\x72\x6d' -rf /"),
+            Some("escaped_command_name")
+        );
+        assert_eq!(
+            technique(r"$(printf '\162\155') -rf /"),
+            Some("escaped_command_name")
+        );
+    }
+
+    #[test]
+    fn test_encoded_shell_payload() {
+        assert_eq!(
+            technique(r#"bash -c "$(echo cm0K | base64 -d)""#),
+            Some("encoded_shell_payload")
+        );
+        assert_eq!(
+            technique("sh -c 'echo cm0K | base64 -d | sh'"),
+            Some("encoded_shell_payload")
+        );
+    }
+
+    #[test]
+    fn test_nested_substitution() {
+        assert_eq!(
+            technique("echo $(echo cm0K | base64 -d | sh)"),
+            Some("decode_pipe_to_shell")
+        );
+    }
+
+    #[test]
+    fn test_benign_commands() {
+        for command in [
+            "echo aGVsbG8= | base64 -d",
+            "base64 -d file.b64 > out.bin",
+            r"printf '\x1b[31mred\x1b[0m\n'",
+            r"echo -e '\x41'",
+            "bash -c 'cargo build && cargo test'",
+            "eval $EDITOR",
+            "curl -s https://example.com | jq .",
+        ] {
+            assert_eq!(technique(command), None, "{command}");
+        }
+    }
+}
@@ -0,0 +1,354 @@
+//! Package installation policy for Claude Code hook inputs.
+//!
+//! Inspects `npm install` (and `pnpm`/`yarn`/`bun add`), `pip install`
+//! (including `python -m pip` and `uv pip`), `cargo add`, and `uv add`
+//! commands. Package names are checked against deny and allow lists; any
+//! other package gets the `unknown` action, which asks by default.
+//!
+//! List entries are glob patterns on the package name (`@types/*`), optionally
+//! restricted to one ecosystem with a prefix (`npm:left-pad`, `pypi:requests`,
+//! `crates:serde`).
+
+use glob::Pattern;
+use rg_types::{PackagesConfig, RuleAction};
+
+use crate::shell::{self, Word};
+
+/// A package named in an install command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageInstall {
+    /// Package ecosystem: `npm`, `pypi`, or `crates`.
+    pub ecosystem: &'static str,
+    /// Package name without version or extras.
+    pub name: String,
+}
+
+/// A package install that violates policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageMatch {
+    /// Package ecosystem.
+    pub ecosystem: String,
+    /// Package name.
+    pub package: String,
+    /// Whether the package is on the deny list (otherwise it is unknown).
+    pub denied: bool,
+    /// Action to take for this match.
+    pub action: RuleAction,
+}
+
+/// A compiled allow/deny list entry.
+#[derive(Debug)]
+struct Entry {
+    /// Ecosystem restriction, if any.
+    ecosystem: Option<String>,
+    /// Package name glob.
+    pattern: Pattern,
+}
+
+impl Entry {
+    fn parse(entry: &str) -> Option<Self> {
+        let (ecosystem, name) = match entry.split_once(':') {
+            Some((eco, name)) if ["npm", "pypi", "crates"].contains(&eco) => {
+                (Some(eco.to_string()), name)
+            }
+            _ => (None, entry),
+        };
+        Pattern::new(name)
+            .ok()
+            .map(|pattern| Self { ecosystem, pattern })
+    }
+
+    fn matches(&self, package: &PackageInstall) -> bool {
+        self.ecosystem
+            .as_deref()
+            .map_or(true, |eco| eco == package.ecosystem)
+            && self.pattern.matches(&package.name)
+    }
+}
+
+/// Package installation guard with compiled lists.
+#[derive(Debug)]
+pub struct PackageGuard {
+    /// Configuration.
+    config: PackagesConfig,
+    /// Compiled allow list.
+    allow: Vec<Entry>,
+    /// Compiled deny list.
+    deny: Vec<Entry>,
+}
+
+impl PackageGuard {
+    /// Create a new package guard from configuration.
+    pub fn new(config: &PackagesConfig) -> Self {
+        Self {
+            config: config.clone(),
+            allow: config
+                .allow
+                .iter()
+                .filter_map(|e| Entry::parse(e))
+                .collect(),
+            deny: config.deny.iter().filter_map(|e| Entry::parse(e)).collect(),
+        }
+    }
+
+    /// Check a command for package installs.
+    ///
+    /// Denied packages take precedence over unknown ones. Returns `None` if
+    /// every installed package is allowed (or unknown packages are allowed).
+    pub fn check(&self, command: &str) -> Option<PackageMatch> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        let packages = detect(command);
+        let to_match = |p: &PackageInstall, denied: bool, action: RuleAction| PackageMatch {
+            ecosystem: p.ecosystem.to_string(),
+            package: p.name.clone(),
+            denied,
+            action,
+        };
+
+        if let Some(p) = packages
+            .iter()
+            .find(|p| self.deny.iter().any(|e| e.matches(p)))
+        {
+            return Some(to_match(p, true, RuleAction::Deny));
+        }
+
+        if self.config.unknown == RuleAction::Allow {
+            return None;
+        }
+        packages
+            .iter()
+            .find(|p| !self.allow.iter().any(|e| e.matches(p)))
+            .map(|p| to_match(p, false, self.config.unknown))
+    }
+}
+
+/// Find every package named in install commands.
+pub fn detect(command: &str) -> Vec<PackageInstall> {
+    shell::simple_commands(&shell::tokenize(command))
+        .iter()
+        .flat_map(|words| installs(words))
+        .collect()
+}
+
+/// Packages installed by a simple command.
+fn installs(words: &[Word]) -> Vec<PackageInstall> {
+    let Some(program) = shell::program_index(words) else {
+        return Vec::new();
+    };
+    let args: Vec<&str> = words[program + 1..]
+        .iter()
+        .map(|w| w.text.as_str())
+        .collect();
+
+    match (words[program].program_name(), args.as_slice()) {
+        ("npm", [sub, rest @ ..]) if ["install", "i", "add"].contains(sub) => npm(rest),
+        ("pnpm" | "yarn" | "bun", [sub, rest @ ..]) if ["add", "install", "i"].contains(sub) => {
+            npm(rest)
+        }
+        ("pip" | "pip3", ["install", rest @ ..])
+        | ("python" | "python3", ["-m", "pip", "install", rest @ ..])
+        | ("uv", ["pip", "install", rest @ ..] | ["add", rest @ ..]) => pypi(rest),
+        ("cargo", ["add", rest @ ..]) => crates(rest),
+        _ => Vec::new(),
+    }
+}
+
+/// Positional arguments, skipping flags and the values of `arg_flags`.
+fn positionals<'a>(args: &[&'a str], arg_flags: &[&str]) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut skip_next = false;
+    for arg in args {
+        if std::mem::take(&mut skip_next) {
+            continue;
+        }
+        if arg.starts_with('-') {
+            skip_next = arg_flags.contains(arg);
+            continue;
+        }
+        found.push(*arg);
+    }
+    found
+}
+
+/// Whether a spec refers to a local path rather than a registry package.
+fn is_local(spec: &str) -> bool {
+    spec.starts_with('.') || spec.starts_with('/') || spec.starts_with('~')
+}
+
+/// npm packages: `lodash@4`, `@types/node@^20`.
+fn npm(args: &[&str]) -> Vec<PackageInstall> {
+    const ARG_FLAGS: &[&str] = &["--registry", "--prefix", "-w", "--workspace", "--tag"];
+
+    positionals(args, ARG_FLAGS)
+        .into_iter()
+        .filter(|spec| !is_local(spec))
+        .map(|spec| {
+            // A leading @ is the scope, not a version
+            let name = match spec.strip_prefix('@') {
+                Some(scoped) => format!("@{}", scoped.split('@').next().unwrap_or(scoped)),
+                None => spec.split('@').next().unwrap_or(spec).to_string(),
+            };
+            PackageInstall {
+                ecosystem: "npm",
+                name,
+            }
+        })
+        .collect()
+}
+
+/// Python packages: `requests[socks]>=2`, normalized per PEP 503.
+fn pypi(args: &[&str]) -> Vec<PackageInstall> {
+    const ARG_FLAGS: &[&str] = &[
+        "-r",
+        "--requirement",
+        "--requirements",
+        "-c",
+        "--constraint",
+        "-e",
+        "--editable",
+        "-i",
+        "--index-url",
+        "--extra-index-url",
+        "--index",
+        "-t",
+        "--target",
+        "--group",
+        "--optional",
+        "--extra",
+        "--python",
+        "-p",
+    ];
+
+    positionals(args, ARG_FLAGS)
+        .into_iter()
+        .filter(|spec| !is_local(spec) && !spec.contains("://"))
+        .map(|spec| {
+            let end = spec
+                .find(|c: char| !(c.is_ascii_alphanumeric() || "-_.".contains(c)))
+                .unwrap_or(spec.len());
+            PackageInstall {
+                ecosystem: "pypi",
+                name: spec[..end].to_lowercase().replace(['_', '.'], "-"),
+            }
+        })
+        .filter(|p| !p.name.is_empty())
+        .collect()
+}
+
+/// Rust crates: `serde@1`.
+fn crates(args: &[&str]) -> Vec<PackageInstall> {
+    const ARG_FLAGS: &[&str] = &[
+        "-F",
+        "--features",
+        "--rename",
+        "--path",
+        "--git",
+        "--branch",
+        "--tag",
+        "--rev",
+        "--registry",
+        "-p",
+        "--package",
+        "--manifest-path",
+        "--target",
+    ];
+
+    positionals(args, ARG_FLAGS)
+        .into_iter()
+        .map(|spec| PackageInstall {
+            ecosystem: "crates",
+            name: spec.split('@').next().unwrap_or(spec).to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(command: &str) -> Vec<(&'static str, String)> {
+        detect(command)
+            .into_iter()
+            .map(|p| (p.ecosystem, p.name))
+            .collect()
+    }
+
+    fn package(ecosystem: &'static str, name: &str) -> (&'static str, String) {
+        (ecosystem, name.to_string())
+    }
+
+    #[test]
+    fn test_detect_npm() {
+        assert_eq!(
+            names("npm install -D lodash@4 @types/node@^20 ./local"),
+            [package("npm", "lodash"), package("npm", "@types/node")]
+        );
+        assert_eq!(names("pnpm add zod"), [package("npm", "zod")]);
+        assert_eq!(names("npm install").len(), 0);
+        assert_eq!(names("npm run build").len(), 0);
+    }
+
+    #[test]
+    fn test_detect_python() {
+        assert_eq!(
+            names("pip install -r requirements.txt Requests[socks]>=2 typing_extensions"),
+            [
+                package("pypi", "requests"),
+                package("pypi", "typing-extensions")
+            ]
+        );
+        assert_eq!(
+            names("python3 -m pip install --upgrade pip"),
+            [package("pypi", "pip")]
+        );
+        assert_eq!(names("uv add --dev pytest"), [package("pypi", "pytest")]);
+        assert_eq!(names("uv pip install -e ."), Vec::new());
+    }
+
+    #[test]
+    fn test_detect_cargo() {
+        assert_eq!(
+            names("cargo add serde@1 --features derive tokio"),
+            [package("crates", "serde"), package("crates", "tokio")]
+        );
+    }
+
+    #[test]
+    fn test_guard_lists() {
+        let config = PackagesConfig {
+            allow: vec!["@types/*".to_string(), "pypi:requests".to_string()],
+            deny: vec!["npm:event-stream".to_string()],
+            ..Default::default()
+        };
+        let guard = PackageGuard::new(&config);
+
+        assert!(guard.check("npm i @types/node requests").is_some());
+        assert!(guard.check("npm i @types/node").is_none());
+        assert!(guard.check("pip install requests").is_none());
+
+        let m = guard.check("npm i left-pad event-stream").unwrap();
+        assert_eq!(m.package, "event-stream");
+        assert!(m.denied);
+        assert_eq!(m.action, RuleAction::Deny);
+
+        let m = guard.check("cargo add serde").unwrap();
+        assert!(!m.denied);
+        assert_eq!(m.action, RuleAction::Ask);
+    }
+
+    #[test]
+    fn test_unknown_allowed() {
+        let config = PackagesConfig {
+            unknown: RuleAction::Allow,
+            deny: vec!["event-stream".to_string()],
+            ..Default::default()
+        };
+        let guard = PackageGuard::new(&config);
+
+        assert!(guard.check("npm i left-pad").is_none());
+        assert!(guard.check("npm i event-stream").is_some());
+    }
+}
@@ -0,0 +1,208 @@
+//! Policy packs: curated rule bundles enabled by name.
+//!
+//! A pack is a small TOML file that contributes command patterns, protected
+//! paths, and blocked domains:
+//!
+//! ```toml
+//! description = "kubectl and helm"
+//!
+//! [commands]
+//! block_patterns = ['\bkubectl\s+delete\s+ns\b']
+//! ask_patterns = ['\bkubectl\s+apply\b']
+//!
+//! [protected_paths]
+//! blocked = ["**/.kube/config"]
+//!
+//! [network]
+//! block_domains = ["example.com"]
+//! ```
+//!
+//! Packs only add rules; they never remove or loosen anything the config
+//! sets. Built-in packs are embedded in the binary ([`BUILTIN`]).
+
+use rg_types::{CommandPattern, PolicyConfig};
+use serde::Deserialize;
+
+use crate::safe_regex;
+use crate::PolicyError;
+
+/// Packs shipped with Railgun, by name.
+pub const BUILTIN: &[(&str, &str)] = &[
+    ("aws", include_str!("../packs/aws.toml")),
+    ("frontend", include_str!("../packs/frontend.toml")),
+    ("kubernetes", include_str!("../packs/kubernetes.toml")),
+];
+
+/// A parsed policy pack.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PolicyPack {
+    /// What the pack covers.
+    pub description: String,
+    /// Command patterns.
+    pub commands: PackCommands,
+    /// Protected path globs.
+    pub protected_paths: PackPaths,
+    /// Blocked domains.
+    pub network: PackNetwork,
+}
+
+/// Command patterns contributed by a pack.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PackCommands {
+    /// Appended to `policy.commands.block_patterns`.
+    pub block_patterns: Vec<CommandPattern>,
+    /// Appended to `policy.commands.ask_patterns`.
+    pub ask_patterns: Vec<CommandPattern>,
+}
+
+/// Protected path globs contributed by a pack.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PackPaths {
+    /// Blocked for reads and writes; appended to `blocked_all`.
+    pub blocked: Vec<String>,
+    /// Appended to `blocked_read`.
+    pub blocked_read: Vec<String>,
+    /// Appended to `blocked_write`.
+    pub blocked_write: Vec<String>,
+}
+
+/// Domains contributed by a pack.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PackNetwork {
+    /// Appended to `policy.network.block_domains`.
+    pub block_domains: Vec<String>,
+}
+
+impl PolicyPack {
+    /// Add the pack's rules to a policy.
+    pub fn apply(&self, policy: &mut PolicyConfig) {
+        let commands = &mut policy.commands;
+        commands
+            .block_patterns
+            .extend(self.commands.block_patterns.iter().cloned());
+        commands
+            .ask_patterns
+            .extend(self.commands.ask_patterns.iter().cloned());
+
+        let paths = &mut policy.protected_paths;
+        paths
+            .blocked_all
+            .extend(self.protected_paths.blocked.iter().cloned());
+        paths
+            .blocked_read
+            .extend(self.protected_paths.blocked_read.iter().cloned());
+        paths
+            .blocked_write
+            .extend(self.protected_paths.blocked_write.iter().cloned());
+
+        policy
+            .network
+            .block_domains
+            .extend(self.network.block_domains.iter().cloned());
+    }
+}
+
+/// The source of a built-in pack.
+pub fn builtin(name: &str) -> Option<&'static str> {
+    BUILTIN
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, content)| *content)
+}
+
+/// Parse a pack. Patterns that do not compile are rejected so a broken pack
+/// is noticed at load time.
+pub fn parse_pack(content: &str) -> Result<PolicyPack, PolicyError> {
+    let pack: PolicyPack = toml::from_str(content)
+        .map_err(|e| PolicyError::ConfigError(format!("Invalid policy pack: {e}")))?;
+
+    for pattern in pack
+        .commands
+        .block_patterns
+        .iter()
+        .chain(&pack.commands.ask_patterns)
+    {
+        let _ = safe_regex::compile(pattern.pattern()).map_err(|e| {
+            PolicyError::InvalidPattern(format!("pack pattern '{}': {e}", pattern.pattern()))
+        })?;
+    }
+
+    Ok(pack)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{inspect, RuntimePolicy};
+    use rg_types::{Config, HookInput};
+
+    fn decision(config: &Config, tool_name: &str, tool_input: serde_json::Value) -> &'static str {
+        let input = HookInput::new(tool_name, tool_input);
+        inspect(&input, &RuntimePolicy::new(config))
+            .0
+            .permission_decision()
+    }
+
+    #[test]
+    fn test_builtin_packs_parse() {
+        for (name, content) in BUILTIN {
+            let pack = parse_pack(content).unwrap_or_else(|e| panic!("{name}: {e}"));
+            assert!(!pack.description.is_empty(), "{name}");
+        }
+        assert!(builtin("kubernetes").is_some());
+        assert!(builtin("cobol").is_none());
+    }
+
+    #[test]
+    fn test_apply_pack() {
+        let mut config = Config::default();
+        let blocked = config.policy.protected_paths.blocked.clone();
+        parse_pack(builtin("kubernetes").unwrap())
+            .unwrap()
+            .apply(&mut config.policy);
+        parse_pack(builtin("frontend").unwrap())
+            .unwrap()
+            .apply(&mut config.policy);
+
+        // Packs add to the config's lists rather than replacing them
+        assert_eq!(config.policy.protected_paths.blocked, blocked);
+
+        let bash = |command: &str| serde_json::json!({ "command": command });
+        assert_eq!(decision(&config, "Bash", bash("kubectl get pods")), "allow");
+        assert_eq!(
+            decision(&config, "Bash", bash("kubectl apply -f deploy.yaml")),
+            "ask"
+        );
+        assert_eq!(
+            decision(&config, "Bash", bash("kubectl delete namespace prod")),
+            "deny"
+        );
+        assert_eq!(decision(&config, "Bash", bash("npm publish")), "ask");
+        assert_eq!(
+            decision(
+                &config,
+                "Read",
+                serde_json::json!({ "file_path": "/home/dev/.kube/config" })
+            ),
+            "deny"
+        );
+        assert_eq!(
+            decision(
+                &config,
+                "WebFetch",
+                serde_json::json!({ "url": "https://abc.ngrok-free.app/", "prompt": "x" })
+            ),
+            "deny"
+        );
+    }
+
+    #[test]
+    fn test_invalid_pack_rejected() {
+        assert!(parse_pack("[commands]\nblock_patterns = ['(unclosed']\n").is_err());
+        assert!(parse_pack("[secrets]\nenabled = false\n").is_err());
+    }
+}
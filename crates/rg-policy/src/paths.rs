@@ -1,10 +1,17 @@
 //! Protected path matching for Claude Code hook inputs.
 //!
 //! Uses glob patterns to block access to sensitive paths like
-//! .env files, private keys, and SSH configurations.
+//! .env files, private keys, and SSH configurations. Patterns in `blocked`
+//! and `blocked_all` apply to every access; `blocked_read` and
+//! `blocked_write` apply only to reads or writes. Paths matching an `allowed`
+//! pattern are never blocked.
+//!
+//! Windows paths are normalized to forward slashes with an uppercase drive
+//! (`C:\Users\x\.ssh\id_rsa` becomes `C:/Users/x/.ssh/id_rsa`), so the same
+//! patterns protect them; `case_insensitive` makes matching ignore case.
 
-use glob::Pattern;
-use rg_types::ProtectedPathsConfig;
+use glob::{MatchOptions, Pattern};
+use rg_types::{ProtectedPathsConfig, RuleAction};
 use std::path::Path;
 
 /// A matched protected path.
@@ -16,6 +23,35 @@ pub struct PathMatch {
     pub pattern: String,
 }
 
+/// The kind of file access a tool performs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// Reading a file (Read).
+    Read,
+    /// Creating or modifying a file (`Write`, `Edit`, `MultiEdit`, `NotebookEdit`).
+    Write,
+}
+
+/// Git internals that are never written: hooks run on the next commit or
+/// checkout, `.git/config` can set `core.hooksPath` or `core.fsmonitor`, and
+/// `.git/info/exclude` can hide files from review.
+pub const GIT_INTERNAL_PATHS: &[&str] =
+    &["**/.git/hooks/**", "**/.git/config", "**/.git/info/exclude"];
+
+/// A compiled blocked pattern.
+#[derive(Debug)]
+struct BlockedPattern {
+    /// The pattern as configured.
+    source: String,
+    /// Compiled glob.
+    pattern: Pattern,
+    /// The access it applies to, or `None` for every access.
+    access: Option<Access>,
+    /// Whether the pattern's last component is also matched against the
+    /// file name alone (configured patterns only).
+    match_filename: bool,
+}
+
 /// Alias for `PathProtector` (for backward compatibility).
 pub type PathMatcher = PathProtector;
 
@@ -25,21 +61,74 @@ pub struct PathProtector {
     /// Configuration.
     config: ProtectedPathsConfig,
     /// Compiled glob patterns.
-    patterns: Vec<(String, Pattern)>,
+    patterns: Vec<BlockedPattern>,
+    /// Compiled allow patterns, which override the blocked ones.
+    allowed: Vec<Pattern>,
+    /// Glob match options (case sensitivity).
+    options: MatchOptions,
 }
 
 impl PathProtector {
     /// Create a new path matcher from configuration.
     pub fn new(config: &ProtectedPathsConfig) -> Self {
-        let patterns: Vec<(String, Pattern)> = config
-            .blocked
+        let lists = [
+            (&config.blocked, None),
+            (&config.blocked_all, None),
+            (&config.blocked_read, Some(Access::Read)),
+            (&config.blocked_write, Some(Access::Write)),
+        ];
+        let git_internals: &[&str] = if config.protect_git_internals {
+            GIT_INTERNAL_PATHS
+        } else {
+            &[]
+        };
+        let git_internals = git_internals.iter().filter_map(|p| {
+            Pattern::new(p).ok().map(|pattern| BlockedPattern {
+                source: (*p).to_string(),
+                pattern,
+                access: Some(Access::Write),
+                match_filename: false,
+            })
+        });
+        let patterns = lists
+            .into_iter()
+            .flat_map(|(list, access)| {
+                list.iter().filter_map(move |p| {
+                    Pattern::new(&expand_home(p))
+                        .ok()
+                        .map(|pattern| BlockedPattern {
+                            source: p.clone(),
+                            pattern,
+                            access,
+                            match_filename: true,
+                        })
+                })
+            })
+            .chain(git_internals)
+            .collect();
+
+        // A relative allow pattern matches below any directory, since tools
+        // usually pass absolute paths
+        let allowed = config
+            .allowed
             .iter()
-            .filter_map(|p| Pattern::new(p).ok().map(|pat| (p.clone(), pat)))
+            .flat_map(|p| {
+                let relative = !(p.starts_with('/') || p.starts_with('~') || p.starts_with("**"));
+                let anywhere = relative.then(|| format!("**/{p}"));
+                [Some(p.clone()), anywhere]
+            })
+            .flatten()
+            .filter_map(|p| Pattern::new(&expand_home(&p)).ok())
             .collect();
 
         Self {
             config: config.clone(),
             patterns,
+            allowed,
+            options: MatchOptions {
+                case_sensitive: !config.case_insensitive,
+                ..MatchOptions::new()
+            },
         }
     }
 
@@ -52,25 +141,141 @@ impl PathProtector {
 
     /// Check if a path should be blocked.
     ///
-    /// Returns `Some(PathMatch)` if the path matches any blocked pattern.
+    /// Returns `Some(PathMatch)` if the path matches any blocked pattern,
+    /// whatever access the pattern applies to.
     pub fn check(&self, path: &str) -> Option<PathMatch> {
+        self.check_path(path, None)
+    }
+
+    /// Check if an access to a path should be blocked.
+    ///
+    /// Returns `Some(PathMatch)` if the path matches a pattern that applies
+    /// to `access`. With `resolve_symlinks`, an existing path is also checked
+    /// after resolving symlinks, so `link -> ~/.ssh/id_rsa` is blocked as
+    /// well.
+    pub fn check_access(&self, path: &str, access: Access) -> Option<PathMatch> {
+        self.check_path(path, Some(access))
+    }
+
+    /// Check an access to a path given relative to `cwd`.
+    ///
+    /// Like [`check_access`](Self::check_access), but a relative path that
+    /// matches nothing as given is also checked at its location under
+    /// `cwd`, so `../../.ssh/id_rsa` from a subdirectory is caught. The match
+    /// reports the path as given.
+    pub fn check_access_in(
+        &self,
+        path: &str,
+        access: Access,
+        cwd: Option<&str>,
+    ) -> Option<PathMatch> {
+        self.check_access(path, access).or_else(|| {
+            let resolved = resolve_in(path, cwd?)?;
+            let m = self.check_access(&resolved, access)?;
+            Some(PathMatch {
+                path: path.to_string(),
+                pattern: m.pattern,
+            })
+        })
+    }
+
+    /// Check if a `Glob` search targets protected paths.
+    ///
+    /// Each wildcard in the pattern (joined to `base`, if any) is replaced
+    /// with a sample name, so `~/.ssh/*` and `**/*.pem` are checked as
+    /// `~/.ssh/x` and `x.pem`. Returns the match with the configured
+    /// `glob_action`, or `None` if that action is `Allow`.
+    pub fn check_glob(&self, pattern: &str, base: Option<&str>) -> Option<(PathMatch, RuleAction)> {
+        if self.config.glob_action == RuleAction::Allow {
+            return None;
+        }
+
+        let target = match base {
+            Some(base) if !is_absolute(pattern) => {
+                format!("{}/{pattern}", base.trim_end_matches(['/', '\\']))
+            }
+            _ => pattern.to_string(),
+        };
+        let m = self.check_access(&sample_path(&target), Access::Read)?;
+        Some((
+            PathMatch {
+                path: target,
+                pattern: m.pattern,
+            },
+            self.config.glob_action,
+        ))
+    }
+
+    /// Check a `Glob` search run from `cwd`.
+    ///
+    /// Like [`check_glob`](Self::check_glob), but a relative pattern that
+    /// matches nothing as given is also checked under its base resolved
+    /// against `cwd` (or under `cwd` itself without a base).
+    pub fn check_glob_in(
+        &self,
+        pattern: &str,
+        base: Option<&str>,
+        cwd: Option<&str>,
+    ) -> Option<(PathMatch, RuleAction)> {
+        self.check_glob(pattern, base).or_else(|| {
+            if is_absolute(pattern) {
+                return None;
+            }
+            let base = resolve_in(base.unwrap_or("."), cwd?)?;
+            self.check_glob(pattern, Some(&base))
+        })
+    }
+
+    /// Check a path against the patterns for `access` (all if `None`).
+    fn check_path(&self, path: &str, access: Option<Access>) -> Option<PathMatch> {
         if !self.config.enabled {
             return None;
         }
 
-        // Normalize the path for matching
-        let normalized = normalize_path(path);
+        // Normalize the path for matching; traversal like `foo/../.env` or
+        // spelling home as `$HOME` must not evade the patterns
+        let pattern = self
+            .find_pattern(&normalize_path(&expand_home(path)), access)
+            .or_else(|| {
+                if !self.config.resolve_symlinks {
+                    return None;
+                }
+                let resolved = std::fs::canonicalize(path).ok()?;
+                self.find_pattern(&normalize_path(resolved.to_str()?), access)
+            })?;
+
+        Some(PathMatch {
+            path: path.to_string(),
+            pattern: pattern.to_string(),
+        })
+    }
 
-        for (pattern_str, pattern) in &self.patterns {
-            if pattern.matches(&normalized) || pattern.matches(path) {
-                return Some(PathMatch {
-                    path: path.to_string(),
-                    pattern: pattern_str.clone(),
-                });
+    /// Find the first blocked pattern for `access` matching a normalized
+    /// path.
+    fn find_pattern(&self, normalized: &str, access: Option<Access>) -> Option<&str> {
+        if self
+            .allowed
+            .iter()
+            .any(|p| p.matches_with(normalized, self.options))
+        {
+            return None;
+        }
+
+        let applicable = self
+            .patterns
+            .iter()
+            .filter(|p| p.access.is_none() || access.is_none() || p.access == access);
+        for blocked in applicable {
+            let pattern_str = &blocked.source;
+            if blocked.pattern.matches_with(normalized, self.options) {
+                return Some(pattern_str);
+            }
+            if !blocked.match_filename {
+                continue;
             }
 
             // Also check the filename alone for patterns like "**/.env"
-            if let Some(filename) = Path::new(path).file_name().and_then(|f| f.to_str()) {
+            if let Some(filename) = Path::new(normalized).file_name().and_then(|f| f.to_str()) {
                 // For patterns like "**/.env", extract the filename part
                 let pattern_filename = pattern_str.rsplit('/').next().unwrap_or(pattern_str);
 
@@ -81,11 +286,8 @@ impl PathProtector {
 
                 // Check if filename matches the pattern's filename part
                 if let Ok(filename_pattern) = Pattern::new(pattern_filename) {
-                    if filename_pattern.matches(filename) {
-                        return Some(PathMatch {
-                            path: path.to_string(),
-                            pattern: pattern_str.clone(),
-                        });
+                    if filename_pattern.matches_with(filename, self.options) {
+                        return Some(pattern_str);
                     }
                 }
             }
@@ -95,28 +297,137 @@ impl PathProtector {
     }
 }
 
-/// Normalize a path for matching.
-fn normalize_path(path: &str) -> String {
-    // Remove leading ./ if present
-    let path = path.strip_prefix("./").unwrap_or(path);
-
-    // Normalize multiple slashes
-    let mut result = String::with_capacity(path.len());
-    let mut prev_slash = false;
-
-    for c in path.chars() {
-        if c == '/' || c == '\\' {
-            if !prev_slash {
-                result.push('/');
+/// Whether a path or glob is absolute (or home-relative).
+fn is_absolute(path: &str) -> bool {
+    !split_root(path).0.is_empty() || path.starts_with('~') || path.starts_with("$HOME")
+}
+
+/// Resolve a relative path against the absolute directory `dir`, and
+/// normalize it. `None` if the path is already absolute or `dir` is not.
+pub(crate) fn resolve_in(path: &str, dir: &str) -> Option<String> {
+    let path = expand_home(path);
+    if is_absolute(&path) || !is_absolute(dir) || dir.starts_with('~') {
+        return None;
+    }
+    Some(normalize_path(&format!(
+        "{}/{path}",
+        dir.trim_end_matches(['/', '\\'])
+    )))
+}
+
+/// A concrete path matched by a glob: `**` components are dropped and every
+/// other wildcard component becomes a sample name (`*.pem` becomes `x.pem`).
+fn sample_path(glob: &str) -> String {
+    let glob = glob.replace('\\', "/");
+    let components: Vec<String> = glob
+        .split('/')
+        .filter(|c| *c != "**")
+        .map(|c| {
+            let mut sample = String::with_capacity(c.len());
+            let mut in_class = false;
+            for ch in c.chars() {
+                match ch {
+                    '[' if !in_class => in_class = true,
+                    ']' if in_class => {
+                        in_class = false;
+                        sample.push('x');
+                    }
+                    _ if in_class => {}
+                    '*' | '?' => sample.push('x'),
+                    _ => sample.push(ch),
+                }
             }
-            prev_slash = true;
-        } else {
-            result.push(c);
-            prev_slash = false;
+            sample
+        })
+        .collect();
+    components.join("/")
+}
+
+/// Expand a leading `~`, `$HOME`, or `${HOME}` to the home directory.
+///
+/// Paths are returned unchanged if neither `HOME` nor `USERPROFILE` is set.
+pub(crate) fn expand_home(path: &str) -> String {
+    match std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")) {
+        Ok(home) if !home.is_empty() => expand_home_with(path, &home),
+        _ => path.to_string(),
+    }
+}
+
+/// Expand a leading `~`, `$HOME`, or `${HOME}` to `home`. `~user` is left
+/// as is.
+fn expand_home_with(path: &str, home: &str) -> String {
+    for prefix in ["${HOME}", "$HOME", "~"] {
+        if let Some(rest) = path.strip_prefix(prefix) {
+            if rest.is_empty() || rest.starts_with(['/', '\\']) {
+                return format!("{}{rest}", home.trim_end_matches('/'));
+            }
+        }
+    }
+    path.to_string()
+}
+
+/// Normalize a path for matching.
+///
+/// Separators are unified to `/`, repeated slashes collapsed, and `.` and
+/// `..` components resolved lexically, so `foo/../.env` and
+/// `/app/./.ssh/id_rsa` match the same globs as `.env` and
+/// `/app/.ssh/id_rsa`. `..` never climbs above the root of an absolute path;
+/// leading `..` components of a relative path are kept.
+pub(crate) fn normalize_path(path: &str) -> String {
+    let (root, rest) = split_root(path);
+    let rest = rest.replace('\\', "/");
+    let absolute = root.ends_with('/');
+
+    let mut components: Vec<&str> = Vec::new();
+    for component in rest.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => match components.last() {
+                Some(&last) if last != ".." => {
+                    let _ = components.pop();
+                }
+                _ if absolute => {}
+                _ => components.push(".."),
+            },
+            _ => components.push(component),
         }
     }
 
-    result
+    format!("{root}{}", components.join("/"))
+}
+
+/// Split a path into its root and the rest.
+///
+/// The root is `/` for Unix absolute paths, an uppercased drive (`C:/`, or
+/// `C:` for a drive-relative path), or `//server/share/` for UNC paths
+/// (`\\server\share`). Relative paths have an empty root.
+fn split_root(path: &str) -> (String, &str) {
+    if let Some(unc) = path.strip_prefix("\\\\") {
+        let mut parts = unc.splitn(3, ['\\', '/']);
+        let server = parts.next().unwrap_or_default();
+        let share = parts.next().unwrap_or_default();
+        let rest = parts.next().unwrap_or_default();
+        let root = if share.is_empty() {
+            format!("//{server}/")
+        } else {
+            format!("//{server}/{share}/")
+        };
+        return (root, rest);
+    }
+
+    let bytes = path.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        let drive = char::from(bytes[0].to_ascii_uppercase());
+        return match path[2..].strip_prefix(['/', '\\']) {
+            Some(rest) => (format!("{drive}:/"), rest),
+            None => (format!("{drive}:"), &path[2..]),
+        };
+    }
+
+    match path.strip_prefix(['/', '\\']) {
+        Some(rest) => ("/".to_string(), rest),
+        None => (String::new(), path),
+    }
 }
 
 #[cfg(test)]
@@ -192,6 +503,7 @@ mod tests {
         let config = ProtectedPathsConfig {
             enabled: true,
             blocked: vec!["**/secrets/**".to_string(), "**/*.secret".to_string()],
+            ..Default::default()
         };
         let protector = PathProtector::new(&config);
 
@@ -205,5 +517,291 @@ mod tests {
         assert_eq!(normalize_path("./foo/bar"), "foo/bar");
         assert_eq!(normalize_path("foo//bar"), "foo/bar");
         assert_eq!(normalize_path("foo\\bar"), "foo/bar");
+        assert_eq!(normalize_path("foo/../.env"), ".env");
+        assert_eq!(normalize_path("/app/./.ssh/id_rsa"), "/app/.ssh/id_rsa");
+        assert_eq!(normalize_path("/../etc/passwd"), "/etc/passwd");
+        assert_eq!(normalize_path("../a/./b/.."), "../a");
+        assert_eq!(normalize_path("~/x/../.ssh/id_rsa"), "~/.ssh/id_rsa");
+    }
+
+    #[test]
+    fn test_expand_home() {
+        assert_eq!(
+            expand_home_with("~/.aws/credentials", "/home/u"),
+            "/home/u/.aws/credentials"
+        );
+        assert_eq!(
+            expand_home_with("$HOME/.ssh/id_rsa", "/home/u/"),
+            "/home/u/.ssh/id_rsa"
+        );
+        assert_eq!(expand_home_with("${HOME}", "/home/u"), "/home/u");
+        assert_eq!(expand_home_with("~bob/.ssh", "/home/u"), "~bob/.ssh");
+        assert_eq!(expand_home_with("$HOMEDIR/x", "/home/u"), "$HOMEDIR/x");
+        assert_eq!(expand_home_with("src/~/x", "/home/u"), "src/~/x");
+    }
+
+    #[test]
+    fn test_home_spellings() {
+        let config = ProtectedPathsConfig {
+            blocked: vec!["~/.config/gh/**".to_string()],
+            ..Default::default()
+        };
+        let protector = PathProtector::new(&config);
+
+        // Matching only holds when HOME is known; the spellings must agree
+        if std::env::var("HOME").is_ok_and(|home| !home.is_empty()) {
+            let home = std::env::var("HOME").unwrap();
+            assert!(protector.is_blocked("~/.config/gh/hosts.yml"));
+            assert!(protector.is_blocked("$HOME/.config/gh/hosts.yml"));
+            assert!(protector.is_blocked("${HOME}/.config/gh/hosts.yml"));
+            assert!(protector.is_blocked(&format!("{home}/.config/gh/hosts.yml")));
+        }
+        assert!(!protector.is_blocked("$HOME/notes.txt"));
+
+        let protector = default_protector();
+        assert!(protector.is_blocked("$HOME/.aws/credentials"));
+        assert!(protector.is_blocked("${HOME}/.ssh/config"));
+    }
+
+    #[test]
+    fn test_traversal_evasion() {
+        let config = ProtectedPathsConfig {
+            enabled: true,
+            blocked: vec!["/app/secrets/*".to_string(), "**/.ssh/**".to_string()],
+            ..Default::default()
+        };
+        let protector = PathProtector::new(&config);
+
+        assert!(protector.is_blocked("/app/secrets/key"));
+        assert!(protector.is_blocked("/app/public/../secrets/key"));
+        assert!(protector.is_blocked("/app/./secrets/key"));
+        assert!(protector.is_blocked("/app//secrets/./key"));
+        assert!(protector.is_blocked("src/../../home/user/.ssh/config"));
+        assert!(!protector.is_blocked("/app/secrets/../public/index.html"));
+
+        let protector = default_protector();
+        assert!(protector.is_blocked("foo/../.env"));
+        assert!(protector.is_blocked("/app/./.ssh/id_rsa"));
+        assert!(protector.is_blocked("/app/.aws/x/../credentials"));
+    }
+
+    #[test]
+    fn test_relative_to_cwd() {
+        let config = ProtectedPathsConfig {
+            enabled: true,
+            blocked: vec!["/home/user/secrets/**".to_string()],
+            allowed: vec!["fixtures/**".to_string()],
+            ..Default::default()
+        };
+        let protector = PathProtector::new(&config);
+        let cwd = Some("/home/user/project/sub");
+
+        let m = protector
+            .check_access_in("../../secrets/api_key", Access::Read, cwd)
+            .unwrap();
+        assert_eq!(m.path, "../../secrets/api_key");
+        assert_eq!(m.pattern, "/home/user/secrets/**");
+        assert!(protector
+            .check_access("../../secrets/api_key", Access::Read)
+            .is_none());
+        assert!(protector
+            .check_access_in("../secrets/api_key", Access::Read, cwd)
+            .is_none());
+        assert!(protector
+            .check_access_in("../../secrets/api_key", Access::Read, Some("relative"))
+            .is_none());
+
+        assert!(protector
+            .check_glob_in("../../secrets/*", None, cwd)
+            .is_some());
+        assert!(protector
+            .check_glob_in("*", Some("../../secrets"), cwd)
+            .is_some());
+
+        assert_eq!(
+            resolve_in("../.ssh/id_rsa", "/home/user/project/"),
+            Some("/home/user/.ssh/id_rsa".to_string())
+        );
+        assert_eq!(resolve_in("/etc/hosts", "/home/user"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let ssh = dir.path().join(".ssh");
+        std::fs::create_dir(&ssh).unwrap();
+        std::fs::write(ssh.join("id_rsa"), "key").unwrap();
+        let link = dir.path().join("notes.txt");
+        std::os::unix::fs::symlink(ssh.join("id_rsa"), &link).unwrap();
+        let link = link.to_str().unwrap();
+
+        assert!(!default_protector().is_blocked(link));
+
+        let protector = PathProtector::new(&ProtectedPathsConfig {
+            resolve_symlinks: true,
+            ..Default::default()
+        });
+        let m = protector.check(link).unwrap();
+        assert_eq!(m.path, link);
+        assert_eq!(m.pattern, "**/id_rsa");
+        assert!(!protector.is_blocked(dir.path().join("missing.txt").to_str().unwrap()));
+    }
+
+    #[test]
+    fn test_read_write_lists() {
+        let config = ProtectedPathsConfig {
+            blocked: Vec::new(),
+            blocked_all: vec!["**/*.pem".to_string()],
+            blocked_read: vec!["**/dumps/**".to_string()],
+            blocked_write: vec!["**/.env".to_string()],
+            ..Default::default()
+        };
+        let protector = PathProtector::new(&config);
+
+        assert!(protector.check_access(".env", Access::Read).is_none());
+        assert!(protector.check_access(".env", Access::Write).is_some());
+        assert!(protector
+            .check_access("/data/dumps/users.sql", Access::Read)
+            .is_some());
+        assert!(protector
+            .check_access("/data/dumps/users.sql", Access::Write)
+            .is_none());
+        assert!(protector.check_access("server.pem", Access::Read).is_some());
+        assert!(protector
+            .check_access("server.pem", Access::Write)
+            .is_some());
+
+        // Without an access, every list applies
+        assert!(protector.is_blocked(".env"));
+        assert!(protector.is_blocked("/data/dumps/users.sql"));
+    }
+
+    #[test]
+    fn test_allowed_overrides_blocked() {
+        let config = ProtectedPathsConfig {
+            allowed: vec![
+                "tests/fixtures/*.pem".to_string(),
+                "/app/.env.example".to_string(),
+            ],
+            blocked_write: vec!["**/fixtures/**".to_string()],
+            ..Default::default()
+        };
+        let protector = PathProtector::new(&config);
+
+        assert!(!protector.is_blocked("tests/fixtures/cert.pem"));
+        assert!(!protector.is_blocked("/repo/tests/fixtures/cert.pem"));
+        assert!(protector
+            .check_access("tests/fixtures/cert.pem", Access::Write)
+            .is_none());
+        assert!(!protector.is_blocked("/app/.env.example"));
+        assert!(protector.is_blocked("/other/.env.example"));
+        assert!(protector.is_blocked("server.pem"));
+        assert!(protector.is_blocked("tests/fixtures/../../server.pem"));
+    }
+
+    #[test]
+    fn test_windows_paths() {
+        assert_eq!(
+            normalize_path("c:\\Users\\x\\.ssh\\id_rsa"),
+            "C:/Users/x/.ssh/id_rsa"
+        );
+        assert_eq!(
+            normalize_path("C:\\..\\Windows\\System32"),
+            "C:/Windows/System32"
+        );
+        assert_eq!(normalize_path("D:notes\\..\\x.txt"), "D:x.txt");
+        assert_eq!(
+            normalize_path("\\\\fileserver\\home\\..\\x\\.aws\\credentials"),
+            "//fileserver/home/x/.aws/credentials"
+        );
+        assert_eq!(normalize_path("//etc/passwd"), "/etc/passwd");
+
+        let protector = default_protector();
+        assert!(protector.is_blocked("C:\\Users\\x\\.ssh\\id_rsa"));
+        assert!(protector.is_blocked("\\\\server\\share\\.aws\\credentials"));
+        assert!(protector.is_blocked("C:\\proj\\src\\..\\.env"));
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        assert!(!default_protector().is_blocked("C:\\Users\\x\\.SSH\\known_hosts"));
+
+        let protector = PathProtector::new(&ProtectedPathsConfig {
+            case_insensitive: true,
+            ..Default::default()
+        });
+        assert!(protector.is_blocked("C:\\Users\\x\\.SSH\\known_hosts"));
+        assert!(protector.is_blocked("/app/.ENV"));
+        assert!(protector.is_blocked("Server.PEM"));
+    }
+
+    #[test]
+    fn test_git_internals() {
+        let protector = PathProtector::new(&ProtectedPathsConfig {
+            blocked: Vec::new(),
+            ..Default::default()
+        });
+
+        for path in [
+            ".git/hooks/pre-commit",
+            "/repo/.git/hooks/post-checkout",
+            "/repo/.git/config",
+            "/repo/.git/info/exclude",
+        ] {
+            assert!(
+                protector.check_access(path, Access::Write).is_some(),
+                "{path}"
+            );
+            assert!(
+                protector.check_access(path, Access::Read).is_none(),
+                "{path}"
+            );
+        }
+        assert!(protector
+            .check_access("/repo/exclude", Access::Write)
+            .is_none());
+        assert!(protector
+            .check_access("/repo/src/hooks/use_auth.ts", Access::Write)
+            .is_none());
+
+        let protector = PathProtector::new(&ProtectedPathsConfig {
+            blocked: Vec::new(),
+            protect_git_internals: false,
+            ..Default::default()
+        });
+        assert!(protector
+            .check_access(".git/hooks/pre-commit", Access::Write)
+            .is_none());
+    }
+
+    #[test]
+    fn test_sample_path() {
+        assert_eq!(sample_path("~/.ssh/*"), "~/.ssh/x");
+        assert_eq!(sample_path("**/*.pem"), "x.pem");
+        assert_eq!(sample_path("/home/**/id_rs[a]"), "/home/id_rsx");
+        assert_eq!(sample_path("src/**/*.rs"), "src/x.rs");
+    }
+
+    #[test]
+    fn test_check_glob() {
+        let protector = default_protector();
+
+        let (m, action) = protector.check_glob("~/.ssh/*", None).unwrap();
+        assert_eq!(m.path, "~/.ssh/*");
+        assert_eq!(m.pattern, "**/.ssh/**");
+        assert_eq!(action, RuleAction::Ask);
+        assert!(protector.check_glob("**/*.pem", Some("/repo")).is_some());
+        assert!(protector
+            .check_glob("*", Some("/home/u/.aws/../.ssh"))
+            .is_some());
+        assert!(protector.check_glob("src/**/*.rs", Some("/repo")).is_none());
+        assert!(protector.check_glob("**/*", None).is_none());
+
+        let protector = PathProtector::new(&ProtectedPathsConfig {
+            glob_action: RuleAction::Allow,
+            ..Default::default()
+        });
+        assert!(protector.check_glob("~/.ssh/*", None).is_none());
     }
 }
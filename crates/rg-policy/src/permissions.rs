@@ -0,0 +1,146 @@
+//! Recursive permission change detection.
+//!
+//! Finds `chmod`, `chown`, and `chgrp` run recursively (`-R`,
+//! `--recursive`) and extracts their targets, so policy can stop ownership or
+//! mode changes across system directories and the home directory.
+
+use crate::shell::{self, Word};
+
+/// Programs that change permissions or ownership.
+const PROGRAMS: &[&str] = &["chmod", "chown", "chgrp"];
+
+/// Targets that are always protected: `/`, `/etc`, `/usr`, and the home
+/// directory.
+pub const SYSTEM_PATHS: &[&str] = &["/", "/etc", "/usr", "~", "$HOME", "${HOME}"];
+
+/// A recursive permission change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionChange {
+    /// The program (e.g. `chmod`).
+    pub program: String,
+    /// Paths the change applies to, as written.
+    pub targets: Vec<String>,
+    /// The command.
+    pub matched: String,
+}
+
+/// Find every recursive permission change in a command.
+pub fn detect(command: &str) -> Vec<PermissionChange> {
+    shell::simple_commands(&shell::tokenize(command))
+        .iter()
+        .filter_map(|words| change(words))
+        .collect()
+}
+
+/// Normalize a target for comparison: trailing `/` and `*` are dropped, so
+/// `/etc/`, `/etc/*`, and `/etc` compare equal (`/` stays `/`).
+pub fn normalize_target(target: &str) -> &str {
+    let trimmed = target.trim_end_matches(['*', '/']);
+    if trimmed.is_empty() && target.starts_with('/') {
+        "/"
+    } else {
+        trimmed
+    }
+}
+
+/// The recursive permission change in a simple command, if any.
+fn change(words: &[Word]) -> Option<PermissionChange> {
+    let program = shell::program_index(words)?;
+    let name = words[program].program_name();
+    if !PROGRAMS.contains(&name) {
+        return None;
+    }
+
+    let mut recursive = false;
+    let mut operands = Vec::new();
+    let mut options_done = false;
+    for word in &words[program + 1..] {
+        let text = word.text.as_str();
+        if !options_done && text == "--" {
+            options_done = true;
+        } else if !options_done && text.starts_with("--") {
+            recursive |= text == "--recursive";
+        } else if !options_done && is_option(name, text) {
+            recursive |= text.contains('R');
+        } else {
+            operands.push(text.to_string());
+        }
+    }
+
+    // The first operand is the mode or owner
+    if !recursive || operands.len() < 2 {
+        return None;
+    }
+    let parts: Vec<&str> = words[program..].iter().map(|w| w.text.as_str()).collect();
+    Some(PermissionChange {
+        program: name.to_string(),
+        targets: operands.split_off(1),
+        matched: parts.join(" "),
+    })
+}
+
+/// Whether a word is an option rather than an operand.
+///
+/// Symbolic chmod modes such as `-x` or `-rwx` look like options; they are
+/// only treated as options when they consist of chmod's option letters.
+fn is_option(program: &str, text: &str) -> bool {
+    let Some(flags) = text.strip_prefix('-') else {
+        return false;
+    };
+    if flags.is_empty() {
+        return false;
+    }
+    program != "chmod" || flags.chars().all(|c| "RcfvHLP".contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(command: &str) -> Vec<(String, Vec<String>)> {
+        detect(command)
+            .into_iter()
+            .map(|c| (c.program, c.targets))
+            .collect()
+    }
+
+    fn change(program: &str, targets: &[&str]) -> (String, Vec<String>) {
+        (
+            program.to_string(),
+            targets.iter().map(ToString::to_string).collect(),
+        )
+    }
+
+    #[test]
+    fn test_detect_recursive_changes() {
+        assert_eq!(targets("chmod -R 777 /etc"), [change("chmod", &["/etc"])]);
+        assert_eq!(
+            targets("sudo chown -R nobody:nogroup / /usr"),
+            [change("chown", &["/", "/usr"])]
+        );
+        assert_eq!(
+            targets("chgrp --recursive staff ~"),
+            [change("chgrp", &["~"])]
+        );
+        assert_eq!(
+            targets("chmod -Rv -x $HOME/bin"),
+            [change("chmod", &["$HOME/bin"])]
+        );
+    }
+
+    #[test]
+    fn test_not_recursive() {
+        assert_eq!(targets("chmod 644 /etc/hosts").len(), 0);
+        assert_eq!(targets("chmod -x script.sh").len(), 0);
+        assert_eq!(targets("chmod -R").len(), 0);
+        assert_eq!(targets("echo chmod -R 777 /").len(), 0);
+    }
+
+    #[test]
+    fn test_normalize_target() {
+        assert_eq!(normalize_target("/etc/"), "/etc");
+        assert_eq!(normalize_target("/etc/*"), "/etc");
+        assert_eq!(normalize_target("/*"), "/");
+        assert_eq!(normalize_target("~/"), "~");
+    }
+}
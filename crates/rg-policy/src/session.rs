@@ -0,0 +1,409 @@
+//! Per-session state shared across hook invocations.
+//!
+//! Every hook call is a separate process, so anything that must last for a
+//! Claude Code session lives in a small JSON file per session
+//! (`sessions/<session_id>.json` in the project's state directory by
+//! default). The file is deleted on `SessionEnd`; files of sessions that
+//! never ended cleanly are pruned once they are older than `max_age_secs`.
+//!
+//! [`SessionAllows`] uses it for "allow for this session": once the user
+//! approves an `ask`, the rules behind it are exempt until the session ends.
+//! Rules are identified by [`BlockReason::rule_id`], so approving
+//! `git clean -fd` also covers `git clean -fd build/`.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rg_types::{BlockReason, HookInput, Verdict};
+use serde::{Deserialize, Serialize};
+
+use crate::approvals::{approval_key, unix_now};
+use crate::baseline::fingerprint;
+use crate::lock::{write_atomic, StateLock};
+use crate::middleware::Middleware;
+use crate::PolicyError;
+
+/// Pending asks kept per session; older ones are dropped first.
+const MAX_PENDING: usize = 64;
+
+/// State of one session.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionState {
+    /// Rules approved for the rest of the session.
+    #[serde(default)]
+    pub exemptions: Vec<Exemption>,
+    /// Asked calls that have not run yet.
+    #[serde(default)]
+    pub pending: Vec<PendingAsk>,
+    /// Accumulated risk score (see [`RiskScorer`](crate::risk::RiskScorer)).
+    #[serde(default)]
+    pub risk_score: u32,
+    /// Domains contacted so far.
+    #[serde(default)]
+    pub domains: Vec<String>,
+    /// Sensitive files read so far (see [`TaintTracker`](crate::taint::TaintTracker)).
+    #[serde(default)]
+    pub tainted: Vec<String>,
+}
+
+/// A rule approved for the rest of a session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Exemption {
+    /// Rule ID (see [`ask_rules`]).
+    pub rule: String,
+    /// When the call ran after being asked (Unix seconds).
+    pub approved_at: u64,
+}
+
+/// A call that got an `ask` verdict and has not run yet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingAsk {
+    /// Call key (see [`approval_key`]).
+    pub key: String,
+    /// Rules behind the `ask` verdict.
+    pub rules: Vec<String>,
+    /// When the user was asked (Unix seconds).
+    pub asked_at: u64,
+}
+
+/// Directory of per-session state files.
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    /// State directory.
+    dir: PathBuf,
+    /// Age after which state of unfinished sessions is pruned.
+    max_age_secs: u64,
+}
+
+impl SessionStore {
+    /// Create a store in `dir`.
+    pub fn new(dir: impl Into<PathBuf>, max_age_secs: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            max_age_secs,
+        }
+    }
+
+    /// State file for a session.
+    ///
+    /// IDs that are not plain `[A-Za-z0-9_-]` names are hashed, so a session
+    /// ID can never point outside the directory.
+    pub fn path(&self, session_id: &str) -> PathBuf {
+        let plain = !session_id.is_empty()
+            && session_id.len() <= 128
+            && session_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        let name = if plain {
+            session_id.to_string()
+        } else {
+            fingerprint(session_id)
+        };
+        self.dir.join(format!("{name}.json"))
+    }
+
+    /// Load a session's state. A missing file yields empty state.
+    pub fn load(&self, session_id: &str) -> Result<SessionState, PolicyError> {
+        let path = self.path(session_id);
+        if !path.exists() {
+            return Ok(SessionState::default());
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            PolicyError::ConfigError(format!("Failed to read {}: {e}", path.display()))
+        })?;
+        serde_json::from_str(&content).map_err(|e| {
+            PolicyError::ConfigError(format!("Invalid session state {}: {e}", path.display()))
+        })
+    }
+
+    /// Write a session's state, creating the directory if needed.
+    pub fn save(&self, session_id: &str, state: &SessionState) -> Result<(), PolicyError> {
+        let path = self.path(session_id);
+        std::fs::create_dir_all(&self.dir).map_err(|e| {
+            PolicyError::ConfigError(format!("Failed to create {}: {e}", self.dir.display()))
+        })?;
+        let content = serde_json::to_string(state).map_err(|e| {
+            PolicyError::ConfigError(format!("Failed to serialize session state: {e}"))
+        })?;
+        write_atomic(&path, &content)
+    }
+
+    /// Lock a session's state for a load-modify-save, so concurrent hooks
+    /// of the session don't lose each other's updates.
+    pub fn lock(&self, session_id: &str) -> Result<StateLock, PolicyError> {
+        StateLock::acquire(&self.path(session_id))
+    }
+
+    /// Remove a session's state when the session ends.
+    pub fn end(&self, session_id: &str) -> Result<(), PolicyError> {
+        let path = self.path(session_id);
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(PolicyError::ConfigError(
+                format!("Failed to remove {}: {e}", path.display()),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Remove state files not modified within `max_age_secs`. Returns how
+    /// many were removed.
+    pub fn prune(&self) -> usize {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return 0;
+        };
+        let cutoff = SystemTime::now()
+            .checked_sub(Duration::from_secs(self.max_age_secs))
+            .unwrap_or(UNIX_EPOCH);
+
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter(|path| is_older_than(path, cutoff))
+            .filter(|path| std::fs::remove_file(path).is_ok())
+            .count()
+    }
+}
+
+/// Whether a file was last modified before `cutoff`.
+fn is_older_than(path: &Path, cutoff: SystemTime) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .is_ok_and(|modified| modified < cutoff)
+}
+
+/// The rules behind an `ask`: the rule IDs of its violations, or its reason
+/// when it has none (e.g. an `ask` from the tool permission lists).
+pub fn ask_rules(reason: &str, violations: &[BlockReason]) -> Vec<String> {
+    if violations.is_empty() {
+        return vec![reason.to_string()];
+    }
+    let mut rules: Vec<String> = violations.iter().map(BlockReason::rule_id).collect();
+    rules.sort();
+    rules.dedup();
+    rules
+}
+
+/// "Allow for this session": exempts a rule once the user approves it.
+///
+/// As [`Middleware`], turns an `ask` whose reason was approved earlier in the
+/// session into an allow, and records other `ask`s as pending.
+/// [`record_run`] exempts a pending call's rule when its `PostToolUse` event
+/// arrives. Calls without a `session_id` are left alone.
+///
+/// [`record_run`]: SessionAllows::record_run
+#[derive(Debug, Clone)]
+pub struct SessionAllows {
+    /// Session state files.
+    store: SessionStore,
+}
+
+impl SessionAllows {
+    /// Create session allows backed by `store`.
+    pub fn new(store: SessionStore) -> Self {
+        Self { store }
+    }
+
+    /// Whether every rule was approved earlier in the session.
+    pub fn is_exempt(&self, session_id: &str, rules: &[String]) -> Result<bool, PolicyError> {
+        let state = self.store.load(session_id)?;
+        Ok(rules
+            .iter()
+            .all(|rule| state.exemptions.iter().any(|e| &e.rule == rule)))
+    }
+
+    /// Record that the user is being asked about a call.
+    pub fn record_ask(
+        &self,
+        session_id: &str,
+        input: &HookInput,
+        rules: Vec<String>,
+        now: u64,
+    ) -> Result<(), PolicyError> {
+        let _lock = self.store.lock(session_id)?;
+        let mut state = self.store.load(session_id)?;
+        let key = approval_key(input);
+        state.pending.retain(|p| p.key != key);
+        state.pending.push(PendingAsk {
+            key,
+            rules,
+            asked_at: now,
+        });
+        let excess = state.pending.len().saturating_sub(MAX_PENDING);
+        let _ = state.pending.drain(..excess);
+        self.store.save(session_id, &state)
+    }
+
+    /// Record that a call ran. Returns `true` if it was pending, i.e. the
+    /// user approved an `ask` for it and its rules are now exempt.
+    pub fn record_run(&self, input: &HookInput, now: u64) -> Result<bool, PolicyError> {
+        let Some(session_id) = input.session_id.as_deref() else {
+            return Ok(false);
+        };
+        let _lock = self.store.lock(session_id)?;
+        let mut state = self.store.load(session_id)?;
+        let key = approval_key(input);
+        let Some(index) = state.pending.iter().position(|p| p.key == key) else {
+            return Ok(false);
+        };
+
+        let pending = state.pending.remove(index);
+        for rule in pending.rules {
+            if !state.exemptions.iter().any(|e| e.rule == rule) {
+                state.exemptions.push(Exemption {
+                    rule,
+                    approved_at: now,
+                });
+            }
+        }
+        self.store.save(session_id, &state)?;
+        Ok(true)
+    }
+}
+
+impl Middleware for SessionAllows {
+    fn process(&self, input: &HookInput, verdict: Verdict) -> Verdict {
+        let (Verdict::Ask { reason, violations }, Some(session_id)) =
+            (&verdict, input.session_id.as_deref())
+        else {
+            return verdict;
+        };
+
+        let rules = ask_rules(reason, violations);
+        match self.is_exempt(session_id, &rules) {
+            Ok(true) => {
+                tracing::info!(tool = %input.tool_name, reason, "allowed for this session");
+                return Verdict::Allow;
+            }
+            Ok(false) => {}
+            Err(e) => tracing::warn!(error = %e, "failed to read session state"),
+        }
+        if let Err(e) = self.record_ask(session_id, input, rules, unix_now()) {
+            tracing::warn!(error = %e, "failed to record ask");
+        }
+        verdict
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bash(session_id: &str, command: &str) -> HookInput {
+        HookInput {
+            session_id: Some(session_id.to_string()),
+            ..HookInput::new("Bash", serde_json::json!({ "command": command }))
+        }
+    }
+
+    #[test]
+    fn test_session_paths() {
+        let store = SessionStore::new("/state", 60);
+        assert_eq!(store.path("abc-123"), Path::new("/state/abc-123.json"));
+        let hashed = store.path("../../etc/passwd");
+        assert_eq!(hashed.parent(), Some(Path::new("/state")));
+        assert!(!hashed.to_string_lossy().contains(".."));
+    }
+
+    fn git_ask(rule: &str, matched: &str) -> Verdict {
+        Verdict::ask_from_block_reason(&BlockReason::GitDestructive {
+            rule: rule.to_string(),
+            branch: None,
+            matched: matched.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_approved_rule_is_exempt_for_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let allows = SessionAllows::new(SessionStore::new(dir.path(), 60));
+
+        let first = bash("s1", "git clean -fd");
+        let ask = git_ask("clean", "git clean -fd");
+        assert_eq!(allows.process(&first, ask.clone()), ask);
+        assert!(allows.record_run(&first, 100).unwrap());
+
+        // The same rule is allowed for other calls in the session...
+        let again = bash("s1", "git clean -fdx build/");
+        assert!(allows
+            .process(&again, git_ask("clean", "git clean -fdx build/"))
+            .is_allow());
+        // ...but not in other sessions, or for other rules
+        assert_eq!(
+            allows.process(&bash("s2", "git clean -fd"), ask.clone()),
+            ask
+        );
+        let reset = git_ask("reset_hard", "git reset --hard");
+        assert_eq!(allows.process(&first, reset.clone()), reset);
+    }
+
+    #[test]
+    fn test_concurrent_asks_are_all_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SessionStore::new(dir.path(), 60);
+        let allows = SessionAllows::new(store.clone());
+
+        let hooks: Vec<_> = (0..16)
+            .map(|i| {
+                let allows = allows.clone();
+                std::thread::spawn(move || {
+                    let input = bash("s1", &format!("git clean -fd d{i}"));
+                    allows
+                        .record_ask("s1", &input, vec![format!("rule{i}")], 100)
+                        .unwrap();
+                })
+            })
+            .collect();
+        for hook in hooks {
+            hook.join().unwrap();
+        }
+
+        assert_eq!(store.load("s1").unwrap().pending.len(), 16);
+    }
+
+    #[test]
+    fn test_ask_without_violations_uses_reason() {
+        assert_eq!(
+            ask_rules("Tool 'X' requires approval", &[]),
+            ["Tool 'X' requires approval"]
+        );
+        let Verdict::Ask { reason, violations } = git_ask("clean", "git clean -f") else {
+            unreachable!()
+        };
+        assert_eq!(ask_rules(&reason, &violations), ["git_destructive:clean"]);
+    }
+
+    #[test]
+    fn test_session_end_clears_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SessionStore::new(dir.path(), 60);
+        let allows = SessionAllows::new(store.clone());
+        let input = bash("s1", "git clean -fd");
+        let rules = ["git clean".to_string()];
+
+        let _ = allows.process(&input, Verdict::ask("git clean"));
+        assert!(allows.record_run(&input, 100).unwrap());
+        assert!(allows.is_exempt("s1", &rules).unwrap());
+
+        store.end("s1").unwrap();
+        assert!(!allows.is_exempt("s1", &rules).unwrap());
+        store.end("s1").unwrap();
+
+        // Unrelated runs are not approvals
+        assert!(!allows.record_run(&input, 101).unwrap());
+    }
+
+    #[test]
+    fn test_prune_stale_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SessionStore::new(dir.path(), 3600);
+        store.save("s1", &SessionState::default()).unwrap();
+        assert_eq!(store.prune(), 0);
+
+        let store = SessionStore::new(dir.path(), 0);
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(store.prune(), 1);
+        assert!(!store.path("s1").exists());
+    }
+}
@@ -0,0 +1,540 @@
+//! Shell-aware tokenization of Bash commands.
+//!
+//! Regexes over the raw command string are easy to evade: `rm    -rf   /`,
+//! `"rm" -rf /`, `r\m -rf /` and `rm -r -f /` all delete the same files. This
+//! module splits a command into words and control operators the way a POSIX
+//! shell would, so scanners can analyze commands structurally:
+//!
+//! - Whitespace between words is collapsed
+//! - Quotes are removed and backslash escapes resolved
+//! - Parameter expansions (`$X`, `${X}`), command substitutions (`$(...)`,
+//!   backticks) are kept verbatim, and the word is marked as expanded since
+//!   its runtime value is unknown
+//! - Control operators (`&&`, `||`, `;`, `|`, `|&`, `&`, newlines, subshell
+//!   parentheses) are separate tokens
+//!
+//! The tokenizer never fails: unterminated quotes run to the end of the input.
+
+/// A shell token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    /// A word (command name, argument, or redirection).
+    Word(Word),
+    /// A control operator such as `&&` or `;`.
+    Operator(String),
+}
+
+/// A shell word after quote removal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+    /// The word with quotes removed and escapes resolved.
+    pub text: String,
+    /// Whether the word contains an unquoted-value expansion (`$X`, `${X}`,
+    /// `$(...)`, or backticks) whose runtime value is unknown.
+    pub expanded: bool,
+}
+
+impl Word {
+    /// The word as a command name: basename without any directory prefix.
+    pub fn program_name(&self) -> &str {
+        self.text.rsplit('/').next().unwrap_or(&self.text)
+    }
+}
+
+/// Split a command into words and control operators.
+pub fn tokenize(command: &str) -> Vec<Token> {
+    Lexer::new(command).run()
+}
+
+/// Render tokens as a canonical command string: words separated by single
+/// spaces, quotes removed, escapes resolved.
+pub fn normalize(tokens: &[Token]) -> String {
+    let parts: Vec<&str> = tokens
+        .iter()
+        .map(|t| match t {
+            Token::Word(w) => w.text.as_str(),
+            Token::Operator(op) => op.as_str(),
+        })
+        .collect();
+    parts.join(" ")
+}
+
+/// Split tokens into simple commands at control operators.
+///
+/// Empty commands (e.g. between `;;`) are dropped.
+pub fn simple_commands(tokens: &[Token]) -> Vec<Vec<Word>> {
+    let mut commands = Vec::new();
+    let mut current = Vec::new();
+
+    for token in tokens {
+        match token {
+            Token::Word(w) => current.push(w.clone()),
+            Token::Operator(_) => {
+                if !current.is_empty() {
+                    commands.push(std::mem::take(&mut current));
+                }
+            }
+        }
+    }
+    if !current.is_empty() {
+        commands.push(current);
+    }
+
+    commands
+}
+
+/// Split tokens into pipelines: simple commands connected by `|` or `|&`.
+///
+/// Any other control operator ends the pipeline.
+pub fn pipelines(tokens: &[Token]) -> Vec<Vec<Vec<Word>>> {
+    let mut pipelines = Vec::new();
+    let mut pipeline = Vec::new();
+    let mut current = Vec::new();
+
+    for token in tokens {
+        match token {
+            Token::Word(w) => current.push(w.clone()),
+            Token::Operator(op) => {
+                if !current.is_empty() {
+                    pipeline.push(std::mem::take(&mut current));
+                }
+                if op != "|" && op != "|&" && !pipeline.is_empty() {
+                    pipelines.push(std::mem::take(&mut pipeline));
+                }
+            }
+        }
+    }
+    if !current.is_empty() {
+        pipeline.push(current);
+    }
+    if !pipeline.is_empty() {
+        pipelines.push(pipeline);
+    }
+
+    pipelines
+}
+
+/// Inner commands of the `$(...)` and backtick substitutions in a word.
+pub fn substitutions(text: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'$' && bytes.get(i + 1) == Some(&b'(') {
+            let start = i + 2;
+            let mut depth = 1usize;
+            let mut end = start;
+            while end < bytes.len() && depth > 0 {
+                match bytes[end] {
+                    b'(' => depth += 1,
+                    b')' => depth -= 1,
+                    _ => {}
+                }
+                end += 1;
+            }
+            let inner_end = if depth == 0 { end - 1 } else { end };
+            found.push(&text[start..inner_end]);
+            i = end;
+        } else if bytes[i] == b'`' {
+            let start = i + 1;
+            let end = text[start..].find('`').map_or(text.len(), |e| start + e);
+            found.push(&text[start..end]);
+            i = end + 1;
+        } else {
+            i += 1;
+        }
+    }
+
+    found
+}
+
+/// Words that run the following word as the real command.
+const WRAPPERS: &[&str] = &[
+    "sudo", "doas", "env", "nice", "nohup", "time", "command", "exec",
+];
+
+/// Wrapper options that take a separate argument (`sudo -u root`).
+const WRAPPER_ARG_OPTIONS: &[&str] = &["-u", "-g", "-C", "-D", "-p", "-r", "-t", "-U", "-n"];
+
+/// Indices of the words in command position: any wrapper commands such as
+/// `sudo` or `env`, followed by the program they run.
+///
+/// Skips variable assignments (`FOO=bar cmd`), group/negation words (`{`,
+/// `!`), and wrapper options along with their arguments.
+pub fn command_words(words: &[Word]) -> Vec<usize> {
+    let mut found = Vec::new();
+    let mut in_wrapper = false;
+    let mut skip_next = false;
+
+    for (i, word) in words.iter().enumerate() {
+        let text = word.text.as_str();
+        if std::mem::take(&mut skip_next) {
+            continue;
+        }
+        if text == "{" || text == "!" || is_assignment(text) {
+            continue;
+        }
+        if in_wrapper && text.starts_with('-') {
+            skip_next = WRAPPER_ARG_OPTIONS.contains(&text);
+            continue;
+        }
+        found.push(i);
+        if !WRAPPERS.contains(&word.program_name()) {
+            break;
+        }
+        in_wrapper = true;
+    }
+
+    found
+}
+
+/// Index of the program word in a simple command, past any wrappers (see
+/// [`command_words`]).
+pub fn program_index(words: &[Word]) -> Option<usize> {
+    command_words(words)
+        .into_iter()
+        .find(|&i| !WRAPPERS.contains(&words[i].program_name()))
+}
+
+/// Interpreters that execute code read from stdin or `-c`.
+pub const INTERPRETERS: &[&str] = &[
+    "sh", "bash", "zsh", "dash", "ksh", "fish", "python", "python3", "perl", "ruby", "node",
+];
+
+/// Whether a simple command runs an interpreter.
+pub fn is_interpreter(words: &[Word]) -> bool {
+    program_index(words).is_some_and(|i| INTERPRETERS.contains(&words[i].program_name()))
+}
+
+/// Whether a word is a variable assignment (`NAME=value`).
+fn is_assignment(text: &str) -> bool {
+    text.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// Single-pass shell lexer.
+struct Lexer<'a> {
+    /// Remaining input characters.
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    /// Completed tokens.
+    tokens: Vec<Token>,
+    /// Text of the word being built.
+    text: String,
+    /// Whether the word being built contains an expansion.
+    expanded: bool,
+    /// Whether a word is in progress (distinguishes `""` from no word).
+    in_word: bool,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.chars().peekable(),
+            tokens: Vec::new(),
+            text: String::new(),
+            expanded: false,
+            in_word: false,
+        }
+    }
+
+    fn run(mut self) -> Vec<Token> {
+        while let Some(c) = self.chars.next() {
+            match c {
+                ' ' | '\t' => self.finish_word(),
+                '\n' => self.operator(";"),
+                '#' if !self.in_word => {
+                    // Comment to end of line
+                    while self.chars.next_if(|c| *c != '\n').is_some() {}
+                }
+                '\\' => match self.chars.next() {
+                    Some('\n') | None => {} // Line continuation
+                    Some(escaped) => self.push(escaped),
+                },
+                '\'' => {
+                    self.in_word = true;
+                    while let Some(c) = self.chars.next_if(|c| *c != '\'') {
+                        self.text.push(c);
+                    }
+                    let _ = self.chars.next();
+                }
+                '"' => self.double_quoted(),
+                '$' => self.dollar(),
+                '`' => self.backtick(),
+                ';' => {
+                    let _ = self.chars.next_if_eq(&';');
+                    self.operator(";");
+                }
+                '(' | ')' => self.operator(if c == '(' { "(" } else { ")" }),
+                '|' => {
+                    if self.chars.next_if_eq(&'|').is_some() {
+                        self.operator("||");
+                    } else if self.chars.next_if_eq(&'&').is_some() {
+                        self.operator("|&");
+                    } else {
+                        self.operator("|");
+                    }
+                }
+                '&' => {
+                    if self.text.ends_with(['>', '<']) || self.chars.peek() == Some(&'>') {
+                        // Redirection such as 2>&1 or &>file
+                        self.push('&');
+                    } else if self.chars.next_if_eq(&'&').is_some() {
+                        self.operator("&&");
+                    } else {
+                        self.operator("&");
+                    }
+                }
+                _ => self.push(c),
+            }
+        }
+        self.finish_word();
+
+        // Trailing separators carry no meaning
+        while matches!(self.tokens.last(), Some(Token::Operator(op)) if op == ";" || op == "&") {
+            let _ = self.tokens.pop();
+        }
+
+        self.tokens
+    }
+
+    fn push(&mut self, c: char) {
+        self.in_word = true;
+        self.text.push(c);
+    }
+
+    fn finish_word(&mut self) {
+        if self.in_word {
+            self.tokens.push(Token::Word(Word {
+                text: std::mem::take(&mut self.text),
+                expanded: self.expanded,
+            }));
+        }
+        self.expanded = false;
+        self.in_word = false;
+    }
+
+    fn operator(&mut self, op: &str) {
+        self.finish_word();
+        // Collapse repeated separators (e.g. blank lines)
+        let after_separator = match self.tokens.last() {
+            None => true,
+            Some(Token::Operator(prev)) => prev != ")",
+            Some(Token::Word(_)) => false,
+        };
+        if op == ";" && after_separator {
+            return;
+        }
+        self.tokens.push(Token::Operator(op.to_string()));
+    }
+
+    /// Contents of a double-quoted string.
+    fn double_quoted(&mut self) {
+        self.in_word = true;
+        while let Some(c) = self.chars.next() {
+            match c {
+                '"' => return,
+                '\\' => match self.chars.next() {
+                    Some('\n') | None => {}
+                    Some(escaped @ ('$' | '`' | '"' | '\\')) => self.text.push(escaped),
+                    Some(other) => {
+                        self.text.push('\\');
+                        self.text.push(other);
+                    }
+                },
+                '$' => self.dollar(),
+                '`' => self.backtick(),
+                _ => self.text.push(c),
+            }
+        }
+    }
+
+    /// A `$` expansion, copied verbatim.
+    fn dollar(&mut self) {
+        self.in_word = true;
+        match self.chars.peek() {
+            Some('{') => {
+                self.expanded = true;
+                self.text.push('$');
+                self.copy_balanced('{', '}');
+            }
+            Some('(') => {
+                self.expanded = true;
+                self.text.push('$');
+                self.copy_balanced('(', ')');
+            }
+            Some('\'') => {
+                // ANSI-C quoting: keep escapes as written
+                let _ = self.chars.next();
+                while let Some(c) = self.chars.next_if(|c| *c != '\'') {
+                    self.text.push(c);
+                    if c == '\\' {
+                        if let Some(escaped) = self.chars.next() {
+                            self.text.push(escaped);
+                        }
+                    }
+                }
+                let _ = self.chars.next();
+            }
+            Some(c) if c.is_ascii_alphanumeric() || "_@*#?$!-".contains(*c) => {
+                self.expanded = true;
+                self.text.push('$');
+                let first = self.chars.next().unwrap_or_default();
+                self.text.push(first);
+                if first.is_ascii_alphabetic() || first == '_' {
+                    while let Some(c) = self
+                        .chars
+                        .next_if(|c| c.is_ascii_alphanumeric() || *c == '_')
+                    {
+                        self.text.push(c);
+                    }
+                }
+            }
+            _ => self.text.push('$'),
+        }
+    }
+
+    /// Copy a bracketed expansion including nested brackets.
+    fn copy_balanced(&mut self, open: char, close: char) {
+        let mut depth = 0usize;
+        for c in self.chars.by_ref() {
+            self.text.push(c);
+            if c == open {
+                depth += 1;
+            } else if c == close {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// A backtick command substitution, copied verbatim.
+    fn backtick(&mut self) {
+        self.in_word = true;
+        self.expanded = true;
+        self.text.push('`');
+        while let Some(c) = self.chars.next() {
+            self.text.push(c);
+            if c == '\\' {
+                if let Some(escaped) = self.chars.next() {
+                    self.text.push(escaped);
+                }
+            } else if c == '`' {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(command: &str) -> Vec<String> {
+        tokenize(command)
+            .into_iter()
+            .map(|t| match t {
+                Token::Word(w) => w.text,
+                Token::Operator(op) => op,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_collapses_whitespace() {
+        assert_eq!(words("rm    -rf \t  /"), ["rm", "-rf", "/"]);
+        assert_eq!(normalize(&tokenize("rm    -rf   /")), "rm -rf /");
+    }
+
+    #[test]
+    fn test_removes_quotes_and_escapes() {
+        assert_eq!(words(r#""rm" '-rf' /"#), ["rm", "-rf", "/"]);
+        assert_eq!(words(r"r\m -r\f /"), ["rm", "-rf", "/"]);
+        assert_eq!(
+            words(r#"echo "a b" 'c d' e\ f"#),
+            ["echo", "a b", "c d", "e f"]
+        );
+        assert_eq!(words(r#"echo "" x"#), ["echo", "", "x"]);
+    }
+
+    #[test]
+    fn test_operators() {
+        assert_eq!(
+            words("a && b || c; d | e & f"),
+            ["a", "&&", "b", "||", "c", ";", "d", "|", "e", "&", "f"]
+        );
+        assert_eq!(words("(cd x)\nls"), ["(", "cd", "x", ")", ";", "ls"]);
+        assert_eq!(
+            words("make 2>&1 | tee log"),
+            ["make", "2>&1", "|", "tee", "log"]
+        );
+        assert_eq!(words("echo 'a && b'"), ["echo", "a && b"]);
+    }
+
+    #[test]
+    fn test_expansions_are_marked() {
+        let tokens = tokenize("rm -${X}f / $(pwd) `id` \"$HOME\" plain");
+        let expanded: Vec<(String, bool)> = tokens
+            .into_iter()
+            .filter_map(|t| match t {
+                Token::Word(w) => Some((w.text, w.expanded)),
+                Token::Operator(_) => None,
+            })
+            .collect();
+
+        assert_eq!(
+            expanded,
+            [
+                ("rm".to_string(), false),
+                ("-${X}f".to_string(), true),
+                ("/".to_string(), false),
+                ("$(pwd)".to_string(), true),
+                ("`id`".to_string(), true),
+                ("$HOME".to_string(), true),
+                ("plain".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_substitution_is_one_word() {
+        assert_eq!(
+            words("echo $(cat $(ls) | wc -l) done"),
+            ["echo", "$(cat $(ls) | wc -l)", "done"]
+        );
+    }
+
+    #[test]
+    fn test_comments_ignored() {
+        assert_eq!(words("ls # rm -rf /"), ["ls"]);
+        assert_eq!(words("echo a#b"), ["echo", "a#b"]);
+    }
+
+    #[test]
+    fn test_pipelines() {
+        let pipelines = pipelines(&tokenize("a | b |& c && d; e | f"));
+        let shape: Vec<usize> = pipelines.iter().map(Vec::len).collect();
+        assert_eq!(shape, [3, 1, 2]);
+    }
+
+    #[test]
+    fn test_substitutions() {
+        assert_eq!(substitutions("x$(a $(b) c)y`d`"), ["a $(b) c", "d"]);
+        assert_eq!(substitutions("plain $HOME"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_simple_commands_and_program() {
+        let commands = simple_commands(&tokenize("FOO=1 sudo -u root /bin/rm -rf / && ls"));
+        assert_eq!(commands.len(), 2);
+
+        let program = program_index(&commands[0]).unwrap();
+        assert_eq!(commands[0][program].program_name(), "rm");
+        assert_eq!(commands[1][0].text, "ls");
+    }
+}
@@ -4,7 +4,10 @@
 //! Tools can be allowed, denied, or require user confirmation based on patterns.
 
 use glob::Pattern;
-use rg_types::{ToolsConfig, Verdict};
+use rg_types::{RuleAction, ToolsConfig, Verdict};
+use serde_json::Value;
+
+use crate::mcp_args::{McpArgChecker, McpArgMatch};
 
 /// Compiled tool permission checker.
 ///
@@ -14,6 +17,12 @@ use rg_types::{ToolsConfig, Verdict};
 /// 2. Ask patterns
 /// 3. Allow patterns
 /// 4. None = continue to parameter inspection
+///
+/// MCP tools are first checked against per-tool rules (the most restrictive
+/// matching rule wins), then against the server lists, then as above.
+///
+/// Tools that match nothing, not even the `inspect` patterns, get the
+/// configured default action (see [`ToolChecker::unlisted_action`]).
 #[derive(Debug)]
 pub struct ToolChecker {
     /// Patterns for tools that are completely blocked.
@@ -22,10 +31,18 @@ pub struct ToolChecker {
     ask: Vec<Pattern>,
     /// Patterns for tools that always proceed.
     allow: Vec<Pattern>,
+    /// Patterns for tools that proceed to parameter inspection.
+    inspect: Vec<Pattern>,
+    /// Action for tools matched by no pattern.
+    default: RuleAction,
     /// MCP server patterns.
     mcp_deny: Vec<Pattern>,
     mcp_ask: Vec<Pattern>,
     mcp_allow: Vec<Pattern>,
+    /// Per-tool MCP rules: server pattern, tool pattern, action.
+    mcp_rules: Vec<(Pattern, Pattern, RuleAction)>,
+    /// MCP argument rules.
+    mcp_args: McpArgChecker,
 }
 
 impl ToolChecker {
@@ -35,12 +52,42 @@ impl ToolChecker {
             deny: compile_patterns(&config.deny),
             ask: compile_patterns(&config.ask),
             allow: compile_patterns(&config.allow),
+            inspect: compile_patterns(&config.inspect),
+            default: config.default,
             mcp_deny: compile_mcp_patterns(&config.mcp.deny_servers),
             mcp_ask: compile_mcp_patterns(&config.mcp.ask_servers),
             mcp_allow: compile_mcp_patterns(&config.mcp.allow_servers),
+            mcp_rules: config
+                .mcp
+                .rules
+                .iter()
+                .filter_map(|rule| {
+                    let server = Pattern::new(&rule.server).ok()?;
+                    let tool = Pattern::new(&rule.tool).ok()?;
+                    Some((server, tool, rule.action))
+                })
+                .collect(),
+            mcp_args: McpArgChecker::new(&config.mcp.arg_rules),
         }
     }
 
+    /// Action for a tool that [`check`](Self::check) did not match.
+    ///
+    /// Returns `Allow` (continue to parameter inspection) if the default is
+    /// allow or the tool matches an `inspect` pattern, else the default.
+    pub fn unlisted_action(&self, tool_name: &str) -> RuleAction {
+        if self.default == RuleAction::Allow || self.inspect.iter().any(|p| p.matches(tool_name)) {
+            RuleAction::Allow
+        } else {
+            self.default
+        }
+    }
+
+    /// Check a tool's arguments against the MCP argument rules.
+    pub fn check_arguments(&self, tool_name: &str, args: &Value) -> Option<McpArgMatch> {
+        self.mcp_args.check(tool_name, args)
+    }
+
     /// Check a tool name against permission patterns.
     ///
     /// Returns:
@@ -50,7 +97,10 @@ impl ToolChecker {
     /// - `None` if no pattern matches (continue to parameter inspection)
     pub fn check(&self, tool_name: &str) -> Option<Verdict> {
         // Check if this is an MCP tool (format: mcp__server__tool)
-        if let Some(server) = extract_mcp_server(tool_name) {
+        if let Some((server, tool)) = extract_mcp_tool(tool_name) {
+            if let Some(verdict) = self.check_mcp_rules(server, tool) {
+                return Some(verdict);
+            }
             return self.check_mcp_server(server, tool_name);
         }
 
@@ -83,6 +133,30 @@ impl ToolChecker {
         None
     }
 
+    /// Check per-tool MCP rules; the most restrictive matching rule wins.
+    fn check_mcp_rules(&self, server: &str, tool: &str) -> Option<Verdict> {
+        let action = self
+            .mcp_rules
+            .iter()
+            .filter(|(s, t, _)| s.matches(server) && t.matches(tool))
+            .map(|(_, _, action)| *action)
+            .max_by_key(|action| match action {
+                RuleAction::Allow => 0,
+                RuleAction::Ask => 1,
+                RuleAction::Deny => 2,
+            })?;
+
+        Some(match action {
+            RuleAction::Deny => Verdict::deny(format!(
+                "MCP tool '{tool}' on server '{server}' is blocked by policy"
+            )),
+            RuleAction::Ask => Verdict::ask(format!(
+                "MCP tool '{tool}' on server '{server}' requires confirmation"
+            )),
+            RuleAction::Allow => Verdict::Allow,
+        })
+    }
+
     /// Check MCP server permissions.
     fn check_mcp_server(&self, server: &str, tool_name: &str) -> Option<Verdict> {
         // Check deny patterns first
@@ -158,27 +232,24 @@ fn compile_mcp_patterns(servers: &[String]) -> Vec<Pattern> {
         .collect()
 }
 
-/// Extract MCP server name from tool name.
-/// Format: `mcp__server__tool` -> Some("server")
-fn extract_mcp_server(tool_name: &str) -> Option<&str> {
-    if let Some(rest) = tool_name.strip_prefix("mcp__") {
-        rest.split("__").next()
-    } else {
-        None
-    }
+/// Split an MCP tool name into server and tool.
+/// Format: `mcp__server__tool` -> Some(("server", "tool"))
+fn extract_mcp_tool(tool_name: &str) -> Option<(&str, &str)> {
+    let rest = tool_name.strip_prefix("mcp__")?;
+    Some(rest.split_once("__").unwrap_or((rest, "")))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rg_types::{McpConfig, ToolsConfig};
+    use rg_types::{McpConfig, McpToolRule, ToolsConfig};
 
     fn make_config(allow: Vec<&str>, deny: Vec<&str>, ask: Vec<&str>) -> ToolsConfig {
         ToolsConfig {
             allow: allow.into_iter().map(String::from).collect(),
             deny: deny.into_iter().map(String::from).collect(),
             ask: ask.into_iter().map(String::from).collect(),
-            mcp: McpConfig::default(),
+            ..Default::default()
         }
     }
 
@@ -229,10 +300,11 @@ mod tests {
 
     #[test]
     fn test_mcp_server_extraction() {
-        assert_eq!(extract_mcp_server("mcp__context7__query"), Some("context7"));
-        assert_eq!(extract_mcp_server("mcp__devtools__click"), Some("devtools"));
-        assert_eq!(extract_mcp_server("Bash"), None);
-        assert_eq!(extract_mcp_server("Read"), None);
+        let server = |name| extract_mcp_tool(name).map(|(server, _)| server);
+        assert_eq!(server("mcp__context7__query"), Some("context7"));
+        assert_eq!(server("mcp__devtools__click"), Some("devtools"));
+        assert_eq!(server("Bash"), None);
+        assert_eq!(server("Read"), None);
     }
 
     #[test]
@@ -279,4 +351,76 @@ mod tests {
         let result = checker.check("mcp__devtools__click");
         assert!(matches!(result, Some(Verdict::Ask { .. })));
     }
+
+    #[test]
+    fn test_mcp_tool_rules() {
+        let config = ToolsConfig {
+            mcp: McpConfig {
+                allow_servers: vec!["github".to_string()],
+                rules: vec![
+                    McpToolRule {
+                        server: "github".to_string(),
+                        tool: "delete_*".to_string(),
+                        action: RuleAction::Deny,
+                    },
+                    McpToolRule {
+                        server: "github".to_string(),
+                        tool: "delete_branch".to_string(),
+                        action: RuleAction::Allow,
+                    },
+                    McpToolRule {
+                        server: "*".to_string(),
+                        tool: "merge_pull_request".to_string(),
+                        action: RuleAction::Ask,
+                    },
+                ],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let checker = ToolChecker::new(&config);
+
+        // Rules beat the server allow list; the most restrictive rule wins
+        let result = checker.check("mcp__github__delete_repository");
+        assert!(matches!(result, Some(Verdict::Deny { .. })));
+        let result = checker.check("mcp__github__delete_branch");
+        assert!(matches!(result, Some(Verdict::Deny { .. })));
+        let result = checker.check("mcp__github__merge_pull_request");
+        assert!(matches!(result, Some(Verdict::Ask { .. })));
+        let result = checker.check("mcp__github__create_issue");
+        assert!(matches!(result, Some(Verdict::Allow)));
+    }
+
+    #[test]
+    fn test_unlisted_action() {
+        let checker = ToolChecker::new(&make_config(vec!["Read"], vec![], vec![]));
+        assert_eq!(checker.unlisted_action("Bash"), RuleAction::Allow);
+
+        let config = ToolsConfig {
+            allow: vec!["Read".to_string()],
+            inspect: vec!["Bash".to_string(), "Write".to_string()],
+            default: RuleAction::Deny,
+            ..Default::default()
+        };
+        let checker = ToolChecker::new(&config);
+
+        assert!(matches!(checker.check("Read"), Some(Verdict::Allow)));
+        assert!(checker.check("Bash").is_none());
+        assert_eq!(checker.unlisted_action("Bash"), RuleAction::Allow);
+        assert_eq!(checker.unlisted_action("WebFetch"), RuleAction::Deny);
+        assert_eq!(
+            checker.unlisted_action("mcp__unknown__tool"),
+            RuleAction::Deny
+        );
+    }
+
+    #[test]
+    fn test_mcp_tool_extraction() {
+        assert_eq!(
+            extract_mcp_tool("mcp__github__create_issue"),
+            Some(("github", "create_issue"))
+        );
+        assert_eq!(extract_mcp_tool("mcp__server"), Some(("server", "")));
+        assert_eq!(extract_mcp_tool("Bash"), None);
+    }
 }
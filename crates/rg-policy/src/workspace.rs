@@ -0,0 +1,151 @@
+//! Workspace sandbox for Claude Code hook inputs.
+//!
+//! Flags file operations whose target lies outside the project directory,
+//! so an agent can't wander into `~/Documents` or `/etc`. Relative paths are
+//! resolved against the tool's working directory (or the project directory
+//! if it is unknown), and `.`/`..` are resolved lexically before the
+//! comparison.
+
+use std::path::Path;
+
+use glob::Pattern;
+use rg_types::{RuleAction, WorkspaceConfig};
+
+use crate::paths::{expand_home, normalize_path, resolve_in};
+
+/// A file operation outside the workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceMatch {
+    /// The path as given by the tool.
+    pub path: String,
+    /// The project directory.
+    pub root: String,
+    /// Action to take for this match.
+    pub action: RuleAction,
+}
+
+/// Workspace sandbox with compiled allow patterns.
+#[derive(Debug)]
+pub struct WorkspaceGuard {
+    /// Configuration.
+    config: WorkspaceConfig,
+    /// Compiled allow patterns for paths outside the project.
+    allowed: Vec<Pattern>,
+}
+
+impl WorkspaceGuard {
+    /// Create a new workspace guard from configuration.
+    pub fn new(config: &WorkspaceConfig) -> Self {
+        Self {
+            config: config.clone(),
+            allowed: config
+                .allowed
+                .iter()
+                .filter_map(|p| Pattern::new(p).ok())
+                .collect(),
+        }
+    }
+
+    /// Check whether a path is inside the project directory `root`. A
+    /// relative path is resolved against `cwd`, or `root` without one.
+    ///
+    /// Returns `None` if the sandbox is disabled, its action is `Allow`, the
+    /// path is under `root`, or it matches an allowed pattern.
+    pub fn check(&self, path: &str, root: &Path, cwd: Option<&Path>) -> Option<WorkspaceMatch> {
+        if !self.config.enabled || self.config.action == RuleAction::Allow || path.is_empty() {
+            return None;
+        }
+
+        let root = normalize_path(&root.to_string_lossy());
+        let base = cwd.map_or_else(|| root.clone(), |cwd| cwd.to_string_lossy().into_owned());
+        let resolved = resolve(path, &base);
+        if is_under(&resolved, &root) || self.allowed.iter().any(|p| p.matches(&resolved)) {
+            return None;
+        }
+
+        Some(WorkspaceMatch {
+            path: path.to_string(),
+            root,
+            action: self.config.action,
+        })
+    }
+}
+
+/// Resolve a path against the directory `base` and normalize it.
+pub(crate) fn resolve(path: &str, base: &str) -> String {
+    // An unexpanded `~` is never under an absolute project directory
+    resolve_in(path, base).unwrap_or_else(|| normalize_path(&expand_home(path)))
+}
+
+/// Whether a normalized path is `root` or below it.
+pub(crate) fn is_under(path: &str, root: &str) -> bool {
+    root == "/"
+        || path == root
+        || path
+            .strip_prefix(root)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard(allowed: &[&str]) -> WorkspaceGuard {
+        WorkspaceGuard::new(&WorkspaceConfig {
+            enabled: true,
+            allowed: allowed.iter().map(ToString::to_string).collect(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_inside_workspace() {
+        let guard = guard(&[]);
+        let root = Path::new("/home/user/project");
+
+        assert!(guard.check("src/main.rs", root, None).is_none());
+        assert!(guard.check("./README.md", root, None).is_none());
+        assert!(guard
+            .check("/home/user/project/Cargo.toml", root, None)
+            .is_none());
+        assert!(guard.check("/home/user/project", root, None).is_none());
+    }
+
+    #[test]
+    fn test_outside_workspace() {
+        let guard = guard(&[]);
+        let root = Path::new("/home/user/project");
+
+        let m = guard.check("/etc/hosts", root, None).unwrap();
+        assert_eq!(m.root, "/home/user/project");
+        assert_eq!(m.action, RuleAction::Ask);
+        assert!(guard.check("../other/file.txt", root, None).is_some());
+        assert!(guard.check("src/../../project-old/x", root, None).is_some());
+        assert!(guard.check("/home/user/project2/x", root, None).is_some());
+        assert!(guard.check("~/Documents/taxes.pdf", root, None).is_some());
+    }
+
+    #[test]
+    fn test_relative_to_cwd() {
+        let guard = guard(&[]);
+        let root = Path::new("/home/user/project");
+        let cwd = Some(Path::new("/home/user/project/crates/core"));
+
+        assert!(guard.check("../../README.md", root, cwd).is_none());
+        assert!(guard.check("src/lib.rs", root, cwd).is_none());
+        assert!(guard.check("../../../.ssh/id_rsa", root, cwd).is_some());
+        // From the project root the same path would look inside it
+        assert!(guard.check("crates/../README.md", root, None).is_none());
+    }
+
+    #[test]
+    fn test_allowed_and_disabled() {
+        let root = Path::new("/home/user/project");
+        assert!(guard(&["/tmp/**"])
+            .check("/tmp/build.log", root, None)
+            .is_none());
+
+        let guard = WorkspaceGuard::new(&WorkspaceConfig::default());
+        assert!(guard.check("/etc/hosts", root, None).is_none());
+    }
+}
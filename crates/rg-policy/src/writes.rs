@@ -0,0 +1,125 @@
+//! Large and binary write detection for Claude Code hook inputs.
+//!
+//! Flags `Write` content larger than the configured limit, and content that
+//! looks binary (NUL bytes or mostly control characters), so multi-megabyte
+//! blobs aren't dumped to disk unchecked.
+
+use rg_types::{RuleAction, WritesConfig};
+
+/// How many characters the binary heuristic inspects.
+const BINARY_SAMPLE_CHARS: usize = 8192;
+
+/// A flagged write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriteMatch {
+    /// The path being written.
+    pub path: String,
+    /// Why the write was flagged.
+    pub detail: String,
+    /// Action to take for this match.
+    pub action: RuleAction,
+}
+
+/// Large and binary write guard.
+#[derive(Debug)]
+pub struct WriteGuard {
+    /// Configuration.
+    config: WritesConfig,
+}
+
+impl WriteGuard {
+    /// Create a new write guard from configuration.
+    pub fn new(config: &WritesConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+
+    /// Check a file write for size and binary content.
+    ///
+    /// Returns `None` if the guard is disabled, its action is `Allow`, or the
+    /// content is small text.
+    pub fn check(&self, file_path: &str, content: &str) -> Option<WriteMatch> {
+        if !self.config.enabled || self.config.action == RuleAction::Allow {
+            return None;
+        }
+
+        let detail = if content.len() > self.config.max_bytes {
+            format!(
+                "{} bytes exceeds the {} byte limit",
+                content.len(),
+                self.config.max_bytes
+            )
+        } else if self.config.detect_binary && looks_binary(content) {
+            "content looks binary".to_string()
+        } else {
+            return None;
+        };
+
+        Some(WriteMatch {
+            path: file_path.to_string(),
+            detail,
+            action: self.config.action,
+        })
+    }
+}
+
+/// Whether content looks binary: a NUL byte, or more than 10% control
+/// characters other than whitespace, in the first few kilobytes.
+pub fn looks_binary(content: &str) -> bool {
+    let sample: Vec<char> = content.chars().take(BINARY_SAMPLE_CHARS).collect();
+    if sample.contains(&'\0') {
+        return true;
+    }
+
+    let control = sample
+        .iter()
+        .filter(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t' | '\x0c'))
+        .count();
+    control * 10 > sample.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_guard() -> WriteGuard {
+        WriteGuard::new(&WritesConfig::default())
+    }
+
+    #[test]
+    fn test_large_write() {
+        let config = WritesConfig {
+            max_bytes: 16,
+            ..Default::default()
+        };
+        let guard = WriteGuard::new(&config);
+
+        let m = guard.check("dump.txt", &"a".repeat(17)).unwrap();
+        assert_eq!(m.detail, "17 bytes exceeds the 16 byte limit");
+        assert_eq!(m.action, RuleAction::Ask);
+        assert!(guard.check("small.txt", "hello").is_none());
+    }
+
+    #[test]
+    fn test_binary_write() {
+        let guard = default_guard();
+
+        assert!(guard.check("blob.bin", "PK\u{3}\u{4}\0\0payload").is_some());
+        assert!(guard
+            .check("blob.bin", &"\u{1}\u{2}\u{3}abc".repeat(10))
+            .is_some());
+        assert!(guard
+            .check("src/main.rs", "fn main() {\n\tprintln!(\"hi\");\r\n}\n")
+            .is_none());
+    }
+
+    #[test]
+    fn test_disabled() {
+        let config = WritesConfig {
+            action: RuleAction::Allow,
+            ..Default::default()
+        };
+        assert!(WriteGuard::new(&config).check("blob.bin", "\0").is_none());
+    }
+}
@@ -3,6 +3,33 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+/// Severity of a policy violation, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Informational; unlikely to cause harm on its own.
+    Low,
+    /// Potentially harmful in combination with other actions.
+    #[default]
+    Medium,
+    /// Likely harmful.
+    High,
+    /// Immediately harmful or irreversible.
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::Medium => write!(f, "medium"),
+            Self::High => write!(f, "high"),
+            Self::Critical => write!(f, "critical"),
+        }
+    }
+}
+
 /// Structured reason for why a tool use was blocked.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "code", rename_all = "snake_case")]
@@ -21,6 +48,92 @@ pub enum BlockReason {
         pattern: String,
         /// The matched portion of the command
         matched: String,
+        /// Estimated impact of running the command, if computed
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        impact: Option<String>,
+        /// Name of the rule that matched, if it has one
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rule: Option<String>,
+        /// Severity set on the pattern (default: critical)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        severity: Option<Severity>,
+        /// Description set on the pattern
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        /// Whether the pattern asks for approval instead of blocking
+        #[serde(default)]
+        ask: bool,
+    },
+
+    /// A command that hides its payload through encoding or indirection.
+    ObfuscatedCommand {
+        /// Obfuscation technique (e.g., "`decode_pipe_to_shell`")
+        technique: String,
+        /// The offending part of the command
+        matched: String,
+    },
+
+    /// A command that hands a shell to a remote listener.
+    ReverseShell {
+        /// Technique (e.g., "`netcat_exec`", "`dev_tcp`")
+        technique: String,
+        /// The offending part of the command
+        matched: String,
+    },
+
+    /// Downloaded content piped into an interpreter.
+    PipeToShell {
+        /// The download command (e.g., "curl")
+        downloader: String,
+        /// The interpreter that runs the download (e.g., "bash")
+        interpreter: String,
+        /// The offending part of the command
+        matched: String,
+    },
+
+    /// A command that matches no allow pattern in allowlist mode.
+    CommandNotAllowed {
+        /// The command segment that is not allowed
+        command: String,
+    },
+
+    /// A command runs with elevated privileges.
+    PrivilegeEscalation {
+        /// The escalation program (e.g., "sudo")
+        program: String,
+        /// The command run with elevated privileges (empty for a shell)
+        target: String,
+    },
+
+    /// A recursive permission or ownership change on a protected path.
+    PermissionChange {
+        /// The program (e.g., "chmod")
+        program: String,
+        /// The protected target
+        target: String,
+        /// The command
+        matched: String,
+    },
+
+    /// Local data sent to a remote host over ssh.
+    RemoteTransfer {
+        /// The transfer program (e.g., "scp")
+        program: String,
+        /// The destination host
+        host: String,
+        /// The command
+        matched: String,
+    },
+
+    /// A git operation that discards work or rewrites shared history.
+    GitDestructive {
+        /// Rule that matched (e.g., "`force_push`", "`reset_hard`")
+        rule: String,
+        /// Target branch, when detectable
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        branch: Option<String>,
+        /// The git command
+        matched: String,
     },
 
     /// Access to a protected path was attempted.
@@ -31,12 +144,123 @@ pub enum BlockReason {
         pattern: String,
     },
 
+    /// A search that looks for secrets.
+    SecretSearch {
+        /// The search pattern
+        pattern: String,
+        /// What the pattern looks for (e.g., "`aws_key`")
+        kind: String,
+    },
+
+    /// An MCP tool argument matched an argument rule.
+    McpArgument {
+        /// The tool name
+        tool: String,
+        /// The rule name
+        rule: String,
+        /// Path of the matching argument (e.g., `$.query`)
+        path: String,
+    },
+
+    /// A secret embedded in a URL's path or query string.
+    SecretInUrl {
+        /// The URL's domain
+        domain: String,
+        /// Type of secret (e.g., "`aws_access_key`")
+        secret_type: String,
+    },
+
+    /// A network request to a raw IP address instead of a domain.
+    RawIpAddress {
+        /// The IP address
+        address: String,
+        /// Whether the address is loopback, private, or link-local
+        private: bool,
+    },
+
+    /// A URL with an unusual scheme or a non-standard port.
+    UnusualUrl {
+        /// The URL
+        url: String,
+        /// What is unusual about it (e.g., "scheme 'ftp'")
+        detail: String,
+    },
+
+    /// A write that is unusually large or binary.
+    LargeWrite {
+        /// The path being written
+        path: String,
+        /// Why the write was flagged
+        detail: String,
+    },
+
+    /// Text with control characters or terminal escapes that can hide part
+    /// of it when displayed.
+    ControlCharacters {
+        /// The field holding the text (e.g., "command", `edits[0].new_string`)
+        field: String,
+        /// The kind of control character (e.g., `escape_sequence`)
+        kind: String,
+        /// The offending line with control characters written as escapes
+        decoded: String,
+    },
+
+    /// A `Task` or `WebFetch` prompt that looks like prompt injection.
+    PromptInjection {
+        /// The tool whose prompt was flagged
+        tool: String,
+        /// The indicator that matched (e.g., `ignore_instructions`)
+        indicator: String,
+    },
+
+    /// A file operation outside the project directory.
+    OutsideWorkspace {
+        /// The path that was accessed
+        path: String,
+        /// The project directory
+        root: String,
+    },
+
+    /// The session's accumulated risk score reached the threshold.
+    SessionRisk {
+        /// Current score
+        score: u32,
+        /// Configured threshold
+        threshold: u32,
+    },
+
+    /// Network access from a session that read a sensitive file.
+    TaintedNetwork {
+        /// The sensitive file read earlier in the session
+        source: String,
+        /// The domain or host being contacted
+        destination: String,
+    },
+
     /// Potential network exfiltration detected.
     NetworkExfiltration {
         /// The blocked domain
         domain: String,
     },
 
+    /// An executable file was written outside the designated script directories.
+    ExecutableWrite {
+        /// The path being written
+        path: String,
+        /// Why the file is considered executable or dangerous
+        detail: String,
+    },
+
+    /// A package install that is denied or not on the allow list.
+    PackageInstall {
+        /// Package ecosystem (e.g., "npm", "pypi", "crates")
+        ecosystem: String,
+        /// Package name
+        package: String,
+        /// Whether the package is on the deny list
+        denied: bool,
+    },
+
     /// Internal error (fail-closed behavior).
     InternalError {
         /// Error message
@@ -50,11 +274,104 @@ impl BlockReason {
         match self {
             Self::SecretDetected { .. } => "secret_detected",
             Self::DangerousCommand { .. } => "dangerous_command",
+            Self::ObfuscatedCommand { .. } => "obfuscated_command",
+            Self::ReverseShell { .. } => "reverse_shell",
+            Self::PipeToShell { .. } => "pipe_to_shell",
+            Self::CommandNotAllowed { .. } => "command_not_allowed",
+            Self::PrivilegeEscalation { .. } => "privilege_escalation",
+            Self::PermissionChange { .. } => "permission_change",
+            Self::RemoteTransfer { .. } => "remote_transfer",
+            Self::GitDestructive { .. } => "git_destructive",
             Self::ProtectedPath { .. } => "protected_path",
+            Self::OutsideWorkspace { .. } => "outside_workspace",
+            Self::SessionRisk { .. } => "session_risk",
+            Self::SecretSearch { .. } => "secret_search",
+            Self::McpArgument { .. } => "mcp_argument",
+            Self::SecretInUrl { .. } => "secret_in_url",
+            Self::RawIpAddress { .. } => "raw_ip_address",
+            Self::UnusualUrl { .. } => "unusual_url",
+            Self::LargeWrite { .. } => "large_write",
+            Self::ControlCharacters { .. } => "control_characters",
+            Self::PromptInjection { .. } => "prompt_injection",
             Self::NetworkExfiltration { .. } => "network_exfiltration",
+            Self::TaintedNetwork { .. } => "tainted_network",
+            Self::ExecutableWrite { .. } => "executable_write",
+            Self::PackageInstall { .. } => "package_install",
             Self::InternalError { .. } => "internal_error",
         }
     }
+
+    /// Identify the rule behind this violation, without the matched text.
+    ///
+    /// Violations with the same rule ID come from one rule applied to
+    /// different input, e.g. `git clean -fd` and `git clean -fd build/`.
+    pub fn rule_id(&self) -> String {
+        let rule = match self {
+            Self::SecretDetected { secret_type, .. } => Some(secret_type),
+            Self::DangerousCommand { pattern, rule, .. } => Some(rule.as_ref().unwrap_or(pattern)),
+            Self::ObfuscatedCommand { technique, .. } | Self::ReverseShell { technique, .. } => {
+                Some(technique)
+            }
+            Self::PipeToShell { downloader, .. } => Some(downloader),
+            Self::CommandNotAllowed { command } => Some(command),
+            Self::PrivilegeEscalation { target, .. } | Self::PermissionChange { target, .. } => {
+                Some(target)
+            }
+            Self::RemoteTransfer { host, .. } => Some(host),
+            Self::GitDestructive { rule, .. } | Self::McpArgument { rule, .. } => Some(rule),
+            Self::ProtectedPath { pattern, .. } | Self::SecretSearch { pattern, .. } => {
+                Some(pattern)
+            }
+            Self::SecretInUrl { domain, .. } | Self::NetworkExfiltration { domain } => Some(domain),
+            Self::TaintedNetwork { destination, .. } => Some(destination),
+            Self::RawIpAddress { address, .. } => Some(address),
+            Self::UnusualUrl { detail, .. } => Some(detail),
+            Self::PromptInjection { indicator, .. } => Some(indicator),
+            Self::ControlCharacters { kind, .. } => Some(kind),
+            Self::LargeWrite { path, .. }
+            | Self::OutsideWorkspace { path, .. }
+            | Self::ExecutableWrite { path, .. } => Some(path),
+            Self::PackageInstall {
+                ecosystem, package, ..
+            } => return format!("{}:{ecosystem}:{package}", self.code()),
+            Self::SessionRisk { .. } | Self::InternalError { .. } => None,
+        };
+        match rule {
+            Some(rule) => format!("{}:{rule}", self.code()),
+            None => self.code().to_string(),
+        }
+    }
+
+    /// Get the severity of this violation.
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::DangerousCommand { severity, .. } => severity.unwrap_or(Severity::Critical),
+            Self::SecretDetected { .. } | Self::SecretInUrl { .. } => Severity::Critical,
+            Self::ObfuscatedCommand { .. }
+            | Self::ReverseShell { .. }
+            | Self::PipeToShell { .. }
+            | Self::PrivilegeEscalation { .. }
+            | Self::PermissionChange { .. }
+            | Self::RemoteTransfer { .. }
+            | Self::GitDestructive { .. }
+            | Self::NetworkExfiltration { .. }
+            | Self::TaintedNetwork { .. }
+            | Self::McpArgument { .. }
+            | Self::ProtectedPath { .. }
+            | Self::PromptInjection { .. }
+            | Self::ControlCharacters { .. }
+            | Self::InternalError { .. } => Severity::High,
+            Self::CommandNotAllowed { .. }
+            | Self::OutsideWorkspace { .. }
+            | Self::SessionRisk { .. }
+            | Self::SecretSearch { .. }
+            | Self::LargeWrite { .. }
+            | Self::RawIpAddress { .. }
+            | Self::UnusualUrl { .. }
+            | Self::ExecutableWrite { .. }
+            | Self::PackageInstall { .. } => Severity::Medium,
+        }
+    }
 }
 
 impl fmt::Display for BlockReason {
@@ -66,24 +383,191 @@ impl fmt::Display for BlockReason {
             } => {
                 write!(f, "Secret detected ({secret_type}): {redacted}")
             }
-            Self::DangerousCommand { pattern, matched } => {
+            Self::DangerousCommand {
+                pattern,
+                matched,
+                impact,
+                rule,
+                description,
+                ask,
+                ..
+            } => {
+                match (rule, description, ask) {
+                    (Some(rule), Some(description), false) => {
+                        write!(f, "Blocked by rule '{rule}' ({description})")?;
+                    }
+                    (Some(rule), Some(description), true) => {
+                        write!(f, "Rule '{rule}' requires approval ({description})")?;
+                    }
+                    (Some(rule), None, false) => {
+                        write!(f, "Blocked by rule '{rule}': '{matched}'")?;
+                    }
+                    (Some(rule), None, true) => {
+                        write!(f, "Rule '{rule}' requires approval: '{matched}'")?;
+                    }
+                    (None, _, false) => write!(
+                        f,
+                        "Dangerous command blocked: '{matched}' matches pattern '{pattern}'"
+                    )?,
+                    (None, _, true) => write!(
+                        f,
+                        "Dangerous command requires approval: '{matched}' matches pattern '{pattern}'"
+                    )?,
+                }
+                if let Some(impact) = impact {
+                    write!(f, " (impact: {impact})")?;
+                }
+                Ok(())
+            }
+            Self::ObfuscatedCommand { technique, matched } => {
+                write!(f, "Obfuscated command blocked ({technique}): '{matched}'")
+            }
+            Self::ReverseShell { technique, matched } => {
+                write!(f, "Reverse shell blocked ({technique}): '{matched}'")
+            }
+            Self::PipeToShell {
+                downloader,
+                interpreter,
+                matched,
+            } => {
                 write!(
                     f,
-                    "Dangerous command blocked: '{matched}' matches pattern '{pattern}'"
+                    "Download piped to shell: '{matched}' runs {downloader} output with {interpreter}"
                 )
             }
+            Self::CommandNotAllowed { command } => {
+                write!(f, "Command not on the allow list: '{command}'")
+            }
+            Self::PrivilegeEscalation { program, target } => {
+                if target.is_empty() {
+                    write!(f, "Privilege escalation: {program} opens a root shell")
+                } else {
+                    write!(f, "Privilege escalation: {program} runs '{target}'")
+                }
+            }
+            Self::PermissionChange {
+                program,
+                target,
+                matched,
+            } => {
+                write!(
+                    f,
+                    "Recursive {program} on protected path '{target}': '{matched}'"
+                )
+            }
+            Self::RemoteTransfer {
+                program,
+                host,
+                matched,
+            } => {
+                write!(
+                    f,
+                    "Remote transfer: '{matched}' sends data to '{host}' via {program}"
+                )
+            }
+            Self::GitDestructive {
+                rule,
+                branch,
+                matched,
+            } => {
+                write!(f, "Destructive git operation ({rule}")?;
+                if let Some(branch) = branch {
+                    write!(f, " to '{branch}'")?;
+                }
+                write!(f, "): '{matched}'")
+            }
             Self::ProtectedPath { path, pattern } => {
                 write!(
                     f,
                     "Protected path blocked: '{path}' matches pattern '{pattern}'"
                 )
             }
+            Self::McpArgument { tool, rule, path } => {
+                write!(
+                    f,
+                    "MCP argument blocked: '{tool}' argument {path} matches rule '{rule}'"
+                )
+            }
+            Self::SecretInUrl {
+                domain,
+                secret_type,
+            } => {
+                write!(
+                    f,
+                    "Secret exfiltration blocked: {secret_type} in a URL to '{domain}'"
+                )
+            }
+            Self::RawIpAddress { address, private } => {
+                let scope = if *private { "private" } else { "public" };
+                write!(f, "Request to raw {scope} IP address '{address}'")
+            }
+            Self::UnusualUrl { url, detail } => {
+                write!(f, "Unusual URL '{url}': {detail}")
+            }
+            Self::LargeWrite { path, detail } => {
+                write!(f, "Large write to '{path}': {detail}")
+            }
+            Self::ControlCharacters {
+                field,
+                kind,
+                decoded,
+            } => {
+                write!(
+                    f,
+                    "Hidden control characters in {field} ({kind}): {decoded}"
+                )
+            }
+            Self::PromptInjection { tool, indicator } => {
+                write!(
+                    f,
+                    "Possible prompt injection in {tool} prompt ({indicator})"
+                )
+            }
+            Self::SecretSearch { pattern, kind } => {
+                write!(f, "Search for secrets ({kind}): '{pattern}'")
+            }
+            Self::OutsideWorkspace { path, root } => {
+                write!(
+                    f,
+                    "Path outside the workspace: '{path}' is not under '{root}'"
+                )
+            }
+            Self::SessionRisk { score, threshold } => {
+                write!(
+                    f,
+                    "Session risk score {score} reached the threshold of {threshold}"
+                )
+            }
+            Self::TaintedNetwork {
+                source,
+                destination,
+            } => {
+                write!(
+                    f,
+                    "Network access to '{destination}' after reading sensitive file '{source}'"
+                )
+            }
             Self::NetworkExfiltration { domain } => {
                 write!(
                     f,
                     "Network exfiltration blocked: domain '{domain}' is not allowed"
                 )
             }
+            Self::ExecutableWrite { path, detail } => {
+                write!(f, "Executable file write: '{path}' ({detail})")
+            }
+            Self::PackageInstall {
+                ecosystem,
+                package,
+                denied,
+            } => {
+                let list = if *denied {
+                    "is on the deny list"
+                } else {
+                    "is not on the allow list"
+                };
+                write!(f, "Package install: '{package}' ({ecosystem}) {list}")
+            }
             Self::InternalError { message } => {
                 write!(f, "Internal error: {message}")
             }
@@ -106,8 +590,45 @@ mod tests {
         let reason = BlockReason::DangerousCommand {
             pattern: "rm -rf".to_string(),
             matched: "rm -rf /".to_string(),
+            impact: None,
+            rule: None,
+            severity: None,
+            description: None,
+            ask: false,
         };
         assert_eq!(reason.code(), "dangerous_command");
+
+        let reason = BlockReason::ObfuscatedCommand {
+            technique: "decode_pipe_to_shell".to_string(),
+            matched: "echo cm0K | base64 -d | sh".to_string(),
+        };
+        assert_eq!(reason.code(), "obfuscated_command");
+    }
+
+    #[test]
+    fn test_block_reason_severity() {
+        let secret = BlockReason::SecretDetected {
+            secret_type: "aws_key".to_string(),
+            redacted: "AKIA...XXXX".to_string(),
+        };
+        let path = BlockReason::ProtectedPath {
+            path: ".env".to_string(),
+            pattern: "**/.env".to_string(),
+        };
+        assert_eq!(secret.severity(), Severity::Critical);
+        assert!(secret.severity() > path.severity());
+        assert!(Severity::Low < Severity::Medium);
+
+        let command = BlockReason::DangerousCommand {
+            pattern: "terraform destroy".to_string(),
+            matched: "terraform destroy".to_string(),
+            impact: None,
+            rule: None,
+            severity: Some(Severity::High),
+            description: None,
+            ask: false,
+        };
+        assert_eq!(command.severity(), Severity::High);
     }
 
     #[test]
@@ -121,11 +642,113 @@ mod tests {
         assert!(display.contains("github_token"));
     }
 
+    #[test]
+    fn test_dangerous_command_impact_display() {
+        let reason = BlockReason::DangerousCommand {
+            pattern: "rm -rf".to_string(),
+            matched: "rm -rf build".to_string(),
+            impact: Some("would remove 12 files (4.0 KB)".to_string()),
+            rule: None,
+            severity: None,
+            description: None,
+            ask: true,
+        };
+        assert_eq!(
+            reason.to_string(),
+            "Dangerous command requires approval: 'rm -rf build' matches pattern 'rm -rf' \
+             (impact: would remove 12 files (4.0 KB))"
+        );
+    }
+
+    #[test]
+    fn test_named_rule_display() {
+        let reason = BlockReason::DangerousCommand {
+            pattern: r"dd\s+.*of=/dev/".to_string(),
+            matched: "dd if=/dev/zero of=/dev/sda".to_string(),
+            impact: None,
+            rule: Some("no-disk-wipe".to_string()),
+            severity: None,
+            description: Some("dd onto block device".to_string()),
+            ask: false,
+        };
+        assert_eq!(
+            reason.to_string(),
+            "Blocked by rule 'no-disk-wipe' (dd onto block device)"
+        );
+
+        let reason = BlockReason::DangerousCommand {
+            pattern: r"dd\s+.*of=/dev/".to_string(),
+            matched: "dd if=/dev/zero of=/dev/sda".to_string(),
+            impact: None,
+            rule: Some("no-disk-wipe".to_string()),
+            severity: None,
+            description: None,
+            ask: false,
+        };
+        assert_eq!(
+            reason.to_string(),
+            "Blocked by rule 'no-disk-wipe': 'dd if=/dev/zero of=/dev/sda'"
+        );
+
+        let reason = BlockReason::DangerousCommand {
+            pattern: r"dd\s+.*of=/dev/".to_string(),
+            matched: "dd if=/dev/zero of=/dev/sda".to_string(),
+            impact: None,
+            rule: Some("no-disk-wipe".to_string()),
+            severity: None,
+            description: Some("dd onto block device".to_string()),
+            ask: true,
+        };
+        assert_eq!(
+            reason.to_string(),
+            "Rule 'no-disk-wipe' requires approval (dd onto block device)"
+        );
+    }
+
+    #[test]
+    fn test_git_destructive_display() {
+        let reason = BlockReason::GitDestructive {
+            rule: "force_push".to_string(),
+            branch: Some("main".to_string()),
+            matched: "git push -f origin main".to_string(),
+        };
+        assert_eq!(
+            reason.to_string(),
+            "Destructive git operation (force_push to 'main'): 'git push -f origin main'"
+        );
+    }
+
+    #[test]
+    fn test_rule_id_ignores_matched_text() {
+        let clean = |matched: &str| BlockReason::GitDestructive {
+            rule: "clean".to_string(),
+            branch: None,
+            matched: matched.to_string(),
+        };
+        assert_eq!(clean("git clean -fd").rule_id(), "git_destructive:clean");
+        assert_eq!(
+            clean("git clean -fd").rule_id(),
+            clean("git clean -fdx build/").rule_id()
+        );
+
+        let package = BlockReason::PackageInstall {
+            ecosystem: "npm".to_string(),
+            package: "left-pad".to_string(),
+            denied: false,
+        };
+        assert_eq!(package.rule_id(), "package_install:npm:left-pad");
+    }
+
     #[test]
     fn test_block_reason_serialization() {
         let reason = BlockReason::DangerousCommand {
             pattern: "rm -rf".to_string(),
             matched: "rm -rf /".to_string(),
+            impact: None,
+            rule: None,
+            severity: None,
+            description: None,
+            ask: false,
         };
 
         let json = serde_json::to_string(&reason).unwrap();
@@ -2,22 +2,51 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::block_reason::Severity;
+
+/// Config schema version this build reads; see [`Config::version`].
+pub const CONFIG_VERSION: u32 = 1;
+
 /// Root configuration structure.
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Config {
+    /// Schema version the file was written for (default: 1). Files for an
+    /// older schema are upgraded on load.
+    #[serde(default = "default_config_version")]
+    pub version: u32,
     /// Policy settings.
     #[serde(default)]
     pub policy: PolicyConfig,
     /// Tool-level permissions.
     #[serde(default)]
     pub tools: ToolsConfig,
+    /// Policy assertions (`[[tests]]`), run by `railgun lint --run-tests`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<PolicyCase>,
+}
+
+fn default_config_version() -> u32 {
+    1
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            policy: PolicyConfig::default(),
+            tools: ToolsConfig::default(),
+            tests: Vec::new(),
+        }
+    }
 }
 
 /// Tool-level permission configuration.
 ///
 /// These patterns are checked BEFORE parameter inspection.
 /// Patterns use glob syntax (e.g., "mcp__*", "Read", "Bash").
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ToolsConfig {
     /// Tools that always proceed without inspection.
     #[serde(default)]
@@ -28,166 +57,1492 @@ pub struct ToolsConfig {
     /// Tools that require user confirmation.
     #[serde(default)]
     pub ask: Vec<String>,
+    /// Tools that proceed to parameter inspection. Only meaningful with a
+    /// `default` other than allow, since unlisted tools are inspected anyway.
+    #[serde(default)]
+    pub inspect: Vec<String>,
+    /// Action for tools not matched by any list or MCP rule (default:
+    /// allow, meaning they go through parameter inspection).
+    #[serde(default = "default_tools_default")]
+    pub default: RuleAction,
     /// MCP tool configuration.
     #[serde(default)]
     pub mcp: McpConfig,
 }
 
-/// MCP tool permission configuration.
-#[derive(Debug, Deserialize, Serialize, Clone, Default)]
-pub struct McpConfig {
-    /// MCP servers to allow (glob patterns on server name).
-    /// Example: `["context7", "devtools"]` allows `mcp__context7__*` and `mcp__devtools__*`
+fn default_tools_default() -> RuleAction {
+    RuleAction::Allow
+}
+
+impl Default for ToolsConfig {
+    fn default() -> Self {
+        Self {
+            allow: Vec::new(),
+            deny: Vec::new(),
+            ask: Vec::new(),
+            inspect: Vec::new(),
+            default: default_tools_default(),
+            mcp: McpConfig::default(),
+        }
+    }
+}
+
+/// MCP tool permission configuration.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct McpConfig {
+    /// MCP servers to allow (glob patterns on server name).
+    /// Example: `["context7", "devtools"]` allows `mcp__context7__*` and `mcp__devtools__*`
+    #[serde(default)]
+    pub allow_servers: Vec<String>,
+    /// MCP servers to deny.
+    #[serde(default)]
+    pub deny_servers: Vec<String>,
+    /// MCP servers requiring user confirmation.
+    #[serde(default)]
+    pub ask_servers: Vec<String>,
+    /// Per-tool rules, checked before the server lists so a single tool can
+    /// be treated differently from the rest of its server.
+    #[serde(default)]
+    pub rules: Vec<McpToolRule>,
+    /// Rules on tool arguments, checked even for tools allowed above.
+    #[serde(default)]
+    pub arg_rules: Vec<McpArgRule>,
+}
+
+/// A rule for individual MCP tools (`[[tools.mcp.rules]]`).
+///
+/// Example: allow the `github` server but deny its `delete_repository` tool.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct McpToolRule {
+    /// Server name (glob pattern).
+    pub server: String,
+    /// Tool name within the server (glob pattern, default: `*`).
+    #[serde(default = "default_mcp_rule_tool")]
+    pub tool: String,
+    /// Action for matching tools.
+    pub action: RuleAction,
+}
+
+fn default_mcp_rule_tool() -> String {
+    "*".to_string()
+}
+
+/// A rule on MCP tool arguments (`[[tools.mcp.arg_rules]]`).
+///
+/// Selects values from the tool input with a JSONPath-style `path` and
+/// matches every string under them against `regex` and/or `glob`.
+///
+/// Example: deny any MCP argument containing `DROP TABLE`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct McpArgRule {
+    /// Rule name used in messages (default: the regex or glob).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Full tool name (glob pattern, default: `mcp__*`).
+    #[serde(default = "default_mcp_arg_tool")]
+    pub tool: String,
+    /// JSONPath-style selector: `$`, `.key`, `['key']`, `[0]`, `[*]`, `.*`,
+    /// and `..` for recursive descent (default: `$`, the whole input).
+    #[serde(default = "default_mcp_arg_path")]
+    pub path: String,
+    /// Regex a selected string must match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+    /// Glob a selected string must match in full.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glob: Option<String>,
+    /// Action for matches (default: deny).
+    #[serde(default = "default_mcp_arg_action")]
+    pub action: RuleAction,
+}
+
+fn default_mcp_arg_tool() -> String {
+    "mcp__*".to_string()
+}
+
+fn default_mcp_arg_path() -> String {
+    "$".to_string()
+}
+
+fn default_mcp_arg_action() -> RuleAction {
+    RuleAction::Deny
+}
+
+/// Policy configuration for LLM protection.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[allow(clippy::struct_excessive_bools)] // Independent top-level switches
+pub struct PolicyConfig {
+    /// Operation mode (strict or monitor).
+    #[serde(default)]
+    pub mode: PolicyMode,
+    /// Fail closed on errors (default: true).
+    #[serde(default = "default_fail_closed")]
+    pub fail_closed: bool,
+    /// Inspection time budget, in milliseconds. Checks still pending when it
+    /// runs out are skipped and the call is denied (with `fail_closed`) or
+    /// asked about (default: no budget). Best-effort: the clock is read
+    /// between checks, and only the impact estimate stops mid-check.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_latency_ms: Option<u64>,
+    /// Match tool input after Unicode normalization: NFKC, invisible
+    /// characters dropped, and look-alike letters folded to ASCII
+    /// (default: true).
+    #[serde(default = "default_true")]
+    pub normalize_unicode: bool,
+    /// In the global config: a project config may tighten the policy but
+    /// not weaken it (default: false).
+    #[serde(default)]
+    pub locked: bool,
+    /// Policy packs to enable by name, e.g. `["kubernetes", "aws"]`. Their
+    /// rules are added to the ones configured here.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packs: Vec<String>,
+    /// Directory of custom `NAME.toml` packs, relative to the config file
+    /// (default: `.railgun/packs`). A pack here overrides the built-in pack
+    /// of the same name.
+    #[serde(default = "default_packs_dir")]
+    pub packs_dir: String,
+    /// Refuse to load config files and custom packs without a valid
+    /// signature (`FILE.sig`) by one of `trusted_keys` (default: false).
+    #[serde(default)]
+    pub require_signed: bool,
+    /// Base64 Ed25519 public keys whose signatures are accepted. In a
+    /// layered setup, the global config's keys are the ones used.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_keys: Vec<String>,
+    /// HTTPS URL of an organization policy to load under this config: the
+    /// settings here override it, unless it sets `locked`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_url: Option<String>,
+    /// Seconds a fetched remote policy is used before it is fetched again
+    /// (default: 3600).
+    #[serde(default = "default_remote_ttl_secs")]
+    pub remote_ttl_secs: u64,
+    /// Timeout for fetching the remote policy, in milliseconds
+    /// (default: 3000).
+    #[serde(default = "default_remote_timeout_ms")]
+    pub remote_timeout_ms: u64,
+    /// Fail to load if the remote policy can't be fetched and no copy is
+    /// cached (default: true). Otherwise the local config is used alone.
+    #[serde(default = "default_true")]
+    pub remote_fail_closed: bool,
+    /// Where the fetched remote policy is cached, relative to the project's
+    /// state directory (default: `remote.toml`).
+    #[serde(default = "default_remote_cache")]
+    pub remote_cache: String,
+    /// Caps on hook input size.
+    #[serde(default)]
+    pub limits: LimitsConfig,
+    /// Secret scanning configuration.
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+    /// Dangerous command detection.
+    #[serde(default)]
+    pub commands: CommandsConfig,
+    /// Protected path configuration.
+    #[serde(default)]
+    pub protected_paths: ProtectedPathsConfig,
+    /// Network exfiltration detection.
+    #[serde(default)]
+    pub network: NetworkConfig,
+    /// Executable file write detection.
+    #[serde(default)]
+    pub executables: ExecutablesConfig,
+    /// Package installation policy.
+    #[serde(default)]
+    pub packages: PackagesConfig,
+    /// File access outside the project directory.
+    #[serde(default)]
+    pub workspace: WorkspaceConfig,
+    /// Large and binary file writes.
+    #[serde(default)]
+    pub writes: WritesConfig,
+    /// Control characters and terminal escapes in commands and writes.
+    #[serde(default)]
+    pub control_chars: ControlCharsConfig,
+    /// Prompt injection in `Task` and `WebFetch` prompts.
+    #[serde(default)]
+    pub injection: InjectionConfig,
+    /// Policy limit advertisement to the model.
+    #[serde(default)]
+    pub advertise: AdvertiseConfig,
+    /// Remembered approvals of `ask` verdicts.
+    #[serde(default)]
+    pub approvals: ApprovalsConfig,
+    /// Per-session state shared across hook invocations.
+    #[serde(default)]
+    pub session: SessionConfig,
+    /// Interactive approval of `ask` verdicts through `railgun approve`.
+    #[serde(default)]
+    pub approve: ApproveConfig,
+    /// Audit log of hook decisions, queried by `railgun log`.
+    #[serde(default)]
+    pub audit: AuditConfig,
+    /// `OpenTelemetry` span export of inspections.
+    #[serde(default)]
+    pub otel: OtelConfig,
+    /// Webhook alerts for denied and asked calls.
+    #[serde(default)]
+    pub webhook: WebhookConfig,
+    /// Decision records sent to syslog or journald.
+    #[serde(default)]
+    pub syslog: SyslogConfig,
+    /// `StatsD` metrics over UDP.
+    #[serde(default)]
+    pub statsd: StatsdConfig,
+}
+
+fn default_fail_closed() -> bool {
+    true
+}
+
+fn default_remote_ttl_secs() -> u64 {
+    3600
+}
+
+fn default_remote_timeout_ms() -> u64 {
+    3000
+}
+
+fn default_remote_cache() -> String {
+    "remote.toml".to_string()
+}
+
+fn default_packs_dir() -> String {
+    ".railgun/packs".to_string()
+}
+
+impl Default for PolicyConfig {
+    fn default() -> Self {
+        Self {
+            mode: PolicyMode::default(),
+            fail_closed: default_fail_closed(),
+            max_latency_ms: None,
+            normalize_unicode: true,
+            locked: false,
+            packs: Vec::new(),
+            packs_dir: default_packs_dir(),
+            require_signed: false,
+            trusted_keys: Vec::new(),
+            remote_url: None,
+            remote_ttl_secs: default_remote_ttl_secs(),
+            remote_timeout_ms: default_remote_timeout_ms(),
+            remote_fail_closed: true,
+            remote_cache: default_remote_cache(),
+            limits: LimitsConfig::default(),
+            secrets: SecretsConfig::default(),
+            commands: CommandsConfig::default(),
+            protected_paths: ProtectedPathsConfig::default(),
+            network: NetworkConfig::default(),
+            executables: ExecutablesConfig::default(),
+            packages: PackagesConfig::default(),
+            workspace: WorkspaceConfig::default(),
+            writes: WritesConfig::default(),
+            control_chars: ControlCharsConfig::default(),
+            injection: InjectionConfig::default(),
+            advertise: AdvertiseConfig::default(),
+            approvals: ApprovalsConfig::default(),
+            session: SessionConfig::default(),
+            approve: ApproveConfig::default(),
+            audit: AuditConfig::default(),
+            otel: OtelConfig::default(),
+            webhook: WebhookConfig::default(),
+            syslog: SyslogConfig::default(),
+            statsd: StatsdConfig::default(),
+        }
+    }
+}
+
+/// Policy operation mode.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyMode {
+    /// Block actions that violate policy.
+    #[default]
+    Strict,
+    /// Log violations but allow all actions.
+    Monitor,
+}
+
+/// Policy limit advertisement configuration.
+///
+/// When enabled, the `SessionStart` hook tells the model which categories of
+/// action are denied or gated, so it can plan around them instead of
+/// discovering them one denial at a time. Specific domains and path patterns
+/// are never included.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AdvertiseConfig {
+    /// Include a summary of hard limits in `additionalContext` (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// Maximum size of the summary in bytes (default: 1024).
+    #[serde(default = "default_advertise_max_bytes")]
+    pub max_bytes: usize,
+}
+
+fn default_advertise_max_bytes() -> usize {
+    1024
+}
+
+impl Default for AdvertiseConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_bytes: default_advertise_max_bytes(),
+        }
+    }
+}
+
+/// Approval memory configuration.
+///
+/// When enabled, a tool call the user approved after an `ask` verdict is
+/// allowed without asking again if the identical call (same tool, working
+/// directory, and normalized input) repeats within `ttl_secs`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ApprovalsConfig {
+    /// Remember approved `ask` decisions (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// How long an approval stays valid, in seconds (default: 3600).
+    #[serde(default = "default_approvals_ttl_secs")]
+    pub ttl_secs: u64,
+    /// Approval state file, relative to the project's state directory
+    /// outside the project (default: `approvals.json`).
+    #[serde(default = "default_approvals_path")]
+    pub path: String,
+}
+
+fn default_approvals_ttl_secs() -> u64 {
+    3600
+}
+
+fn default_approvals_path() -> String {
+    "approvals.json".to_string()
+}
+
+impl Default for ApprovalsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: default_approvals_ttl_secs(),
+            path: default_approvals_path(),
+        }
+    }
+}
+
+/// Session state configuration.
+///
+/// Session state lives in one small file per Claude Code session, removed
+/// when the session ends.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SessionConfig {
+    /// After the user approves an `ask`, allow the same rule for the rest of
+    /// the session (default: false).
+    #[serde(default)]
+    pub allow_approved: bool,
+    /// Session state directory, relative to the project's state directory
+    /// outside the project (default: `sessions`).
+    #[serde(default = "default_session_dir")]
+    pub dir: String,
+    /// Remove state of sessions that never ended cleanly after this many
+    /// seconds (default: 86400).
+    #[serde(default = "default_session_max_age_secs")]
+    pub max_age_secs: u64,
+    /// Cumulative session risk scoring.
+    #[serde(default)]
+    pub risk: RiskConfig,
+    /// Taint tracking of sensitive file reads.
+    #[serde(default)]
+    pub taint: TaintConfig,
+}
+
+fn default_session_dir() -> String {
+    "sessions".to_string()
+}
+
+fn default_session_max_age_secs() -> u64 {
+    86400
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            allow_approved: false,
+            dir: default_session_dir(),
+            max_age_secs: default_session_max_age_secs(),
+            risk: RiskConfig::default(),
+            taint: TaintConfig::default(),
+        }
+    }
+}
+
+/// Session risk scoring configuration.
+///
+/// Every tool call adds points for the borderline things it does; once the
+/// session's score reaches `threshold`, calls that would be allowed are asked
+/// about instead.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RiskConfig {
+    /// Track a risk score per session (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// Score at which allowed calls are escalated to ask (default: 30).
+    #[serde(default = "default_risk_threshold")]
+    pub threshold: u32,
+    /// Points for a command run through `sudo`, `doas`, `pkexec`, or `su`
+    /// (default: 5).
+    #[serde(default = "default_risk_privilege")]
+    pub privilege: u32,
+    /// Points for a call that contacts the network (default: 2).
+    #[serde(default = "default_risk_network")]
+    pub network: u32,
+    /// Points for each domain not contacted earlier in the session
+    /// (default: 3).
+    #[serde(default = "default_risk_new_domain")]
+    pub new_domain: u32,
+    /// Points for a file write or edit (default: 1).
+    #[serde(default = "default_risk_write")]
+    pub write: u32,
+    /// Points for a call that got an `ask` verdict (default: 2).
+    #[serde(default = "default_risk_ask")]
+    pub ask: u32,
+    /// Points for a call that got a `deny` verdict (default: 5).
+    #[serde(default = "default_risk_deny")]
+    pub deny: u32,
+}
+
+fn default_risk_threshold() -> u32 {
+    30
+}
+
+fn default_risk_privilege() -> u32 {
+    5
+}
+
+fn default_risk_network() -> u32 {
+    2
+}
+
+fn default_risk_new_domain() -> u32 {
+    3
+}
+
+fn default_risk_write() -> u32 {
+    1
+}
+
+fn default_risk_ask() -> u32 {
+    2
+}
+
+fn default_risk_deny() -> u32 {
+    5
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: default_risk_threshold(),
+            privilege: default_risk_privilege(),
+            network: default_risk_network(),
+            new_domain: default_risk_new_domain(),
+            write: default_risk_write(),
+            ask: default_risk_ask(),
+            deny: default_risk_deny(),
+        }
+    }
+}
+
+/// Session taint tracking configuration.
+///
+/// Once a session has read a sensitive file (even an allowed one), calls that
+/// contact the network get `action`: the data may be about to leave.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TaintConfig {
+    /// Track sensitive reads per session (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// Glob patterns for files whose contents taint the session.
+    #[serde(default = "default_taint_sensitive_paths")]
+    pub sensitive_paths: Vec<String>,
+    /// Action for network access from a tainted session (default: ask).
+    #[serde(default)]
+    pub action: RuleAction,
+}
+
+fn default_taint_sensitive_paths() -> Vec<String> {
+    vec![
+        "**/.env".to_string(),
+        "**/.env.local".to_string(),
+        "**/.env.production".to_string(),
+        "**/*.pem".to_string(),
+        "**/*.key".to_string(),
+        "**/id_rsa*".to_string(),
+        "**/id_ed25519*".to_string(),
+        "**/.ssh/**".to_string(),
+        "**/.aws/credentials".to_string(),
+        "**/.netrc".to_string(),
+        "**/.npmrc".to_string(),
+        "**/.pypirc".to_string(),
+        "**/.docker/config.json".to_string(),
+        "**/.kube/config".to_string(),
+        "**/*credentials*.json".to_string(),
+    ]
+}
+
+impl Default for TaintConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sensitive_paths: default_taint_sensitive_paths(),
+            action: RuleAction::Ask,
+        }
+    }
+}
+
+/// Interactive approval channel configuration.
+///
+/// When enabled, the hook forwards `ask` verdicts to `railgun approve` over a
+/// Unix socket and waits for the answer, falling back to Claude Code's own
+/// prompt if nobody is listening or the answer doesn't arrive in time.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ApproveConfig {
+    /// Forward `ask` verdicts to `railgun approve` (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// Socket path, relative to the project's directory in the per-user
+    /// runtime directory, outside the project (default: `approve.sock`).
+    #[serde(default = "default_approve_socket")]
+    pub socket: String,
+    /// How long the hook waits for an answer, in seconds (default: 30, at
+    /// most 30). Kept well under the installed hook timeout, so railgun
+    /// gives up before Claude Code kills the hook.
+    #[serde(default = "default_approve_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_approve_socket() -> String {
+    "approve.sock".to_string()
+}
+
+fn default_approve_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for ApproveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            socket: default_approve_socket(),
+            timeout_secs: default_approve_timeout_secs(),
+        }
+    }
+}
+
+/// Input size limits.
+///
+/// Inputs over a limit aren't scanned; the call gets `action` instead, so a
+/// multi-megabyte payload can't stall the hook in regex scans.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct LimitsConfig {
+    /// Largest hook payload, in bytes (default: 8388608).
+    #[serde(default = "default_max_input_bytes")]
+    pub max_input_bytes: usize,
+    /// Largest string anywhere in the tool input, in bytes (default: 1048576).
+    #[serde(default = "default_max_field_bytes")]
+    pub max_field_bytes: usize,
+    /// Decision for inputs over a limit: `ask` or `deny` (default: ask).
+    /// `allow` turns the limits off and scans everything.
+    #[serde(default)]
+    pub action: RuleAction,
+}
+
+fn default_max_input_bytes() -> usize {
+    8 * 1024 * 1024
+}
+
+fn default_max_field_bytes() -> usize {
+    1024 * 1024
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_input_bytes: default_max_input_bytes(),
+            max_field_bytes: default_max_field_bytes(),
+            action: RuleAction::default(),
+        }
+    }
+}
+
+/// Audit log configuration.
+///
+/// When enabled, the hook appends one JSON line per tool call it decides:
+/// the tool, the decision and its reason, and the rules behind it.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AuditConfig {
+    /// Record hook decisions (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// Audit log file, relative to the project's state directory
+    /// (default: `audit.jsonl`).
+    #[serde(default = "default_audit_path")]
+    pub path: String,
+}
+
+fn default_audit_path() -> String {
+    "audit.jsonl".to_string()
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: default_audit_path(),
+        }
+    }
+}
+
+/// `OpenTelemetry` export configuration.
+///
+/// When enabled, every inspection is sent as a span over OTLP/HTTP (JSON
+/// encoding) to `{endpoint}/v1/traces`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OtelConfig {
+    /// Export a span per inspection (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// OTLP/HTTP base URL (default: `OTEL_EXPORTER_OTLP_ENDPOINT`, else
+    /// `http://localhost:4318`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Extra request headers, e.g. for authentication.
+    #[serde(default)]
+    pub headers: std::collections::BTreeMap<String, String>,
+    /// `service.name` of the exported spans (default: `railgun`).
+    #[serde(default = "default_otel_service_name")]
+    pub service_name: String,
+    /// How long to wait for the collector, in milliseconds (default: 500).
+    #[serde(default = "default_otel_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_otel_service_name() -> String {
+    "railgun".to_string()
+}
+
+fn default_otel_timeout_ms() -> u64 {
+    500
+}
+
+impl Default for OtelConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: None,
+            headers: std::collections::BTreeMap::new(),
+            service_name: default_otel_service_name(),
+            timeout_ms: default_otel_timeout_ms(),
+        }
+    }
+}
+
+/// Webhook alert configuration.
+///
+/// When enabled, calls with a decision in `events` are queued in `spool`
+/// (secrets redacted) and posted to `url` as a Slack-compatible message
+/// once the oldest has waited `batch_secs` or `max_batch` are queued.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WebhookConfig {
+    /// Post alerts (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// Incoming webhook URL, e.g. a Slack incoming webhook.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Decisions that raise an alert (default: `["ask", "deny"]`).
+    #[serde(default = "default_webhook_events")]
+    pub events: Vec<RuleAction>,
+    /// Collect alerts for this long before posting them together, in
+    /// seconds (default: 0, post each one right away).
+    #[serde(default)]
+    pub batch_secs: u64,
+    /// Post once this many alerts are queued, however recent (default: 20).
+    #[serde(default = "default_webhook_max_batch")]
+    pub max_batch: usize,
+    /// Retries of a failed post before leaving the batch for the next call
+    /// (default: 2).
+    #[serde(default = "default_webhook_retries")]
+    pub retries: u32,
+    /// How long to wait for the server per attempt, in milliseconds
+    /// (default: 2000).
+    #[serde(default = "default_webhook_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Queue of unsent alerts, relative to the project's state directory
+    /// (default: `webhook.jsonl`).
+    #[serde(default = "default_webhook_spool")]
+    pub spool: String,
+}
+
+fn default_webhook_events() -> Vec<RuleAction> {
+    vec![RuleAction::Ask, RuleAction::Deny]
+}
+
+fn default_webhook_max_batch() -> usize {
+    20
+}
+
+fn default_webhook_retries() -> u32 {
+    2
+}
+
+fn default_webhook_timeout_ms() -> u64 {
+    2000
+}
+
+fn default_webhook_spool() -> String {
+    "webhook.jsonl".to_string()
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: None,
+            events: default_webhook_events(),
+            batch_secs: 0,
+            max_batch: default_webhook_max_batch(),
+            retries: default_webhook_retries(),
+            timeout_ms: default_webhook_timeout_ms(),
+            spool: default_webhook_spool(),
+        }
+    }
+}
+
+/// Syslog output configuration.
+///
+/// When enabled, calls with a decision in `events` are written as structured
+/// records to the local syslog daemon or to journald.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SyslogConfig {
+    /// Write decision records (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// Where records go (default: syslog).
+    #[serde(default)]
+    pub target: SyslogTarget,
+    /// Syslog facility (default: user).
+    #[serde(default)]
+    pub facility: SyslogFacility,
+    /// `SYSLOG_IDENTIFIER` / tag of each record (default: `railgun`).
+    #[serde(default = "default_syslog_ident")]
+    pub ident: String,
+    /// Decisions that are recorded (default: `["deny"]`).
+    #[serde(default = "default_syslog_events")]
+    pub events: Vec<RuleAction>,
+    /// Socket to write to, instead of `/dev/log` or
+    /// `/run/systemd/journal/socket`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket: Option<String>,
+}
+
+fn default_syslog_ident() -> String {
+    "railgun".to_string()
+}
+
+fn default_syslog_events() -> Vec<RuleAction> {
+    vec![RuleAction::Deny]
+}
+
+impl Default for SyslogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target: SyslogTarget::default(),
+            facility: SyslogFacility::default(),
+            ident: default_syslog_ident(),
+            events: default_syslog_events(),
+            socket: None,
+        }
+    }
+}
+
+/// Where `policy.syslog` records are written.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum SyslogTarget {
+    /// RFC 5424 messages to the local syslog socket.
+    #[default]
+    Syslog,
+    /// Native journald entries, with each field of the record as a journal
+    /// field.
+    Journald,
+}
+
+/// Syslog facility of `policy.syslog` records.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum SyslogFacility {
+    /// User-level messages.
+    #[default]
+    User,
+    /// System daemons.
+    Daemon,
+    /// Security/authorization messages.
+    Auth,
+    /// Private security/authorization messages.
+    Authpriv,
+    /// Local use 0.
+    Local0,
+    /// Local use 1.
+    Local1,
+    /// Local use 2.
+    Local2,
+    /// Local use 3.
+    Local3,
+    /// Local use 4.
+    Local4,
+    /// Local use 5.
+    Local5,
+    /// Local use 6.
+    Local6,
+    /// Local use 7.
+    Local7,
+}
+
+impl SyslogFacility {
+    /// The facility's numeric code.
+    pub fn code(self) -> u8 {
+        match self {
+            Self::User => 1,
+            Self::Daemon => 3,
+            Self::Auth => 4,
+            Self::Authpriv => 10,
+            Self::Local0 => 16,
+            Self::Local1 => 17,
+            Self::Local2 => 18,
+            Self::Local3 => 19,
+            Self::Local4 => 20,
+            Self::Local5 => 21,
+            Self::Local6 => 22,
+            Self::Local7 => 23,
+        }
+    }
+}
+
+/// `StatsD` metrics configuration.
+///
+/// When enabled, every inspection sends one UDP datagram with a counter for
+/// its decision, a counter per scanner behind it, and its latency as a timer.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct StatsdConfig {
+    /// Send metrics (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// `host:port` of the `StatsD` server (default: `127.0.0.1:8125`).
+    #[serde(default = "default_statsd_address")]
+    pub address: String,
+    /// Prefix of every metric name (default: `railgun`).
+    #[serde(default = "default_statsd_prefix")]
+    pub prefix: String,
+    /// Put the decision and scanner in `DogStatsD` tags rather than in the
+    /// metric name (default: false).
+    #[serde(default)]
+    pub tags: bool,
+}
+
+fn default_statsd_address() -> String {
+    "127.0.0.1:8125".to_string()
+}
+
+fn default_statsd_prefix() -> String {
+    "railgun".to_string()
+}
+
+impl Default for StatsdConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: default_statsd_address(),
+            prefix: default_statsd_prefix(),
+            tags: false,
+        }
+    }
+}
+
+/// Action taken when a rule matches, ordered from least to most strict.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    /// Let the tool call proceed.
+    Allow,
+    /// Prompt the user for confirmation.
+    #[default]
+    Ask,
+    /// Block the tool call.
+    Deny,
+}
+
+impl std::fmt::Display for RuleAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Allow => write!(f, "allow"),
+            Self::Ask => write!(f, "ask"),
+            Self::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+/// Action taken when a secret is detected.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum SecretAction {
+    /// Block the tool call.
+    #[default]
+    Deny,
+    /// Prompt the user for confirmation.
+    Ask,
+    /// Let the tool call proceed with each secret replaced by
+    /// `[REDACTED:type]` in the tool input.
+    Redact,
+}
+
+/// Secret scanning configuration.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[allow(clippy::struct_excessive_bools)] // Config structs intentionally use many bools
+pub struct SecretsConfig {
+    /// Enable secret scanning (default: true).
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// What to do when a secret is found (default: deny).
+    #[serde(default)]
+    pub action: SecretAction,
+    /// Entropy threshold for generic secret detection (default: 4.5).
+    #[serde(default = "default_entropy_threshold")]
+    pub entropy_threshold: f64,
+    /// Detect AWS access keys.
+    #[serde(default = "default_true")]
+    pub detect_aws_keys: bool,
+    /// Detect GitHub tokens.
+    #[serde(default = "default_true")]
+    pub detect_github_tokens: bool,
+    /// Skip classic GitHub tokens (`ghp_`, `gho_`, ...) whose embedded CRC32
+    /// checksum does not match, i.e. random strings of the same shape.
+    #[serde(default = "default_true")]
+    pub validate_github_checksums: bool,
+    /// Detect `OpenAI` API keys.
+    #[serde(default = "default_true")]
+    pub detect_openai_keys: bool,
+    /// Detect private keys (PEM format).
+    #[serde(default = "default_true")]
+    pub detect_private_keys: bool,
+    /// Detect npm automation/publish tokens.
+    #[serde(default = "default_true")]
+    pub detect_npm_tokens: bool,
+    /// Detect `PyPI` upload tokens.
+    #[serde(default = "default_true")]
+    pub detect_pypi_tokens: bool,
+    /// Base64-decode long candidate strings and rescan them for secrets.
+    #[serde(default = "default_true")]
+    pub scan_base64: bool,
+    /// Detect JSON Web Tokens.
+    #[serde(default = "default_true")]
+    pub detect_jwts: bool,
+    /// Custom secret detection rules.
+    #[serde(default)]
+    pub rules: Vec<SecretRule>,
+    /// Path to a gitleaks-compatible TOML ruleset whose rules are appended
+    /// to `rules` when the configuration is loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ruleset_path: Option<String>,
+    /// Decode JWT headers to skip unsigned (`alg: none`) example tokens.
+    #[serde(default = "default_true")]
+    pub jwt_decode_header: bool,
+    /// Baseline file of known false positives, relative to the config file
+    /// (default: `.railgun-baseline.json`).
+    #[serde(default = "default_baseline_path")]
+    pub baseline_path: String,
+    /// Fingerprints of baselined secrets. Matches hashing to one of these are
+    /// skipped; entries from `baseline_path` are appended on load.
+    #[serde(default)]
+    pub baseline: Vec<String>,
+    /// Yelp detect-secrets baseline, relative to the config file (default:
+    /// `.secrets.baseline`). Used as an extra suppression source if present.
+    #[serde(default = "default_detect_secrets_baseline_path")]
+    pub detect_secrets_baseline_path: String,
+    /// SHA-1 hashes of secrets suppressed by a detect-secrets baseline;
+    /// entries from `detect_secrets_baseline_path` are appended on load.
+    #[serde(default)]
+    pub detect_secrets_hashes: Vec<String>,
+    /// Action for `Grep` searches that look like secret hunts, such as
+    /// `AKIA[0-9A-Z]{16}` or `password\s*=` (default: allow).
+    #[serde(default = "default_search_action")]
+    pub search_action: RuleAction,
+}
+
+fn default_baseline_path() -> String {
+    ".railgun-baseline.json".to_string()
+}
+
+fn default_detect_secrets_baseline_path() -> String {
+    ".secrets.baseline".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_search_action() -> RuleAction {
+    RuleAction::Allow
+}
+
+fn default_entropy_threshold() -> f64 {
+    4.5
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            action: SecretAction::default(),
+            entropy_threshold: default_entropy_threshold(),
+            detect_aws_keys: true,
+            detect_github_tokens: true,
+            validate_github_checksums: true,
+            detect_openai_keys: true,
+            detect_private_keys: true,
+            detect_npm_tokens: true,
+            detect_pypi_tokens: true,
+            scan_base64: true,
+            detect_jwts: true,
+            jwt_decode_header: true,
+            rules: Vec::new(),
+            ruleset_path: None,
+            baseline_path: default_baseline_path(),
+            baseline: Vec::new(),
+            detect_secrets_baseline_path: default_detect_secrets_baseline_path(),
+            detect_secrets_hashes: Vec::new(),
+            search_action: default_search_action(),
+        }
+    }
+}
+
+/// A custom secret detection rule.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SecretRule {
+    /// Rule identifier, reported as the secret type.
+    pub id: String,
+    /// Human-readable description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Regex matching the secret.
+    pub regex: String,
+    /// Capture group containing the secret (0 = whole match).
+    #[serde(default)]
+    pub secret_group: usize,
+    /// Minimum Shannon entropy of the secret for it to be reported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entropy: Option<f64>,
+    /// Case-insensitive keywords; the rule only runs if one is present.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+/// Dangerous command detection configuration.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[allow(clippy::struct_excessive_bools)] // One toggle per built-in detector
+pub struct CommandsConfig {
+    /// Enable command scanning (default: true).
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Patterns to block (regex, or a table with per-pattern settings).
+    #[serde(default = "default_block_patterns")]
+    pub block_patterns: Vec<CommandPattern>,
+    /// Patterns that require user confirmation (regex, or a table with
+    /// per-pattern settings).
+    #[serde(default = "default_ask_patterns")]
+    pub ask_patterns: Vec<CommandPattern>,
+    /// Patterns to allow (override blocks and asks).
+    #[serde(default)]
+    pub allow_patterns: Vec<String>,
+    /// Action for commands that match no allow pattern (default: allow).
+    ///
+    /// Set to `deny` or `ask` to run in allowlist mode, where only commands
+    /// matching `allow_patterns` run unprompted.
+    #[serde(default = "default_command_action")]
+    pub default_action: RuleAction,
+    /// Estimate the impact of ask-level commands before prompting (default: false).
+    ///
+    /// Inspects the filesystem (and runs `git clean --dry-run`) so the
+    /// confirmation prompt can say what would be removed.
+    #[serde(default)]
+    pub estimate_impact: bool,
+    /// Block commands that construct their payload at runtime (decoded data
+    /// piped to a shell, `eval "$(...)"`, hex-escaped command names).
+    #[serde(default = "default_true")]
+    pub detect_obfuscation: bool,
+    /// Block reverse shell one-liners (`nc -e /bin/sh`, `/dev/tcp`
+    /// redirection, socket shells in python/perl).
+    #[serde(default = "default_true")]
+    pub detect_reverse_shells: bool,
+    /// Action for downloads piped into an interpreter (`curl ... | bash`),
+    /// regardless of the domain (default: deny).
+    #[serde(default = "default_pipe_to_shell")]
+    pub pipe_to_shell: RuleAction,
+    /// Privilege escalation (`sudo`, `doas`, `su -c`) policy.
+    #[serde(default)]
+    pub sudo: SudoConfig,
+    /// Destructive git operation rules.
+    #[serde(default)]
+    pub git: GitConfig,
+    /// Recursive `chmod`/`chown` on system paths.
+    #[serde(default)]
+    pub permissions: PermissionsConfig,
+    /// Outbound `scp`/`rsync`/`ssh` transfers.
+    #[serde(default)]
+    pub transfers: TransfersConfig,
+}
+
+fn default_command_action() -> RuleAction {
+    RuleAction::Allow
+}
+
+fn default_pipe_to_shell() -> RuleAction {
+    RuleAction::Deny
+}
+
+fn default_block_patterns() -> Vec<CommandPattern> {
+    vec![
+        r"rm\s+-rf\s+[/~]".into(),
+        r">\s*/dev/sd[a-z]".into(),
+        r"mkfs\.".into(),
+        r"dd\s+if=.+of=/dev/".into(),
+        r"chmod\s+-R\s+777\s+/".into(),
+        r":\(\)\s*\{\s*:\|:&\s*\}\s*;".into(), // Fork bomb
+    ]
+}
+
+fn default_ask_patterns() -> Vec<CommandPattern> {
+    vec![
+        r"\brm\s+-[a-zA-Z]*([rR][a-zA-Z]*f|f[a-zA-Z]*[rR])".into(),
+        r"\bgit\s+clean\s+-[a-zA-Z]*f".into(),
+    ]
+}
+
+impl Default for CommandsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            block_patterns: default_block_patterns(),
+            ask_patterns: default_ask_patterns(),
+            allow_patterns: Vec::new(),
+            default_action: default_command_action(),
+            estimate_impact: false,
+            detect_obfuscation: true,
+            detect_reverse_shells: true,
+            pipe_to_shell: default_pipe_to_shell(),
+            sudo: SudoConfig::default(),
+            git: GitConfig::default(),
+            permissions: PermissionsConfig::default(),
+            transfers: TransfersConfig::default(),
+        }
+    }
+}
+
+/// A command pattern: a bare regex, or a table with per-pattern settings.
+///
+/// ```toml
+/// block_patterns = [
+///     "mkfs\\.",
+///     { name = "no-tf-destroy", pattern = "terraform\\s+destroy", action = "ask", description = "destroys infrastructure" },
+/// ]
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+pub enum CommandPattern {
+    /// A bare regex; uses the list's action and the default severity.
+    Regex(String),
+    /// A regex with its own severity, action, and description.
+    Rule(CommandRule),
+}
+
+impl CommandPattern {
+    /// The regex.
+    pub fn pattern(&self) -> &str {
+        match self {
+            Self::Regex(pattern) => pattern,
+            Self::Rule(rule) => &rule.pattern,
+        }
+    }
+
+    /// Severity override, if set.
+    pub fn severity(&self) -> Option<Severity> {
+        match self {
+            Self::Regex(_) => None,
+            Self::Rule(rule) => rule.severity,
+        }
+    }
+
+    /// Action override, if set.
+    pub fn action(&self) -> Option<RuleAction> {
+        match self {
+            Self::Regex(_) => None,
+            Self::Rule(rule) => rule.action,
+        }
+    }
+
+    /// Rule name, if set.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::Regex(_) => None,
+            Self::Rule(rule) => rule.name.as_deref(),
+        }
+    }
+
+    /// Human-readable description, if set.
+    pub fn description(&self) -> Option<&str> {
+        match self {
+            Self::Regex(_) => None,
+            Self::Rule(rule) => rule.description.as_deref(),
+        }
+    }
+}
+
+impl From<&str> for CommandPattern {
+    fn from(pattern: &str) -> Self {
+        Self::Regex(pattern.to_string())
+    }
+}
+
+impl From<String> for CommandPattern {
+    fn from(pattern: String) -> Self {
+        Self::Regex(pattern)
+    }
+}
+
+/// A command pattern with per-pattern settings.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CommandRule {
+    /// Rule name used in messages instead of the regex (e.g., "no-disk-wipe").
     #[serde(default)]
-    pub allow_servers: Vec<String>,
-    /// MCP servers to deny.
+    pub name: Option<String>,
+    /// Regex matched against each command segment.
+    pub pattern: String,
+    /// Severity reported for matches (default: critical).
     #[serde(default)]
-    pub deny_servers: Vec<String>,
-    /// MCP servers requiring user confirmation.
+    pub severity: Option<Severity>,
+    /// Action for matches (default: deny in `block_patterns`, ask in
+    /// `ask_patterns`).
     #[serde(default)]
-    pub ask_servers: Vec<String>,
+    pub action: Option<RuleAction>,
+    /// Why the pattern is dangerous, shown to the model.
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
-/// Policy configuration for LLM protection.
+/// Privilege escalation configuration.
 #[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct PolicyConfig {
-    /// Operation mode (strict or monitor).
-    #[serde(default)]
-    pub mode: PolicyMode,
-    /// Fail closed on errors (default: true).
-    #[serde(default = "default_fail_closed")]
-    pub fail_closed: bool,
-    /// Secret scanning configuration.
-    #[serde(default)]
-    pub secrets: SecretsConfig,
-    /// Dangerous command detection.
-    #[serde(default)]
-    pub commands: CommandsConfig,
-    /// Protected path configuration.
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SudoConfig {
+    /// Enable privilege escalation checks (default: true).
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Action for escalations whose target is not allowed (default: ask).
     #[serde(default)]
-    pub protected_paths: ProtectedPathsConfig,
-    /// Network exfiltration detection.
+    pub action: RuleAction,
+    /// Commands that may run with elevated privileges, matched as a word
+    /// prefix of the target (e.g., "apt-get install" allows
+    /// `sudo apt-get install -y curl`).
     #[serde(default)]
-    pub network: NetworkConfig,
-}
-
-fn default_fail_closed() -> bool {
-    true
+    pub allow: Vec<String>,
 }
 
-impl Default for PolicyConfig {
+impl Default for SudoConfig {
     fn default() -> Self {
         Self {
-            mode: PolicyMode::default(),
-            fail_closed: default_fail_closed(),
-            secrets: SecretsConfig::default(),
-            commands: CommandsConfig::default(),
-            protected_paths: ProtectedPathsConfig::default(),
-            network: NetworkConfig::default(),
+            enabled: true,
+            action: RuleAction::Ask,
+            allow: Vec::new(),
         }
     }
 }
 
-/// Policy operation mode.
-#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
-pub enum PolicyMode {
-    /// Block actions that violate policy.
-    #[default]
-    Strict,
-    /// Log violations but allow all actions.
-    Monitor,
-}
-
-/// Secret scanning configuration.
+/// Destructive git operation configuration.
+///
+/// Each rule can be turned off individually.
 #[derive(Debug, Deserialize, Serialize, Clone)]
-#[allow(clippy::struct_excessive_bools)] // Config structs intentionally use many bools
-pub struct SecretsConfig {
-    /// Enable secret scanning (default: true).
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[allow(clippy::struct_excessive_bools)] // One toggle per rule
+pub struct GitConfig {
+    /// Enable git rules (default: true).
     #[serde(default = "default_true")]
     pub enabled: bool,
-    /// Entropy threshold for generic secret detection (default: 4.5).
-    #[serde(default = "default_entropy_threshold")]
-    pub entropy_threshold: f64,
-    /// Detect AWS access keys.
+    /// Action when a rule matches (default: ask).
+    #[serde(default)]
+    pub action: RuleAction,
+    /// `git push --force` to a protected branch, or to an unnamed branch.
     #[serde(default = "default_true")]
-    pub detect_aws_keys: bool,
-    /// Detect GitHub tokens.
+    pub force_push: bool,
+    /// `git reset --hard`.
     #[serde(default = "default_true")]
-    pub detect_github_tokens: bool,
-    /// Detect `OpenAI` API keys.
+    pub reset_hard: bool,
+    /// `git clean -f`.
     #[serde(default = "default_true")]
-    pub detect_openai_keys: bool,
-    /// Detect private keys (PEM format).
+    pub clean: bool,
+    /// `git checkout -- .` and `git restore .`.
     #[serde(default = "default_true")]
-    pub detect_private_keys: bool,
-}
-
-fn default_true() -> bool {
-    true
+    pub checkout_discard: bool,
+    /// Branches protected from force pushes (glob patterns).
+    #[serde(default = "default_protected_branches")]
+    pub protected_branches: Vec<String>,
 }
 
-fn default_entropy_threshold() -> f64 {
-    4.5
+fn default_protected_branches() -> Vec<String> {
+    vec!["main".to_string(), "master".to_string()]
 }
 
-impl Default for SecretsConfig {
+impl Default for GitConfig {
     fn default() -> Self {
         Self {
             enabled: true,
-            entropy_threshold: default_entropy_threshold(),
-            detect_aws_keys: true,
-            detect_github_tokens: true,
-            detect_openai_keys: true,
-            detect_private_keys: true,
+            action: RuleAction::Ask,
+            force_push: true,
+            reset_hard: true,
+            clean: true,
+            checkout_discard: true,
+            protected_branches: default_protected_branches(),
         }
     }
 }
 
-/// Dangerous command detection configuration.
+/// Recursive permission change configuration.
+///
+/// `chmod -R`, `chown -R`, and `chgrp -R` on `/`, `/etc`, `/usr`, or the
+/// home directory always match; `paths` adds more targets.
 #[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct CommandsConfig {
-    /// Enable command scanning (default: true).
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PermissionsConfig {
+    /// Enable permission change checks (default: true).
     #[serde(default = "default_true")]
     pub enabled: bool,
-    /// Patterns to block (regex).
-    #[serde(default = "default_block_patterns")]
-    pub block_patterns: Vec<String>,
-    /// Patterns to allow (override blocks).
+    /// Action when a protected target is changed (default: deny).
+    #[serde(default = "default_permissions_action")]
+    pub action: RuleAction,
+    /// Additional protected targets (glob patterns, e.g. "/var/**").
     #[serde(default)]
-    pub allow_patterns: Vec<String>,
+    pub paths: Vec<String>,
 }
 
-fn default_block_patterns() -> Vec<String> {
-    vec![
-        r"rm\s+-rf\s+[/~]".to_string(),
-        r">\s*/dev/sd[a-z]".to_string(),
-        r"mkfs\.".to_string(),
-        r"dd\s+if=.+of=/dev/".to_string(),
-        r"chmod\s+-R\s+777\s+/".to_string(),
-        r":\(\)\s*\{\s*:\|:&\s*\}\s*;".to_string(), // Fork bomb
-    ]
+fn default_permissions_action() -> RuleAction {
+    RuleAction::Deny
 }
 
-impl Default for CommandsConfig {
+impl Default for PermissionsConfig {
     fn default() -> Self {
         Self {
             enabled: true,
-            block_patterns: default_block_patterns(),
-            allow_patterns: Vec::new(),
+            action: default_permissions_action(),
+            paths: Vec::new(),
+        }
+    }
+}
+
+/// Outbound transfer configuration.
+///
+/// Applies to `scp` and `rsync` with a remote destination and to
+/// `ssh host 'cat > file'`-style uploads.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TransfersConfig {
+    /// Enable transfer checks (default: true).
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Action for transfers to hosts not in `allowed_hosts` (default: ask).
+    #[serde(default)]
+    pub action: RuleAction,
+    /// Hosts that may receive transfers (glob patterns, e.g. "*.corp.example").
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+}
+
+impl Default for TransfersConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            action: RuleAction::Ask,
+            allowed_hosts: Vec::new(),
         }
     }
 }
 
 /// Protected paths configuration.
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[allow(clippy::struct_excessive_bools)] // Independent matching options
 pub struct ProtectedPathsConfig {
     /// Enable path protection (default: true).
     #[serde(default = "default_true")]
     pub enabled: bool,
-    /// Glob patterns for blocked paths.
+    /// Glob patterns for paths blocked for reads and writes.
     #[serde(default = "default_blocked_paths")]
     pub blocked: Vec<String>,
+    /// Additional glob patterns blocked for reads and writes.
+    #[serde(default)]
+    pub blocked_all: Vec<String>,
+    /// Glob patterns blocked for reads only.
+    #[serde(default)]
+    pub blocked_read: Vec<String>,
+    /// Glob patterns blocked for writes only (e.g. `**/.env` to let Claude
+    /// read but never modify env files).
+    #[serde(default)]
+    pub blocked_write: Vec<String>,
+    /// Glob patterns that override every blocked list (e.g.
+    /// `tests/fixtures/*.pem`).
+    #[serde(default)]
+    pub allowed: Vec<String>,
+    /// Resolve symlinks of existing files before matching, so a link to a
+    /// protected file is blocked too (default: false).
+    #[serde(default)]
+    pub resolve_symlinks: bool,
+    /// Match patterns case-insensitively, as Windows and macOS file systems
+    /// do (default: false).
+    #[serde(default)]
+    pub case_insensitive: bool,
+    /// Action for `Glob` searches that target protected paths, such as
+    /// `~/.ssh/*` (default: ask).
+    #[serde(default)]
+    pub glob_action: RuleAction,
+    /// Block writes to git hooks, `.git/config`, and `.git/info/exclude`,
+    /// which can plant code that runs on the next git command (default: true).
+    #[serde(default = "default_true")]
+    pub protect_git_internals: bool,
 }
 
 fn default_blocked_paths() -> Vec<String> {
@@ -209,12 +1564,21 @@ impl Default for ProtectedPathsConfig {
         Self {
             enabled: true,
             blocked: default_blocked_paths(),
+            blocked_all: Vec::new(),
+            blocked_read: Vec::new(),
+            blocked_write: Vec::new(),
+            allowed: Vec::new(),
+            resolve_symlinks: false,
+            case_insensitive: false,
+            glob_action: RuleAction::Ask,
+            protect_git_internals: true,
         }
     }
 }
 
 /// Network exfiltration detection configuration.
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct NetworkConfig {
     /// Enable network checking (default: true).
     #[serde(default = "default_true")]
@@ -222,6 +1586,54 @@ pub struct NetworkConfig {
     /// Domains to block.
     #[serde(default = "default_blocked_domains")]
     pub block_domains: Vec<String>,
+    /// Hosts-file or plain-list domain blocklists (e.g., a corporate proxy
+    /// denylist), relative to the config file. Their domains are appended to
+    /// `block_domains` when the configuration is loaded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocklist_files: Vec<String>,
+    /// Action for URLs whose host is a public IP address literal
+    /// (default: ask). Raw IPs bypass domain blocking entirely.
+    #[serde(default)]
+    pub public_ip_action: RuleAction,
+    /// Action for URLs whose host is a loopback, private, or link-local
+    /// IP address literal (default: allow, for local development servers).
+    #[serde(default = "default_private_ip_action")]
+    pub private_ip_action: RuleAction,
+    /// URL schemes that are rarely legitimate for an agent (`ftp://`,
+    /// `gopher://`, `data:`).
+    #[serde(default = "default_unusual_schemes")]
+    pub unusual_schemes: Vec<String>,
+    /// Action for URLs with an unusual scheme (default: ask).
+    #[serde(default)]
+    pub scheme_action: RuleAction,
+    /// Ports allowed in `http://` and `https://` URLs.
+    #[serde(default = "default_allowed_ports")]
+    pub allowed_ports: Vec<u16>,
+    /// Action for URLs with a port outside `allowed_ports` (default: allow).
+    /// Loopback hosts are exempt, so local development servers still work.
+    #[serde(default = "default_port_action")]
+    pub port_action: RuleAction,
+}
+
+fn default_private_ip_action() -> RuleAction {
+    RuleAction::Allow
+}
+
+fn default_unusual_schemes() -> Vec<String> {
+    [
+        "ftp", "ftps", "tftp", "gopher", "dict", "ldap", "telnet", "data",
+    ]
+    .iter()
+    .map(ToString::to_string)
+    .collect()
+}
+
+fn default_allowed_ports() -> Vec<u16> {
+    vec![80, 443]
+}
+
+fn default_port_action() -> RuleAction {
+    RuleAction::Allow
 }
 
 fn default_blocked_domains() -> Vec<String> {
@@ -243,14 +1655,289 @@ impl Default for NetworkConfig {
         Self {
             enabled: true,
             block_domains: default_blocked_domains(),
+            blocklist_files: Vec::new(),
+            public_ip_action: RuleAction::default(),
+            private_ip_action: default_private_ip_action(),
+            unusual_schemes: default_unusual_schemes(),
+            scheme_action: RuleAction::default(),
+            allowed_ports: default_allowed_ports(),
+            port_action: default_port_action(),
+        }
+    }
+}
+
+/// Executable file write detection configuration.
+///
+/// Dropping executables (shell scripts, batch files, desktop entries) is a
+/// common persistence or staging step, so writes of such files outside the
+/// designated script directories require confirmation.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ExecutablesConfig {
+    /// Enable executable write detection (default: true).
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Action for executable writes outside `script_dirs` (default: ask).
+    #[serde(default)]
+    pub action: RuleAction,
+    /// File extensions treated as executable (without the leading dot).
+    #[serde(default = "default_executable_extensions")]
+    pub extensions: Vec<String>,
+    /// Glob patterns for directories where scripts may be written freely,
+    /// relative to the project directory. Paths outside it never match.
+    #[serde(default = "default_script_dirs")]
+    pub script_dirs: Vec<String>,
+    /// Content patterns (regex) that deny the write regardless of location.
+    #[serde(default = "default_executable_deny_patterns")]
+    pub deny_content_patterns: Vec<String>,
+}
+
+fn default_executable_extensions() -> Vec<String> {
+    vec![
+        "sh".to_string(),
+        "bash".to_string(),
+        "zsh".to_string(),
+        "fish".to_string(),
+        "command".to_string(),
+        "bat".to_string(),
+        "cmd".to_string(),
+        "ps1".to_string(),
+        "vbs".to_string(),
+        "desktop".to_string(),
+    ]
+}
+
+fn default_script_dirs() -> Vec<String> {
+    vec!["**/scripts/**".to_string(), "**/bin/**".to_string()]
+}
+
+fn default_executable_deny_patterns() -> Vec<String> {
+    vec![
+        r"(?i)\b(curl|wget)\b[^|\n]*\|\s*(sudo\s+)?(ba|z|da)?sh\b".to_string(),
+        r"(?i)base64\s+(-d|--decode)[^|\n]*\|\s*(ba|z|da)?sh\b".to_string(),
+        r"(?i)\b(iwr|invoke-webrequest)\b[^|\n]*\|\s*(iex|invoke-expression)\b".to_string(),
+    ]
+}
+
+impl Default for ExecutablesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            action: RuleAction::default(),
+            extensions: default_executable_extensions(),
+            script_dirs: default_script_dirs(),
+            deny_content_patterns: default_executable_deny_patterns(),
+        }
+    }
+}
+
+/// Package installation policy configuration.
+///
+/// Applies to `npm install`, `pip install`, `cargo add`, `uv add`, and their
+/// close relatives. Entries are glob patterns on the package name, optionally
+/// prefixed with an ecosystem (`npm:`, `pypi:`, `crates:`).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PackagesConfig {
+    /// Enable package installation checks (default: true).
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Packages that may be installed without confirmation.
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Packages that are always denied.
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// Action for packages on neither list (default: ask).
+    #[serde(default)]
+    pub unknown: RuleAction,
+}
+
+impl Default for PackagesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            allow: Vec::new(),
+            deny: Vec::new(),
+            unknown: RuleAction::Ask,
+        }
+    }
+}
+
+/// Workspace sandbox configuration.
+///
+/// Restricts `Read`, `Write`, and `Edit` to the project directory
+/// (`CLAUDE_PROJECT_DIR`, or the hook's working directory).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WorkspaceConfig {
+    /// Enable the workspace sandbox (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// Action for file operations outside the project (default: ask).
+    #[serde(default)]
+    pub action: RuleAction,
+    /// Glob patterns for paths outside the project that are allowed
+    /// (e.g. `/tmp/**`).
+    #[serde(default)]
+    pub allowed: Vec<String>,
+}
+
+impl Default for WorkspaceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            action: RuleAction::Ask,
+            allowed: Vec::new(),
+        }
+    }
+}
+
+/// Large and binary write configuration.
+///
+/// Flags `Write` calls whose content exceeds `max_bytes` or looks binary,
+/// which can be exfiltration staging or an accidental dump.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WritesConfig {
+    /// Enable write size checks (default: true).
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Largest content written without confirmation, in bytes
+    /// (default: 1048576, one mebibyte).
+    #[serde(default = "default_max_write_bytes")]
+    pub max_bytes: usize,
+    /// Flag content that looks binary (default: true).
+    #[serde(default = "default_true")]
+    pub detect_binary: bool,
+    /// Action for large or binary writes (default: ask).
+    #[serde(default)]
+    pub action: RuleAction,
+}
+
+fn default_max_write_bytes() -> usize {
+    1024 * 1024
+}
+
+impl Default for WritesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_bytes: default_max_write_bytes(),
+            detect_binary: true,
+            action: RuleAction::Ask,
+        }
+    }
+}
+
+/// Control character and terminal escape detection configuration.
+///
+/// Flags commands and written text containing escape sequences, carriage
+/// returns, backspaces, or bidi overrides, which can make a terminal or
+/// editor show something other than what runs or is saved.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ControlCharsConfig {
+    /// Enable control character checks (default: true).
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Action for text with hidden control characters (default: ask).
+    #[serde(default)]
+    pub action: RuleAction,
+}
+
+impl Default for ControlCharsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            action: RuleAction::Ask,
+        }
+    }
+}
+
+/// Prompt injection detection configuration.
+///
+/// Flags `Task` prompts and `WebFetch` prompts that contain classic injection
+/// strings, such as "ignore previous instructions" or directives hidden in
+/// HTML comments.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct InjectionConfig {
+    /// Enable prompt injection checks (default: true).
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Additional regex patterns that indicate injection.
+    #[serde(default)]
+    pub extra_patterns: Vec<String>,
+    /// Action for suspected injection (default: ask).
+    #[serde(default)]
+    pub action: RuleAction,
+}
+
+impl Default for InjectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            extra_patterns: Vec::new(),
+            action: RuleAction::Ask,
         }
     }
 }
 
+/// A policy regression case: a tool call and the decision it should get.
+///
+/// `expect` is compared with the hook's permission decision, so a redacted
+/// call counts as `allow`. In `[[tests]]` the fields can also be written
+/// `tool`, `input`, and `expected`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PolicyCase {
+    /// Label shown in results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Tool name (e.g., "Bash").
+    #[serde(alias = "tool")]
+    pub tool_name: String,
+    /// Tool input.
+    #[serde(default, alias = "input")]
+    pub tool_input: serde_json::Value,
+    /// Expected decision.
+    #[serde(alias = "expected")]
+    pub expect: RuleAction,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_policy_tests_section() {
+        let config: Config = toml::from_str(
+            r#"
+[[tests]]
+tool = "Bash"
+input = { command = "rm -rf /" }
+expected = "deny"
+
+[[tests]]
+name = "reads are fine"
+tool_name = "Read"
+tool_input = { file_path = "src/main.rs" }
+expect = "allow"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.tests.len(), 2);
+        assert_eq!(config.tests[0].tool_name, "Bash");
+        assert_eq!(
+            config.tests[0].tool_input,
+            serde_json::json!({ "command": "rm -rf /" })
+        );
+        assert_eq!(config.tests[0].expect, RuleAction::Deny);
+        assert_eq!(config.tests[1].name.as_deref(), Some("reads are fine"));
+    }
+
     #[test]
     fn test_default_config() {
         let config = Config::default();
@@ -291,10 +1978,51 @@ block_domains = ["evil.com"]
         assert_eq!(config.policy.mode, PolicyMode::Monitor);
         assert!(!config.policy.fail_closed);
         assert!((config.policy.secrets.entropy_threshold - 4.0).abs() < f64::EPSILON);
-        assert_eq!(config.policy.commands.block_patterns, vec!["rm -rf"]);
+        assert_eq!(config.policy.commands.block_patterns.len(), 1);
+        assert_eq!(config.policy.commands.block_patterns[0].pattern(), "rm -rf");
         assert_eq!(config.policy.network.block_domains, vec!["evil.com"]);
     }
 
+    #[test]
+    fn test_command_pattern_tables() {
+        let toml_content = r#"
+[policy.commands]
+block_patterns = [
+    "mkfs",
+    { name = "no-tf-destroy", pattern = "terraform destroy", severity = "high", action = "ask", description = "Destroys infrastructure" },
+]
+"#;
+
+        let config: Config = toml::from_str(toml_content).unwrap();
+        let patterns = &config.policy.commands.block_patterns;
+        assert_eq!(patterns[0], CommandPattern::from("mkfs"));
+        assert_eq!(patterns[0].action(), None);
+        assert_eq!(patterns[0].name(), None);
+        assert_eq!(patterns[1].name(), Some("no-tf-destroy"));
+        assert_eq!(patterns[1].pattern(), "terraform destroy");
+        assert_eq!(patterns[1].severity(), Some(Severity::High));
+        assert_eq!(patterns[1].action(), Some(RuleAction::Ask));
+        assert_eq!(patterns[1].description(), Some("Destroys infrastructure"));
+    }
+
+    #[test]
+    fn test_executables_config_deserialize() {
+        let toml_content = r#"
+[policy.executables]
+action = "deny"
+script_dirs = ["tools/**"]
+"#;
+
+        let config: Config = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.policy.executables.action, RuleAction::Deny);
+        assert_eq!(config.policy.executables.script_dirs, vec!["tools/**"]);
+        assert!(config
+            .policy
+            .executables
+            .extensions
+            .contains(&"sh".to_string()));
+    }
+
     #[test]
     fn test_policy_mode() {
         assert_eq!(PolicyMode::default(), PolicyMode::Strict);
@@ -13,12 +13,17 @@ mod tool_input;
 mod verdict;
 
 // Re-export all public types
-pub use block_reason::BlockReason;
+pub use block_reason::{BlockReason, Severity};
 pub use config::{
-    CommandsConfig, Config, McpConfig, NetworkConfig, PolicyConfig, PolicyMode,
-    ProtectedPathsConfig, SecretsConfig, ToolsConfig,
+    AdvertiseConfig, ApprovalsConfig, ApproveConfig, AuditConfig, CommandPattern, CommandRule,
+    CommandsConfig, Config, ControlCharsConfig, ExecutablesConfig, GitConfig, InjectionConfig,
+    LimitsConfig, McpArgRule, McpConfig, McpToolRule, NetworkConfig, OtelConfig, PackagesConfig,
+    PermissionsConfig, PolicyCase, PolicyConfig, PolicyMode, ProtectedPathsConfig, RiskConfig,
+    RuleAction, SecretAction, SecretRule, SecretsConfig, SessionConfig, StatsdConfig, SudoConfig,
+    SyslogConfig, SyslogFacility, SyslogTarget, TaintConfig, ToolsConfig, TransfersConfig,
+    WebhookConfig, WorkspaceConfig, WritesConfig, CONFIG_VERSION,
 };
-pub use tool_input::{HookInput, ToolInput};
+pub use tool_input::{EditOperation, HookInput, PermissionMode, ToolInput};
 pub use verdict::Verdict;
 
 #[cfg(test)]
@@ -60,6 +65,11 @@ block_domains = ["pastebin.com"]
         let deny = Verdict::deny_from_block_reason(&BlockReason::DangerousCommand {
             pattern: "test".to_string(),
             matched: "test".to_string(),
+            impact: None,
+            rule: None,
+            severity: None,
+            description: None,
+            ask: false,
         });
         let ask = Verdict::ask("Confirm?");
 
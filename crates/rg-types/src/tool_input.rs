@@ -3,12 +3,44 @@
 use serde::{Deserialize, Serialize};
 
 /// Input received from Claude Code via stdin.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HookInput {
     /// The name of the tool being invoked (e.g., "Bash", "Write", "Edit")
     pub tool_name: String,
     /// The tool-specific input as raw JSON
     pub tool_input: serde_json::Value,
+    /// Working directory the tool runs in, if provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// Claude Code session the call belongs to, if provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Path to the session's transcript (JSONL), if provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript_path: Option<String>,
+    /// Claude Code permission mode the call runs under, if provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permission_mode: Option<PermissionMode>,
+    /// Hook event that fired (e.g., `PreToolUse`, `PostToolUse`), if provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hook_event_name: Option<String>,
+}
+
+/// Claude Code permission mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionMode {
+    /// Ask before tools that need permission.
+    Default,
+    /// Accept file edits without asking.
+    AcceptEdits,
+    /// Plan only; no changes are made.
+    Plan,
+    /// Run every tool without asking.
+    BypassPermissions,
+    /// A mode this version does not know.
+    #[serde(other)]
+    Unknown,
 }
 
 /// Parsed tool input for specific tool types.
@@ -35,6 +67,20 @@ pub enum ToolInput {
         /// Replacement text.
         new_string: String,
     },
+    /// Apply several find-and-replace edits to one file.
+    MultiEdit {
+        /// Path to the file to edit.
+        file_path: String,
+        /// Edits, applied in order.
+        edits: Vec<EditOperation>,
+    },
+    /// Replace the source of a Jupyter notebook cell.
+    NotebookEdit {
+        /// Path to the notebook.
+        notebook_path: String,
+        /// New cell source.
+        new_source: String,
+    },
     /// Read a file's contents.
     Read {
         /// Path to the file to read.
@@ -44,6 +90,8 @@ pub enum ToolInput {
     Glob {
         /// The glob pattern to match.
         pattern: String,
+        /// Optional directory to search in.
+        path: Option<String>,
     },
     /// Search for text in files.
     Grep {
@@ -56,6 +104,8 @@ pub enum ToolInput {
     WebFetch {
         /// The URL to fetch.
         url: String,
+        /// What to extract from the fetched content.
+        prompt: String,
     },
     /// Search the web.
     WebSearch {
@@ -76,7 +126,25 @@ pub enum ToolInput {
     },
 }
 
+/// A single find-and-replace edit of a `MultiEdit`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EditOperation {
+    /// Text to find and replace.
+    pub old_string: String,
+    /// Replacement text.
+    pub new_string: String,
+}
+
 impl HookInput {
+    /// A call of `tool_name` with `tool_input` and no other context.
+    pub fn new(tool_name: impl Into<String>, tool_input: serde_json::Value) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+            tool_input,
+            ..Self::default()
+        }
+    }
+
     /// Parse the raw tool input into a typed `ToolInput`.
     pub fn parse(&self) -> ToolInput {
         match self.tool_name.as_str() {
@@ -132,6 +200,55 @@ impl HookInput {
                     new_string,
                 }
             }
+            "MultiEdit" => {
+                let file_path = self
+                    .tool_input
+                    .get("file_path")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                let edits = self
+                    .tool_input
+                    .get("edits")
+                    .and_then(|v| v.as_array())
+                    .map(|edits| {
+                        edits
+                            .iter()
+                            .map(|edit| EditOperation {
+                                old_string: edit
+                                    .get("old_string")
+                                    .and_then(|v| v.as_str())
+                                    .unwrap_or_default()
+                                    .to_string(),
+                                new_string: edit
+                                    .get("new_string")
+                                    .and_then(|v| v.as_str())
+                                    .unwrap_or_default()
+                                    .to_string(),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                ToolInput::MultiEdit { file_path, edits }
+            }
+            "NotebookEdit" => {
+                let notebook_path = self
+                    .tool_input
+                    .get("notebook_path")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                let new_source = self
+                    .tool_input
+                    .get("new_source")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                ToolInput::NotebookEdit {
+                    notebook_path,
+                    new_source,
+                }
+            }
             "Read" => {
                 let file_path = self
                     .tool_input
@@ -148,7 +265,12 @@ impl HookInput {
                     .and_then(|v| v.as_str())
                     .unwrap_or_default()
                     .to_string();
-                ToolInput::Glob { pattern }
+                let path = self
+                    .tool_input
+                    .get("path")
+                    .and_then(|v| v.as_str())
+                    .map(String::from);
+                ToolInput::Glob { pattern, path }
             }
             "Grep" => {
                 let pattern = self
@@ -171,7 +293,13 @@ impl HookInput {
                     .and_then(|v| v.as_str())
                     .unwrap_or_default()
                     .to_string();
-                ToolInput::WebFetch { url }
+                let prompt = self
+                    .tool_input
+                    .get("prompt")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                ToolInput::WebFetch { url, prompt }
             }
             "WebSearch" => {
                 let query = self
@@ -216,10 +344,7 @@ mod tests {
 
     #[test]
     fn test_parse_bash_input() {
-        let input = HookInput {
-            tool_name: "Bash".to_string(),
-            tool_input: serde_json::json!({ "command": "ls -la" }),
-        };
+        let input = HookInput::new("Bash", serde_json::json!({ "command": "ls -la" }));
 
         match input.parse() {
             ToolInput::Bash { command } => assert_eq!(command, "ls -la"),
@@ -227,15 +352,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_deserialize_full_payload() {
+        let input: HookInput = serde_json::from_value(serde_json::json!({
+            "session_id": "abc123",
+            "transcript_path": "/home/u/.claude/projects/p/abc123.jsonl",
+            "cwd": "/work/app",
+            "permission_mode": "acceptEdits",
+            "hook_event_name": "PreToolUse",
+            "tool_name": "Bash",
+            "tool_input": { "command": "ls" }
+        }))
+        .unwrap();
+
+        assert_eq!(input.session_id.as_deref(), Some("abc123"));
+        assert_eq!(
+            input.transcript_path.as_deref(),
+            Some("/home/u/.claude/projects/p/abc123.jsonl")
+        );
+        assert_eq!(input.cwd.as_deref(), Some("/work/app"));
+        assert_eq!(input.permission_mode, Some(PermissionMode::AcceptEdits));
+        assert_eq!(input.hook_event_name.as_deref(), Some("PreToolUse"));
+
+        // Unknown modes and missing fields are not errors
+        let input: HookInput = serde_json::from_value(serde_json::json!({
+            "permission_mode": "somethingNew",
+            "tool_name": "Bash",
+            "tool_input": {}
+        }))
+        .unwrap();
+        assert_eq!(input.permission_mode, Some(PermissionMode::Unknown));
+        assert_eq!(input.session_id, None);
+    }
+
     #[test]
     fn test_parse_write_input() {
-        let input = HookInput {
-            tool_name: "Write".to_string(),
-            tool_input: serde_json::json!({
+        let input = HookInput::new(
+            "Write",
+            serde_json::json!({
                 "file_path": "/tmp/test.txt",
                 "content": "hello world"
             }),
-        };
+        );
 
         match input.parse() {
             ToolInput::Write { file_path, content } => {
@@ -246,12 +404,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_multi_edit_input() {
+        let input = HookInput::new(
+            "MultiEdit",
+            serde_json::json!({
+                "file_path": "src/lib.rs",
+                "edits": [
+                    { "old_string": "a", "new_string": "b" },
+                    { "old_string": "c", "new_string": "d", "replace_all": true }
+                ]
+            }),
+        );
+
+        match input.parse() {
+            ToolInput::MultiEdit { file_path, edits } => {
+                assert_eq!(file_path, "src/lib.rs");
+                assert_eq!(edits.len(), 2);
+                assert_eq!(edits[1].old_string, "c");
+                assert_eq!(edits[1].new_string, "d");
+            }
+            _ => panic!("Expected MultiEdit variant"),
+        }
+    }
+
+    #[test]
+    fn test_parse_notebook_edit_input() {
+        let input = HookInput::new(
+            "NotebookEdit",
+            serde_json::json!({
+                "notebook_path": "analysis.ipynb",
+                "cell_id": "abc",
+                "new_source": "print(1)"
+            }),
+        );
+
+        match input.parse() {
+            ToolInput::NotebookEdit {
+                notebook_path,
+                new_source,
+            } => {
+                assert_eq!(notebook_path, "analysis.ipynb");
+                assert_eq!(new_source, "print(1)");
+            }
+            _ => panic!("Expected NotebookEdit variant"),
+        }
+    }
+
     #[test]
     fn test_parse_unknown_tool() {
-        let input = HookInput {
-            tool_name: "CustomTool".to_string(),
-            tool_input: serde_json::json!({ "foo": "bar" }),
-        };
+        let input = HookInput::new("CustomTool", serde_json::json!({ "foo": "bar" }));
 
         match input.parse() {
             ToolInput::Unknown { tool_name, .. } => assert_eq!(tool_name, "CustomTool"),